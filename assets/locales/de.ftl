@@ -0,0 +1,107 @@
+# German messages. Missing keys fall back to English.
+
+## Stats labels
+stat-activity = Aktivität
+stat-active-blocks = Aktive Blöcke
+stat-autosave = Autospeichern
+stat-benchmark = Benchmark
+stat-births = Geburten
+stat-block-steps = Blockschritte
+stat-brush = Pinsel
+stat-bundle = Paket
+stat-calibration = Kalibrierung
+stat-camera-script = Kameraskript
+stat-captures = Aufnahmen
+stat-clipboard = Zwischenablage
+stat-collision = Kollision
+stat-cursor = Cursor
+stat-determinism = Determinismus
+stat-draw-time = Zeichenzeit
+stat-dying = Sterbend
+stat-engine = Engine
+stat-engines = Engines
+stat-entropy = Entropie
+stat-frame-export = Frame-Export
+stat-generation = Generation
+stat-guides = Hilfslinien
+stat-heatmap = Heatmap
+stat-history = Verlauf
+stat-import = Import
+stat-jump = Sprung
+stat-lens = Lupe
+stat-link = Link
+stat-low-power = Energiesparmodus
+stat-macro = Makro
+stat-memory = Speicher
+stat-paste-mode = Einfügemodus
+stat-pattern-hints = Musterhinweise
+stat-paused = Pausiert
+stat-period = Periode
+stat-phase-stepping = Phasenschritte
+stat-pipelined = Pipeline
+stat-population = Population
+stat-recording = Aufnahme
+stat-remote = Fernsteuerung
+stat-rule = Regel
+stat-saved = Gespeichert
+stat-seed = Seed
+stat-selection = Auswahl
+stat-shader = Shader
+stat-skipped-ticks = Übersprungene Ticks
+stat-snap = Raster
+stat-soup-noise = Suppenrauschen
+stat-stamp = Stempel
+stat-steps-s = Schritte/s
+stat-tick-rate = Tickrate
+stat-tile-uploads = Kachel-Uploads
+stat-torus = Torus
+stat-turbo = Turbo
+stat-unfocused = Im Hintergrund
+stat-velocity = Geschwindigkeit
+stat-workspace = Arbeitsbereich
+
+## Stats board
+stats-loading = Statistiken werden geladen...
+stats-empty = Keine Statistiken
+
+## Menus
+menu-presets = Regelvorlagen (Enter übernimmt)
+menu-generators = Erzeugen (Links/Rechts stellt ein, Enter fügt hinzu)
+generator-glider-gun-array = Gleiterkanonen-Reihe
+generator-lwss-fleet = LWSS-Flotte
+generator-blinker-field = Blinkerfeld
+param-guns = Kanonen
+param-spacing = Abstand
+param-phase-offset = Phasenversatz
+param-ships = Schiffe
+param-stagger = Versatz
+param-size = Größe
+param-density = Dichte %
+
+## Tutorial
+tutorial-title = Einführung, Schritt { $step } von { $steps }
+tutorial-draw = Linke Maustaste gedrückt halten und ziehen, um Zellen zu zeichnen
+tutorial-pause = \ drücken, um die Simulation anzuhalten
+tutorial-resume = \ erneut drücken, um sie weiterlaufen zu lassen
+tutorial-zoom = Mit dem Mausrad zoomen
+tutorial-load = Eine .rle-Datei auf das Fenster ziehen und per Klick ablegen oder P und Enter für eine Vorlage drücken
+tutorial-engine = Eine Zifferntaste von 1 bis 9 drücken, um die Engine zu wechseln
+tutorial-end = Rücktaste beendet die Einführung
+
+## Screen reader announcements
+announce-paused = Angehalten
+announce-running = Läuft
+announce-generation = Generation { $generation } erreicht
+announce-loaded = { $name } geladen
+
+## Error toasts
+error-io = { $path }: { $reason }
+error-invalid-rule = ungültige Regel: { $detail }
+error-invalid-pattern = ungültiges Muster: { $detail }
+error-out-of-range = { $count } Zellen liegen außerhalb des unterstützten Bereichs von ±{ $limit } und wurden ignoriert
+error-engine-crashed = die Engine { $engine } ist abgestürzt ({ $reason }); die Simulation ruht, bis das Universum geleert, geladen oder auf eine andere Engine gewechselt wird
+error-diverged = { $engine } weicht ab Generation { $generation } von { $shadow } ab, zuerst bei ({ $x }, { $y }); die Simulation ruht
+error-too-large = { $name } hat { $cells } Zellen, etwa { $estimate } MB auf { $engine }, mehr als das Importbudget von { $budget } MB; { $suggestion }
+error-export = Export fehlgeschlagen: { $detail }
+error-upload = Hochladen fehlgeschlagen: { $detail }
+error-remote = entferntes Universum: { $detail }
@@ -0,0 +1,108 @@
+# English messages, the fallback for every other language.
+# Lines are `key = text`; `{ $name }` is filled in by the program.
+
+## Stats labels
+stat-activity = Activity
+stat-active-blocks = Active Blocks
+stat-autosave = Autosave
+stat-benchmark = Benchmark
+stat-births = Births
+stat-block-steps = Block Steps
+stat-brush = Brush
+stat-bundle = Bundle
+stat-calibration = Calibration
+stat-camera-script = Camera Script
+stat-captures = Captures
+stat-clipboard = Clipboard
+stat-collision = Collision
+stat-cursor = Cursor
+stat-determinism = Determinism
+stat-draw-time = Draw Time
+stat-dying = Dying
+stat-engine = Engine
+stat-engines = Engines
+stat-entropy = Entropy
+stat-frame-export = Frame Export
+stat-generation = Generation
+stat-guides = Guides
+stat-heatmap = Heatmap
+stat-history = History
+stat-import = Import
+stat-jump = Jump
+stat-lens = Lens
+stat-link = Link
+stat-low-power = Low Power
+stat-macro = Macro
+stat-memory = Memory
+stat-paste-mode = Paste Mode
+stat-pattern-hints = Pattern Hints
+stat-paused = Paused
+stat-period = Period
+stat-phase-stepping = Phase Stepping
+stat-pipelined = Pipelined
+stat-population = Population
+stat-recording = Recording
+stat-remote = Remote
+stat-rule = Rule
+stat-saved = Saved
+stat-seed = Seed
+stat-selection = Selection
+stat-shader = Shader
+stat-skipped-ticks = Skipped Ticks
+stat-snap = Snap
+stat-soup-noise = Soup Noise
+stat-stamp = Stamp
+stat-steps-s = Steps/s
+stat-tick-rate = Tick Rate
+stat-tile-uploads = Tile Uploads
+stat-torus = Torus
+stat-turbo = Turbo
+stat-unfocused = Unfocused
+stat-velocity = Velocity
+stat-workspace = Workspace
+
+## Stats board
+stats-loading = Initializing Stats...
+stats-empty = No Stats
+
+## Menus
+menu-presets = Rule presets (Enter to apply)
+menu-generators = Generate (Left/Right to set, Enter to add)
+generator-glider-gun-array = Glider gun array
+generator-lwss-fleet = LWSS fleet
+generator-blinker-field = Blinker field
+param-guns = guns
+param-spacing = spacing
+param-phase-offset = phase offset
+param-ships = ships
+param-stagger = stagger
+param-size = size
+param-density = density %
+
+## Tutorial
+tutorial-title = Tutorial, step { $step } of { $steps }
+tutorial-draw = Hold the left mouse button and drag to draw cells
+tutorial-pause = Press \ to pause the simulation
+tutorial-resume = Press \ again to let it run
+tutorial-zoom = Scroll the mouse wheel to zoom
+tutorial-load = Drop an .rle file on the window and click to place it, or press P and Enter for a preset
+tutorial-engine = Press a number key from 1 to 9 to switch engines
+tutorial-end = Backspace ends the tutorial
+
+## Screen reader announcements
+announce-paused = Paused
+announce-running = Running
+announce-generation = Generation { $generation } reached
+announce-loaded = Loaded { $name }
+
+## Error toasts
+error-io = { $path }: { $reason }
+error-invalid-rule = invalid rule: { $detail }
+error-invalid-pattern = invalid pattern: { $detail }
+error-out-of-range = { $count } cells lie outside the supported range of ±{ $limit } and were ignored
+error-engine-crashed = the { $engine } engine crashed ({ $reason }); stepping is paused until the universe is cleared, loaded or switched to another engine
+error-diverged = { $engine } diverged from { $shadow } by generation { $generation }, first at ({ $x }, { $y }); stepping is paused
+error-too-large = { $name } has { $cells } cells, about { $estimate } MB on { $engine }, over the import budget of { $budget } MB; { $suggestion }
+error-export = export failed: { $detail }
+error-upload = upload failed: { $detail }
+error-remote = remote universe: { $detail }
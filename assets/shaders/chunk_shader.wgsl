@@ -3,25 +3,74 @@
 struct BitChunkMaterial {
     color_alive: vec4<f32>,
     color_dead: vec4<f32>,
+    // x: buffer pixels per cell, y/z: fractional world offset of the buffer origin
+    grid: vec4<f32>,
+    // State colors for the palette variant; only the first `palette_len` are set
+    palette: array<vec4<f32>, 16>,
+    palette_len: u32,
+    // Buffer value meaning full coverage (255 for R8 layers, 65535 for R16)
+    max_value: u32,
 };
 
 @group(2) @binding(0) var<uniform> material: BitChunkMaterial;
 @group(2) @binding(1) var data_texture: texture_2d<u32>;
 
+// Heat-style ramp used by the age palette: dead -> blue -> cyan -> yellow -> alive
+fn age_palette(t: f32) -> vec4<f32> {
+    let blue = vec4<f32>(0.1, 0.2, 0.8, 1.0);
+    let cyan = vec4<f32>(0.1, 0.8, 0.9, 1.0);
+    let yellow = vec4<f32>(1.0, 0.85, 0.2, 1.0);
+    if (t <= 0.0) {
+        return material.color_dead;
+    } else if (t < 0.33) {
+        return mix(blue, cyan, t / 0.33);
+    } else if (t < 0.66) {
+        return mix(cyan, yellow, (t - 0.33) / 0.33);
+    }
+    return mix(yellow, material.color_alive, (t - 0.66) / 0.34);
+}
+
 @fragment
 fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
     let dims = textureDimensions(data_texture);
 
     // Map UV to pixel coordinates
-    let x = clamp(u32(in.uv.x * f32(dims.x)), 0u, dims.x - 1u);
-    let y = clamp(u32((1.0 - in.uv.y) * f32(dims.y)), 0u, dims.y - 1u);
+    let fx = in.uv.x * f32(dims.x);
+    let fy = (1.0 - in.uv.y) * f32(dims.y);
+    let x = clamp(u32(fx), 0u, dims.x - 1u);
+    let y = clamp(u32(fy), 0u, dims.y - 1u);
 
-    // Load the density value (0 to 255)
+    // Load the density value (0 to max_value) or state index
     let raw_value = textureLoad(data_texture, vec2<u32>(x, y), 0).r;
 
     // Normalize the integer to a float factor (0.0 to 1.0)
-    let t = f32(raw_value) / 255.0;
+    let t = f32(raw_value) / f32(material.max_value);
 
-    // Linear Interpolation (Lerp)
+#ifdef SHADER_PALETTE
+    if (material.palette_len == 0u) {
+        return material.color_dead;
+    }
+    return material.palette[min(raw_value, material.palette_len - 1u)];
+#else ifdef SHADER_BINARY
+    // Any coverage at all counts as alive
+    return select(material.color_dead, material.color_alive, raw_value > 0u);
+#else ifdef SHADER_AGE_PALETTE
+    return age_palette(t);
+#else ifdef SHADER_GRID_LINES
+    let color = mix(material.color_dead, material.color_alive, t);
+    let cell_px = material.grid.x;
+    // Only draw lines once cells are big enough for them to be readable
+    if (cell_px < 4.0) {
+        return color;
+    }
+    let wx = fract(material.grid.y + fx / cell_px) * cell_px;
+    let wy = fract(material.grid.z + fy / cell_px) * cell_px;
+    if (wx < 1.0 || wy < 1.0) {
+        return mix(color, vec4<f32>(0.4, 0.4, 0.4, 1.0), 0.5);
+    }
+    return color;
+#else
+    // Gradient: Linear Interpolation (Lerp)
     return mix(material.color_dead, material.color_alive, t);
+#endif
 }
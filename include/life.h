@@ -0,0 +1,99 @@
+#ifndef LIFE_H
+#define LIFE_H
+
+/* Generated by cbindgen from src/ffi.rs; don't edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Returned instead of a count when a handle or buffer is null or a count too large.
+#define LIFE_INVALID_ARGUMENT -1
+
+// Returned instead of a count when a region reaches past the coordinates of `i64`.
+#define LIFE_OUT_OF_RANGE -2
+
+// An engine and its cells; opaque to C.
+typedef struct LifeEngineHandle LifeEngineHandle;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Creates the engine registered as `id` (such as `"hash-life"`), or the default engine
+// if `id` is null. Returns null for unknown ids.
+//
+// # Safety
+// `id` must be null or a NUL-terminated string.
+LifeEngineHandle *life_engine_create(const char *id);
+
+// Creates an engine running a Life-like rule such as `"B36/S23"`. Returns null if the
+// rule can't be read.
+//
+// # Safety
+// `rule` must be a NUL-terminated string.
+LifeEngineHandle *life_engine_create_rule(const char *rule);
+
+// Frees an engine. Null is ignored.
+//
+// # Safety
+// `engine` must come from one of the create functions and not be used afterwards.
+void life_engine_free(LifeEngineHandle *engine);
+
+// Sets `count` cells alive (or dead), given as `x, y` pairs in `coords`. Cells beyond
+// the coordinates every engine supports are skipped. Returns the number of cells set,
+// or [`LIFE_INVALID_ARGUMENT`] for a null handle or too many coordinates to address.
+//
+// # Safety
+// `engine` must be a live handle and `coords` must hold `2 * count` values.
+int64_t life_engine_set_cells(LifeEngineHandle *engine,
+                              const int64_t *coords,
+                              size_t count,
+                              bool alive);
+
+// Kills every cell and resets the generation.
+//
+// # Safety
+// `engine` must be a live handle.
+void life_engine_clear(LifeEngineHandle *engine);
+
+// Steps `steps` generations, returning how many were stepped (engines may step in
+// larger increments).
+//
+// # Safety
+// `engine` must be a live handle.
+uint64_t life_engine_step(LifeEngineHandle *engine, uint64_t steps);
+
+// Generations stepped since the engine was created or cleared.
+//
+// # Safety
+// `engine` must be a live handle.
+uint64_t life_engine_generation(const LifeEngineHandle *engine);
+
+// Live cells.
+//
+// # Safety
+// `engine` must be a live handle.
+uint64_t life_engine_population(const LifeEngineHandle *engine);
+
+// Writes the `width` × `height` cells from (`x`, `y`) up into `out`, one byte per cell
+// (1 alive, 0 dead): `out[row * width + column]` is cell (`x + column`, `y + row`).
+// Returns the number of live cells written, [`LIFE_INVALID_ARGUMENT`] for a null handle
+// or buffer, or [`LIFE_OUT_OF_RANGE`] if the region reaches past `i64`.
+//
+// # Safety
+// `engine` must be a live handle and `out` must hold `width * height` bytes.
+int64_t life_engine_read_region(const LifeEngineHandle *engine,
+                                int64_t x,
+                                int64_t y,
+                                size_t width,
+                                size_t height,
+                                uint8_t *out);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* LIFE_H */
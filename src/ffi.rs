@@ -0,0 +1,235 @@
+//! C interface to the engines, for embedding them in C and C++ tools. The header is
+//! `include/life.h`, generated with `cbindgen --config cbindgen.toml --output include/life.h`;
+//! build the library with `cargo rustc --lib --release --features ffi --crate-type cdylib`
+//! (or `staticlib`).
+//!
+//! Engines are handles that must be freed with [`life_engine_free`]. A handle may be used
+//! from any thread, but not from two at once. Panics abort the process.
+
+use std::ffi::{CStr, c_char};
+
+use bevy::math::I64Vec2;
+
+use crate::simulation::engine::{
+    EngineRegistry, LifeEngine, TILE_SIZE, create_rule_engine, default_engine, in_supported_range,
+    tile_cells,
+};
+use crate::simulation::rules::LifeRule;
+
+/// Returned instead of a count when a handle or buffer is null or a count too large.
+pub const LIFE_INVALID_ARGUMENT: i64 = -1;
+/// Returned instead of a count when a region reaches past the coordinates of `i64`.
+pub const LIFE_OUT_OF_RANGE: i64 = -2;
+
+/// An engine and its cells; opaque to C.
+pub struct LifeEngineHandle {
+    engine: Box<dyn LifeEngine>,
+}
+
+fn into_handle(engine: Box<dyn LifeEngine>) -> *mut LifeEngineHandle {
+    Box::into_raw(Box::new(LifeEngineHandle { engine }))
+}
+
+/// Creates the engine registered as `id` (such as `"hash-life"`), or the default engine
+/// if `id` is null. Returns null for unknown ids.
+///
+/// # Safety
+/// `id` must be null or a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn life_engine_create(id: *const c_char) -> *mut LifeEngineHandle {
+    if id.is_null() {
+        return into_handle(default_engine());
+    }
+    // SAFETY: the caller passes a NUL-terminated string
+    let id = unsafe { CStr::from_ptr(id) }.to_string_lossy();
+    match EngineRegistry::with_builtins().create(&id) {
+        Some(engine) => into_handle(engine),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Creates an engine running a Life-like rule such as `"B36/S23"`. Returns null if the
+/// rule can't be read.
+///
+/// # Safety
+/// `rule` must be a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn life_engine_create_rule(rule: *const c_char) -> *mut LifeEngineHandle {
+    if rule.is_null() {
+        return std::ptr::null_mut();
+    }
+    // SAFETY: the caller passes a NUL-terminated string
+    let rule = unsafe { CStr::from_ptr(rule) }.to_string_lossy();
+    match LifeRule::parse(&rule) {
+        Ok(rule) if rule == LifeRule::CONWAY => into_handle(default_engine()),
+        Ok(rule) => into_handle(create_rule_engine(rule)),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Frees an engine. Null is ignored.
+///
+/// # Safety
+/// `engine` must come from one of the create functions and not be used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn life_engine_free(engine: *mut LifeEngineHandle) {
+    if !engine.is_null() {
+        // SAFETY: the caller hands back ownership of a handle made by into_handle
+        drop(unsafe { Box::from_raw(engine) });
+    }
+}
+
+/// Sets `count` cells alive (or dead), given as `x, y` pairs in `coords`. Cells beyond
+/// the coordinates every engine supports are skipped. Returns the number of cells set,
+/// or [`LIFE_INVALID_ARGUMENT`] for a null handle or too many coordinates to address.
+///
+/// # Safety
+/// `engine` must be a live handle and `coords` must hold `2 * count` values.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn life_engine_set_cells(
+    engine: *mut LifeEngineHandle,
+    coords: *const i64,
+    count: usize,
+    alive: bool,
+) -> i64 {
+    if count == 0 {
+        return 0;
+    }
+    let Some(len) = count.checked_mul(2) else {
+        return LIFE_INVALID_ARGUMENT;
+    };
+    if engine.is_null() || coords.is_null() {
+        return LIFE_INVALID_ARGUMENT;
+    }
+    // SAFETY: the caller passes a live handle and 2 * count coordinates
+    let (engine, coords) = unsafe { (&mut *engine, std::slice::from_raw_parts(coords, len)) };
+    let cells: Vec<I64Vec2> = coords
+        .chunks_exact(2)
+        .map(|pair| I64Vec2::new(pair[0], pair[1]))
+        .filter(|&pos| in_supported_range(pos))
+        .collect();
+    engine.engine.set_cells(&cells, alive);
+    cells.len() as i64
+}
+
+/// Kills every cell and resets the generation.
+///
+/// # Safety
+/// `engine` must be a live handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn life_engine_clear(engine: *mut LifeEngineHandle) {
+    // SAFETY: the caller passes a live handle
+    if let Some(engine) = unsafe { engine.as_mut() } {
+        engine.engine.clear();
+    }
+}
+
+/// Steps `steps` generations, returning how many were stepped (engines may step in
+/// larger increments).
+///
+/// # Safety
+/// `engine` must be a live handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn life_engine_step(engine: *mut LifeEngineHandle, steps: u64) -> u64 {
+    // SAFETY: the caller passes a live handle
+    unsafe { engine.as_mut() }.map_or(0, |engine| engine.engine.step(steps))
+}
+
+/// Generations stepped since the engine was created or cleared.
+///
+/// # Safety
+/// `engine` must be a live handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn life_engine_generation(engine: *const LifeEngineHandle) -> u64 {
+    // SAFETY: the caller passes a live handle
+    unsafe { engine.as_ref() }.map_or(0, |engine| engine.engine.generation())
+}
+
+/// Live cells.
+///
+/// # Safety
+/// `engine` must be a live handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn life_engine_population(engine: *const LifeEngineHandle) -> u64 {
+    // SAFETY: the caller passes a live handle
+    unsafe { engine.as_ref() }.map_or(0, |engine| engine.engine.population())
+}
+
+/// Writes the `width` × `height` cells from (`x`, `y`) up into `out`, one byte per cell
+/// (1 alive, 0 dead): `out[row * width + column]` is cell (`x + column`, `y + row`).
+/// Returns the number of live cells written, [`LIFE_INVALID_ARGUMENT`] for a null handle
+/// or buffer, or [`LIFE_OUT_OF_RANGE`] if the region reaches past `i64`.
+///
+/// # Safety
+/// `engine` must be a live handle and `out` must hold `width * height` bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn life_engine_read_region(
+    engine: *const LifeEngineHandle,
+    x: i64,
+    y: i64,
+    width: usize,
+    height: usize,
+    out: *mut u8,
+) -> i64 {
+    if width == 0 || height == 0 {
+        return 0;
+    }
+    if engine.is_null() || out.is_null() {
+        return LIFE_INVALID_ARGUMENT;
+    }
+    let Some(area) = width.checked_mul(height) else {
+        return LIFE_OUT_OF_RANGE;
+    };
+    // Offset of the far corner, and the corner itself, both in range
+    let extent = i64::try_from(width)
+        .ok()
+        .zip(i64::try_from(height).ok())
+        .map(|(width, height)| I64Vec2::new(width - 1, height - 1));
+    let min = I64Vec2::new(x, y);
+    let Some((extent, max)) = extent.and_then(|extent| {
+        let max = I64Vec2::new(x.checked_add(extent.x)?, y.checked_add(extent.y)?);
+        Some((extent, max))
+    }) else {
+        return LIFE_OUT_OF_RANGE;
+    };
+    // SAFETY: the caller passes a live handle and a buffer of width * height bytes
+    let (engine, out) = unsafe { (&(*engine).engine, std::slice::from_raw_parts_mut(out, area)) };
+    out.fill(0);
+
+    let mut alive = 0;
+    let mut set = |pos: I64Vec2| {
+        if pos.cmpge(min).all() && pos.cmple(max).all() {
+            // A tile can come up more than once
+            let local = pos - min;
+            let cell = &mut out[local.y as usize * width + local.x as usize];
+            if *cell == 0 {
+                *cell = 1;
+                alive += 1;
+            }
+        }
+    };
+    // Small regions of big universes are quicker to look up than to find among all cells
+    if area as u64 <= engine.population() {
+        for row in 0..=extent.y {
+            for column in 0..=extent.x {
+                let pos = min + I64Vec2::new(column, row);
+                if engine.get_cell(pos) {
+                    set(pos);
+                }
+            }
+        }
+    } else {
+        let tile_min = min.div_euclid(I64Vec2::splat(TILE_SIZE));
+        let tile_max = max.div_euclid(I64Vec2::splat(TILE_SIZE));
+        engine.visit_tiles(&mut |tile_pos, tile| {
+            if tile_pos.cmplt(tile_min).any() || tile_pos.cmpgt(tile_max).any() {
+                return;
+            }
+            let base = tile_pos * TILE_SIZE;
+            for local in tile_cells(tile) {
+                set(base + local);
+            }
+        });
+    }
+    alive
+}
@@ -0,0 +1,3 @@
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod simulation;
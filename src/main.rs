@@ -1,12 +1,34 @@
-mod simulation;
-
 use bevy::math::I64Vec2;
 use bevy::prelude::*;
 
-use crate::simulation::SimulationPlugin;
-use crate::simulation::universe::Universe;
+use game_of_life::simulation::SimulationPlugin;
 
 fn main() {
+    // The same build runs the page and its step worker
+    #[cfg(target_arch = "wasm32")]
+    if game_of_life::simulation::step_worker::serve_if_worker() {
+        return;
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    if game_of_life::simulation::batch::run_from_args()
+        || game_of_life::simulation::census::run_from_args()
+        || game_of_life::simulation::remote::serve_from_args()
+    {
+        return;
+    }
+
+    // Settings and the saved workspace come out of IndexedDB before anything reads them
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_futures::spawn_local(async {
+        game_of_life::simulation::browser_storage::preload().await;
+        run_app();
+    });
+    #[cfg(not(target_arch = "wasm32"))]
+    run_app();
+}
+
+fn run_app() {
     let mut app = App::new();
 
     app.add_plugins(DefaultPlugins.set(WindowPlugin {
@@ -20,10 +42,9 @@ fn main() {
     //app.add_plugins(FpsOverlayPlugin::default());
     app.insert_resource(Time::<Fixed>::from_hz(30.0));
 
-    app.add_plugins(SimulationPlugin);
+    app.add_plugins(SimulationPlugin::default().with_initial_pattern(initial_pattern()));
 
     app.add_systems(Startup, spawn_camera);
-    app.add_systems(Startup, spawn_initial_pattern);
 
     app.run();
 }
@@ -32,8 +53,8 @@ fn spawn_camera(mut commands: Commands) {
     commands.spawn((Camera2d, Transform::default()));
 }
 
-fn spawn_initial_pattern(mut universe: ResMut<Universe>) {
-    let coords = vec![
+fn initial_pattern() -> Vec<I64Vec2> {
+    vec![
         I64Vec2 { x: -4, y: 0 },
         I64Vec2 { x: -4, y: -1 },
         I64Vec2 { x: -3, y: -2 },
@@ -50,7 +71,5 @@ fn spawn_initial_pattern(mut universe: ResMut<Universe>) {
         I64Vec2 { x: -1, y: 3 },
         I64Vec2 { x: -2, y: 2 },
         I64Vec2 { x: -3, y: 1 },
-    ];
-
-    universe.add_cells(coords);
+    ]
 }
@@ -0,0 +1,87 @@
+use bevy::prelude::*;
+
+use crate::simulation::settings::Settings;
+use crate::simulation::theme::Theme;
+
+/// Settings key holding the enabled options, comma separated.
+const SETTINGS_KEY: &str = "accessibility";
+/// How much larger text and panels get with `large-text`.
+#[cfg(feature = "ui")]
+const LARGE_TEXT_SCALE: f32 = 1.5;
+
+/// Options for users with visual or vestibular sensitivities:
+///
+/// - `high-contrast`: black and white cells without blending, and strong overlay colors;
+/// - `large-text`: all on-screen text and panels half again as large;
+/// - `reduced-motion`: the camera cuts instead of gliding (kiosk drifts, camera scripts)
+///   and panels don't flash.
+///
+/// `--accessibility high-contrast,large-text` (or `none`) picks them and remembers the
+/// choice in the settings.
+pub struct AccessibilityPlugin;
+
+impl Plugin for AccessibilityPlugin {
+    fn build(&self, app: &mut App) {
+        #[cfg(not(target_arch = "wasm32"))]
+        let chosen = crate::simulation::batch::arg_value("--accessibility");
+        #[cfg(target_arch = "wasm32")]
+        let chosen: Option<String> = None;
+
+        let options = {
+            let mut settings = app.world_mut().get_resource_mut::<Settings>();
+            match chosen {
+                Some(options) => {
+                    if let Some(settings) = settings.as_mut()
+                        && let Err(err) = settings.set(SETTINGS_KEY, &options)
+                    {
+                        warn!("Couldn't remember the accessibility options: {err}");
+                    }
+                    options
+                }
+                None => settings
+                    .and_then(|settings| settings.get(SETTINGS_KEY).map(str::to_string))
+                    .unwrap_or_default(),
+            }
+        };
+        app.insert_resource(Accessibility::parse(&options))
+            .add_systems(Startup, apply_accessibility);
+    }
+}
+
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct Accessibility {
+    pub high_contrast: bool,
+    pub large_text: bool,
+    pub reduced_motion: bool,
+}
+
+impl Accessibility {
+    /// Reads a comma separated list of options; unknown ones are warned about.
+    pub fn parse(text: &str) -> Self {
+        let mut options = Self::default();
+        for option in text.split(',').map(str::trim) {
+            match option {
+                "high-contrast" => options.high_contrast = true,
+                "large-text" => options.large_text = true,
+                "reduced-motion" => options.reduced_motion = true,
+                "" | "none" => {}
+                other => warn!("Unknown accessibility option '{other}'"),
+            }
+        }
+        options
+    }
+}
+
+fn apply_accessibility(
+    accessibility: Res<Accessibility>,
+    mut theme: ResMut<Theme>,
+    #[cfg(feature = "ui")] mut ui_scale: ResMut<UiScale>,
+) {
+    if accessibility.high_contrast {
+        *theme = Theme::high_contrast();
+    }
+    #[cfg(feature = "ui")]
+    if accessibility.large_text {
+        ui_scale.0 = LARGE_TEXT_SCALE;
+    }
+}
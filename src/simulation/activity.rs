@@ -0,0 +1,221 @@
+use bevy::math::DVec2;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::simulation::SimulationInput;
+use crate::simulation::graphics::{
+    LayerCanvas, LayerDesc, LayerSpawner, LayerViewport, PixelLayer,
+};
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::universe::Universe;
+use crate::simulation::view::SimulationView;
+
+const OCCUPIED: u8 = 1;
+const ACTIVE: u8 = 2;
+
+/// A shades the blocks an engine tracks (SparseLife): occupied blocks faintly, blocks
+/// that changed last step (and so get evaluated with their neighbours) in orange.
+/// Still lifes should go dark while anything moving keeps its blocks lit. Meanwhile the
+/// stats board counts the blocks evaluated, skipped, spawned and freed per generation.
+/// Ctrl+A moves the camera to the nearest active block out of view, for when the action
+/// has drifted away.
+pub struct ActivityPlugin;
+
+impl Plugin for ActivityPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActivityOverlay>()
+            .add_systems(Startup, setup_activity_layer)
+            .add_systems(
+                Update,
+                (
+                    toggle_activity.in_set(SimulationInput),
+                    jump_to_activity.in_set(SimulationInput),
+                    render_activity,
+                    show_block_stats,
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct ActivityOverlay {
+    pub enabled: bool,
+}
+
+#[derive(Component)]
+struct ActivityLayer;
+
+fn setup_activity_layer(mut layers: LayerSpawner) {
+    layers
+        .spawn(
+            LayerDesc::new("activity", 0.04)
+                .palette([
+                    Vec4::ZERO,
+                    Vec4::new(0.3, 0.5, 1.0, 0.15),
+                    Vec4::new(1.0, 0.6, 0.1, 0.35),
+                ])
+                .hidden(),
+        )
+        .insert(ActivityLayer);
+}
+
+// A: toggle the block activity overlay
+fn toggle_activity(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut overlay: ResMut<ActivityOverlay>,
+    mut q_layer: Query<&mut PixelLayer, With<ActivityLayer>>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    // Shift+A is the heatmap, Ctrl+A jumps
+    if !keys.just_pressed(KeyCode::KeyA)
+        || keys.any_pressed([
+            KeyCode::ShiftLeft,
+            KeyCode::ShiftRight,
+            KeyCode::ControlLeft,
+            KeyCode::ControlRight,
+        ])
+    {
+        return;
+    }
+    overlay.enabled = !overlay.enabled;
+    for mut layer in &mut q_layer {
+        layer.visible = overlay.enabled;
+    }
+    if !overlay.enabled {
+        stats.remove("Active Blocks");
+        stats.remove("Block Steps");
+    }
+}
+
+// Ctrl+A: center the view on the nearest active block that is out of sight
+fn jump_to_activity(
+    keys: Res<ButtonInput<KeyCode>>,
+    universe: Res<Universe>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut view: ResMut<SimulationView>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    if !keys.just_pressed(KeyCode::KeyA)
+        || !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+    {
+        return;
+    }
+    let Some(activity) = universe.read_engine().block_activity() else {
+        stats.insert("Jump", format!("not tracked by {}", universe.engine_name()));
+        return;
+    };
+    let half_screen = q_window
+        .single()
+        .map(|window| DVec2::new(window.width() as f64, window.height() as f64) / view.zoom / 2.0)
+        .unwrap_or(DVec2::ZERO);
+
+    let size = activity.block_size as f64;
+    let centers = activity
+        .active
+        .iter()
+        .map(|pos| (pos.as_dvec2() + DVec2::splat(0.5)) * size);
+    let nearest = centers
+        .filter(|center| {
+            let offset = (*center - view.center).abs();
+            offset.x > half_screen.x || offset.y > half_screen.y
+        })
+        .min_by(|a, b| {
+            a.distance_squared(view.center)
+                .total_cmp(&b.distance_squared(view.center))
+        });
+    match nearest {
+        Some(center) => {
+            view.center = center;
+            stats.insert("Jump", format!("to ({:.0}, {:.0})", center.x, center.y));
+        }
+        None if activity.active.is_empty() => stats.insert("Jump", "nothing is changing"),
+        None => stats.insert("Jump", "all activity is in view"),
+    }
+}
+
+fn render_activity(
+    overlay: Res<ActivityOverlay>,
+    universe: Res<Universe>,
+    view: Res<SimulationView>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut q_layer: Query<(&PixelLayer, &mut LayerCanvas), With<ActivityLayer>>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    if !overlay.enabled {
+        return;
+    }
+    let Ok((layer, mut canvas)) = q_layer.single_mut() else {
+        return;
+    };
+    let Ok(window) = q_window.single() else {
+        return;
+    };
+    let Some(viewport) = LayerViewport::new(window, &view, layer) else {
+        return;
+    };
+    let buffer = canvas.buffer(&viewport);
+    buffer.fill(0);
+
+    let activity = universe.read_engine().block_activity();
+    let Some(activity) = activity else {
+        stats.insert(
+            "Active Blocks",
+            format!("not tracked by {}", universe.engine_name()),
+        );
+        return;
+    };
+
+    let size = activity.block_size;
+    for pos in &activity.occupied {
+        viewport.draw_rect(buffer, pos.x * size, pos.y * size, size, size, OCCUPIED);
+    }
+    for pos in &activity.active {
+        viewport.draw_rect(buffer, pos.x * size, pos.y * size, size, size, ACTIVE);
+    }
+
+    stats.insert(
+        "Active Blocks",
+        format!(
+            "{} active, {} occupied",
+            activity.active.len(),
+            activity.occupied.len()
+        ),
+    );
+}
+
+// Per generation averages of the last step, which may have run many generations
+fn show_block_stats(
+    overlay: Res<ActivityOverlay>,
+    universe: Res<Universe>,
+    mut shown_at: Local<Option<u64>>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    if !overlay.enabled {
+        *shown_at = None;
+        return;
+    }
+    let generation = universe.generation();
+    if *shown_at == Some(generation) {
+        return;
+    }
+    *shown_at = Some(generation);
+
+    let counters = universe.read_engine().block_stats();
+    let Some(counters) = counters.filter(|counters| counters.generations > 0) else {
+        stats.remove("Block Steps");
+        return;
+    };
+    let per_generation = |count: u64| count as f64 / counters.generations as f64;
+    stats.insert(
+        "Block Steps",
+        format!(
+            "{:.0} evaluated, {:.0} skipped, {:.1} spawned, {:.1} freed, {} asleep",
+            per_generation(counters.evaluated),
+            per_generation(counters.skipped),
+            per_generation(counters.spawned),
+            per_generation(counters.freed),
+            counters.cold,
+        ),
+    );
+}
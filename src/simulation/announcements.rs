@@ -0,0 +1,105 @@
+use accesskit::{Live, Node, Role};
+use bevy::a11y::AccessibilityNode;
+use bevy::prelude::*;
+
+use crate::simulation::error::LifeError;
+use crate::simulation::locale::Localization;
+use crate::simulation::universe::{PatternLoaded, Universe};
+
+/// Generations below this aren't worth announcing; above it every power of ten is.
+const FIRST_MILESTONE: u64 = 100;
+
+/// Tells screen readers what the canvas can't: pausing and resuming, the generation
+/// passing each power of ten, loaded patterns and errors. They go into an AccessKit
+/// live region, which screen readers read out as it changes.
+pub struct AnnouncementPlugin;
+
+impl Plugin for AnnouncementPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Announcer>()
+            .add_systems(Startup, setup_live_region)
+            .add_systems(Update, (announce_changes, update_live_region).chain());
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Announcer {
+    /// The latest announcement.
+    pub text: String,
+    paused: Option<bool>,
+    // Last power of ten the generation was announced at
+    milestone: u64,
+}
+
+impl Announcer {
+    pub fn announce(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+    }
+}
+
+#[derive(Component)]
+struct LiveRegion;
+
+fn setup_live_region(mut commands: Commands) {
+    let mut node = Node::new(Role::Status);
+    node.set_live(Live::Polite);
+    commands.spawn((AccessibilityNode(node), LiveRegion));
+}
+
+fn announce_changes(
+    universe: Res<Universe>,
+    locale: Res<Localization>,
+    mut loaded: MessageReader<PatternLoaded>,
+    mut errors: MessageReader<LifeError>,
+    mut announcer: ResMut<Announcer>,
+) {
+    if announcer.paused != Some(universe.paused) {
+        // Starting up isn't news
+        if announcer.paused.is_some() {
+            let key = if universe.paused {
+                "announce-paused"
+            } else {
+                "announce-running"
+            };
+            announcer.announce(locale.get(key));
+        }
+        announcer.paused = Some(universe.paused);
+    }
+
+    let generation = universe.generation();
+    let milestone = if generation < FIRST_MILESTONE {
+        0
+    } else {
+        10u64.pow(generation.ilog10())
+    };
+    if milestone > announcer.milestone {
+        announcer.announce(locale.format("announce-generation", &[("generation", &milestone)]));
+    }
+    // Going back (clearing, loading) starts the count over
+    if announcer.milestone != milestone {
+        announcer.milestone = milestone;
+    }
+
+    for pattern in loaded.read() {
+        let text = locale.format("announce-loaded", &[("name", &pattern.name)]);
+        announcer.announce(text);
+    }
+    if let Some(err) = errors.read().last() {
+        let text = locale.error(err);
+        announcer.announce(text);
+    }
+}
+
+fn update_live_region(
+    announcer: Res<Announcer>,
+    mut q_region: Query<&mut AccessibilityNode, With<LiveRegion>>,
+) {
+    if !announcer.is_changed() {
+        return;
+    }
+    for mut node in &mut q_region {
+        if node.label() != Some(announcer.text.as_str()) {
+            node.set_label(announcer.text.as_str());
+        }
+    }
+}
@@ -0,0 +1,545 @@
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use bevy::math::I64Vec2;
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+use bevy::tasks::{AsyncComputeTaskPool, Task, block_on};
+
+use crate::simulation::SimulationInput;
+use crate::simulation::batch::arg_value;
+use crate::simulation::codec::{parse_edit, write_edit};
+use crate::simulation::engine::{EngineRegistry, LifeEngine};
+use crate::simulation::error::LifeError;
+use crate::simulation::import::ImportBudget;
+use crate::simulation::macrocell;
+use crate::simulation::macros::Macros;
+use crate::simulation::seed::SimulationRng;
+use crate::simulation::selection::Selection;
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::theme::Theme;
+use crate::simulation::universe::{Edit, Universe, poll_task_once};
+use crate::simulation::view::SimulationView;
+use crate::simulation::workspace::Workspace;
+
+/// How often the whole state is written out, if it changed.
+const SNAPSHOT_INTERVAL: Duration = Duration::from_secs(30);
+
+const SNAPSHOT_FILE: &str = "autosave.workspace";
+const JOURNAL_FILE: &str = "autosave.journal";
+const LOCK_FILE: &str = "lock";
+
+/// Memory a snapshot listing its cells takes per live cell: the list, its copy flipped
+/// for RLE and the RLE itself.
+const SNAPSHOT_BYTES_PER_CELL: u64 = 2 * size_of::<I64Vec2>() as u64 + 2;
+
+/// Keeps the work of a session on disk so a crash loses at most the last few edits of
+/// it. Every [`SNAPSHOT_INTERVAL`] the state is saved as a workspace in the background,
+/// and every edit in between is appended to a journal. Tree engines (HashLife) save
+/// their cells as a macrocell file next to it; other engines skip snapshots whose cell
+/// list wouldn't fit the [`ImportBudget`].
+///
+/// Each running instance keeps its files in a `session-<pid>` directory of
+/// `--autosave-dir DIR` (a `life.rs-autosave` temp directory by default), locked while
+/// it runs and deleted on a clean exit. An unlocked one found at startup belongs to a
+/// session that crashed: Enter restores its snapshot and replays its journal, Escape
+/// discards them. `--no-autosave` turns it off. Desktop only.
+pub struct AutosavePlugin;
+
+impl Plugin for AutosavePlugin {
+    fn build(&self, app: &mut App) {
+        if std::env::args().any(|arg| arg == "--no-autosave") {
+            return;
+        }
+        let root = arg_value("--autosave-dir").map_or_else(
+            || std::env::temp_dir().join("life.rs-autosave"),
+            PathBuf::from,
+        );
+        app.insert_resource(Autosave::new(root))
+            .add_systems(Startup, (lock_session, find_crashed_session).chain())
+            .add_systems(
+                Update,
+                (
+                    recover_session.in_set(SimulationInput),
+                    autosave.run_if(|autosave: Res<Autosave>| autosave.recovery.is_none()),
+                )
+                    .chain(),
+            )
+            .add_systems(Last, remove_on_exit);
+    }
+}
+
+#[derive(Resource)]
+pub struct Autosave {
+    /// Holds the session directory of every instance.
+    pub root: PathBuf,
+    /// This instance's session directory.
+    pub dir: PathBuf,
+    // Held while the instance runs, so others don't take its session for a crashed one
+    lock: Option<File>,
+    /// A crashed session, waiting for Enter (restore) or Escape.
+    recovery: Option<Recovery>,
+    journal: Option<BufWriter<File>>,
+    // Numbers the snapshots, so a journal is never replayed onto a newer snapshot
+    serial: u64,
+    // Generation and revision the snapshot and journal add up to so far
+    generation: u64,
+    revision: u64,
+    saved_at: Option<Instant>,
+    dirty: bool,
+    // Cells were replaced in a way the journal can't hold
+    replaced: bool,
+    // The snapshot being written, which opens the next journal, and the journal text of
+    // the edits made meanwhile
+    writing: Option<Task<Result<BufWriter<File>, LifeError>>>,
+    backlog: String,
+}
+
+impl Autosave {
+    fn new(root: PathBuf) -> Self {
+        Self {
+            dir: root.join(format!("session-{}", std::process::id())),
+            root,
+            lock: None,
+            recovery: None,
+            journal: None,
+            serial: 0,
+            generation: 0,
+            revision: 0,
+            saved_at: None,
+            dirty: false,
+            replaced: false,
+            writing: None,
+            backlog: String::new(),
+        }
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(name)
+    }
+}
+
+/// The files of a session that didn't exit cleanly.
+struct Recovery {
+    dir: PathBuf,
+    snapshot: String,
+    journal: String,
+    // Keeps other instances from offering the same session
+    lock: File,
+}
+
+impl Recovery {
+    /// Deletes the session's files, once restored or declined.
+    fn discard(self) {
+        drop(self.lock);
+        remove_dir(&self.dir);
+    }
+}
+
+/// A snapshot for [`SnapshotJob::write`] to put on disk off the main thread.
+struct SnapshotJob {
+    dir: PathBuf,
+    serial: u64,
+    workspace: Workspace,
+    engine: Box<dyn LifeEngine>,
+    // Center of a tree engine's root, which its macrocell file doesn't keep
+    tree: Option<I64Vec2>,
+}
+
+fn tree_file(serial: u64) -> String {
+    format!("tree-{serial}.mc")
+}
+
+// Claims this instance's session directory for as long as it runs
+fn lock_session(mut autosave: ResMut<Autosave>, mut errors: MessageWriter<LifeError>) {
+    let path = autosave.path(LOCK_FILE);
+    let locked = std::fs::create_dir_all(&autosave.dir)
+        .and_then(|()| File::create(&path))
+        .and_then(|lock| {
+            lock.try_lock()?;
+            Ok(lock)
+        });
+    match locked {
+        Ok(lock) => autosave.lock = Some(lock),
+        Err(err) => {
+            errors.write(LifeError::io(path, err));
+        }
+    }
+}
+
+fn find_crashed_session(mut autosave: ResMut<Autosave>, mut stats: ResMut<StatsBoard>) {
+    let Some(recovery) = crashed_session(&autosave) else {
+        return;
+    };
+    let edits = recovery
+        .journal
+        .lines()
+        .filter(|line| !line.starts_with("gen") && !line.starts_with("snapshot"))
+        .count();
+    stats.insert(
+        "Autosave",
+        format!(
+            "the last session didn't exit cleanly ({edits} edits after the last snapshot): \
+             Enter restores, Esc discards"
+        ),
+    );
+    autosave.recovery = Some(recovery);
+}
+
+/// The newest session directory with a snapshot whose instance is gone, locked for this
+/// one.
+fn crashed_session(autosave: &Autosave) -> Option<Recovery> {
+    let mut sessions: Vec<(SystemTime, PathBuf)> = std::fs::read_dir(&autosave.root)
+        .ok()?
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|dir| *dir != autosave.dir)
+        .filter_map(|dir| {
+            let saved = std::fs::metadata(dir.join(SNAPSHOT_FILE)).and_then(|m| m.modified());
+            Some((saved.ok()?, dir))
+        })
+        .collect();
+    sessions.sort_unstable();
+    sessions.into_iter().rev().find_map(|(_, dir)| {
+        // A running instance holds its lock
+        let lock = File::options().write(true).open(dir.join(LOCK_FILE)).ok()?;
+        lock.try_lock().ok()?;
+        Some(Recovery {
+            snapshot: std::fs::read_to_string(dir.join(SNAPSHOT_FILE)).ok()?,
+            journal: std::fs::read_to_string(dir.join(JOURNAL_FILE)).unwrap_or_default(),
+            dir,
+            lock,
+        })
+    })
+}
+
+// Enter: restore the crashed session, Esc: discard it
+#[allow(clippy::too_many_arguments)]
+fn recover_session(
+    keys: Res<ButtonInput<KeyCode>>,
+    budget: Res<ImportBudget>,
+    registry: Res<EngineRegistry>,
+    mut autosave: ResMut<Autosave>,
+    mut universe: ResMut<Universe>,
+    mut view: ResMut<SimulationView>,
+    mut selection: ResMut<Selection>,
+    mut rng: ResMut<SimulationRng>,
+    mut macros: ResMut<Macros>,
+    mut theme: ResMut<Theme>,
+    mut stats: ResMut<StatsBoard>,
+    mut errors: MessageWriter<LifeError>,
+) {
+    if autosave.recovery.is_none() {
+        return;
+    }
+    if keys.just_pressed(KeyCode::Enter) {
+        let Some(recovery) = autosave.recovery.take() else {
+            return;
+        };
+        let restored =
+            Workspace::parse(&recovery.snapshot, *budget, &registry).and_then(|workspace| {
+                let serial = read_serial(&recovery.snapshot);
+                let tree = match (read_tree_center(&recovery.snapshot), serial) {
+                    (Some(center), Some(serial)) => Some((
+                        center,
+                        macrocell::read_file(&recovery.dir.join(tree_file(serial)))?,
+                    )),
+                    _ => None,
+                };
+                let edits = parse_journal(&recovery.journal, serial, workspace.generation);
+                workspace.restore(
+                    &registry,
+                    &mut universe,
+                    &mut view,
+                    &mut selection,
+                    &mut rng,
+                    &mut macros,
+                    &mut theme,
+                );
+                if let Some((center, text)) = tree {
+                    universe.load_macrocell(&text, center).unwrap_or_else(|| {
+                        Err(LifeError::InvalidPattern(format!(
+                            "{} can't read the autosaved macrocell file",
+                            universe.engine_name()
+                        )))
+                    })?;
+                }
+                Ok(edits)
+            });
+        match restored {
+            Ok(edits) => {
+                let replayed = edits.len();
+                universe.replay(edits);
+                info!("Restored the last session and replayed {replayed} edits");
+                recovery.discard();
+            }
+            Err(err) => {
+                errors.write(err);
+            }
+        }
+    } else if keys.just_pressed(KeyCode::Escape) {
+        if let Some(recovery) = autosave.recovery.take() {
+            recovery.discard();
+        }
+    } else {
+        return;
+    }
+    stats.remove("Autosave");
+}
+
+#[allow(clippy::too_many_arguments)]
+fn autosave(
+    mut autosave: ResMut<Autosave>,
+    mut universe: ResMut<Universe>,
+    budget: Res<ImportBudget>,
+    view: Res<SimulationView>,
+    selection: Res<Selection>,
+    rng: Res<SimulationRng>,
+    macros: Res<Macros>,
+    theme: Res<Theme>,
+    mut errors: MessageWriter<LifeError>,
+) {
+    universe.journaling = true;
+    let edits = universe.take_journal();
+
+    // Each edit bumps the revision once; anything else that did (clears, imports, engine
+    // and rule switches) can't be journaled and needs a new snapshot
+    autosave.replaced |= universe.revision() != autosave.revision + edits.len() as u64;
+    autosave.revision = universe.revision();
+    autosave.dirty |= !edits.is_empty() || universe.generation() != autosave.generation;
+    let text = journal_text(&mut autosave, &edits);
+
+    if let Some(task) = autosave.writing.as_mut() {
+        let Some(written) = poll_task_once(task) else {
+            autosave.backlog.push_str(&text);
+            return;
+        };
+        autosave.writing = None;
+        let backlog = std::mem::take(&mut autosave.backlog);
+        match written {
+            // Cells replaced meanwhile make the backlog useless; a new snapshot follows
+            Ok(journal) => {
+                autosave.journal = Some(journal);
+                if !autosave.replaced {
+                    append_journal(&mut autosave, &backlog, &mut errors);
+                }
+            }
+            Err(err) => {
+                errors.write(err);
+                autosave.dirty = true;
+            }
+        }
+    }
+
+    let due = autosave
+        .saved_at
+        .is_none_or(|at| at.elapsed() >= SNAPSHOT_INTERVAL);
+    if autosave.replaced || (due && autosave.dirty) || autosave.saved_at.is_none() {
+        let workspace =
+            Workspace::capture_settings(&universe, &view, &selection, &rng, &macros, &theme);
+        start_snapshot(&mut autosave, &universe, *budget, workspace);
+        return;
+    }
+    append_journal(&mut autosave, &text, &mut errors);
+}
+
+/// The journal lines of `edits`, with a `gen` line wherever the generation moved on.
+fn journal_text(autosave: &mut Autosave, edits: &[(u64, Edit)]) -> String {
+    let mut text = String::new();
+    for (generation, edit) in edits {
+        if *generation != autosave.generation {
+            let _ = writeln!(text, "gen {generation}");
+            autosave.generation = *generation;
+        }
+        write_edit(&mut text, edit);
+    }
+    text
+}
+
+fn append_journal(autosave: &mut Autosave, text: &str, errors: &mut MessageWriter<LifeError>) {
+    if text.is_empty() {
+        return;
+    }
+    let path = autosave.path(JOURNAL_FILE);
+    let Some(journal) = autosave.journal.as_mut() else {
+        return;
+    };
+    if let Err(err) = journal
+        .write_all(text.as_bytes())
+        .and_then(|()| journal.flush())
+    {
+        errors.write(LifeError::io(path, err));
+        autosave.journal = None;
+    }
+}
+
+/// Starts writing a snapshot of `universe` with the settings in `workspace`. A cell list
+/// that wouldn't fit `budget` is skipped with a warning; the journal then goes on
+/// following the last snapshot, unless the cells were replaced since.
+fn start_snapshot(
+    autosave: &mut Autosave,
+    universe: &Universe,
+    budget: ImportBudget,
+    workspace: Workspace,
+) {
+    autosave.saved_at = Some(Instant::now());
+    autosave.dirty = false;
+    let replaced = std::mem::take(&mut autosave.replaced);
+
+    let engine = universe.fork();
+    let tree = engine
+        .inspect_tree(&[])
+        .map(|root| root.region.min + root.region.size() / 2);
+    let population = universe.population();
+    let estimate = population.saturating_mul(SNAPSHOT_BYTES_PER_CELL);
+    if tree.is_none() && estimate > budget.0 {
+        warn!(
+            "Skipped an autosave snapshot: {population} cells would take about {} MB, over \
+             the {} MB import budget",
+            estimate / 1_000_000,
+            budget.0 / 1_000_000
+        );
+        // Tried again next interval
+        autosave.dirty = true;
+        if replaced {
+            autosave.journal = None;
+        }
+        return;
+    }
+
+    autosave.serial += 1;
+    autosave.generation = universe.generation();
+    autosave.journal = None;
+    let job = SnapshotJob {
+        dir: autosave.dir.clone(),
+        serial: autosave.serial,
+        workspace,
+        engine,
+        tree,
+    };
+    autosave.writing = Some(AsyncComputeTaskPool::get().spawn(async move { job.write() }));
+}
+
+impl SnapshotJob {
+    /// Replaces the snapshot (atomically, by renaming) and starts a new, empty journal.
+    fn write(mut self) -> Result<BufWriter<File>, LifeError> {
+        let _span = info_span!("autosave::write_snapshot", serial = self.serial).entered();
+        std::fs::create_dir_all(&self.dir).map_err(|err| LifeError::io(&self.dir, err))?;
+        let serial = self.serial;
+
+        let mut header = format!("#W autosave {serial}\n");
+        match self.tree {
+            Some(center) => {
+                let path = self.dir.join(tree_file(serial));
+                let mut text = Vec::new();
+                if let Some(written) = self.engine.write_macrocell(&mut text) {
+                    written.map_err(|err| LifeError::io(&path, err))?;
+                }
+                write_atomically(&path, &text)?;
+                let _ = writeln!(header, "#W autosave-tree {} {}", center.x, center.y);
+            }
+            None => self.workspace.cells = self.engine.export(),
+        }
+        let text = format!("{header}{}", self.workspace.to_text());
+        write_atomically(&self.dir.join(SNAPSHOT_FILE), text.as_bytes())?;
+        remove_file(&self.dir.join(tree_file(serial - 1)));
+
+        // Until this is written, the old journal names the old snapshot and is ignored
+        let path = self.dir.join(JOURNAL_FILE);
+        File::create(&path)
+            .map(BufWriter::new)
+            .and_then(|mut journal| {
+                writeln!(journal, "snapshot {serial}")?;
+                journal.flush()?;
+                Ok(journal)
+            })
+            .map_err(|err| LifeError::io(&path, err))
+    }
+}
+
+fn write_atomically(path: &Path, bytes: &[u8]) -> Result<(), LifeError> {
+    let temp = path.with_extension("tmp");
+    std::fs::write(&temp, bytes)
+        .and_then(|()| std::fs::rename(&temp, path))
+        .map_err(|err| LifeError::io(path, err))
+}
+
+fn read_serial(snapshot: &str) -> Option<u64> {
+    snapshot
+        .lines()
+        .find_map(|line| line.strip_prefix("#W autosave "))
+        .and_then(|serial| serial.trim().parse().ok())
+}
+
+fn read_tree_center(snapshot: &str) -> Option<I64Vec2> {
+    let center = snapshot
+        .lines()
+        .find_map(|line| line.strip_prefix("#W autosave-tree "))?;
+    let mut fields = center.split_whitespace().map(str::parse);
+    Some(I64Vec2::new(fields.next()?.ok()?, fields.next()?.ok()?))
+}
+
+/// The edits of a journal written after snapshot `serial` (none if it belongs to another
+/// one), with the generations they were made at; edits before the first `gen` line were
+/// made at `base`, the snapshot's. A line cut off by the crash ends it.
+fn parse_journal(text: &str, serial: Option<u64>, base: u64) -> Vec<(u64, Edit)> {
+    let mut lines = text.lines();
+    let header = lines.next().and_then(|line| line.strip_prefix("snapshot "));
+    if serial.is_none() || header.and_then(|s| s.trim().parse().ok()) != serial {
+        return Vec::new();
+    }
+
+    let mut edits = Vec::new();
+    let mut generation = base;
+    for line in lines {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let edit = match fields.as_slice() {
+            ["gen", value] => match value.parse() {
+                Ok(value) => {
+                    generation = value;
+                    continue;
+                }
+                Err(_) => None,
+            },
+            fields => parse_edit(fields),
+        };
+        let Some(edit) = edit else {
+            warn!("Autosave journal ends in a damaged line, replaying up to it");
+            break;
+        };
+        edits.push((generation, edit));
+    }
+    edits
+}
+
+// A clean exit leaves nothing to recover
+fn remove_on_exit(mut exits: MessageReader<AppExit>, mut autosave: ResMut<Autosave>) {
+    if exits.read().next().is_none() {
+        return;
+    }
+    // A snapshot still being written would land after the removal
+    if let Some(task) = autosave.writing.take() {
+        let _ = block_on(task);
+    }
+    autosave.journal = None;
+    autosave.lock = None;
+    remove_dir(&autosave.dir);
+}
+
+fn remove_file(path: &Path) {
+    if let Err(err) = std::fs::remove_file(path)
+        && err.kind() != std::io::ErrorKind::NotFound
+    {
+        warn!("Couldn't remove {}: {err}", path.display());
+    }
+}
+
+fn remove_dir(dir: &Path) {
+    if let Err(err) = std::fs::remove_dir_all(dir)
+        && err.kind() != std::io::ErrorKind::NotFound
+    {
+        warn!("Couldn't remove {}: {err}", dir.display());
+    }
+}
@@ -0,0 +1,246 @@
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use bevy::math::I64Vec2;
+
+use crate::simulation::engine::{EngineRegistry, LifeEngine, create_rule_engine};
+use crate::simulation::error::LifeError;
+use crate::simulation::import::{ImportBudget, PatternFormat};
+use crate::simulation::period::{MAX_PERIOD, MAX_POPULATION, PeriodDetector, Periodicity};
+use crate::simulation::plaintext;
+use crate::simulation::rules::LifeRule;
+
+/// Generations per pattern when `--generations` is not given.
+const DEFAULT_GENERATIONS: u64 = 1000;
+
+/// Runs every `.rle` and `.cells` pattern in `--batch DIR` for `--generations N` on each
+/// engine that supports its rule and prints a tab-separated report, without opening a
+/// window.
+/// `--hash FILE` does the same for a single pattern and prints only its state hash.
+/// Returns false when neither was requested. Exits with an error status if a pattern
+/// could not be read or the engines disagree on its result.
+pub fn run_from_args() -> bool {
+    if let Some(path) = arg_value("--hash").map(PathBuf::from) {
+        if !print_hash(&path, generations_arg()) {
+            std::process::exit(1);
+        }
+        return true;
+    }
+    let Some(dir) = arg_value("--batch").map(PathBuf::from) else {
+        return false;
+    };
+    let generations = generations_arg();
+
+    let patterns = match pattern_files(&dir) {
+        Ok(patterns) => patterns,
+        Err(err) => {
+            eprintln!("Cannot read patterns: {err}");
+            std::process::exit(2);
+        }
+    };
+
+    println!(
+        "pattern\tengine\tgeneration\tpopulation\tperiod\tbounding box\thash\truntime ms\tagrees"
+    );
+    let mut failures = 0;
+    for path in &patterns {
+        if !run_pattern(path, generations) {
+            failures += 1;
+        }
+    }
+
+    eprintln!("{} patterns, {failures} failed", patterns.len());
+    if failures > 0 {
+        std::process::exit(1);
+    }
+    true
+}
+
+fn generations_arg() -> u64 {
+    match arg_value("--generations").map(|v| v.parse::<u64>()) {
+        None => DEFAULT_GENERATIONS,
+        Some(Ok(n)) => n,
+        Some(Err(err)) => {
+            eprintln!("Invalid --generations: {err}");
+            std::process::exit(2);
+        }
+    }
+}
+
+/// Reads `NAME VALUE` or `NAME=VALUE` from the command line.
+pub(crate) fn arg_value(name: &str) -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == name {
+            return args.next();
+        } else if let Some(value) = arg.strip_prefix(name).and_then(|v| v.strip_prefix('=')) {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
+fn pattern_files(dir: &Path) -> Result<Vec<PathBuf>, LifeError> {
+    let mut files = Vec::new();
+    let entries = std::fs::read_dir(dir).map_err(|err| LifeError::io(dir, err))?;
+    for entry in entries {
+        let path = entry.map_err(|err| LifeError::io(dir, err))?.path();
+        if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("rle"))
+            || plaintext::is_plaintext(&path)
+        {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Runs one pattern on every applicable engine and prints a row each.
+/// Returns false if it could not be read or the engines ended up with different cells.
+fn run_pattern(path: &Path, generations: u64) -> bool {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let engines = match load_pattern(path) {
+        Ok(engines) => engines,
+        Err(err) => {
+            eprintln!("{name}: {err}");
+            return false;
+        }
+    };
+
+    let mut reference: Option<Vec<I64Vec2>> = None;
+    let mut period = None;
+    let mut agree = true;
+    for mut engine in engines {
+        let start = Instant::now();
+        engine.step(generations);
+        let runtime = start.elapsed();
+
+        let mut cells = engine.export();
+        cells.sort_unstable_by_key(|p| (p.y, p.x));
+        let agrees = match &reference {
+            Some(reference) => *reference == cells,
+            None => {
+                // Every engine should agree, so the period is only searched for once
+                period = detect_period(engine.as_ref());
+                true
+            }
+        };
+        agree &= agrees;
+
+        let period = match period {
+            Some(Periodicity {
+                period,
+                displacement,
+            }) if displacement == I64Vec2::ZERO => format!("p{period}"),
+            Some(Periodicity {
+                period,
+                displacement,
+            }) => format!("p{period} ({}, {})", displacement.x, displacement.y),
+            None => "-".to_string(),
+        };
+        let bounds = match (
+            cells.iter().copied().reduce(I64Vec2::min),
+            cells.iter().copied().reduce(I64Vec2::max),
+        ) {
+            (Some(min), Some(max)) => {
+                let size = max - min + I64Vec2::ONE;
+                format!("{}x{} at ({}, {})", size.x, size.y, min.x, min.y)
+            }
+            _ => "empty".to_string(),
+        };
+        println!(
+            "{name}\t{}\t{}\t{}\t{period}\t{bounds}\t{:016x}\t{:.1}\t{}",
+            engine.name(),
+            engine.generation(),
+            engine.population(),
+            engine.state_hash(),
+            runtime.as_secs_f64() * 1000.0,
+            if agrees { "yes" } else { "NO" },
+        );
+
+        reference.get_or_insert(cells);
+    }
+    agree
+}
+
+/// Prints the state hash of one pattern after `generations`, or the hash of every engine
+/// and their names if they disagree. Returns false if they disagree or it can't be read.
+fn print_hash(path: &Path, generations: u64) -> bool {
+    let engines = match load_pattern(path) {
+        Ok(engines) => engines,
+        Err(err) => {
+            eprintln!("{}: {err}", path.display());
+            return false;
+        }
+    };
+
+    let hashes: Vec<(String, u64)> = engines
+        .into_iter()
+        .map(|mut engine| {
+            engine.step(generations);
+            (engine.name().to_string(), engine.state_hash())
+        })
+        .collect();
+    if hashes.iter().all(|(_, hash)| *hash == hashes[0].1) {
+        println!("{:016x}", hashes[0].1);
+        return true;
+    }
+    for (engine, hash) in &hashes {
+        println!("{hash:016x}\t{engine}");
+    }
+    eprintln!("{}: engines disagree", path.display());
+    false
+}
+
+/// Reads a pattern and sets it up on every engine that supports its rule: the bitwise
+/// engines for Conway's rule, plus the rule engine. Refused unless it fits the
+/// [`ImportBudget`] on each of them.
+fn load_pattern(path: &Path) -> Result<Vec<Box<dyn LifeEngine>>, LifeError> {
+    let text = std::fs::read_to_string(path).map_err(|err| LifeError::io(path, err))?;
+    let format = PatternFormat::of(path);
+    let size = format.measure(&text)?;
+
+    let rule = size.rule.unwrap_or(LifeRule::CONWAY);
+    let mut engines: Vec<Box<dyn LifeEngine>> = Vec::new();
+    if rule == LifeRule::CONWAY {
+        // Other rules only run on the rule engine
+        engines.extend(
+            EngineRegistry::with_builtins()
+                .iter()
+                .filter(|entry| entry.exact_conway)
+                .map(|entry| entry.create()),
+        );
+    }
+    engines.push(create_rule_engine(rule));
+    let budget = ImportBudget::default();
+    let name = path.display().to_string();
+    for engine in &engines {
+        budget.check(&size, &name, engine.as_ref(), None)?;
+    }
+
+    let pattern = format.decode(&text)?;
+    for engine in &mut engines {
+        engine.import(&pattern.cells);
+    }
+    Ok(engines)
+}
+
+/// Steps a copy of `engine` until it repeats, up to [`MAX_PERIOD`] generations.
+fn detect_period(engine: &dyn LifeEngine) -> Option<Periodicity> {
+    let mut engine = engine.box_clone();
+    let mut detector = PeriodDetector::default();
+    for _ in 0..=MAX_PERIOD {
+        let cells = engine.export();
+        if cells.len() as u64 > MAX_POPULATION {
+            return None;
+        }
+        detector.observe(engine.generation(), &cells);
+        if detector.detected.is_some() {
+            return detector.detected;
+        }
+        engine.step(1);
+    }
+    None
+}
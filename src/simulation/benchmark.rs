@@ -0,0 +1,125 @@
+use bevy::math::I64Vec2;
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+use bevy::tasks::{AsyncComputeTaskPool, Task};
+
+use crate::simulation::SimulationInput;
+use crate::simulation::engine::LifeEngine;
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::universe::{Universe, poll_task_once};
+
+/// Generations the benchmark runs.
+const GENERATIONS: u64 = 1000;
+/// Generations stepped between population samples.
+const CHUNK: u64 = 10;
+
+/// Acorn: a 7-cell methuselah that keeps growing for about 5000 generations.
+const ACORN: [(i64, i64); 7] = [(1, 0), (3, 1), (0, 2), (1, 2), (4, 2), (5, 2), (6, 2)];
+
+/// F5 runs a fixed workload on a copy of the current engine, off screen,
+/// so engines can be compared on the same machine (Shift+F5 runs it on all of them, see
+/// [`CalibrationPlugin`]).
+///
+/// [`CalibrationPlugin`]: crate::simulation::calibration::CalibrationPlugin
+pub struct BenchmarkPlugin;
+
+impl Plugin for BenchmarkPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Benchmark>().add_systems(
+            Update,
+            (start_benchmark.in_set(SimulationInput), poll_benchmark).chain(),
+        );
+    }
+}
+
+pub(crate) struct BenchmarkResult {
+    pub engine: String,
+    pub seconds: f64,
+    /// Sum of the population over all generations, i.e. live cells updated.
+    pub cell_updates: u64,
+}
+
+impl BenchmarkResult {
+    pub fn generations_per_sec(&self) -> f64 {
+        GENERATIONS as f64 / self.seconds.max(f64::EPSILON)
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Benchmark {
+    task: Option<Task<BenchmarkResult>>,
+}
+
+impl Benchmark {
+    pub fn is_running(&self) -> bool {
+        self.task.is_some()
+    }
+}
+
+// F5: benchmark the current engine
+fn start_benchmark(
+    keys: Res<ButtonInput<KeyCode>>,
+    universe: Res<Universe>,
+    mut benchmark: ResMut<Benchmark>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    // Shift+F5 calibrates every engine instead
+    if !keys.just_pressed(KeyCode::F5)
+        || keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight])
+        || benchmark.is_running()
+    {
+        return;
+    }
+
+    let engine = workload(universe.blank_engine());
+    stats.insert(
+        "Benchmark",
+        format!("running {GENERATIONS} generations of acorn..."),
+    );
+    benchmark.task = Some(AsyncComputeTaskPool::get().spawn(async move { run(engine) }));
+}
+
+/// `engine` set up with the benchmark's starting pattern.
+pub(crate) fn workload(mut engine: Box<dyn LifeEngine>) -> Box<dyn LifeEngine> {
+    // The whole acorn has to run, whatever is selected
+    engine.set_step_region(None);
+    let cells: Vec<I64Vec2> = ACORN.iter().map(|&(x, y)| I64Vec2::new(x, y)).collect();
+    engine.import(&cells);
+    engine
+}
+
+/// Runs the benchmark on `engine`, set up by [`workload`].
+pub(crate) fn run(mut engine: Box<dyn LifeEngine>) -> BenchmarkResult {
+    let _span = info_span!("benchmark::run", engine = engine.name()).entered();
+    let start = Instant::now();
+    let mut cell_updates = 0;
+    for _ in 0..GENERATIONS / CHUNK {
+        cell_updates += engine.population() * CHUNK;
+        engine.step(CHUNK);
+    }
+    BenchmarkResult {
+        engine: engine.name().to_string(),
+        seconds: start.elapsed().as_secs_f64(),
+        cell_updates,
+    }
+}
+
+fn poll_benchmark(mut benchmark: ResMut<Benchmark>, mut stats: ResMut<StatsBoard>) {
+    let Some(task) = benchmark.task.as_mut() else {
+        return;
+    };
+    let Some(result) = poll_task_once(task) else {
+        return;
+    };
+    benchmark.task = None;
+
+    let seconds = result.seconds.max(f64::EPSILON);
+    let summary = format!(
+        "{}: {:.0} gen/s, {:.2}M cells/s",
+        result.engine,
+        result.generations_per_sec(),
+        result.cell_updates as f64 / seconds / 1e6,
+    );
+    info!("Benchmark {summary}");
+    stats.insert("Benchmark", summary);
+}
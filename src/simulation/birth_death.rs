@@ -0,0 +1,225 @@
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::simulation::SimulationInput;
+use crate::simulation::graphics::{
+    LayerCanvas, LayerDesc, LayerSpawner, LayerViewport, PixelLayer,
+};
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::universe::{RuleChanged, Universe};
+use crate::simulation::view::SimulationView;
+
+/// Beyond this many visible cells the overlay switches itself off (cells are sub-pixel anyway).
+const MAX_SAMPLED_CELLS: usize = 1 << 21;
+
+const BORN: u8 = 1;
+const DYING: u8 = 2;
+
+pub struct BirthDeathPlugin;
+
+impl Plugin for BirthDeathPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BirthDeathOverlay>()
+            .add_systems(Startup, setup_birth_death_layer)
+            .add_systems(
+                Update,
+                (
+                    toggle_overlay.in_set(SimulationInput),
+                    reset_on_rule_change,
+                    render_birth_death,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// The visible part of one generation, sampled at one byte per cell.
+struct Snapshot {
+    generation: u64,
+    origin: I64Vec2,
+    width: usize,
+    height: usize,
+    cells: Vec<u8>,
+}
+
+impl Snapshot {
+    fn alive(&self, pos: I64Vec2) -> Option<bool> {
+        let local = pos - self.origin;
+        if local.x < 0 || local.y < 0 {
+            return None;
+        }
+        let (x, y) = (local.x as usize, local.y as usize);
+        (x < self.width && y < self.height).then(|| self.cells[y * self.width + x] > 0)
+    }
+}
+
+/// Colors cells born in the current generation green and cells about to die red.
+#[derive(Resource, Default)]
+pub struct BirthDeathOverlay {
+    pub enabled: bool,
+    current: Option<Snapshot>,
+    previous: Option<Snapshot>,
+}
+
+impl BirthDeathOverlay {
+    fn reset(&mut self) {
+        self.current = None;
+        self.previous = None;
+    }
+
+    /// Keeps the last two distinct generations so births can be diffed out.
+    fn record(&mut self, snapshot: Snapshot) {
+        let advanced = self
+            .current
+            .as_ref()
+            .is_some_and(|c| c.generation != snapshot.generation);
+        if advanced {
+            self.previous = self.current.take();
+        } else if snapshot.generation == 0 {
+            // Cleared or replaced: nothing to diff against
+            self.previous = None;
+        }
+        self.current = Some(snapshot);
+    }
+}
+
+#[derive(Component)]
+struct BirthDeathLayer;
+
+fn setup_birth_death_layer(mut layers: LayerSpawner) {
+    layers
+        .spawn(
+            LayerDesc::new("birth_death", 0.05)
+                .palette([
+                    Vec4::ZERO,
+                    Vec4::new(0.2, 1.0, 0.3, 0.8),
+                    Vec4::new(1.0, 0.2, 0.2, 0.8),
+                ])
+                .hidden(),
+        )
+        .insert(BirthDeathLayer);
+}
+
+// B: toggle the births/deaths overlay
+fn toggle_overlay(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut overlay: ResMut<BirthDeathOverlay>,
+    mut q_layer: Query<&mut PixelLayer, With<BirthDeathLayer>>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    if !keys.just_pressed(KeyCode::KeyB) {
+        return;
+    }
+    overlay.enabled = !overlay.enabled;
+    overlay.reset();
+    for mut layer in &mut q_layer {
+        layer.visible = overlay.enabled;
+    }
+    if !overlay.enabled {
+        stats.remove("Births");
+        stats.remove("Dying");
+    }
+}
+
+// The previous generation ran under another rule, so its diff would be misleading
+fn reset_on_rule_change(
+    mut changed: MessageReader<RuleChanged>,
+    mut overlay: ResMut<BirthDeathOverlay>,
+) {
+    if changed.read().last().is_some() {
+        overlay.reset();
+    }
+}
+
+fn render_birth_death(
+    universe: Res<Universe>,
+    view: Res<SimulationView>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut q_layer: Query<(&PixelLayer, &mut LayerCanvas), With<BirthDeathLayer>>,
+    mut overlay: ResMut<BirthDeathOverlay>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    if !overlay.enabled {
+        return;
+    }
+    let Ok((layer, mut canvas)) = q_layer.single_mut() else {
+        return;
+    };
+    let Ok(window) = q_window.single() else {
+        return;
+    };
+    let Some(viewport) = LayerViewport::new(window, &view, layer) else {
+        return;
+    };
+    let buffer = canvas.buffer(&viewport);
+    buffer.fill(0);
+
+    // Sample the visible cells plus a one cell margin (needed for neighbour counts)
+    let rect = viewport.get_world_rect();
+    let origin = I64Vec2::new(rect.min.x.floor() as i64 - 1, rect.min.y.floor() as i64 - 1);
+    let width = rect.width().ceil() as usize + 3;
+    let height = rect.height().ceil() as usize + 3;
+    if width * height > MAX_SAMPLED_CELLS {
+        stats.insert("Births", "zoom in");
+        stats.remove("Dying");
+        return;
+    }
+
+    let mut cells = vec![0u8; width * height];
+    let sample_rect = Rect::new(
+        origin.x as f32,
+        origin.y as f32,
+        (origin.x + width as i64) as f32,
+        (origin.y + height as i64) as f32,
+    );
+    universe.draw_to_buffer(sample_rect, &mut cells, width, height);
+    overlay.record(Snapshot {
+        generation: universe.generation(),
+        origin,
+        width,
+        height,
+        cells,
+    });
+
+    let Some(current) = overlay.current.as_ref() else {
+        return;
+    };
+    let previous = overlay.previous.as_ref();
+    let rule = universe.rule();
+
+    let (mut births, mut dying) = (0u64, 0u64);
+    for y in 1..height - 1 {
+        for x in 1..width - 1 {
+            if current.cells[y * width + x] == 0 {
+                continue;
+            }
+            let pos = origin + I64Vec2::new(x as i64, y as i64);
+
+            // Survival under the current rule on the sampled neighbourhood
+            let mut neighbours = 0;
+            for dy in 0..3 {
+                for dx in 0..3 {
+                    if (dx, dy) != (1, 1) && current.cells[(y + dy - 1) * width + x + dx - 1] > 0 {
+                        neighbours += 1;
+                    }
+                }
+            }
+
+            let is_dying = !rule.survives(neighbours);
+            let is_born = previous.and_then(|p| p.alive(pos)) == Some(false);
+            births += is_born as u64;
+            dying += is_dying as u64;
+
+            // A cell born only to die right away shows as dying
+            if is_dying {
+                viewport.draw_cell(buffer, pos.x, pos.y, DYING);
+            } else if is_born {
+                viewport.draw_cell(buffer, pos.x, pos.y, BORN);
+            }
+        }
+    }
+
+    stats.insert("Births", births);
+    stats.insert("Dying", dying);
+}
@@ -0,0 +1,115 @@
+//! The web build's stand-in for the files the desktop build keeps: a small key-value
+//! store in IndexedDB. Everything in it is read once before the app starts
+//! ([`preload`]), so [`get`] answers right away; [`put`] updates that copy and writes
+//! through in the background.
+
+use std::collections::BTreeMap;
+use std::sync::{Mutex, PoisonError};
+
+use bevy::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{IdbDatabase, IdbRequest, IdbTransactionMode};
+
+const DATABASE: &str = "life.rs";
+/// Bumped when the object stores change; opening creates the missing ones.
+const DATABASE_VERSION: u32 = 1;
+const STORE: &str = "files";
+
+/// Key of the settings, in the format of the desktop settings file.
+pub const SETTINGS_KEY: &str = "settings";
+/// Key of the workspace saved with Ctrl+Shift+S.
+pub const WORKSPACE_KEY: &str = "workspace";
+
+static STORED: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+
+/// Reads every entry into memory. Call it before building the app; without IndexedDB
+/// (private windows in some browsers) the store stays empty and nothing is kept.
+pub async fn preload() {
+    let loaded = async {
+        let db = open().await?;
+        let store = db.transaction_with_str(STORE)?.object_store(STORE)?;
+        let keys = finish(&store.get_all_keys()?).await?;
+        let values = finish(&store.get_all()?).await?;
+        Ok::<_, JsValue>((js_sys::Array::from(&keys), js_sys::Array::from(&values)))
+    };
+    match loaded.await {
+        Ok((keys, values)) => {
+            let mut stored = STORED.lock().unwrap_or_else(PoisonError::into_inner);
+            for (key, value) in keys.iter().zip(values.iter()) {
+                if let (Some(key), Some(value)) = (key.as_string(), value.as_string()) {
+                    stored.insert(key, value);
+                }
+            }
+        }
+        Err(err) => warn!("Browser storage isn't available: {err:?}"),
+    }
+}
+
+pub fn get(key: &str) -> Option<String> {
+    STORED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(key)
+        .cloned()
+}
+
+/// Stores `value` under `key`. The write finishes in the background; failures are
+/// only logged, like a full disk would be for a file nobody waits on.
+pub fn put(key: &str, value: String) {
+    STORED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(key.to_string(), value.clone());
+
+    let key = key.to_string();
+    wasm_bindgen_futures::spawn_local(async move {
+        let written = async {
+            let db = open().await?;
+            let store = db
+                .transaction_with_str_and_mode(STORE, IdbTransactionMode::Readwrite)?
+                .object_store(STORE)?;
+            let request =
+                store.put_with_key(&JsValue::from_str(&value), &JsValue::from_str(&key))?;
+            finish(&request).await
+        };
+        if let Err(err) = written.await {
+            warn!("Couldn't store {key} in the browser: {err:?}");
+        }
+    });
+}
+
+async fn open() -> Result<IdbDatabase, JsValue> {
+    let factory = web_sys::window()
+        .ok_or("no window")?
+        .indexed_db()?
+        .ok_or("no IndexedDB")?;
+    let request = factory.open_with_u32(DATABASE, DATABASE_VERSION)?;
+
+    // A new or older database gets its store before the open succeeds
+    let upgrading = request.clone();
+    let on_upgrade = Closure::once_into_js(move || {
+        if let Ok(db) = upgrading
+            .result()
+            .and_then(|db| db.dyn_into::<IdbDatabase>())
+            && !db.object_store_names().contains(STORE)
+            && let Err(err) = db.create_object_store(STORE)
+        {
+            warn!("Couldn't create the browser store: {err:?}");
+        }
+    });
+    request.set_onupgradeneeded(Some(on_upgrade.unchecked_ref()));
+
+    finish(&request).await?.dyn_into()
+}
+
+/// Waits for `request` and hands out its result.
+async fn finish(request: &IdbRequest) -> Result<JsValue, JsValue> {
+    let done = js_sys::Promise::new(&mut |resolve, reject| {
+        request.set_onsuccess(Some(&resolve));
+        request.set_onerror(Some(&reject));
+    });
+    JsFuture::from(done).await?;
+    request.result()
+}
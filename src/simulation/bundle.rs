@@ -0,0 +1,426 @@
+use std::path::{Path, PathBuf};
+
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+use bevy::window::{FileDragAndDrop, PrimaryWindow};
+use rustc_hash::FxHashMap;
+
+use crate::simulation::engine::EngineRegistry;
+use crate::simulation::error::LifeError;
+use crate::simulation::import::{ImportBudget, PatternFormat, fit_view};
+use crate::simulation::import_ghost::PendingImport;
+use crate::simulation::rle::PatternSize;
+use crate::simulation::rules::LifeRule;
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::universe::Universe;
+use crate::simulation::view::SimulationView;
+
+/// Largest file unpacked from a bundle; puzzle packs are far smaller.
+const MAX_ENTRY_SIZE: usize = 64 << 20;
+
+/// Opens Golly rule bundles in one drop: a ZIP of `.rule` and pattern files, or a
+/// `.rule` file dropped together with its patterns. The rule is applied first, then the
+/// first pattern (by name) is opened for a click to put down, its header naming the rule
+/// however the bundle calls it. Rule files work when their name is a B/S rulestring or
+/// their `@TABLE` is a two-state Moore table with `permute` symmetry, i.e. Life-like;
+/// other tables and `@TREE`s are refused. Desktop only.
+pub struct BundlePlugin;
+
+impl Plugin for BundlePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, open_dropped_bundles);
+    }
+}
+
+/// A rule and patterns to load together.
+#[derive(Default)]
+struct Bundle {
+    rules: FxHashMap<String, LifeRule>,
+    /// (file name, RLE text), sorted by name.
+    patterns: Vec<(String, String)>,
+}
+
+impl Bundle {
+    fn add(&mut self, name: &str, text: String) -> Result<(), LifeError> {
+        match extension(Path::new(name)).as_deref() {
+            Some("rule") => {
+                let (rule_name, rule) = parse_rule_file(&text)?;
+                self.rules.insert(rule_name.to_ascii_lowercase(), rule);
+            }
+            Some("rle") => self.patterns.push((name.to_string(), text)),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// The rule a pattern's header names: one from the bundle, a rulestring, or the
+    /// bundle's only rule.
+    fn rule_for(&self, header_rule: Option<&str>) -> Option<LifeRule> {
+        let named = header_rule.and_then(|name| {
+            self.rules
+                .get(&name.trim().to_ascii_lowercase())
+                .copied()
+                .or_else(|| LifeRule::parse(name).ok())
+        });
+        named.or_else(|| {
+            let mut rules = self.rules.values();
+            rules.next().copied().filter(|_| rules.next().is_none())
+        })
+    }
+}
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+}
+
+/// Whether a dropped file is opened here rather than as a single pattern: ZIPs, rule
+/// files, and patterns dropped along with a rule file.
+pub fn opens(dropped: &[PathBuf], path: &Path) -> bool {
+    let with_rule = dropped
+        .iter()
+        .any(|path| extension(path).as_deref() == Some("rule"));
+    match extension(path).as_deref() {
+        Some("zip" | "rule") => true,
+        Some("rle") => with_rule,
+        _ => false,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn open_dropped_bundles(
+    mut drops: MessageReader<FileDragAndDrop>,
+    budget: Res<ImportBudget>,
+    registry: Res<EngineRegistry>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut universe: ResMut<Universe>,
+    mut view: ResMut<SimulationView>,
+    mut stats: ResMut<StatsBoard>,
+    mut pending: ResMut<PendingImport>,
+    mut errors: MessageWriter<LifeError>,
+) {
+    let dropped: Vec<PathBuf> = drops
+        .read()
+        .filter_map(|drop| match drop {
+            FileDragAndDrop::DroppedFile { path_buf, .. } => Some(path_buf.clone()),
+            _ => None,
+        })
+        .collect();
+    let paths: Vec<&PathBuf> = dropped
+        .iter()
+        .filter(|path| opens(&dropped, path))
+        .collect();
+    if paths.is_empty() {
+        return;
+    }
+
+    let mut bundle = Bundle::default();
+    for path in paths {
+        if let Err(err) = read_into(&mut bundle, path) {
+            errors.write(err);
+            return;
+        }
+    }
+    bundle.patterns.sort();
+
+    let Some((name, text)) = bundle.patterns.first() else {
+        // Just a rule: apply it to what is there
+        match bundle.rule_for(None) {
+            Some(rule) => {
+                universe.set_rule(rule);
+                stats.insert("Bundle", format!("rule {rule}"));
+            }
+            None => {
+                errors.write(LifeError::InvalidPattern(
+                    "the bundle holds no rule or pattern".to_string(),
+                ));
+            }
+        }
+        return;
+    };
+    let (header_rule, text) = split_header_rule(text);
+    let rule = bundle.rule_for(header_rule.as_deref());
+    // Measured on the engine of the bundle's rule, which the header no longer names
+    let loaded = PatternFormat::Rle.measure(&text).and_then(|size| {
+        let size = PatternSize { rule, ..size };
+        budget.check_in(&size, name, &registry, &universe)?;
+        PatternFormat::Rle.decode(&text)
+    });
+    let pattern = match loaded {
+        Ok(pattern) => pattern,
+        Err(err) => {
+            errors.write(err);
+            return;
+        }
+    };
+
+    // The rule goes first, so the pattern lands on the engine it runs on
+    if let Some(rule) = rule
+        && rule != universe.rule()
+    {
+        universe.set_rule(rule);
+    }
+    // Rows run down in the file and up on screen
+    let cells: Vec<I64Vec2> = pattern
+        .cells
+        .into_iter()
+        .map(|p| I64Vec2::new(p.x, -p.y))
+        .collect();
+    if let Ok(window) = q_window.single()
+        && let Some(fitted) = fit_view(&cells, window)
+    {
+        *view = fitted;
+    }
+    pending.hold(name.clone(), cells, None);
+
+    let others = bundle.patterns.len() - 1;
+    info!(
+        "Opened {name} with rule {} ({others} more patterns in the bundle)",
+        universe.rule()
+    );
+    stats.insert(
+        "Bundle",
+        match others {
+            0 => format!("{name}, rule {}", universe.rule()),
+            n => format!("{name}, rule {} ({n} more patterns)", universe.rule()),
+        },
+    );
+}
+
+fn read_into(bundle: &mut Bundle, path: &Path) -> Result<(), LifeError> {
+    let bytes = std::fs::read(path).map_err(|err| LifeError::io(path, err))?;
+    if extension(path).as_deref() != Some("zip") {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        return bundle.add(&name, String::from_utf8_lossy(&bytes).into_owned());
+    }
+    for (name, data) in read_zip(&bytes)? {
+        // Golly bundles keep patterns and rules in folders; only the file name matters
+        let name = name.rsplit('/').next().unwrap_or(&name).to_string();
+        bundle.add(&name, String::from_utf8_lossy(&data).into_owned())?;
+    }
+    Ok(())
+}
+
+/// Takes the rule out of an RLE header, so names only the bundle knows don't fail to
+/// parse, returning it with the rest of the text.
+fn split_header_rule(text: &str) -> (Option<String>, String) {
+    let mut rule = None;
+    let mut out = String::with_capacity(text.len());
+    let mut seen_header = false;
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if !seen_header && trimmed.starts_with('x') {
+            seen_header = true;
+            let fields: Vec<&str> = trimmed
+                .split(',')
+                .filter(|field| match field.split_once('=') {
+                    Some((key, value)) if key.trim() == "rule" => {
+                        rule = Some(value.trim().to_string());
+                        false
+                    }
+                    _ => true,
+                })
+                .collect();
+            out.push_str(&fields.join(","));
+        } else {
+            out.push_str(line);
+        }
+        out.push('\n');
+    }
+    (rule, out)
+}
+
+/// Reads a Golly `.rule` file into its name and rule. The name is used directly if it
+/// is a rulestring; otherwise the `@TABLE` is evaluated for every neighbour count.
+pub fn parse_rule_file(text: &str) -> Result<(String, LifeRule), LifeError> {
+    let error = |reason: String| LifeError::InvalidRule(format!(".rule file: {reason}"));
+    let name = text
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("@RULE"))
+        .map(|name| name.trim().to_string())
+        .ok_or_else(|| error("no @RULE line".to_string()))?;
+    if let Ok(rule) = LifeRule::parse(&name) {
+        return Ok((name, rule));
+    }
+
+    let table: Vec<&str> = text
+        .lines()
+        .skip_while(|line| !line.trim().starts_with("@TABLE"))
+        .skip(1)
+        .take_while(|line| !line.trim().starts_with('@'))
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .collect();
+    if table.is_empty() {
+        return Err(error(format!(
+            "{name} has no @TABLE; only Life-like tables are supported"
+        )));
+    }
+
+    let mut variables: FxHashMap<&str, Vec<u8>> = FxHashMap::default();
+    // New state per (state, live neighbours), from the first transition that matches
+    let mut next: [[Option<u8>; 9]; 2] = [[None; 9]; 2];
+    for line in table {
+        if let Some((key, value)) = line.split_once(':') {
+            let value = value.trim();
+            let supported = match key.trim() {
+                "n_states" => value == "2",
+                "neighborhood" => value == "Moore",
+                "symmetries" => value == "permute",
+                _ => true,
+            };
+            if !supported {
+                return Err(error(format!(
+                    "{name} has {} {value}; only two-state Moore tables with permute \
+                     symmetry are supported",
+                    key.trim()
+                )));
+            }
+            continue;
+        }
+        if let Some(definition) = line.strip_prefix("var ") {
+            let (var, values) = definition
+                .split_once('=')
+                .ok_or_else(|| error(format!("invalid variable '{line}'")))?;
+            let values = values
+                .trim()
+                .trim_start_matches('{')
+                .trim_end_matches('}')
+                .split(',')
+                .map(|value| {
+                    let value = value.trim();
+                    value
+                        .parse()
+                        .ok()
+                        .map(|state| vec![state])
+                        .or_else(|| variables.get(value).cloned())
+                        .ok_or_else(|| error(format!("invalid variable '{line}'")))
+                })
+                .collect::<Result<Vec<Vec<u8>>, _>>()?
+                .concat();
+            variables.insert(var.trim(), values);
+            continue;
+        }
+
+        let fields: Vec<&str> = if line.contains(',') {
+            line.split(',').map(str::trim).collect()
+        } else if line.contains(char::is_whitespace) {
+            line.split_whitespace().collect()
+        } else {
+            // Compact form: one digit per state
+            line.split("").filter(|digit| !digit.is_empty()).collect()
+        };
+        if fields.len() != 10 {
+            return Err(error(format!("transition '{line}' needs 10 states")));
+        }
+        apply_transition(&fields, &variables, &mut next)
+            .map_err(|field| error(format!("unknown state '{field}' in '{line}'")))?;
+    }
+
+    // Cells no transition matches keep their state
+    let counts = |state: usize| {
+        (0..=8)
+            .filter(|&count| next[state][count].unwrap_or(state as u8) == 1)
+            .map(|count| count.to_string())
+            .collect::<String>()
+    };
+    let rule = LifeRule::parse(&format!("B{}/S{}", counts(0), counts(1)))?;
+    Ok((name, rule))
+}
+
+/// Fills in the (state, count) pairs a transition decides that no earlier one did.
+/// Variables are bound: the same name takes the same value throughout the transition.
+fn apply_transition<'a>(
+    fields: &[&'a str],
+    variables: &FxHashMap<&str, Vec<u8>>,
+    next: &mut [[Option<u8>; 9]; 2],
+) -> Result<(), &'a str> {
+    let mut bound: Vec<(&str, &[u8])> = Vec::new();
+    for &field in fields {
+        if field.parse::<u8>().is_ok() || bound.iter().any(|(name, _)| *name == field) {
+            continue;
+        }
+        let values = variables.get(field).ok_or(field)?;
+        bound.push((field, values.as_slice()));
+    }
+
+    let combinations: usize = bound.iter().map(|(_, values)| values.len()).product();
+    for mut index in 0..combinations {
+        let mut assignment: FxHashMap<&str, u8> = FxHashMap::default();
+        for (name, values) in &bound {
+            assignment.insert(*name, values[index % values.len()]);
+            index /= values.len();
+        }
+        let value = |field: &str| field.parse().unwrap_or_else(|_| assignment[field]);
+        let state = value(fields[0]);
+        let count = fields[1..9].iter().filter(|&&f| value(f) == 1).count();
+        if state > 1 {
+            continue;
+        }
+        let slot = &mut next[state as usize][count];
+        if slot.is_none() {
+            *slot = Some(value(fields[9]));
+        }
+    }
+    Ok(())
+}
+
+/// The files in a ZIP archive, stored or deflated, as (path, contents).
+fn read_zip(bytes: &[u8]) -> Result<Vec<(String, Vec<u8>)>, LifeError> {
+    let error = |reason: &str| LifeError::InvalidPattern(format!("zip: {reason}"));
+    let u16_at = |at: usize| {
+        bytes
+            .get(at..at + 2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]) as usize)
+            .ok_or_else(|| error("truncated"))
+    };
+    let u32_at = |at: usize| {
+        bytes
+            .get(at..at + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
+            .ok_or_else(|| error("truncated"))
+    };
+
+    // The end of central directory record sits in the last 64 KB (after the comment)
+    let search_from = bytes.len().saturating_sub(22 + 0xFFFF);
+    let end = (search_from..bytes.len().saturating_sub(21))
+        .rev()
+        .find(|&at| bytes[at..at + 4] == [0x50, 0x4B, 0x05, 0x06])
+        .ok_or_else(|| error("not a ZIP archive"))?;
+    let entries = u16_at(end + 10)?;
+    let mut at = u32_at(end + 16)?;
+
+    let mut files = Vec::new();
+    for _ in 0..entries {
+        if u32_at(at)? != 0x0201_4B50 {
+            return Err(error("damaged central directory"));
+        }
+        let method = u16_at(at + 10)?;
+        let compressed = u32_at(at + 20)?;
+        let name_len = u16_at(at + 28)?;
+        let extra_len = u16_at(at + 30)?;
+        let comment_len = u16_at(at + 32)?;
+        let local = u32_at(at + 42)?;
+        let name = bytes
+            .get(at + 46..at + 46 + name_len)
+            .map(|name| String::from_utf8_lossy(name).into_owned())
+            .ok_or_else(|| error("truncated"))?;
+        at += 46 + name_len + extra_len + comment_len;
+        if name.ends_with('/') {
+            continue;
+        }
+
+        let data_start = local + 30 + u16_at(local + 26)? + u16_at(local + 28)?;
+        let data = bytes
+            .get(data_start..data_start + compressed)
+            .ok_or_else(|| error("truncated"))?;
+        let contents = match method {
+            0 => data.to_vec(),
+            8 => miniz_oxide::inflate::decompress_to_vec_with_limit(data, MAX_ENTRY_SIZE)
+                .map_err(|_| error(&format!("can't inflate {name}")))?,
+            _ => return Err(error(&format!("{name} uses an unsupported compression"))),
+        };
+        files.push((name, contents));
+    }
+    Ok(files)
+}
@@ -0,0 +1,139 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use bevy::tasks::{AsyncComputeTaskPool, Task};
+
+use crate::simulation::SimulationInput;
+use crate::simulation::benchmark::{self, Benchmark};
+use crate::simulation::engine::EngineRegistry;
+use crate::simulation::error::LifeError;
+use crate::simulation::settings::Settings;
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::universe::poll_task_once;
+
+/// Settings key of an engine's measured speed, followed by the engine id.
+const SETTINGS_PREFIX: &str = "calibration-";
+
+/// Measures how fast each exact Conway engine runs on this machine, with the benchmark
+/// workload (see [`BenchmarkPlugin`]), one engine after another in the background. Runs
+/// on the first launch and again with Shift+F5; the speeds are kept in the settings, and
+/// turbo starts out with steps sized from them. The web build only calibrates on demand,
+/// as measuring would stall the page's single thread.
+///
+/// [`BenchmarkPlugin`]: crate::simulation::benchmark::BenchmarkPlugin
+pub struct CalibrationPlugin;
+
+impl Plugin for CalibrationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Calibration>()
+            .add_systems(Startup, load_calibration)
+            .add_systems(
+                Update,
+                (start_calibration.in_set(SimulationInput), poll_calibration).chain(),
+            );
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Calibration {
+    /// Generations per second through the benchmark workload, by engine id.
+    pub speeds: BTreeMap<String, f64>,
+    task: Option<Task<Vec<(String, f64)>>>,
+}
+
+impl Calibration {
+    /// Generations `engine` gets through in about `millis`, if it was calibrated.
+    pub fn generations_in(&self, engine: &str, millis: f64) -> Option<u64> {
+        let speed = self.speeds.get(engine)?;
+        Some((speed * millis / 1000.0).max(1.0) as u64)
+    }
+
+    fn start(&mut self, registry: &EngineRegistry, stats: &mut StatsBoard) {
+        let engines: Vec<_> = registry
+            .iter()
+            .filter(|entry| entry.exact_conway)
+            .map(|entry| (entry.id.clone(), benchmark::workload(entry.create())))
+            .collect();
+        stats.insert(
+            "Calibration",
+            format!("measuring {} engines...", engines.len()),
+        );
+        self.task = Some(AsyncComputeTaskPool::get().spawn(async move {
+            engines
+                .into_iter()
+                .map(|(id, engine)| (id, benchmark::run(engine).generations_per_sec()))
+                .collect()
+        }));
+    }
+}
+
+// Reads the saved speeds, and measures them if some engine has none yet
+fn load_calibration(
+    settings: Res<Settings>,
+    registry: Res<EngineRegistry>,
+    mut calibration: ResMut<Calibration>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    let mut missing = false;
+    for entry in registry.iter().filter(|entry| entry.exact_conway) {
+        let key = format!("{SETTINGS_PREFIX}{}", entry.id);
+        match settings.get(&key).and_then(|value| value.parse().ok()) {
+            Some(speed) => {
+                calibration.speeds.insert(entry.id.clone(), speed);
+            }
+            None => missing = true,
+        }
+    }
+    if missing && cfg!(not(target_arch = "wasm32")) {
+        calibration.start(&registry, &mut stats);
+    }
+}
+
+// Shift+F5: calibrate every engine again
+fn start_calibration(
+    keys: Res<ButtonInput<KeyCode>>,
+    registry: Res<EngineRegistry>,
+    benchmark: Res<Benchmark>,
+    mut calibration: ResMut<Calibration>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    if keys.just_pressed(KeyCode::F5)
+        && keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight])
+        && calibration.task.is_none()
+        && !benchmark.is_running()
+    {
+        calibration.start(&registry, &mut stats);
+    }
+}
+
+fn poll_calibration(
+    registry: Res<EngineRegistry>,
+    mut calibration: ResMut<Calibration>,
+    mut settings: ResMut<Settings>,
+    mut stats: ResMut<StatsBoard>,
+    mut errors: MessageWriter<LifeError>,
+) {
+    let Some(task) = calibration.task.as_mut() else {
+        return;
+    };
+    let Some(speeds) = poll_task_once(task) else {
+        return;
+    };
+    calibration.task = None;
+
+    for (id, speed) in &speeds {
+        info!("Calibrated {id}: {speed:.0} gen/s");
+        calibration.speeds.insert(id.clone(), *speed);
+        if let Err(err) = settings.set(&format!("{SETTINGS_PREFIX}{id}"), format!("{speed:.0}")) {
+            errors.write(err);
+        }
+    }
+    let fastest = speeds
+        .iter()
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .and_then(|(id, _)| registry.get(id));
+    match fastest {
+        Some(entry) => stats.insert("Calibration", format!("fastest is {}", entry.name)),
+        None => stats.remove("Calibration"),
+    }
+}
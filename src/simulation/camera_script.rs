@@ -0,0 +1,206 @@
+use bevy::math::DVec2;
+use bevy::prelude::*;
+
+use crate::simulation::accessibility::Accessibility;
+use crate::simulation::error::LifeError;
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::universe::Universe;
+use crate::simulation::view::SimulationView;
+
+/// Drives the view from waypoints stored in a pattern's comments, LifeViewer style:
+///
+/// ```text
+/// #C [[ ZOOM 8 GEN 500 X 40 Y -20 GEN 1000 ZOOM 2 ]]
+/// ```
+///
+/// `GEN n` starts a waypoint; `X`/`Y` (cells from the pattern's top-left corner,
+/// y pointing down) and `ZOOM` (pixels per cell) set where the view should be by then.
+/// Unset values carry over from the previous waypoint. In between, the view pans
+/// linearly and zooms geometrically, or, with reduced motion, cuts to each waypoint.
+///
+/// The script comes with a pattern opened from a file (see `ImportPlugin`) and starts
+/// once the pattern is put down.
+pub struct CameraScriptPlugin;
+
+impl Plugin for CameraScriptPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CameraScript>()
+            .add_systems(Update, run_camera_script);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Waypoint {
+    pub generation: u64,
+    pub x: Option<f64>,
+    pub y: Option<f64>,
+    pub zoom: Option<f64>,
+}
+
+impl Waypoint {
+    fn at(generation: u64) -> Self {
+        Self {
+            generation,
+            x: None,
+            y: None,
+            zoom: None,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.x.is_none() && self.y.is_none() && self.zoom.is_none()
+    }
+}
+
+/// The waypoints of the loaded pattern, sorted by generation.
+/// Stops once the last one is reached, handing the view back to the user.
+#[derive(Resource, Default)]
+pub struct CameraScript {
+    pub waypoints: Vec<Waypoint>,
+    pub active: bool,
+    /// Generation the pattern is meant to be run to (LifeViewer's `STOP`).
+    pub stop: Option<u64>,
+    // The view when the script took over, for values no waypoint sets
+    start: Option<SimulationView>,
+}
+
+impl CameraScript {
+    /// Reads every `[[ ... ]]` block in `comments`; unknown keywords are skipped.
+    pub fn parse(comments: &[String]) -> Result<Self, LifeError> {
+        let text = comments.join(" ");
+        let mut waypoints = Vec::new();
+        let mut current = Waypoint::at(0);
+        let mut stop = None;
+
+        for block in text.split("[[").skip(1) {
+            let Some((block, _)) = block.split_once("]]") else {
+                return Err(script_error("unclosed [[ block"));
+            };
+            let mut tokens = block.split_whitespace();
+            while let Some(token) = tokens.next() {
+                let mut value = || {
+                    tokens
+                        .next()
+                        .ok_or_else(|| script_error(&format!("{token} needs a value")))
+                };
+                match token.to_ascii_uppercase().as_str() {
+                    "GEN" => {
+                        let generation = parse_value(token, value()?)?;
+                        if !current.is_empty() {
+                            waypoints.push(current);
+                        }
+                        current = Waypoint::at(generation);
+                    }
+                    "X" => current.x = Some(parse_value(token, value()?)?),
+                    "Y" => current.y = Some(parse_value(token, value()?)?),
+                    "ZOOM" => {
+                        let zoom: f64 = parse_value(token, value()?)?;
+                        if zoom <= 0.0 {
+                            return Err(script_error("ZOOM must be positive"));
+                        }
+                        current.zoom = Some(zoom);
+                    }
+                    "STOP" => stop = Some(parse_value(token, value()?)?),
+                    _ => {}
+                }
+            }
+        }
+        if !current.is_empty() {
+            waypoints.push(current);
+        }
+        waypoints.sort_by_key(|w| w.generation);
+
+        Ok(Self {
+            active: !waypoints.is_empty(),
+            waypoints,
+            stop,
+            start: None,
+        })
+    }
+
+    /// Moves the waypoints along with a pattern put down `offset` cells (y up) away from
+    /// where its file has it.
+    pub fn translate(&mut self, offset: bevy::math::I64Vec2) {
+        for waypoint in &mut self.waypoints {
+            waypoint.x = waypoint.x.map(|x| x + offset.x as f64);
+            // Rows run down in the file
+            waypoint.y = waypoint.y.map(|y| y - offset.y as f64);
+        }
+    }
+
+    /// Where the view should be at `generation`, starting from `start`; gliding between
+    /// waypoints or, if not, cutting to each one once it is reached.
+    fn view_at(&self, generation: u64, start: SimulationView, glide: bool) -> SimulationView {
+        // Fill in values that carry over, then find the surrounding pair
+        let mut resolved = Vec::with_capacity(self.waypoints.len());
+        let mut last = start;
+        for waypoint in &self.waypoints {
+            last = SimulationView {
+                // Rows run down in the file and up on screen
+                center: DVec2::new(
+                    waypoint.x.unwrap_or(last.center.x),
+                    waypoint.y.map_or(last.center.y, |y| -y),
+                ),
+                zoom: waypoint.zoom.unwrap_or(last.zoom),
+            };
+            resolved.push((waypoint.generation, last));
+        }
+
+        let next = resolved.partition_point(|&(g, _)| g <= generation);
+        match (next.checked_sub(1).map(|i| resolved[i]), resolved.get(next)) {
+            (Some((from_gen, from)), Some(&(to_gen, to))) if glide => {
+                let t = (generation - from_gen) as f64 / (to_gen - from_gen) as f64;
+                SimulationView {
+                    center: from.center.lerp(to.center, t),
+                    zoom: from.zoom * (to.zoom / from.zoom).powf(t),
+                }
+            }
+            (Some((_, from)), _) => from,
+            // Before the first waypoint: stay put
+            (None, _) => start,
+        }
+    }
+}
+
+fn parse_value<T: std::str::FromStr>(keyword: &str, value: &str) -> Result<T, LifeError> {
+    value
+        .parse()
+        .map_err(|_| script_error(&format!("invalid {keyword} value '{value}'")))
+}
+
+fn script_error(reason: &str) -> LifeError {
+    LifeError::InvalidPattern(format!("camera script: {reason}"))
+}
+
+fn run_camera_script(
+    accessibility: Res<Accessibility>,
+    mut script: ResMut<CameraScript>,
+    universe: Res<Universe>,
+    mut view: ResMut<SimulationView>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    if !script.active {
+        return;
+    }
+
+    let generation = universe.generation();
+    let start = *script.start.get_or_insert(*view);
+    let target = script.view_at(generation, start, !accessibility.reduced_motion);
+    if target.center != view.center || target.zoom != view.zoom {
+        *view = target;
+    }
+
+    let last = script.waypoints.last().map_or(0, |w| w.generation);
+    if generation >= last {
+        script.active = false;
+        stats.remove("Camera Script");
+    } else {
+        stats.insert(
+            "Camera Script",
+            format!(
+                "{} waypoints, until generation {last}",
+                script.waypoints.len()
+            ),
+        );
+    }
+}
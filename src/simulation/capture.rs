@@ -0,0 +1,124 @@
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+use bevy::render::view::screenshot::{Screenshot, save_to_disk};
+
+use crate::simulation::error::LifeError;
+use crate::simulation::period::PeriodDetector;
+use crate::simulation::seed::SimulationRng;
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::universe::Universe;
+use crate::simulation::{plaintext, rle};
+
+/// Ash that fits in a square this many cells wide is saved as plaintext too.
+const PLAINTEXT_SIZE: i64 = 100;
+
+/// Saves a screenshot, the final ash as RLE (and as plaintext `.cells` when it is small)
+/// and a stats summary whenever the universe stabilizes, so runs can be left unattended.
+/// Enabled with `--capture-dir DIR`; desktop only.
+pub struct AutoCapturePlugin;
+
+impl Plugin for AutoCapturePlugin {
+    fn build(&self, app: &mut App) {
+        let Some(dir) = capture_dir_from_args() else {
+            return;
+        };
+        info!("Capturing stabilized universes to {}", dir.display());
+        app.insert_resource(AutoCapture {
+            dir,
+            captured: false,
+            count: 0,
+        })
+        .add_systems(Update, capture_on_stabilization);
+    }
+}
+
+#[derive(Resource)]
+pub struct AutoCapture {
+    pub dir: PathBuf,
+    // Already saved the current stable state
+    captured: bool,
+    pub count: u64,
+}
+
+/// Reads `--capture-dir DIR` or `--capture-dir=DIR` from the command line.
+fn capture_dir_from_args() -> Option<PathBuf> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--capture-dir" {
+            return args.next().map(PathBuf::from);
+        } else if let Some(value) = arg.strip_prefix("--capture-dir=") {
+            return Some(PathBuf::from(value));
+        }
+    }
+    None
+}
+
+fn capture_on_stabilization(
+    mut commands: Commands,
+    mut capture: ResMut<AutoCapture>,
+    detector: Res<PeriodDetector>,
+    universe: Res<Universe>,
+    rng: Res<SimulationRng>,
+    mut stats: ResMut<StatsBoard>,
+    mut errors: MessageWriter<LifeError>,
+) {
+    // Moving patterns never settle into ash; only a standing period counts
+    let Some(periodicity) = detector
+        .detected
+        .filter(|d| d.displacement == I64Vec2::ZERO)
+    else {
+        capture.captured = false;
+        return;
+    };
+    if capture.captured {
+        return;
+    }
+    capture.captured = true;
+
+    let generation = universe.generation();
+    let stem = format!("seed{}-gen{generation}", rng.seed());
+    if let Err(err) = std::fs::create_dir_all(&capture.dir) {
+        errors.write(LifeError::io(&capture.dir, err));
+        return;
+    }
+
+    let cells = universe.read_engine().export();
+    let mut summary = format!(
+        "seed: {}\nrule: {}\ngeneration: {generation}\npopulation: {}\nperiod: {}\n",
+        rng.seed(),
+        universe.rule(),
+        cells.len(),
+        periodicity.period,
+    );
+    for (key, value) in stats.iter() {
+        let _ = writeln!(summary, "{key}: {value}");
+    }
+
+    let mut files = vec![(
+        format!("{stem}.rle"),
+        rle::encode(&cells, universe.rule(), &summary),
+    )];
+    if let Some(min) = cells.iter().copied().reduce(I64Vec2::min) {
+        let max = cells.iter().copied().reduce(I64Vec2::max).unwrap_or(min);
+        if (max - min).max_element() < PLAINTEXT_SIZE {
+            files.push((format!("{stem}.cells"), plaintext::encode(&cells, &summary)));
+        }
+    }
+    files.push((format!("{stem}.txt"), summary));
+    for (name, contents) in files {
+        let path = capture.dir.join(name);
+        if let Err(err) = std::fs::write(&path, contents) {
+            errors.write(LifeError::io(path, err));
+        }
+    }
+    commands
+        .spawn(Screenshot::primary_window())
+        .observe(save_to_disk(capture.dir.join(format!("{stem}.png"))));
+
+    capture.count += 1;
+    info!("Stabilized at generation {generation}, saved {stem}");
+    stats.insert("Captures", capture.count);
+}
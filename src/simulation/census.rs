@@ -0,0 +1,569 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use bevy::math::I64Vec2;
+use rand::Rng;
+use rand::distr::Alphanumeric;
+use rustc_hash::FxHashSet;
+
+use crate::simulation::batch::arg_value;
+use crate::simulation::engine::{LifeEngine, create_rule_engine, default_engine};
+use crate::simulation::error::LifeError;
+use crate::simulation::period::{MAX_PERIOD, PeriodDetector, Periodicity};
+use crate::simulation::rules::LifeRule;
+
+/// Soups are 16×16, the only size Catagolue's C1 symmetry knows.
+const SOUP_SIZE: i64 = 16;
+/// Soups that haven't settled by then are counted as `PATHOLOGICAL`.
+const MAX_GENERATIONS: u64 = 40_000;
+/// Generations the population has to repeat over to call a soup settled; escaping
+/// gliders and other spaceships keep it constant.
+const SETTLE_WINDOW: usize = 300;
+/// Longest population period looked for while settling.
+const MAX_POPULATION_PERIOD: usize = 60;
+/// Soups kept as examples of each object.
+const SAMPLE_SOUPS: usize = 10;
+const CATAGOLUE_URL: &str = "https://catagolue.hatsya.com";
+
+/// `--soup-search N` runs N random soups without opening a window and prints the census
+/// of what they leave behind: the apgcode of every object with how often it turned up.
+/// Soups are seeded the way apgsearch does, from the SHA-256 of `--soup-root ROOT` (a
+/// random `k_…` root by default) followed by the soup number, so Catagolue can replay
+/// them. `--soup-rule RULE` picks the rule, B3/S23 by default.
+///
+/// With `--catagolue-key KEY`, the census is then submitted to Catagolue as a C1 haul
+/// (with `curl`, so it has to be installed), making this a lightweight distributed-search
+/// client. Objects are told apart by distance, splitting clusters whose parts evolve
+/// independently; pseudo-objects that interact are counted as one. Returns false when no
+/// search was requested.
+pub fn run_from_args() -> bool {
+    let Some(count) = arg_value("--soup-search") else {
+        return false;
+    };
+    let count = match count.parse::<u64>() {
+        Ok(count) => count,
+        Err(err) => {
+            eprintln!("Invalid --soup-search: {err}");
+            std::process::exit(2);
+        }
+    };
+    let rule = match arg_value("--soup-rule").map(|rule| LifeRule::parse(&rule)) {
+        None => LifeRule::CONWAY,
+        Some(Ok(rule)) => rule,
+        Some(Err(err)) => {
+            eprintln!("{err}");
+            std::process::exit(2);
+        }
+    };
+    let root = arg_value("--soup-root").unwrap_or_else(|| {
+        let suffix: String = rand::rng()
+            .sample_iter(Alphanumeric)
+            .take(12)
+            .map(char::from)
+            .collect();
+        format!("k_{suffix}")
+    });
+
+    let mut census = Census::default();
+    for number in 0..count {
+        let soup = format!("{root}{number}");
+        for object in search_soup(&soup, rule) {
+            census.record(object, &soup);
+        }
+        if (number + 1) % 1000 == 0 {
+            eprintln!("{} soups, {} objects", number + 1, census.objects());
+        }
+    }
+
+    println!("apgcode\tcount\tsample soup");
+    for (apgcode, count, samples) in census.sorted() {
+        println!("{apgcode}\t{count}\t{}", samples[0]);
+    }
+    eprintln!(
+        "{count} soups from root {root}, {} objects",
+        census.objects()
+    );
+
+    let Some(key) = arg_value("--catagolue-key") else {
+        return true;
+    };
+    let url = arg_value("--catagolue-url").unwrap_or_else(|| CATAGOLUE_URL.to_string());
+    let haul = census.haul(&root, rule, count);
+    match submit(&url, &key, &haul) {
+        Ok(response) => eprintln!("Submitted the census to {url}: {}", response.trim()),
+        Err(err) => {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }
+    }
+    true
+}
+
+#[derive(Default)]
+struct Census {
+    /// apgcode -> (count, sample soups)
+    counts: BTreeMap<String, (u64, Vec<String>)>,
+}
+
+impl Census {
+    fn record(&mut self, apgcode: String, soup: &str) {
+        let (count, samples) = self.counts.entry(apgcode).or_default();
+        *count += 1;
+        if samples.len() < SAMPLE_SOUPS {
+            samples.push(soup.to_string());
+        }
+    }
+
+    fn objects(&self) -> u64 {
+        self.counts.values().map(|(count, _)| count).sum()
+    }
+
+    /// Objects by count, most common first.
+    fn sorted(&self) -> Vec<(&str, u64, &[String])> {
+        let mut sorted: Vec<_> = self
+            .counts
+            .iter()
+            .map(|(apgcode, (count, samples))| (apgcode.as_str(), *count, samples.as_slice()))
+            .collect();
+        sorted.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        sorted
+    }
+
+    /// The census as an apgsearch haul, without the payment line.
+    fn haul(&self, root: &str, rule: LifeRule, soups: u64) -> String {
+        let rule = rule.to_string().to_ascii_lowercase().replace('/', "");
+        let mut haul = format!(
+            "@VERSION life.rs-{}\n@MD5 {}\n@ROOT {root}\n@RULE {rule}\n@SYMMETRY C1\n\
+             @NUM_SOUPS {soups}\n@NUM_OBJECTS {}\n\n@CENSUS TABLE\n",
+            env!("CARGO_PKG_VERSION"),
+            hex(&md5(root.as_bytes())),
+            self.objects(),
+        );
+        let sorted = self.sorted();
+        for (apgcode, count, _) in &sorted {
+            haul.push_str(&format!("{apgcode} {count}\n"));
+        }
+        haul.push_str("\n@SAMPLE_SOUPIDS\n");
+        for (apgcode, _, samples) in &sorted {
+            haul.push_str(&format!("{apgcode} {}\n", samples.join(" ")));
+        }
+        haul
+    }
+}
+
+fn new_engine(rule: LifeRule) -> Box<dyn LifeEngine> {
+    if rule == LifeRule::CONWAY {
+        default_engine()
+    } else {
+        create_rule_engine(rule)
+    }
+}
+
+/// The cells of soup `id`: the bits of its SHA-256, two bytes per row.
+fn hash_soup(id: &str) -> Vec<I64Vec2> {
+    let digest = sha256(id.as_bytes());
+    let mut cells = Vec::new();
+    for (j, byte) in digest.iter().enumerate() {
+        for k in 0..8 {
+            if byte & (1 << (7 - k)) != 0 {
+                // Rows run down in apgsearch and up here
+                cells.push(I64Vec2::new(k + 8 * (j as i64 % 2), -(j as i64 / 2)));
+            }
+        }
+    }
+    debug_assert!(cells.iter().all(|p| p.x < SOUP_SIZE && -p.y < SOUP_SIZE));
+    cells
+}
+
+/// Runs soup `id` until its population repeats and names the objects it left.
+fn search_soup(id: &str, rule: LifeRule) -> Vec<String> {
+    let mut engine = new_engine(rule);
+    engine.import(&hash_soup(id));
+    let mut populations = Vec::new();
+    loop {
+        if engine.generation() >= MAX_GENERATIONS {
+            return vec!["PATHOLOGICAL".to_string()];
+        }
+        engine.step(1);
+        populations.push(engine.population());
+        if populations.len() % 50 == 0 && settled(&populations) {
+            break;
+        }
+    }
+
+    let mut objects = Vec::new();
+    for cluster in clusters(&engine.export(), 2) {
+        match classify(&cluster, rule) {
+            Some(names) => objects.extend(names),
+            None => objects.push("PATHOLOGICAL".to_string()),
+        }
+    }
+    objects
+}
+
+/// Whether the last [`SETTLE_WINDOW`] populations repeat with a short period.
+fn settled(populations: &[u64]) -> bool {
+    if populations.len() < SETTLE_WINDOW + MAX_POPULATION_PERIOD {
+        return false;
+    }
+    let recent = &populations[populations.len() - SETTLE_WINDOW - MAX_POPULATION_PERIOD..];
+    (1..=MAX_POPULATION_PERIOD).any(|period| {
+        (MAX_POPULATION_PERIOD..recent.len()).all(|i| recent[i] == recent[i - period])
+    })
+}
+
+/// Groups cells no more than `distance` apart (in both directions) from each other.
+fn clusters(cells: &[I64Vec2], distance: i64) -> Vec<Vec<I64Vec2>> {
+    let mut unvisited: FxHashSet<I64Vec2> = cells.iter().copied().collect();
+    let mut clusters = Vec::new();
+    for &start in cells {
+        if !unvisited.remove(&start) {
+            continue;
+        }
+        let mut cluster = vec![start];
+        let mut next = 0;
+        while let Some(&pos) = cluster.get(next) {
+            next += 1;
+            for dy in -distance..=distance {
+                for dx in -distance..=distance {
+                    let neighbour = pos + I64Vec2::new(dx, dy);
+                    if unvisited.remove(&neighbour) {
+                        cluster.push(neighbour);
+                    }
+                }
+            }
+        }
+        clusters.push(cluster);
+    }
+    clusters
+}
+
+/// Names the objects in a cluster: one apgcode for the cluster, or one for each of its
+/// parts if they evolve the same apart as together. None if it doesn't repeat.
+fn classify(cells: &[I64Vec2], rule: LifeRule) -> Option<Vec<String>> {
+    let mut engine = new_engine(rule);
+    engine.import(cells);
+    let mut detector = PeriodDetector::default();
+    let mut phases = Vec::new();
+    for _ in 0..=MAX_PERIOD {
+        let phase = engine.export();
+        detector.observe(engine.generation(), &phase);
+        if detector.detected.is_some() {
+            break;
+        }
+        phases.push(phase);
+        engine.step(1);
+    }
+    let Periodicity {
+        period,
+        displacement,
+    } = detector.detected?;
+    // The cluster may take a few generations to start repeating
+    let phases = &phases[phases.len().saturating_sub(period as usize)..];
+
+    let parts = clusters(cells, 1);
+    if parts.len() > 1 && independent(cells, &parts, rule, period) {
+        let mut names = Vec::new();
+        for part in parts {
+            names.extend(classify(&part, rule)?);
+        }
+        return Some(names);
+    }
+
+    let code = phases
+        .iter()
+        .flat_map(|phase| orientations(phase))
+        .map(|cells| wechsler(&cells))
+        .min_by(|a, b| a.len().cmp(&b.len()).then(a.cmp(b)))?;
+    let prefix = match period {
+        1 if displacement == I64Vec2::ZERO => format!("xs{}", cells.len()),
+        period if displacement == I64Vec2::ZERO => format!("xp{period}"),
+        period => format!("xq{period}"),
+    };
+    Some(vec![format!("{prefix}_{code}")])
+}
+
+/// Whether `parts` evolve into the same cells on their own as `cells` does, over two
+/// periods.
+fn independent(cells: &[I64Vec2], parts: &[Vec<I64Vec2>], rule: LifeRule, period: u64) -> bool {
+    let mut together = new_engine(rule);
+    together.import(cells);
+    let mut apart: Vec<_> = parts
+        .iter()
+        .map(|part| {
+            let mut engine = new_engine(rule);
+            engine.import(part);
+            engine
+        })
+        .collect();
+    for _ in 0..2 * period.max(2) {
+        together.step(1);
+        let mut expected = together.export();
+        let mut actual: Vec<I64Vec2> = apart
+            .iter_mut()
+            .flat_map(|engine| {
+                engine.step(1);
+                engine.export()
+            })
+            .collect();
+        expected.sort_unstable_by_key(|p| (p.y, p.x));
+        actual.sort_unstable_by_key(|p| (p.y, p.x));
+        if expected != actual {
+            return false;
+        }
+    }
+    true
+}
+
+/// The eight rotations and reflections of `cells`.
+fn orientations(cells: &[I64Vec2]) -> Vec<Vec<I64Vec2>> {
+    let transforms: [fn(I64Vec2) -> I64Vec2; 8] = [
+        |p| p,
+        |p| I64Vec2::new(-p.x, p.y),
+        |p| I64Vec2::new(p.x, -p.y),
+        |p| -p,
+        |p| I64Vec2::new(p.y, p.x),
+        |p| I64Vec2::new(-p.y, p.x),
+        |p| I64Vec2::new(p.y, -p.x),
+        |p| I64Vec2::new(-p.y, -p.x),
+    ];
+    transforms
+        .iter()
+        .map(|transform| cells.iter().map(|&p| transform(p)).collect())
+        .collect()
+}
+
+/// Encodes `cells` in extended Wechsler format: strips of five rows, each a column at a
+/// time as a base-32 digit, runs of empty columns shortened to `w`, `x` and `y…`.
+fn wechsler(cells: &[I64Vec2]) -> String {
+    const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    let Some(min) = cells.iter().copied().reduce(I64Vec2::min) else {
+        return String::new();
+    };
+    let max = cells.iter().copied().reduce(I64Vec2::max).unwrap_or(min);
+    let alive: FxHashSet<I64Vec2> = cells.iter().map(|&p| p - min).collect();
+    let size = max - min + I64Vec2::ONE;
+
+    let mut code = String::new();
+    for strip in 0..(size.y + 4) / 5 {
+        if strip > 0 {
+            code.push('z');
+        }
+        let columns: Vec<usize> = (0..size.x)
+            .map(|x| {
+                (0..5)
+                    .filter(|row| alive.contains(&I64Vec2::new(x, strip * 5 + row)))
+                    .map(|row| 1 << row)
+                    .sum()
+            })
+            .collect();
+        let end = columns.iter().rposition(|&c| c != 0).map_or(0, |i| i + 1);
+        let mut zeros = 0;
+        for &column in &columns[..end] {
+            if column == 0 {
+                zeros += 1;
+                continue;
+            }
+            while zeros > 0 {
+                let run = zeros.min(39);
+                match run {
+                    1 => code.push('0'),
+                    2 => code.push('w'),
+                    3 => code.push('x'),
+                    _ => {
+                        code.push('y');
+                        code.push(DIGITS[run - 4] as char);
+                    }
+                }
+                zeros -= run;
+            }
+            code.push(DIGITS[column] as char);
+        }
+    }
+    code
+}
+
+/// Pays for and posts a haul with Catagolue's payosha256 protocol: a token bought with
+/// the key and a proof of work is sent along with it.
+fn submit(url: &str, key: &str, haul: &str) -> Result<String, LifeError> {
+    let response = post(
+        &format!("{url}/payosha256"),
+        &format!("payosha256:get_token:{key}:post_apgsearch_haul\n"),
+    )?;
+    let (target, token) = response
+        .lines()
+        .find_map(|line| {
+            let fields: Vec<&str> = line.trim().split(':').collect();
+            match fields.as_slice() {
+                ["payosha256", "good", target, token, ..] => Some((*target, *token)),
+                _ => None,
+            }
+        })
+        .ok_or_else(|| LifeError::Upload(format!("no token for the key: {}", response.trim())))?;
+    let nonce = (0u64..)
+        .find(|nonce| hex(&sha256(format!("{token}:{nonce}").as_bytes())).as_str() < target)
+        .unwrap_or_default();
+
+    let payment = format!("payosha256:pay_token:{token}:{nonce}\n");
+    post(&format!("{url}/apgsearch"), &format!("{payment}{haul}"))
+}
+
+fn post(url: &str, body: &str) -> Result<String, LifeError> {
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--data-binary", "@-"])
+        .args(["--header", "Content-Type: text/plain"])
+        .arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| LifeError::Upload(format!("cannot start curl ({err}); install it")))?;
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    stdin
+        .write_all(body.as_bytes())
+        .map_err(|err| LifeError::Upload(format!("{url}: {err}")))?;
+    drop(stdin);
+    let output = child
+        .wait_with_output()
+        .map_err(|err| LifeError::Upload(format!("{url}: {err}")))?;
+    if !output.status.success() {
+        return Err(LifeError::Upload(format!(
+            "{url}: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Pads a message to whole 64-byte blocks, ending in its length in bits.
+fn pad(data: &[u8], big_endian: bool) -> Vec<u8> {
+    let bits = data.len() as u64 * 8;
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&if big_endian {
+        bits.to_be_bytes()
+    } else {
+        bits.to_le_bytes()
+    });
+    message
+}
+
+/// SHA-256, which seeds the soups and proves the work for the upload.
+fn sha256(data: &[u8]) -> [u8; 32] {
+    const K: [u32; 64] = [
+        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4,
+        0xab1c5ed5, 0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe,
+        0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f,
+        0x4a7484aa, 0x5cb0a9dc, 0x76f988da, 0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7,
+        0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc,
+        0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b,
+        0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070, 0x19a4c116,
+        0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7,
+        0xc67178f2,
+    ];
+    let mut state: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+    for block in pad(data, true).chunks_exact(64) {
+        let mut w = [0u32; 64];
+        for (i, word) in block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+        for (k, word) in K.into_iter().zip(w) {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let choice = (e & f) ^ (!e & g);
+            let t1 = h
+                .wrapping_add(s1)
+                .wrapping_add(choice)
+                .wrapping_add(k)
+                .wrapping_add(word);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let majority = (a & b) ^ (a & c) ^ (b & c);
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(s0.wrapping_add(majority));
+        }
+        for (word, value) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *word = word.wrapping_add(value);
+        }
+    }
+    let mut digest = [0; 32];
+    for (bytes, word) in digest.chunks_exact_mut(4).zip(state) {
+        bytes.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+/// MD5, which Catagolue wants of the root.
+fn md5(data: &[u8]) -> [u8; 16] {
+    const SHIFTS: [u32; 16] = [7, 12, 17, 22, 5, 9, 14, 20, 4, 11, 16, 23, 6, 10, 15, 21];
+    const K: [u32; 64] = [
+        0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a, 0xa8304613,
+        0xfd469501, 0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be, 0x6b901122, 0xfd987193,
+        0xa679438e, 0x49b40821, 0xf61e2562, 0xc040b340, 0x265e5a51, 0xe9b6c7aa, 0xd62f105d,
+        0x02441453, 0xd8a1e681, 0xe7d3fbc8, 0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed,
+        0xa9e3e905, 0xfcefa3f8, 0x676f02d9, 0x8d2a4c8a, 0xfffa3942, 0x8771f681, 0x6d9d6122,
+        0xfde5380c, 0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70, 0x289b7ec6, 0xeaa127fa,
+        0xd4ef3085, 0x04881d05, 0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665, 0xf4292244,
+        0x432aff97, 0xab9423a7, 0xfc93a039, 0x655b59c3, 0x8f0ccc92, 0xffeff47d, 0x85845dd1,
+        0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1, 0xf7537e82, 0xbd3af235, 0x2ad7d2bb,
+        0xeb86d391,
+    ];
+    let mut state: [u32; 4] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476];
+    for block in pad(data, false).chunks_exact(64) {
+        let mut m = [0u32; 16];
+        for (i, word) in block.chunks_exact(4).enumerate() {
+            m[i] = u32::from_le_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        let [mut a, mut b, mut c, mut d] = state;
+        for i in 0..64 {
+            let (f, g) = match i / 16 {
+                0 => ((b & c) | (!b & d), i),
+                1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+                2 => (b ^ c ^ d, (3 * i + 5) % 16),
+                _ => (c ^ (b | !d), (7 * i) % 16),
+            };
+            let rotated = a
+                .wrapping_add(f)
+                .wrapping_add(K[i])
+                .wrapping_add(m[g])
+                .rotate_left(SHIFTS[i / 16 * 4 + i % 4]);
+            a = d;
+            d = c;
+            c = b;
+            b = b.wrapping_add(rotated);
+        }
+        for (word, value) in state.iter_mut().zip([a, b, c, d]) {
+            *word = word.wrapping_add(value);
+        }
+    }
+    let mut digest = [0; 16];
+    for (bytes, word) in digest.chunks_exact_mut(4).zip(state) {
+        bytes.copy_from_slice(&word.to_le_bytes());
+    }
+    digest
+}
@@ -0,0 +1,304 @@
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+
+use crate::simulation::SimulationInput;
+use crate::simulation::engine::{CellRegion, EngineRegistry, PasteMode};
+use crate::simulation::error::LifeError;
+use crate::simulation::import::{ImportBudget, PatternFormat};
+use crate::simulation::rle::{self, PatternSize, RlePattern};
+use crate::simulation::selection::Selection;
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::system_clipboard::SystemClipboard;
+use crate::simulation::universe::Universe;
+use crate::simulation::versus::no_match_running;
+use crate::simulation::view::MouseWorldPosition;
+
+/// Periods offered for snapping, in order (common gun and oscillator periods).
+const SNAP_PERIODS: [u64; 10] = [2, 3, 4, 8, 14, 15, 30, 46, 60, 120];
+
+/// Ctrl+C copies the selection and Ctrl+V pastes it at the mouse. The copy also goes to
+/// the system clipboard as RLE the size of the selection, for Golly and other programs,
+/// in browsers too. A pattern
+/// copied in another program (RLE, or plaintext from LifeWiki) takes precedence: Ctrl+V
+/// attaches it to the mouse, and a click puts it down (see [`MouseDrawPlugin`]).
+///
+/// [`MouseDrawPlugin`]: crate::simulation::draw::MouseDrawPlugin
+pub struct ClipboardPlugin;
+
+impl Plugin for ClipboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Clipboard>()
+            .init_resource::<SystemClipboard>();
+        #[cfg(target_arch = "wasm32")]
+        app.add_systems(
+            Startup,
+            crate::simulation::system_clipboard::listen_for_shortcuts,
+        );
+        app.add_systems(
+            Update,
+            (
+                cycle_snap_period.in_set(SimulationInput),
+                clipboard_input.in_set(SimulationInput),
+                paste_system_clipboard,
+                show_phase,
+            )
+                .chain()
+                .run_if(no_match_running),
+        );
+    }
+}
+
+/// A copied pattern, with cells relative to its top-left corner.
+#[derive(Resource, Default)]
+pub struct Clipboard {
+    pub size: I64Vec2,
+    pub cells: Vec<I64Vec2>,
+    pub mode: PasteMode,
+    /// Where the pattern was copied from, and the generation it was copied at.
+    pub source: I64Vec2,
+    pub copied_at: u64,
+    /// While set, pastes land on offsets from `source` that are multiples of this period.
+    /// For guns emitting c/4 gliders that keeps the copy's stream on the original's
+    /// glider lattice; paste while the phase matches to keep them in step too.
+    pub snap_period: Option<u64>,
+    /// Follows the mouse until a click puts it down.
+    pub attached: bool,
+    // Holds the pattern that was on the system clipboard, unchanged since
+    from_system: Option<String>,
+    // The RLE Ctrl+C put on the system clipboard; reading it back is the copy itself
+    written: Option<String>,
+}
+
+impl Clipboard {
+    /// Where a paste aimed at `pos` lands, after snapping.
+    pub fn snap(&self, pos: I64Vec2) -> I64Vec2 {
+        let Some(period) = self.snap_period else {
+            return pos;
+        };
+        let period = period as i64;
+        let offset = pos - self.source;
+        let snapped = I64Vec2::new(
+            (offset.x + period / 2).div_euclid(period) * period,
+            (offset.y + period / 2).div_euclid(period) * period,
+        );
+        self.source + snapped
+    }
+
+    /// The clipboard's cells with its corner at `origin`, and the region they cover.
+    pub fn placed_at(&self, origin: I64Vec2) -> (CellRegion, Vec<I64Vec2>) {
+        let region = CellRegion {
+            min: origin,
+            max: origin + self.size,
+        };
+        let cells = self.cells.iter().map(|&pos| origin + pos).collect();
+        (region, cells)
+    }
+}
+
+// Ctrl+C: copy the selection, Ctrl+V: paste at the mouse, Ctrl+Z: undo a paste,
+// M: cycle the paste mode
+fn clipboard_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    selection: Res<Selection>,
+    system: Res<SystemClipboard>,
+    mut clipboard: ResMut<Clipboard>,
+    mut universe: ResMut<Universe>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    if clipboard.attached && keys.just_pressed(KeyCode::Escape) {
+        clipboard.attached = false;
+    }
+    if keys.just_pressed(KeyCode::KeyM) {
+        clipboard.mode = clipboard.mode.next();
+        stats.insert("Paste Mode", format!("{:?}", clipboard.mode));
+    }
+
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+
+    if keys.just_pressed(KeyCode::KeyC)
+        && let Some(region) = selection.region
+    {
+        clipboard.size = region.size();
+        clipboard.source = region.min;
+        clipboard.copied_at = universe.generation();
+        clipboard.attached = false;
+        clipboard.from_system = None;
+        clipboard.cells = universe
+            .cells_in(region)
+            .into_iter()
+            .map(|pos| pos - region.min)
+            .collect();
+        // Rows run down in RLE and up on screen; the whole selection goes out, counted
+        // from its top-left cell, blank margins included
+        let size = region.size();
+        let flipped: Vec<I64Vec2> = clipboard
+            .cells
+            .iter()
+            .map(|pos| I64Vec2::new(pos.x, size.y - 1 - pos.y))
+            .collect();
+        let text = rle::encode_region(
+            &flipped,
+            CellRegion {
+                min: I64Vec2::ZERO,
+                max: size,
+            },
+            universe.rule(),
+            "",
+        );
+        system.write_text(&text);
+        clipboard.written = Some(text);
+        stats.insert(
+            "Clipboard",
+            format!(
+                "{} cells, {}x{}",
+                clipboard.cells.len(),
+                region.size().x,
+                region.size().y
+            ),
+        );
+    }
+
+    // Pasting waits for the system clipboard, which may hold a pattern from elsewhere; in
+    // browsers the page asked for it during the key press already
+    #[cfg(not(target_arch = "wasm32"))]
+    if keys.just_pressed(KeyCode::KeyV) {
+        system.request_text();
+    }
+
+    if keys.just_pressed(KeyCode::KeyZ) && !universe.undo() {
+        info!("Nothing to undo");
+    }
+}
+
+// A new pattern on the system clipboard replaces the copy and is attached to the mouse;
+// otherwise the copy is pasted at the mouse
+#[allow(clippy::too_many_arguments)]
+fn paste_system_clipboard(
+    system: Res<SystemClipboard>,
+    budget: Res<ImportBudget>,
+    registry: Res<EngineRegistry>,
+    mouse_res: Res<MouseWorldPosition>,
+    mut clipboard: ResMut<Clipboard>,
+    mut universe: ResMut<Universe>,
+    mut stats: ResMut<StatsBoard>,
+    mut errors: MessageWriter<LifeError>,
+) {
+    let Some(text) = system.take_text() else {
+        return;
+    };
+    let parsed = text
+        .filter(|text| {
+            clipboard.from_system.as_ref() != Some(text) && clipboard.written.as_ref() != Some(text)
+        })
+        .map(|text| {
+            let pattern = parse_pattern(&text, *budget, &registry, &universe);
+            (text, pattern)
+        });
+    if let Some((text, pattern)) = parsed
+        && let Some(pattern) = pattern.unwrap_or_else(|err| {
+            errors.write(err);
+            None
+        })
+    {
+        // Rows run down in the text and up on screen
+        let height = pattern.cells.iter().map(|pos| pos.y + 1).max().unwrap_or(0);
+        let width = pattern.cells.iter().map(|pos| pos.x + 1).max().unwrap_or(0);
+        clipboard.cells = pattern
+            .cells
+            .into_iter()
+            .map(|pos| I64Vec2::new(pos.x, height - 1 - pos.y))
+            .collect();
+        clipboard.size = I64Vec2::new(width, height);
+        clipboard.source = I64Vec2::ZERO;
+        clipboard.copied_at = universe.generation();
+        clipboard.from_system = Some(text);
+        stats.insert(
+            "Clipboard",
+            format!(
+                "{} cells, {width}x{height}, click to place",
+                clipboard.cells.len()
+            ),
+        );
+    }
+
+    if clipboard.from_system.is_some() {
+        clipboard.attached = true;
+    } else if !clipboard.cells.is_empty()
+        && let Some(pos) = mouse_res.grid_pos
+    {
+        let (region, cells) = clipboard.placed_at(clipboard.snap(pos));
+        universe.paste(region, cells, clipboard.mode);
+    }
+}
+
+/// `text` as a pattern with its top-left cell at the origin, if it is an RLE pattern
+/// (with its `x = ...` header) or a plaintext one with live cells. Text that isn't a
+/// pattern is `None`; a pattern too large for the [`ImportBudget`] is an error.
+fn parse_pattern(
+    text: &str,
+    budget: ImportBudget,
+    registry: &EngineRegistry,
+    universe: &Universe,
+) -> Result<Option<RlePattern>, LifeError> {
+    let has_header = text
+        .lines()
+        .map(str::trim_start)
+        .any(|line| line.starts_with('x') && line.contains('='));
+    let format = if has_header {
+        PatternFormat::Rle
+    } else {
+        PatternFormat::Plaintext
+    };
+    let Ok(size) = format.measure(text) else {
+        return Ok(None);
+    };
+    // Pasting keeps the universe's rule, so the pattern lands on its engine
+    budget.check_in(
+        &PatternSize { rule: None, ..size },
+        "clipboard",
+        registry,
+        universe,
+    )?;
+    let Ok(pattern) = format.decode(text) else {
+        return Ok(None);
+    };
+    Ok((!pattern.cells.is_empty()).then_some(pattern))
+}
+
+// , and .: step through the snap periods (below the first one snapping is off)
+fn cycle_snap_period(keys: Res<ButtonInput<KeyCode>>, mut clipboard: ResMut<Clipboard>) {
+    let current = clipboard
+        .snap_period
+        .and_then(|p| SNAP_PERIODS.iter().position(|&q| q == p));
+    let next = if keys.just_pressed(KeyCode::Period) {
+        Some(current.map_or(0, |i| (i + 1).min(SNAP_PERIODS.len() - 1)))
+    } else if keys.just_pressed(KeyCode::Comma) {
+        current.and_then(|i| i.checked_sub(1))
+    } else {
+        return;
+    };
+    clipboard.snap_period = next.map(|i| SNAP_PERIODS[i]);
+}
+
+// The phase the universe is in now versus when the clipboard was copied
+fn show_phase(clipboard: Res<Clipboard>, universe: Res<Universe>, mut stats: ResMut<StatsBoard>) {
+    let Some(period) = clipboard.snap_period else {
+        if clipboard.is_changed() {
+            stats.remove("Snap");
+        }
+        return;
+    };
+    let phase = universe.generation() % period;
+    let copied = clipboard.copied_at % period;
+    let status = if phase == copied {
+        "in phase"
+    } else {
+        "out of phase"
+    };
+    stats.insert(
+        "Snap",
+        format!("p{period}, phase {phase} (copied at {copied}, {status})"),
+    );
+}
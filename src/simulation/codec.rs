@@ -0,0 +1,517 @@
+use std::fmt::Write as _;
+
+use bevy::math::I64Vec2;
+use rustc_hash::FxHashMap;
+
+use crate::simulation::engine::{CellRegion, LifeEngine, PasteMode, TILE_SIZE, Tile, tile_cells};
+use crate::simulation::error::LifeError;
+use crate::simulation::universe::Edit;
+
+/// First bytes of every encoded diff.
+const MAGIC: &[u8; 3] = b"LBD";
+/// Written after [`MAGIC`]; bumped whenever the layout changes, so older builds refuse
+/// diffs they would misread instead of loading garbage.
+pub const VERSION: u8 = 1;
+
+pub const EMPTY_TILE: Tile = [0; TILE_SIZE as usize];
+
+/// An incremental update of a universe: the tiles that changed, XORed with what they
+/// held before (or, for a list of cells, just those cells). Shared by the remote viewer
+/// stream, the autosave journal and the history, which all move such updates around.
+///
+/// Encoded as `LBD`, the [`VERSION`] byte, the generation and the tile count, then the
+/// tiles by row and column, each position a delta from the previous one. A tile lists
+/// its non-empty rows, each with the rows skipped before it and its runs of dead and
+/// live cells, so sparse tiles take a few bytes. All numbers are LEB128 varints (signed
+/// ones zigzagged).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BlockDiff {
+    pub generation: u64,
+    pub tiles: Vec<(I64Vec2, Tile)>,
+}
+
+impl BlockDiff {
+    /// The tiles holding `cells`.
+    pub fn from_cells(cells: &[I64Vec2]) -> Self {
+        let mut tiles: FxHashMap<I64Vec2, Tile> = FxHashMap::default();
+        for pos in cells {
+            let tile = tiles
+                .entry(pos.div_euclid(I64Vec2::splat(TILE_SIZE)))
+                .or_insert(EMPTY_TILE);
+            let local = pos.rem_euclid(I64Vec2::splat(TILE_SIZE));
+            tile[local.y as usize] |= 1 << local.x;
+        }
+        Self {
+            generation: 0,
+            tiles: tiles.into_iter().collect(),
+        }
+    }
+
+    /// Every cell set in the tiles.
+    pub fn cells(&self) -> Vec<I64Vec2> {
+        self.tiles
+            .iter()
+            .flat_map(|(pos, tile)| {
+                let base = *pos * TILE_SIZE;
+                tile_cells(tile).map(move |local| base + local)
+            })
+            .collect()
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(16 + self.tiles.len() * 32);
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        write_varint(&mut out, self.generation);
+        write_varint(&mut out, self.tiles.len() as u64);
+
+        let mut order: Vec<&(I64Vec2, Tile)> = self.tiles.iter().collect();
+        order.sort_unstable_by_key(|(pos, _)| (pos.y, pos.x));
+        let mut previous = I64Vec2::ZERO;
+        for (pos, tile) in order {
+            let step = pos.wrapping_sub(previous);
+            write_signed(&mut out, step.x);
+            write_signed(&mut out, step.y);
+            previous = *pos;
+
+            let rows = tile.iter().filter(|&&row| row != 0).count();
+            write_varint(&mut out, rows as u64);
+            let mut next_row = 0;
+            for (y, &row) in tile.iter().enumerate().filter(|(_, row)| **row != 0) {
+                write_varint(&mut out, (y - next_row) as u64);
+                write_row(&mut out, row);
+                next_row = y + 1;
+            }
+        }
+        out
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, LifeError> {
+        let mut reader = Reader { bytes, at: 0 };
+        if bytes.get(..MAGIC.len()) != Some(&MAGIC[..]) {
+            return Err(invalid("not a block diff"));
+        }
+        reader.at = MAGIC.len();
+        let version = reader.byte()?;
+        if version != VERSION {
+            return Err(invalid(&format!(
+                "version {version}, this build reads version {VERSION}"
+            )));
+        }
+        let generation = reader.varint()?;
+        let count = reader.varint()? as usize;
+
+        // Every tile takes at least three bytes, which bounds what a bad count allocates
+        let mut tiles = Vec::with_capacity(count.min(bytes.len() / 3));
+        let mut pos = I64Vec2::ZERO;
+        for _ in 0..count {
+            pos = pos.wrapping_add(I64Vec2::new(reader.signed()?, reader.signed()?));
+            let mut tile = EMPTY_TILE;
+            let mut y = 0usize;
+            for _ in 0..reader.varint()? {
+                y = y.saturating_add(reader.varint()? as usize);
+                let row = tile
+                    .get_mut(y)
+                    .ok_or_else(|| invalid("row out of its tile"))?;
+                *row = reader.row()?;
+                y += 1;
+            }
+            tiles.push((pos, tile));
+        }
+        Ok(Self { generation, tiles })
+    }
+
+    /// The encoding as base64, for text files.
+    pub fn to_text(&self) -> String {
+        base64(&self.encode())
+    }
+
+    pub fn from_text(text: &str) -> Result<Self, LifeError> {
+        Self::decode(&base64_decode(text).ok_or_else(|| invalid("damaged base64"))?)
+    }
+}
+
+/// The tiles of `engine` holding live cells.
+pub fn collect_tiles(engine: &dyn LifeEngine) -> FxHashMap<I64Vec2, Tile> {
+    let mut tiles: FxHashMap<I64Vec2, Tile> = FxHashMap::default();
+    engine.visit_tiles(&mut |pos, tile| {
+        let entry = tiles.entry(pos).or_insert(EMPTY_TILE);
+        for (row, &bits) in entry.iter_mut().zip(tile) {
+            *row |= bits;
+        }
+    });
+    tiles
+}
+
+/// The tiles that differ between `old` and the tiles of `new` that `keep` accepts,
+/// XORed; tiles of `old` left out of `new` come out whole, as they're gone.
+pub fn diff_tiles(
+    old: &FxHashMap<I64Vec2, Tile>,
+    new: &FxHashMap<I64Vec2, Tile>,
+    keep: impl Fn(I64Vec2) -> bool,
+) -> Vec<(I64Vec2, Tile)> {
+    let mut changed: Vec<(I64Vec2, Tile)> = Vec::new();
+    for (&pos, tile) in new.iter().filter(|(pos, _)| keep(**pos)) {
+        let previous = old.get(&pos).unwrap_or(&EMPTY_TILE);
+        let xor: Tile = std::array::from_fn(|y| tile[y] ^ previous[y]);
+        if xor.iter().any(|&row| row != 0) {
+            changed.push((pos, xor));
+        }
+    }
+    changed.extend(
+        old.iter()
+            .filter(|(pos, _)| !keep(**pos) || !new.contains_key(pos))
+            .map(|(&pos, &tile)| (pos, tile)),
+    );
+    changed
+}
+
+/// XORs `diff` into `tiles` (undoing or redoing it), returning the cells that were born
+/// and died by that.
+pub fn apply_diff(
+    tiles: &mut FxHashMap<I64Vec2, Tile>,
+    diff: &[(I64Vec2, Tile)],
+) -> (Vec<I64Vec2>, Vec<I64Vec2>) {
+    let (mut born, mut died) = (Vec::new(), Vec::new());
+    for (pos, xor) in diff {
+        let tile = tiles.entry(*pos).or_insert(EMPTY_TILE);
+        let mut flipped_on = EMPTY_TILE;
+        let mut flipped_off = EMPTY_TILE;
+        for y in 0..TILE_SIZE as usize {
+            tile[y] ^= xor[y];
+            flipped_on[y] = xor[y] & tile[y];
+            flipped_off[y] = xor[y] & !tile[y];
+        }
+        let base = *pos * TILE_SIZE;
+        born.extend(tile_cells(&flipped_on).map(|local| base + local));
+        died.extend(tile_cells(&flipped_off).map(|local| base + local));
+        if tile.iter().all(|&row| row == 0) {
+            tiles.remove(pos);
+        }
+    }
+    (born, died)
+}
+
+fn invalid(reason: &str) -> LifeError {
+    LifeError::InvalidPattern(format!("block diff: {reason}"))
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn write_signed(out: &mut Vec<u8>, value: i64) {
+    write_varint(out, ((value << 1) ^ (value >> 63)) as u64);
+}
+
+/// A row as its number of runs, then (dead, alive) run lengths from bit 0 up; the dead
+/// cells after the last live run are implied.
+fn write_row(out: &mut Vec<u8>, row: u64) {
+    let mut runs = Vec::new();
+    let mut x = 0;
+    while x < 64 && row >> x != 0 {
+        let dead = (row >> x).trailing_zeros();
+        x += dead;
+        let alive = (!(row >> x)).trailing_zeros().min(64 - x);
+        x += alive;
+        runs.push((dead, alive));
+    }
+    write_varint(out, runs.len() as u64);
+    for (dead, alive) in runs {
+        write_varint(out, dead as u64);
+        write_varint(out, alive as u64);
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    at: usize,
+}
+
+impl Reader<'_> {
+    fn byte(&mut self) -> Result<u8, LifeError> {
+        let byte = *self
+            .bytes
+            .get(self.at)
+            .ok_or_else(|| invalid("truncated"))?;
+        self.at += 1;
+        Ok(byte)
+    }
+
+    fn varint(&mut self) -> Result<u64, LifeError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            value |= ((byte & 0x7F) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(invalid("number too long"))
+    }
+
+    fn signed(&mut self) -> Result<i64, LifeError> {
+        let value = self.varint()?;
+        Ok((value >> 1) as i64 ^ -((value & 1) as i64))
+    }
+
+    fn row(&mut self) -> Result<u64, LifeError> {
+        let mut row = 0u64;
+        let mut x = 0u64;
+        for _ in 0..self.varint()? {
+            let dead = self.varint()?;
+            let alive = self.varint()?;
+            let end = x
+                .checked_add(dead)
+                .and_then(|start| start.checked_add(alive));
+            if alive == 0 || end.is_none_or(|end| end > 64) {
+                return Err(invalid("run out of its row"));
+            }
+            x += dead;
+            row |= (u64::MAX >> (64 - alive)) << x;
+            x += alive;
+        }
+        Ok(row)
+    }
+}
+
+const BASE64: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+pub(crate) fn base64(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &byte)| n | ((byte as u32) << (16 - 8 * i)));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64[((n >> (18 - 6 * i)) & 0x3F) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+fn base64_decode(text: &str) -> Option<Vec<u8>> {
+    let text = text.trim().trim_end_matches('=');
+    let mut out = Vec::with_capacity(text.len() * 3 / 4);
+    let (mut bits, mut count) = (0u32, 0);
+    for byte in text.bytes() {
+        let value = BASE64.iter().position(|&c| c == byte)? as u32;
+        bits = (bits << 6) | value;
+        count += 6;
+        if count >= 8 {
+            count -= 8;
+            out.push((bits >> count) as u8);
+        }
+    }
+    Some(out)
+}
+
+/// Writes `edit` as one line: `cells`, `walls` or `paste`, then its cells.
+pub(crate) fn write_edit(out: &mut String, edit: &Edit) {
+    let (line, cells) = match edit {
+        Edit::Cells(cells, alive) => (format!("cells {}", *alive as u8), cells),
+        Edit::Walls(cells, _) => ("walls".to_string(), cells),
+        Edit::Paste(region, cells, mode) => (
+            format!(
+                "paste {mode:?} {},{} {},{}",
+                region.min.x, region.min.y, region.max.x, region.max.y
+            ),
+            cells,
+        ),
+    };
+    let _ = writeln!(out, "{line} @{}", BlockDiff::from_cells(cells).to_text());
+}
+
+/// Reads a line written by [`write_edit`], split into fields.
+pub(crate) fn parse_edit(fields: &[&str]) -> Option<Edit> {
+    match fields {
+        ["cells", alive, cells @ ..] => {
+            parse_cells(cells).map(|cells| Edit::Cells(cells, *alive == "1"))
+        }
+        ["walls", cells @ ..] => parse_cells(cells).map(|cells| Edit::Walls(cells, true)),
+        ["paste", mode, min, max, cells @ ..] => {
+            let corners = parse_cells(&[min, max]);
+            parse_mode(mode)
+                .zip(corners)
+                .zip(parse_cells(cells))
+                .map(|((mode, corners), cells)| {
+                    let region = CellRegion {
+                        min: corners[0],
+                        max: corners[1],
+                    };
+                    Edit::Paste(region, cells, mode)
+                })
+        }
+        _ => None,
+    }
+}
+
+/// Reads cells written as a [`BlockDiff`] (`@` and its base64) or, as older journals
+/// have them, `x,y` pairs.
+fn parse_cells(pairs: &[&str]) -> Option<Vec<I64Vec2>> {
+    if let [encoded] = pairs
+        && let Some(text) = encoded.strip_prefix('@')
+    {
+        return BlockDiff::from_text(text).ok().map(|diff| diff.cells());
+    }
+    pairs
+        .iter()
+        .map(|pair| {
+            let (x, y) = pair.split_once(',')?;
+            Some(I64Vec2::new(x.parse().ok()?, y.parse().ok()?))
+        })
+        .collect()
+}
+
+fn parse_mode(name: &str) -> Option<PasteMode> {
+    [
+        PasteMode::Or,
+        PasteMode::Xor,
+        PasteMode::And,
+        PasteMode::Copy,
+    ]
+    .into_iter()
+    .find(|mode| format!("{mode:?}") == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(mut diff: BlockDiff) -> BlockDiff {
+        diff.tiles.sort_unstable_by_key(|(pos, _)| (pos.y, pos.x));
+        diff
+    }
+
+    fn sample() -> BlockDiff {
+        let mut sparse = EMPTY_TILE;
+        sparse[0] = 1;
+        sparse[17] = 0b1011 << 30;
+        sparse[63] = 1 << 63;
+        let mut full = [u64::MAX; TILE_SIZE as usize];
+        full[5] = 0x5555_5555_5555_5555;
+        BlockDiff {
+            generation: 12_345,
+            tiles: vec![
+                (I64Vec2::new(0, 0), EMPTY_TILE),
+                (I64Vec2::new(3, 0), sparse),
+                (I64Vec2::new(-2, -7), full),
+                (
+                    I64Vec2::new(i64::MIN / TILE_SIZE, i64::MAX / TILE_SIZE),
+                    sparse,
+                ),
+            ],
+        }
+    }
+
+    #[test]
+    fn round_trips() {
+        let diff = sample();
+        assert_eq!(
+            sorted(BlockDiff::decode(&diff.encode()).unwrap()),
+            sorted(diff.clone())
+        );
+        assert_eq!(
+            sorted(BlockDiff::from_text(&diff.to_text()).unwrap()),
+            sorted(diff)
+        );
+        assert_eq!(
+            BlockDiff::decode(&BlockDiff::default().encode()).unwrap(),
+            BlockDiff::default()
+        );
+    }
+
+    #[test]
+    fn round_trips_cells() {
+        let cells = [
+            I64Vec2::new(0, 0),
+            I64Vec2::new(-1, -1),
+            I64Vec2::new(63, 64),
+            I64Vec2::new(-65, 200),
+        ];
+        let decoded = BlockDiff::decode(&BlockDiff::from_cells(&cells).encode()).unwrap();
+        let mut found = decoded.cells();
+        found.sort_unstable_by_key(|pos| (pos.x, pos.y));
+        let mut expected = cells.to_vec();
+        expected.sort_unstable_by_key(|pos| (pos.x, pos.y));
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn refuses_other_formats() {
+        let mut bytes = sample().encode();
+        bytes[0] = b'X';
+        assert!(BlockDiff::decode(&bytes).is_err());
+
+        let mut bytes = sample().encode();
+        bytes[MAGIC.len()] = VERSION + 1;
+        assert!(BlockDiff::decode(&bytes).is_err());
+        assert!(BlockDiff::decode(b"LB").is_err());
+    }
+
+    #[test]
+    fn refuses_truncated_input() {
+        let bytes = sample().encode();
+        for len in 0..bytes.len() {
+            assert!(BlockDiff::decode(&bytes[..len]).is_err(), "{len} bytes");
+        }
+    }
+
+    #[test]
+    fn refuses_rows_out_of_their_tile() {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(VERSION);
+        write_varint(&mut bytes, 0);
+        write_varint(&mut bytes, 1);
+        write_signed(&mut bytes, 0);
+        write_signed(&mut bytes, 0);
+        // One row, skipping past the last one
+        write_varint(&mut bytes, 1);
+        write_varint(&mut bytes, TILE_SIZE as u64);
+        write_row(&mut bytes, 1);
+        assert!(BlockDiff::decode(&bytes).is_err());
+    }
+
+    #[test]
+    fn refuses_runs_out_of_their_row() {
+        // One run (dead, alive) in row 0 whose end overflows rather than passing the row's
+        for (dead, alive) in [(u64::MAX, 1), (2, u64::MAX - 1)] {
+            let mut bytes = MAGIC.to_vec();
+            bytes.push(VERSION);
+            write_varint(&mut bytes, 0);
+            write_varint(&mut bytes, 1);
+            write_signed(&mut bytes, 0);
+            write_signed(&mut bytes, 0);
+            write_varint(&mut bytes, 1);
+            write_varint(&mut bytes, 0);
+            write_varint(&mut bytes, 1);
+            write_varint(&mut bytes, dead);
+            write_varint(&mut bytes, alive);
+            assert!(
+                BlockDiff::decode(&bytes).is_err(),
+                "{dead} dead, {alive} alive"
+            );
+        }
+    }
+
+    #[test]
+    fn refuses_more_tiles_than_it_holds() {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(VERSION);
+        write_varint(&mut bytes, 0);
+        write_varint(&mut bytes, u64::MAX);
+        write_signed(&mut bytes, 0);
+        write_signed(&mut bytes, 0);
+        write_varint(&mut bytes, 0);
+        assert!(BlockDiff::decode(&bytes).is_err());
+    }
+}
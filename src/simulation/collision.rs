@@ -0,0 +1,292 @@
+use bevy::math::{DVec2, I64Vec2};
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use rustc_hash::FxHashSet;
+
+use crate::simulation::SimulationInput;
+use crate::simulation::engine::{CellRegion, create_rule_engine};
+use crate::simulation::follow::describe_speed;
+use crate::simulation::graphics::{
+    LayerCanvas, LayerDesc, LayerSpawner, LayerViewport, PixelLayer,
+};
+use crate::simulation::period::{PeriodDetector, Periodicity};
+use crate::simulation::rules::LifeRule;
+use crate::simulation::selection::Selection;
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::universe::Universe;
+use crate::simulation::view::SimulationView;
+
+/// Cells closer than this (Chebyshev distance) belong to the same object.
+const OBJECT_SPACING: i64 = 2;
+/// Generations an object is run on its own to find its period and velocity.
+const MEASURE_GENERATIONS: u64 = 256;
+/// How far ahead collisions are looked for.
+const LOOKAHEAD: u64 = 100_000;
+
+const OUTLINE: u8 = 1;
+const IMPACT: u8 = 2;
+
+/// Y splits the selection into objects, measures how each one moves by running it alone,
+/// and predicts where and when the first two (at least one of them moving) come close
+/// enough to interact. Their positions at that moment are outlined, the first cell of
+/// contact marked.
+pub struct CollisionPlugin;
+
+impl Plugin for CollisionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CollisionPredictor>()
+            .add_systems(Startup, setup_collision_layer)
+            .add_systems(
+                Update,
+                (
+                    predict_collision.in_set(SimulationInput),
+                    expire_prediction,
+                    render_prediction,
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct CollisionPredictor {
+    pub prediction: Option<Collision>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Collision {
+    /// Generation of the universe at which the objects first interact.
+    pub generation: u64,
+    /// Where they touch.
+    pub cell: I64Vec2,
+    /// Bounding boxes of the two objects at that generation.
+    pub boxes: [CellRegion; 2],
+}
+
+/// A group of cells in the selection and how it moves when left alone.
+struct MovingObject {
+    bounds: CellRegion,
+    periodicity: Periodicity,
+}
+
+#[derive(Component)]
+struct CollisionLayer;
+
+fn setup_collision_layer(mut layers: LayerSpawner) {
+    layers
+        .spawn(
+            LayerDesc::new("collision", 0.16)
+                .palette([
+                    Vec4::ZERO,
+                    Vec4::new(1.0, 0.9, 0.2, 0.8),
+                    Vec4::new(1.0, 0.1, 0.1, 1.0),
+                ])
+                .hidden(),
+        )
+        .insert(CollisionLayer);
+}
+
+// Y: predict the collision of the objects in the selection (again: clear it)
+fn predict_collision(
+    keys: Res<ButtonInput<KeyCode>>,
+    selection: Res<Selection>,
+    universe: Res<Universe>,
+    mut predictor: ResMut<CollisionPredictor>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    if !keys.just_pressed(KeyCode::KeyY) {
+        return;
+    }
+    if predictor.prediction.take().is_some() {
+        stats.remove("Collision");
+        return;
+    }
+    let Some(region) = selection.region else {
+        stats.insert("Collision", "select two objects first (Shift + drag)");
+        return;
+    };
+
+    let objects: Vec<MovingObject> = find_objects(&universe.cells_in(region))
+        .into_iter()
+        .filter_map(|cells| measure(&cells, universe.rule()))
+        .collect();
+    let pair = objects.iter().enumerate().find_map(|(i, a)| {
+        objects[i + 1..]
+            .iter()
+            .find(|b| a.periodicity.displacement != b.periodicity.displacement)
+            .map(|b| [a, b])
+    });
+    let Some([a, b]) = pair else {
+        stats.insert(
+            "Collision",
+            format!(
+                "needs two objects moving differently, found {} that repeat",
+                objects.len()
+            ),
+        );
+        return;
+    };
+
+    let speeds = format!(
+        "{} and {}",
+        describe_speed(a.periodicity),
+        describe_speed(b.periodicity)
+    );
+    match first_contact(a, b) {
+        Some((delay, cell, boxes)) => {
+            let generation = universe.generation() + delay;
+            stats.insert(
+                "Collision",
+                format!(
+                    "{speeds} meet at generation {generation} (in {delay}) near ({}, {})",
+                    cell.x, cell.y
+                ),
+            );
+            predictor.prediction = Some(Collision {
+                generation,
+                cell,
+                boxes,
+            });
+        }
+        None => stats.insert(
+            "Collision",
+            format!("{speeds} don't meet within {LOOKAHEAD} generations"),
+        ),
+    }
+}
+
+// Forget the prediction once it came true (or the universe was reset)
+fn expire_prediction(
+    universe: Res<Universe>,
+    mut predictor: ResMut<CollisionPredictor>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    let Some(collision) = predictor.prediction else {
+        return;
+    };
+    if universe.generation() > collision.generation + MEASURE_GENERATIONS
+        || universe.population() == 0
+    {
+        predictor.prediction = None;
+        stats.remove("Collision");
+    }
+}
+
+fn render_prediction(
+    predictor: Res<CollisionPredictor>,
+    view: Res<SimulationView>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut q_layer: Query<(&mut PixelLayer, &mut LayerCanvas), With<CollisionLayer>>,
+) {
+    let Ok((mut layer, mut canvas)) = q_layer.single_mut() else {
+        return;
+    };
+    let shown = predictor.prediction.is_some();
+    if layer.visible != shown {
+        layer.visible = shown;
+    }
+    let Some(collision) = predictor.prediction else {
+        return;
+    };
+    let Ok(window) = q_window.single() else {
+        return;
+    };
+    let Some(viewport) = LayerViewport::new(window, &view, &layer) else {
+        return;
+    };
+    let buffer = canvas.buffer(&viewport);
+    buffer.fill(0);
+
+    for region in collision.boxes {
+        let (min, size) = (region.min, region.size());
+        viewport.draw_rect(buffer, min.x - 1, min.y - 1, size.x + 2, 1, OUTLINE);
+        viewport.draw_rect(buffer, min.x - 1, region.max.y, size.x + 2, 1, OUTLINE);
+        viewport.draw_rect(buffer, min.x - 1, min.y, 1, size.y, OUTLINE);
+        viewport.draw_rect(buffer, region.max.x, min.y, 1, size.y, OUTLINE);
+    }
+    let cell = collision.cell;
+    viewport.draw_cell(buffer, cell.x, cell.y, IMPACT);
+}
+
+/// Splits `cells` into groups no closer than [`OBJECT_SPACING`] to each other.
+fn find_objects(cells: &[I64Vec2]) -> Vec<Vec<I64Vec2>> {
+    let mut unvisited: FxHashSet<I64Vec2> = cells.iter().copied().collect();
+    let mut objects = Vec::new();
+    while let Some(&start) = unvisited.iter().next() {
+        unvisited.remove(&start);
+        let mut object = vec![start];
+        let mut next = 0;
+        while let Some(&pos) = object.get(next) {
+            next += 1;
+            for dy in -OBJECT_SPACING..=OBJECT_SPACING {
+                for dx in -OBJECT_SPACING..=OBJECT_SPACING {
+                    let neighbour = pos + I64Vec2::new(dx, dy);
+                    if unvisited.remove(&neighbour) {
+                        object.push(neighbour);
+                    }
+                }
+            }
+        }
+        objects.push(object);
+    }
+    // Scanning order, so "the first two" is predictable
+    objects.sort_by_key(|object| object.iter().map(|p| (p.y, p.x)).min().unwrap_or_default());
+    objects
+}
+
+/// Runs an object on its own until it repeats; `None` if it doesn't within
+/// [`MEASURE_GENERATIONS`] (it grows, dies or is unstable).
+fn measure(cells: &[I64Vec2], rule: LifeRule) -> Option<MovingObject> {
+    let bounds = bounding_box(cells)?;
+    let mut engine = create_rule_engine(rule);
+    engine.import(cells);
+    let mut detector = PeriodDetector::default();
+    for _ in 0..=MEASURE_GENERATIONS {
+        detector.observe(engine.generation(), &engine.export());
+        if let Some(periodicity) = detector.detected {
+            return Some(MovingObject {
+                bounds,
+                periodicity,
+            });
+        }
+        engine.step(1);
+    }
+    None
+}
+
+fn bounding_box(cells: &[I64Vec2]) -> Option<CellRegion> {
+    let min = cells.iter().copied().reduce(I64Vec2::min)?;
+    let max = cells.iter().copied().reduce(I64Vec2::max)?;
+    Some(CellRegion {
+        min,
+        max: max + I64Vec2::ONE,
+    })
+}
+
+/// Generations until the two objects, moving at their average velocity, come within
+/// reach of each other (a cell apart, so births between them become possible), with
+/// the contact cell and where both are by then.
+fn first_contact(a: &MovingObject, b: &MovingObject) -> Option<(u64, I64Vec2, [CellRegion; 2])> {
+    let velocity =
+        |o: &MovingObject| o.periodicity.displacement.as_dvec2() / o.periodicity.period as f64;
+    let (va, vb) = (velocity(a), velocity(b));
+    let at = |o: &MovingObject, v: DVec2, t: u64| {
+        let shift = (v * t as f64).round().as_i64vec2();
+        CellRegion {
+            min: o.bounds.min + shift,
+            max: o.bounds.max + shift,
+        }
+    };
+
+    (0..=LOOKAHEAD).find_map(|t| {
+        let (box_a, box_b) = (at(a, va, t), at(b, vb, t));
+        // Within reach: the boxes grown by one cell overlap
+        let min = box_a.min.max(box_b.min) - I64Vec2::ONE;
+        let max = box_a.max.min(box_b.max) + I64Vec2::ONE;
+        if min.x >= max.x || min.y >= max.y {
+            return None;
+        }
+        let cell = (min + max - I64Vec2::ONE).as_dvec2() / 2.0;
+        Some((t, cell.round().as_i64vec2(), [box_a, box_b]))
+    })
+}
@@ -0,0 +1,202 @@
+use bevy::input::ButtonState;
+use bevy::input::keyboard::KeyboardInput;
+use bevy::math::{DVec2, I64Vec2};
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::simulation::engine::CellRegion;
+use crate::simulation::graphics::{
+    LayerCanvas, LayerDesc, LayerSpawner, LayerViewport, PixelLayer,
+};
+use crate::simulation::presentation::not_presenting;
+use crate::simulation::seed::view_center;
+use crate::simulation::selection::Selection;
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::universe::Universe;
+use crate::simulation::view::{MouseWorldPosition, SimulationView};
+use crate::simulation::{SimulationInput, ViewInput};
+
+const CURSOR: u8 = 1;
+
+/// ` enters a keyboard editing mode for precise edits without a mouse: a cell cursor
+/// moves with the arrows or h/j/k/l (holding the key repeats), Space toggles the cell
+/// under it and Shift + moving extends the selection from where it started. The view
+/// follows the cursor. Other shortcuts are off meanwhile; ` or Esc leaves the mode.
+pub struct CursorPlugin;
+
+impl Plugin for CursorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CellCursor>()
+            .configure_sets(PreUpdate, SimulationInput.run_if(not_cursor_editing))
+            .configure_sets(Update, SimulationInput.run_if(not_cursor_editing))
+            .add_systems(Startup, setup_cursor_layer)
+            .add_systems(
+                Update,
+                (
+                    (toggle_cursor, move_cursor, toggle_cell)
+                        .in_set(ViewInput)
+                        .after(SimulationInput)
+                        .run_if(not_presenting),
+                    render_cursor,
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct CellCursor {
+    pub active: bool,
+    pub pos: I64Vec2,
+    /// Where the selection being extended with Shift started.
+    anchor: Option<I64Vec2>,
+}
+
+/// Run condition for input that is off while the cell cursor has the keyboard.
+pub fn not_cursor_editing(cursor: Res<CellCursor>) -> bool {
+    !cursor.active
+}
+
+#[derive(Component)]
+struct CursorLayer;
+
+fn setup_cursor_layer(mut layers: LayerSpawner) {
+    layers
+        .spawn(
+            LayerDesc::new("cursor", 0.17)
+                .palette([Vec4::ZERO, Vec4::new(0.2, 0.9, 1.0, 0.9)])
+                .hidden(),
+        )
+        .insert(CursorLayer);
+}
+
+// `: toggle the cell cursor (Esc also leaves it)
+fn toggle_cursor(
+    keys: Res<ButtonInput<KeyCode>>,
+    view: Res<SimulationView>,
+    mouse_res: Res<MouseWorldPosition>,
+    mut cursor: ResMut<CellCursor>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    let leave = cursor.active && keys.just_pressed(KeyCode::Escape);
+    if !keys.just_pressed(KeyCode::Backquote) && !leave {
+        return;
+    }
+    cursor.active = !cursor.active;
+    cursor.anchor = None;
+    if cursor.active {
+        cursor.pos = mouse_res.grid_pos.unwrap_or_else(|| view_center(&view));
+        stats.insert(
+            "Cursor",
+            "arrows/hjkl: move, Shift: select, Space: toggle, `: leave",
+        );
+    } else {
+        stats.remove("Cursor");
+    }
+}
+
+// Arrows or h/j/k/l: move the cursor, with Shift: extend the selection
+fn move_cursor(
+    mut inputs: MessageReader<KeyboardInput>,
+    keys: Res<ButtonInput<KeyCode>>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut cursor: ResMut<CellCursor>,
+    mut selection: ResMut<Selection>,
+    mut view: ResMut<SimulationView>,
+) {
+    if !cursor.active {
+        inputs.clear();
+        return;
+    }
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let mut moved = false;
+    // Key presses rather than just_pressed, so holding a key repeats the move
+    for input in inputs.read() {
+        if input.state != ButtonState::Pressed {
+            continue;
+        }
+        // y points up
+        let step = match input.key_code {
+            KeyCode::ArrowLeft | KeyCode::KeyH => I64Vec2::NEG_X,
+            KeyCode::ArrowDown | KeyCode::KeyJ => I64Vec2::NEG_Y,
+            KeyCode::ArrowUp | KeyCode::KeyK => I64Vec2::Y,
+            KeyCode::ArrowRight | KeyCode::KeyL => I64Vec2::X,
+            _ => continue,
+        };
+        if shift && cursor.anchor.is_none() {
+            cursor.anchor = Some(cursor.pos);
+        }
+        cursor.pos += step;
+        moved = true;
+    }
+    if !moved {
+        return;
+    }
+
+    match cursor.anchor {
+        Some(anchor) if shift => {
+            selection.region = Some(CellRegion::from_corners(anchor, cursor.pos));
+        }
+        _ => cursor.anchor = None,
+    }
+    if let Ok(window) = q_window.single() {
+        keep_in_view(&mut view, window, cursor.pos);
+    }
+}
+
+/// Pans the view just enough to keep `pos` a cell away from its edges.
+fn keep_in_view(view: &mut SimulationView, window: &Window, pos: I64Vec2) {
+    let half = DVec2::new(window.width() as f64, window.height() as f64) / (2.0 * view.zoom);
+    let margin = (half - DVec2::ONE).max(DVec2::ZERO);
+    let cell = pos.as_dvec2() + DVec2::splat(0.5);
+    let offset = cell - view.center;
+    let shift = offset - offset.clamp(-margin, margin);
+    if shift != DVec2::ZERO {
+        view.center += shift;
+    }
+}
+
+// Space: toggle the cell under the cursor
+fn toggle_cell(
+    keys: Res<ButtonInput<KeyCode>>,
+    cursor: Res<CellCursor>,
+    mut universe: ResMut<Universe>,
+) {
+    if !cursor.active || !keys.just_pressed(KeyCode::Space) {
+        return;
+    }
+    let alive = universe.read_engine().get_cell(cursor.pos);
+    universe.set_cell(cursor.pos, !alive);
+}
+
+fn render_cursor(
+    cursor: Res<CellCursor>,
+    view: Res<SimulationView>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut q_layer: Query<(&mut PixelLayer, &mut LayerCanvas), With<CursorLayer>>,
+) {
+    let Ok((mut layer, mut canvas)) = q_layer.single_mut() else {
+        return;
+    };
+    if layer.visible != cursor.active {
+        layer.visible = cursor.active;
+    }
+    if !cursor.active {
+        return;
+    }
+    let Ok(window) = q_window.single() else {
+        return;
+    };
+    let Some(viewport) = LayerViewport::new(window, &view, &layer) else {
+        return;
+    };
+    let buffer = canvas.buffer(&viewport);
+    buffer.fill(0);
+
+    // A frame around the cell, so whether it is alive stays visible
+    let pos = cursor.pos;
+    viewport.draw_rect(buffer, pos.x - 1, pos.y - 1, 3, 1, CURSOR);
+    viewport.draw_rect(buffer, pos.x - 1, pos.y + 1, 3, 1, CURSOR);
+    viewport.draw_rect(buffer, pos.x - 1, pos.y, 1, 1, CURSOR);
+    viewport.draw_rect(buffer, pos.x + 1, pos.y, 1, 1, CURSOR);
+}
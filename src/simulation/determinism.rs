@@ -0,0 +1,169 @@
+use bevy::math::{DVec2, I64Vec2};
+use bevy::prelude::*;
+use rustc_hash::FxHashSet;
+
+use crate::simulation::SimulationInput;
+use crate::simulation::engine::{EngineRegistry, LifeEngine, create_rule_engine};
+use crate::simulation::error::LifeError;
+use crate::simulation::rules::LifeRule;
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::universe::Universe;
+use crate::simulation::view::SimulationView;
+
+/// Zoom the view jumps to when showing a divergent cell.
+const DIVERGENCE_ZOOM: f64 = 20.0;
+
+/// D runs the rule engine as a shadow of the current engine and compares their cells
+/// whenever the universe advances. The first difference pauses stepping and centers the
+/// view on it; D again switches the check off and resumes.
+pub struct DeterminismPlugin;
+
+impl Plugin for DeterminismPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DeterminismCheck>().add_systems(
+            Update,
+            (toggle_check.in_set(SimulationInput), check_determinism).chain(),
+        );
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct DeterminismCheck {
+    pub enabled: bool,
+    /// Generations compared since the check was switched on.
+    pub checked: u64,
+    pub divergence: Option<I64Vec2>,
+    shadow: Option<Box<dyn LifeEngine>>,
+    // (generation, revision) of the universe the shadow matches
+    synced_at: Option<(u64, u64)>,
+}
+
+// D: toggle the determinism check
+fn toggle_check(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut check: ResMut<DeterminismCheck>,
+    mut universe: ResMut<Universe>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    if !keys.just_pressed(KeyCode::KeyD) {
+        return;
+    }
+    let was_diverged = check.divergence.is_some();
+    *check = DeterminismCheck {
+        enabled: !check.enabled,
+        ..default()
+    };
+    if was_diverged {
+        universe.paused = false;
+    }
+    if !check.enabled {
+        stats.remove("Determinism");
+    }
+}
+
+fn check_determinism(
+    mut check: ResMut<DeterminismCheck>,
+    registry: Res<EngineRegistry>,
+    mut universe: ResMut<Universe>,
+    mut view: ResMut<SimulationView>,
+    mut stats: ResMut<StatsBoard>,
+    mut errors: MessageWriter<LifeError>,
+) {
+    if !check.enabled || check.divergence.is_some() {
+        return;
+    }
+
+    let check = &mut *check;
+    let now = (universe.generation(), universe.revision());
+    if check.synced_at == Some(now) {
+        return;
+    }
+
+    // Only stepped since the last comparison: step the shadow to match
+    let stepped_from = check
+        .synced_at
+        .filter(|&(generation, revision)| revision == now.1 && generation < now.0)
+        .map(|(generation, _)| generation);
+    match (stepped_from, check.shadow.as_mut()) {
+        (Some(generation), Some(shadow)) => {
+            shadow.step(now.0 - generation);
+            let primary = universe.read_engine().export();
+            check.checked += now.0 - generation;
+            check.synced_at = Some(now);
+
+            if let Some(cell) = first_difference(&primary, &shadow.export()) {
+                let shadow = shadow.name().to_string();
+                universe.paused = true;
+                view.center = cell.as_dvec2() + DVec2::splat(0.5);
+                view.zoom = DIVERGENCE_ZOOM;
+                check.divergence = Some(cell);
+                stats.insert(
+                    "Determinism",
+                    format!("diverged at ({}, {}), D resumes", cell.x, cell.y),
+                );
+                errors.write(LifeError::Diverged {
+                    engine: universe.engine_name(),
+                    shadow,
+                    generation: now.0,
+                    cell,
+                });
+                return;
+            }
+        }
+        // Edited, replaced or just switched on: start over from the current cells
+        _ => {
+            check.shadow = build_shadow(&universe, &registry);
+            check.synced_at = Some(now);
+            if check.shadow.is_none() {
+                stats.insert(
+                    "Determinism",
+                    format!("nothing to compare {} against", universe.engine_name()),
+                );
+                return;
+            }
+        }
+    }
+
+    stats.insert(
+        "Determinism",
+        format!("{} generations match", check.checked),
+    );
+}
+
+/// A rule engine set up like the universe, holding its current cells, or `None` when
+/// the current engine doesn't compute the same thing (continuous or random rules).
+fn build_shadow(universe: &Universe, registry: &EngineRegistry) -> Option<Box<dyn LifeEngine>> {
+    let rule = universe.rule();
+    // Other rules only ever run on the rule engine itself
+    let exact = rule != LifeRule::CONWAY
+        || registry
+            .get(&universe.engine_id())
+            .is_some_and(|entry| entry.exact_conway);
+    if !exact {
+        return None;
+    }
+
+    let mut shadow = create_rule_engine(rule);
+    let walls: Vec<I64Vec2> = universe.walls().collect();
+    if (universe.torus().is_some() && !shadow.supports_torus())
+        || (!walls.is_empty() && !shadow.supports_walls())
+        || (universe.step_region().is_some() && !shadow.supports_step_region())
+    {
+        return None;
+    }
+    shadow.set_torus(universe.torus());
+    shadow.set_step_region(universe.step_region());
+    shadow.import(&universe.read_engine().export());
+    shadow.set_walls(&walls, true);
+    Some(shadow)
+}
+
+/// The first cell (by row, then column) alive in only one of the two.
+fn first_difference(a: &[I64Vec2], b: &[I64Vec2]) -> Option<I64Vec2> {
+    let a_set: FxHashSet<I64Vec2> = a.iter().copied().collect();
+    let b_set: FxHashSet<I64Vec2> = b.iter().copied().collect();
+    a_set
+        .symmetric_difference(&b_set)
+        .copied()
+        .min_by_key(|p| (p.y, p.x))
+}
@@ -3,46 +3,169 @@ use bevy::platform::collections::HashSet;
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
 
-use crate::simulation::graphics::{GridLayerMaterial, LayerViewport, PixelLayer, PixelLayerBundle};
-use crate::simulation::universe::Universe;
+use crate::simulation::SimulationInput;
+use crate::simulation::camera_script::CameraScript;
+use crate::simulation::clipboard::Clipboard;
+use crate::simulation::graphics::{
+    LayerCanvas, LayerDesc, LayerSpawner, LayerViewport, PixelLayer,
+};
+use crate::simulation::import_ghost::PendingImport;
+use crate::simulation::sandbox::no_sandbox_running;
+use crate::simulation::selection::no_selection_input;
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::theme::Theme;
+use crate::simulation::universe::{PatternLoaded, Universe};
+use crate::simulation::versus::no_match_running;
 use crate::simulation::view::{MouseWorldPosition, SimulationView};
 
+/// Largest clipboard (per side) the texture brush tiles; bigger ones aren't textures.
+const MAX_TEXTURE_SIZE: i64 = 64;
+/// Cells the texture brush reaches from the stroke, so it covers a 9×9 square.
+const TEXTURE_RADIUS: i64 = 4;
+/// Tiled when the clipboard is empty: blocks two cells apart, a stable agar. Rows run
+/// up like the cells do.
+const DEFAULT_TEXTURE: [&str; 4] = ["oo..", "oo..", "....", "...."];
+
+/// Left drag draws, or a click puts down a pattern attached to the mouse by pasting it
+/// (see [`Clipboard::attached`]), or one opened from a file (see [`PendingImport`]). W
+/// switches the brush between cells, walls and a texture: the texture brush paints a
+/// wide stroke with the clipboard (or a block agar) tiled across the plane, setting the
+/// cells it covers to the texture's, so strokes join seamlessly into agars and large
+/// textured regions.
 pub struct MouseDrawPlugin;
 
 impl Plugin for MouseDrawPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<DrawingBuffer>()
             .add_systems(Startup, setup_draw_layer)
-            .add_systems(Update, (accumulate_drawing, commit_drawing, render_overlay));
+            .add_systems(
+                Update,
+                (
+                    (
+                        place_attached.in_set(SimulationInput),
+                        accumulate_drawing.in_set(SimulationInput),
+                        commit_drawing.in_set(SimulationInput),
+                    )
+                        .chain()
+                        .run_if(no_match_running)
+                        .run_if(no_sandbox_running)
+                        .run_if(no_selection_input),
+                    toggle_brush.in_set(SimulationInput),
+                    render_overlay,
+                ),
+            );
+    }
+}
+
+/// What a stroke turns the cells under it into.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Brush {
+    #[default]
+    Cells,
+    Walls,
+    Texture,
+}
+
+/// A pattern repeating every `size` cells, anchored at the origin.
+struct Texture {
+    size: I64Vec2,
+    cells: HashSet<I64Vec2>,
+}
+
+impl Texture {
+    /// The clipboard if it holds something small enough to tile, otherwise the default.
+    fn from_clipboard(clipboard: &Clipboard) -> Self {
+        let size = clipboard.size;
+        if !clipboard.cells.is_empty()
+            && size.min_element() > 0
+            && size.max_element() <= MAX_TEXTURE_SIZE
+        {
+            return Self {
+                size,
+                cells: clipboard.cells.iter().copied().collect(),
+            };
+        }
+        let cells = DEFAULT_TEXTURE
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.char_indices()
+                    .filter(|&(_, c)| c == 'o')
+                    .map(move |(x, _)| I64Vec2::new(x as i64, y as i64))
+            })
+            .collect();
+        Self {
+            size: I64Vec2::new(
+                DEFAULT_TEXTURE[0].len() as i64,
+                DEFAULT_TEXTURE.len() as i64,
+            ),
+            cells,
+        }
     }
+
+    fn is_alive(&self, pos: I64Vec2) -> bool {
+        let local = I64Vec2::new(pos.x.rem_euclid(self.size.x), pos.y.rem_euclid(self.size.y));
+        self.cells.contains(&local)
+    }
+}
+
+/// The cells a stroke through `pos` covers.
+fn footprint(brush: Brush, pos: I64Vec2) -> impl Iterator<Item = I64Vec2> {
+    let radius = if brush == Brush::Texture {
+        TEXTURE_RADIUS
+    } else {
+        0
+    };
+    (-radius..=radius)
+        .flat_map(move |dy| (-radius..=radius).map(move |dx| pos + I64Vec2::new(dx, dy)))
 }
 
 #[derive(Resource, Default)]
 struct DrawingBuffer {
     pub positions: HashSet<I64Vec2>,
     pub last_pos: Option<I64Vec2>,
+    brush: Brush,
+    // The button went down to put down a pattern, so it draws nothing until released
+    placing: bool,
 }
 
 #[derive(Component)]
 struct DrawLayer;
 
-fn setup_draw_layer(
-    mut commands: Commands,
-    mut images: ResMut<Assets<Image>>,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<GridLayerMaterial>>,
+fn setup_draw_layer(mut layers: LayerSpawner, theme: Res<Theme>) {
+    layers
+        .spawn(LayerDesc::new("draw", 0.1).colors(theme.draw_color, Vec4::ZERO))
+        .insert(DrawLayer);
+}
+
+// Click: put down the opened pattern, or else the one attached to the mouse
+#[allow(clippy::too_many_arguments)]
+fn place_attached(
+    buttons: Res<ButtonInput<MouseButton>>,
+    mouse_res: Res<MouseWorldPosition>,
+    mut pending: ResMut<PendingImport>,
+    mut clipboard: ResMut<Clipboard>,
+    mut universe: ResMut<Universe>,
+    mut script: ResMut<CameraScript>,
+    mut buffer: ResMut<DrawingBuffer>,
+    mut patterns_loaded: MessageWriter<PatternLoaded>,
 ) {
-    commands.spawn((
-        PixelLayerBundle::new(
-            &mut images,
-            &mut meshes,
-            &mut materials,
-            0.1, // Z-Index 0.1
-            Vec4::new(0.0, 1.0, 1.0, 0.6),
-            Vec4::new(0.0, 0.0, 0.0, 0.0),
-        ),
-        DrawLayer,
-    ));
+    if !(pending.is_pending() || clipboard.attached) || !buttons.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let Some(pos) = mouse_res.grid_pos else {
+        return;
+    };
+    if pending.is_pending() {
+        if let Some(name) = pending.place(pos, &mut universe, &mut script) {
+            patterns_loaded.write(PatternLoaded { name });
+        }
+    } else {
+        let (region, cells) = clipboard.placed_at(clipboard.snap(pos));
+        universe.paste(region, cells, clipboard.mode);
+        clipboard.attached = false;
+    }
+    buffer.placing = true;
 }
 
 fn accumulate_drawing(
@@ -52,6 +175,10 @@ fn accumulate_drawing(
 ) {
     if !buttons.pressed(MouseButton::Left) {
         buffer.last_pos = None;
+        buffer.placing = false;
+        return;
+    }
+    if buffer.placing {
         return;
     }
 
@@ -69,8 +196,11 @@ fn accumulate_drawing(
     let sy = if prev_pos.y < cur_pos.y { 1 } else { -1 };
     let mut err = (if dx > dy { dx } else { -dy }) / 2;
 
+    let brush = buffer.brush;
     loop {
-        buffer.positions.insert(I64Vec2::new(x, y));
+        buffer
+            .positions
+            .extend(footprint(brush, I64Vec2::new(x, y)));
         if x == cur_pos.x && y == cur_pos.y {
             break;
         }
@@ -90,42 +220,75 @@ fn accumulate_drawing(
 fn commit_drawing(
     mut universe: ResMut<Universe>,
     mut buffer: ResMut<DrawingBuffer>,
+    clipboard: Res<Clipboard>,
     buttons: Res<ButtonInput<MouseButton>>,
 ) {
     if !buttons.pressed(MouseButton::Left) && !buffer.positions.is_empty() {
         let points: Vec<I64Vec2> = buffer.positions.drain().collect();
-        universe.add_cells(points);
+        match buffer.brush {
+            Brush::Cells => universe.add_cells(points),
+            Brush::Walls => universe.add_walls(points),
+            Brush::Texture => {
+                let texture = Texture::from_clipboard(&clipboard);
+                let (alive, dead): (Vec<I64Vec2>, Vec<I64Vec2>) =
+                    points.into_iter().partition(|&pos| texture.is_alive(pos));
+                universe.remove_cells(dead);
+                universe.add_cells(alive);
+            }
+        }
     }
 }
 
+// W: switch between drawing cells, walls and the texture
+fn toggle_brush(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut buffer: ResMut<DrawingBuffer>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    if !keys.just_pressed(KeyCode::KeyW) {
+        return;
+    }
+    buffer.brush = match buffer.brush {
+        Brush::Cells => Brush::Walls,
+        Brush::Walls => Brush::Texture,
+        Brush::Texture => Brush::Cells,
+    };
+    stats.insert("Brush", format!("{:?}", buffer.brush));
+}
+
 fn render_overlay(
-    mut images: ResMut<Assets<Image>>,
     q_window: Query<&Window, With<PrimaryWindow>>,
-    q_layer: Query<&PixelLayer, With<DrawLayer>>,
+    mut q_layer: Query<(&PixelLayer, &mut LayerCanvas), With<DrawLayer>>,
     view: Res<SimulationView>,
     buffer: Res<DrawingBuffer>,
+    clipboard: Res<Clipboard>,
     mouse_res: Res<MouseWorldPosition>,
 ) {
-    let Ok(layer) = q_layer.single() else { return };
-    let Some(image) = images.get_mut(&layer.image_handle) else {
+    let Ok((layer, mut canvas)) = q_layer.single_mut() else {
         return;
     };
     let Ok(window) = q_window.single() else {
         return;
     };
 
-    let Some(viewport) = LayerViewport::new(window, &view) else {
+    let Some(viewport) = LayerViewport::new(window, &view, layer) else {
         return;
     };
-    let pixel_buffer = viewport.get_buffer(image);
+    let pixel_buffer = canvas.buffer(&viewport);
 
     // Clear and Draw
     pixel_buffer.fill(0);
 
-    for &pos in &buffer.positions {
-        viewport.draw_cell(pixel_buffer, pos.x as i64, pos.y as i64, 255);
-    }
-    if let Some(pos) = mouse_res.grid_pos {
-        viewport.draw_cell(pixel_buffer, pos.x as i64, pos.y as i64, 255);
+    // The texture brush shows what its cells become, under the stroke and the mouse
+    let texture = (buffer.brush == Brush::Texture).then(|| Texture::from_clipboard(&clipboard));
+    let shown = |pos: I64Vec2| texture.as_ref().is_none_or(|texture| texture.is_alive(pos));
+    let hovered = mouse_res
+        .grid_pos
+        .into_iter()
+        .flat_map(|pos| footprint(buffer.brush, pos));
+    for pos in buffer.positions.iter().copied().chain(hovered) {
+        if shown(pos) {
+            viewport.draw_cell(pixel_buffer, pos.x, pos.y, 255);
+        }
     }
 }
@@ -0,0 +1,269 @@
+use std::collections::VecDeque;
+
+#[cfg(feature = "ui")]
+use bevy::asset::RenderAssetUsages;
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+#[cfg(feature = "ui")]
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use rustc_hash::FxHashMap;
+
+use crate::simulation::SimulationInput;
+use crate::simulation::engine::{TILE_SIZE, Tile};
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::universe::Universe;
+
+/// Larger universes aren't measured; comparing their tiles every frame would stall it.
+const MAX_POPULATION: u64 = 2_000_000;
+/// Samples kept for the plot, one per pixel column.
+const HISTORY_LEN: usize = 200;
+/// Side of the square blocks the entropy counts cells in.
+const BLOCK_SIZE: usize = 8;
+#[cfg(feature = "ui")]
+const PLOT_HEIGHT: usize = 60;
+
+/// F11 measures how busy the universe is, to tell when a soup has settled: its activity,
+/// the share of live cells changing per generation (the popcount of consecutive states
+/// XORed tile by tile), and its entropy, how evenly the cells spread over 8×8 blocks in
+/// bits. Both go on the stats board and, with the `ui` feature, into a plot in the
+/// bottom left corner (activity orange, entropy blue). Once a soup settles the entropy
+/// flattens out and only oscillators keep the activity above zero.
+pub struct EnergyPlugin;
+
+impl Plugin for EnergyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Energy>().add_systems(
+            Update,
+            (toggle_energy.in_set(SimulationInput), measure_energy).chain(),
+        );
+        #[cfg(feature = "ui")]
+        app.add_systems(Startup, setup_energy_plot)
+            .add_systems(Update, update_energy_plot.after(measure_energy));
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Energy {
+    pub enabled: bool,
+    /// Cells that changed per generation, as a share of the live cells.
+    pub activity: f64,
+    /// Shannon entropy of the live cells over blocks, in bits.
+    pub entropy: f64,
+    /// Recent (activity, entropy) samples, oldest first.
+    pub history: VecDeque<(f64, f64)>,
+    // The state last measured, and its generation
+    previous: FxHashMap<I64Vec2, Tile>,
+    generation: u64,
+}
+
+// F11: toggle the activity and entropy metrics
+fn toggle_energy(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut energy: ResMut<Energy>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    if !keys.just_pressed(KeyCode::F11) {
+        return;
+    }
+    energy.enabled = !energy.enabled;
+    if !energy.enabled {
+        *energy = Energy::default();
+        stats.remove("Activity");
+        stats.remove("Entropy");
+    }
+}
+
+fn measure_energy(
+    universe: Res<Universe>,
+    mut energy: ResMut<Energy>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    if !energy.enabled {
+        return;
+    }
+    let generation = universe.generation();
+    if generation == energy.generation && !energy.previous.is_empty() {
+        return;
+    }
+    let population = universe.population();
+    if population > MAX_POPULATION {
+        energy.previous.clear();
+        stats.insert(
+            "Activity",
+            format!("not measured over {MAX_POPULATION} cells"),
+        );
+        stats.remove("Entropy");
+        return;
+    }
+
+    // Engines may hand over parts of a tile separately
+    let mut tiles: FxHashMap<I64Vec2, Tile> = FxHashMap::default();
+    universe.read_engine().visit_tiles(&mut |pos, tile| {
+        let merged = tiles.entry(pos).or_insert([0; TILE_SIZE as usize]);
+        for (row, bits) in merged.iter_mut().zip(tile) {
+            *row |= bits;
+        }
+    });
+
+    let entropy = entropy(&tiles);
+    // Going back (undo, loading) has no meaningful rate
+    let elapsed = generation.checked_sub(energy.generation).filter(|&n| n > 0);
+    let activity = match elapsed {
+        Some(elapsed) if !energy.previous.is_empty() => {
+            changed_cells(&energy.previous, &tiles) as f64
+                / elapsed as f64
+                / population.max(1) as f64
+        }
+        _ => 0.0,
+    };
+
+    energy.previous = tiles;
+    energy.generation = generation;
+    energy.activity = activity;
+    energy.entropy = entropy;
+    if energy.history.len() == HISTORY_LEN {
+        energy.history.pop_front();
+    }
+    energy.history.push_back((activity, entropy));
+
+    stats.insert(
+        "Activity",
+        format!("{:.2}% of cells per generation", activity * 100.0),
+    );
+    stats.insert("Entropy", format!("{entropy:.2} bits"));
+}
+
+/// Cells alive in one state but not the other.
+fn changed_cells(before: &FxHashMap<I64Vec2, Tile>, after: &FxHashMap<I64Vec2, Tile>) -> u64 {
+    let popcount = |tile: &Tile| tile.iter().map(|row| row.count_ones() as u64).sum::<u64>();
+    let mut changed = 0;
+    for (pos, tile) in after {
+        changed += match before.get(pos) {
+            Some(old) => old
+                .iter()
+                .zip(tile)
+                .map(|(a, b)| (a ^ b).count_ones() as u64)
+                .sum(),
+            None => popcount(tile),
+        };
+    }
+    for (pos, tile) in before {
+        if !after.contains_key(pos) {
+            changed += popcount(tile);
+        }
+    }
+    changed
+}
+
+/// Shannon entropy of the share of live cells in each [`BLOCK_SIZE`] block.
+fn entropy(tiles: &FxHashMap<I64Vec2, Tile>) -> f64 {
+    let blocks = TILE_SIZE as usize / BLOCK_SIZE;
+    let mask = (1u64 << BLOCK_SIZE) - 1;
+    let mut counts = Vec::new();
+    for tile in tiles.values() {
+        for rows in tile.chunks_exact(BLOCK_SIZE) {
+            for bx in 0..blocks {
+                let count: u32 = rows
+                    .iter()
+                    .map(|row| ((row >> (bx * BLOCK_SIZE)) & mask).count_ones())
+                    .sum();
+                if count > 0 {
+                    counts.push(count as f64);
+                }
+            }
+        }
+    }
+    let total: f64 = counts.iter().sum();
+    counts
+        .iter()
+        .map(|count| {
+            let p = count / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+#[cfg(feature = "ui")]
+#[derive(Component)]
+struct EnergyPlot;
+
+#[cfg(feature = "ui")]
+fn setup_energy_plot(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let image = Image::new_fill(
+        Extent3d {
+            width: HISTORY_LEN as u32,
+            height: PLOT_HEIGHT as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD | RenderAssetUsages::MAIN_WORLD,
+    );
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(10.0),
+            left: Val::Px(10.0),
+            width: Val::Px(HISTORY_LEN as f32),
+            height: Val::Px(PLOT_HEIGHT as f32),
+            ..default()
+        },
+        BackgroundColor(Color::BLACK.with_alpha(0.7)),
+        ImageNode::new(images.add(image)),
+        GlobalZIndex(90),
+        Visibility::Hidden,
+        EnergyPlot,
+    ));
+}
+
+#[cfg(feature = "ui")]
+fn update_energy_plot(
+    energy: Res<Energy>,
+    mut images: ResMut<Assets<Image>>,
+    mut q_plot: Query<(&mut Visibility, &ImageNode), With<EnergyPlot>>,
+) {
+    const ACTIVITY: [u8; 4] = [255, 150, 30, 255];
+    const ENTROPY: [u8; 4] = [80, 160, 255, 255];
+
+    if !energy.is_changed() {
+        return;
+    }
+    let Ok((mut visibility, node)) = q_plot.single_mut() else {
+        return;
+    };
+    visibility.set_if_neq(if energy.enabled {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    });
+    let Some(data) = images
+        .get_mut(&node.image)
+        .and_then(|image| image.data.as_mut())
+    else {
+        return;
+    };
+
+    data.fill(0);
+    // Each line scaled to its own peak, newest samples on the right
+    let peak = |value: fn(&(f64, f64)) -> f64| {
+        energy
+            .history
+            .iter()
+            .map(value)
+            .fold(f64::EPSILON, f64::max)
+    };
+    let activity_peak = peak(|sample| sample.0);
+    let entropy_peak = peak(|sample| sample.1);
+    let offset = HISTORY_LEN - energy.history.len();
+    for (i, &(activity, entropy)) in energy.history.iter().enumerate() {
+        for (value, color) in [
+            (activity / activity_peak, ACTIVITY),
+            (entropy / entropy_peak, ENTROPY),
+        ] {
+            let y = ((1.0 - value) * (PLOT_HEIGHT - 1) as f64).round() as usize;
+            let idx = (y * HISTORY_LEN + offset + i) * 4;
+            data[idx..idx + 4].copy_from_slice(&color);
+        }
+    }
+}
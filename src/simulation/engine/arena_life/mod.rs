@@ -1,7 +1,12 @@
-use crate::simulation::engine::LifeEngine;
+use crate::simulation::engine::block_kernel::{self, Window};
+use crate::simulation::engine::{
+    BlockStats, CellRegion, LifeEngine, PasteMode, Tile, blocks_touched, draw_blocks_sparse,
+};
+use crate::simulation::par::*;
+use bevy::log::info_span;
 use bevy::math::{I64Vec2, Rect};
-use rayon::prelude::*;
 use rustc_hash::FxHashMap;
+use std::sync::Arc;
 use thunderdome::{Arena, Index};
 
 const BLOCK_SIZE: usize = 64;
@@ -18,6 +23,8 @@ const SE: usize = 7;
 #[derive(Clone, Copy)]
 struct Block {
     rows: [u64; BLOCK_SIZE],
+    // Wall bit-plane: counts as alive for neighbours, but never changes
+    walls: [u64; BLOCK_SIZE],
     // Cache the Index of neighbors.
     neighbors: [Option<Index>; 8],
     alive: bool,
@@ -27,18 +34,21 @@ impl Default for Block {
     fn default() -> Self {
         Self {
             rows: [0; BLOCK_SIZE],
+            walls: [0; BLOCK_SIZE],
             neighbors: [None; 8],
             alive: false,
         }
     }
 }
 
+/// Clones share the blocks until either side changes them (copy on write), so forking
+/// the universe is cheap; the first step or edit of either copy pays for the copy.
 #[derive(Clone)]
 pub struct ArenaLife {
     // The Data Store
-    arena: Arena<Block>,
+    arena: Arc<Arena<Block>>,
     // The Spatial Map
-    lookup: FxHashMap<I64Vec2, Index>,
+    lookup: Arc<FxHashMap<I64Vec2, Index>>,
 
     // Scratchpads
     active_indices: Vec<(I64Vec2, Index)>,
@@ -46,17 +56,22 @@ pub struct ArenaLife {
     update_buffer: Vec<(Index, [u64; BLOCK_SIZE], bool)>,
 
     generation: u64,
+    // Cells outside keep their state when stepping
+    step_region: Option<CellRegion>,
+    block_stats: BlockStats,
 }
 
 impl ArenaLife {
     pub fn new() -> Self {
         Self {
-            arena: Arena::new(),
-            lookup: FxHashMap::default(),
+            arena: Arc::new(Arena::new()),
+            lookup: Arc::default(),
             active_indices: Vec::new(),
             growth_requests: Vec::new(),
             update_buffer: Vec::new(),
             generation: 0,
+            step_region: None,
+            block_stats: BlockStats::default(),
         }
     }
 
@@ -81,11 +96,12 @@ impl ArenaLife {
             (1, 1, SE, NW),
         ];
 
+        let arena = Arc::make_mut(&mut self.arena);
         for &(dx, dy, dir, opp_dir) in &offsets {
             let neighbor_pos = pos + I64Vec2::new(dx, dy);
             if let Some(&n_idx) = self.lookup.get(&neighbor_pos) {
-                self.arena[idx].neighbors[dir] = Some(n_idx);
-                self.arena[n_idx].neighbors[opp_dir] = Some(idx);
+                arena[idx].neighbors[dir] = Some(n_idx);
+                arena[n_idx].neighbors[opp_dir] = Some(idx);
             }
         }
     }
@@ -94,8 +110,8 @@ impl ArenaLife {
         if let Some(&idx) = self.lookup.get(&pos) {
             idx
         } else {
-            let idx = self.arena.insert(Block::default());
-            self.lookup.insert(pos, idx);
+            let idx = Arc::make_mut(&mut self.arena).insert(Block::default());
+            Arc::make_mut(&mut self.lookup).insert(pos, idx);
             self.link(pos, idx);
             idx
         }
@@ -104,54 +120,20 @@ impl ArenaLife {
     // --- Rendering Helpers ---
 
     /// Path A: Sparse Rendering (World Space -> Screen Space)
-    /// Used when population is low. Iterates active blocks and draws rectangles.
-    fn draw_sparse(&self, rect: Rect, buffer: &mut [u8], width: usize, height: usize, scale: f64) {
-        // Clear buffer first (memset optimized)
-        buffer.fill(0);
-
-        let view_min_x = rect.min.x as f64;
-        let view_min_y = rect.min.y as f64;
-        let bs = BLOCK_SIZE as i64;
-        let block_screen_size = bs as f64 * scale;
-
-        for (chunk_pos, &block_idx) in &self.lookup {
-            let block = &self.arena[block_idx];
-            if !block.alive {
-                continue;
-            }
-
-            // Culling
-            let block_world_x = chunk_pos.x * bs;
-            let block_world_y = chunk_pos.y * bs;
-            let screen_block_x = (block_world_x as f64 - view_min_x) * scale;
-            let screen_block_y = (block_world_y as f64 - view_min_y) * scale;
-
-            if screen_block_x > width as f64
-                || screen_block_x + block_screen_size < 0.0
-                || screen_block_y > height as f64
-                || screen_block_y + block_screen_size < 0.0
-            {
-                continue;
-            }
-
-            for ly in 0..BLOCK_SIZE {
-                let row = block.rows[ly];
-                if row == 0 {
-                    continue;
-                }
-
-                let world_y = (block_world_y + ly as i64) as f64;
-                let sy = (world_y - view_min_y) * scale;
-
-                for lx in 0..BLOCK_SIZE {
-                    if (row >> lx) & 1 == 1 {
-                        let world_x = (block_world_x + lx as i64) as f64;
-                        let sx = (world_x - view_min_x) * scale;
-                        self.fill_rect_safe(buffer, width, height, sx, sy, scale);
-                    }
-                }
-            }
-        }
+    /// Used when population is low. Draws the cells of the live blocks under the view,
+    /// in parallel bands.
+    fn draw_sparse(&self, rect: Rect, buffer: &mut [u8], width: usize, scale: f64) {
+        draw_blocks_sparse(
+            &*self.lookup,
+            |&idx| {
+                let block = &self.arena[idx];
+                block.alive.then_some(&block.rows)
+            },
+            rect,
+            buffer,
+            width,
+            scale,
+        );
     }
 
     /// Path B: Dense Rendering (Screen Space -> World Space)
@@ -247,37 +229,39 @@ impl ArenaLife {
             });
     }
 
-    /// Safe rectangle filler using rounding to avoid 'fat' blocks
-    fn fill_rect_safe(
-        &self,
-        buffer: &mut [u8],
-        width: usize,
-        height: usize,
-        x: f64,
-        y: f64,
-        size: f64,
-    ) {
-        let effective_size = size.max(1.0);
-
-        // FIX: Rounding instead of Floor/Ceil prevents drift and overshoot
-        let start_x = x.round() as isize;
-        let start_y = y.round() as isize;
-        let end_x = (x + effective_size).round() as isize;
-        let end_y = (y + effective_size).round() as isize;
-
-        let sx = start_x.max(0).min(width as isize) as usize;
-        let sy = start_y.max(0).min(height as isize) as usize;
-        let ex = end_x.max(0).min(width as isize) as usize;
-        let ey = end_y.max(0).min(height as isize) as usize;
-
-        if sx >= ex || sy >= ey {
-            return;
-        }
+    /// Per-row bit masks of the cells of the block at `pos` that lie inside `region`.
+    fn region_mask(region: &CellRegion, pos: I64Vec2) -> [u64; BLOCK_SIZE] {
+        let bs = BLOCK_SIZE as i64;
+        let base = pos * bs;
+        let x0 = (region.min.x - base.x).clamp(0, bs);
+        let x1 = (region.max.x - base.x).clamp(0, bs);
+        let y0 = (region.min.y - base.y).clamp(0, bs) as usize;
+        let y1 = (region.max.y - base.y).clamp(0, bs) as usize;
+
+        let columns = match x1 - x0 {
+            0 => 0,
+            64 => !0u64,
+            w => ((1u64 << w) - 1) << x0,
+        };
+        std::array::from_fn(|y| if (y0..y1).contains(&y) { columns } else { 0 })
+    }
 
-        for row in sy..ey {
-            let offset = row * width;
-            buffer[offset + sx..offset + ex].fill(255);
+    /// Reverts the cells of the block at `pos` that lie outside `region` to their
+    /// `current` state. Returns whether the block still has live cells.
+    #[allow(clippy::needless_range_loop)]
+    fn freeze_outside(
+        region: &CellRegion,
+        pos: I64Vec2,
+        current: &[u64; BLOCK_SIZE],
+        next: &mut [u64; BLOCK_SIZE],
+    ) -> bool {
+        let mask = Self::region_mask(region, pos);
+        let mut alive = false;
+        for y in 0..BLOCK_SIZE {
+            next[y] = (next[y] & mask[y]) | (current[y] & !mask[y]);
+            alive |= next[y] != 0;
         }
+        alive
     }
 
     fn evolve_block_internal(
@@ -285,125 +269,40 @@ impl ArenaLife {
         current_idx: Index,
     ) -> ([u64; BLOCK_SIZE], bool, u8) {
         let current = &arena[current_idx];
-        let mut next_rows = [0u64; BLOCK_SIZE];
-        let mut is_alive = false;
+        // Walls take part in the neighbour counts like live cells
+        let occupied: [u64; BLOCK_SIZE] =
+            std::array::from_fn(|y| current.rows[y] | current.walls[y]);
         let mut growth_flags: u8 = 0;
 
-        macro_rules! calc_row {
-            ($y_idx:expr, $up:expr, $center:expr, $down:expr, $w_bit_u:expr, $w_bit_c:expr, $w_bit_d:expr, $e_bit_u:expr, $e_bit_c:expr, $e_bit_d:expr) => {{
-                let l_up = ($up << 1) | $w_bit_u;
-                let r_up = ($up >> 1) | $e_bit_u;
-                let l_curr = ($center << 1) | $w_bit_c;
-                let r_curr = ($center >> 1) | $e_bit_c;
-                let l_down = ($down << 1) | $w_bit_d;
-                let r_down = ($down >> 1) | $e_bit_d;
-
-                let mut s0 = 0u64;
-                let mut s1 = 0u64;
-                let mut s2 = 0u64;
-
-                for x in [l_up, $up, r_up, l_curr, r_curr, l_down, $down, r_down] {
-                    let c0 = s0 & x;
-                    s0 ^= x;
-                    let c1 = s1 & c0;
-                    s1 ^= c0;
-                    s2 |= c1;
-                }
-
-                let res = (s1 & !s2) & ($center | s0);
-                next_rows[$y_idx] = res;
-                if res != 0 {
-                    is_alive = true;
-                }
-            }};
-        }
-
-        let get_row = |dir: usize, row: usize| -> u64 {
-            match current.neighbors[dir] {
-                Some(idx) => arena[idx].rows[row],
-                None => 0,
-            }
-        };
-
-        let bit_w = |dir: usize, row: usize| -> u64 {
-            match current.neighbors[dir] {
-                Some(idx) => (arena[idx].rows[row] >> 63) & 1,
-                None => 0,
-            }
-        };
-
-        let bit_e = |dir: usize, row: usize| -> u64 {
-            match current.neighbors[dir] {
-                Some(idx) => (arena[idx].rows[row] & 1) << 63,
-                None => 0,
-            }
-        };
-
-        {
-            let up = get_row(N, BLOCK_SIZE - 1);
-            let center = current.rows[0];
-            let down = current.rows[1];
-            if center != 0 && current.neighbors[N].is_none() {
-                growth_flags |= 1 << N;
-            }
-            calc_row!(
-                0,
-                up,
-                center,
-                down,
-                bit_w(NW, BLOCK_SIZE - 1),
-                bit_w(W, 0),
-                bit_w(W, 1),
-                bit_e(NE, BLOCK_SIZE - 1),
-                bit_e(E, 0),
-                bit_e(E, 1)
-            );
+        let window = Window::gather(&occupied, |dx, dy, y| {
+            let dir = match (dx, dy) {
+                (0, -1) => N,
+                (0, 1) => S,
+                (-1, 0) => W,
+                (1, 0) => E,
+                (-1, -1) => NW,
+                (1, -1) => NE,
+                (-1, 1) => SW,
+                _ => SE,
+            };
+            current.neighbors[dir].map_or(0, |idx| arena[idx].rows[y] | arena[idx].walls[y])
+        });
+        let mut next_rows = block_kernel::step(&window);
+        let mut is_alive = false;
+        for (row, walls) in next_rows.iter_mut().zip(&current.walls) {
+            *row &= !walls;
+            is_alive |= *row != 0;
         }
 
-        for y in 1..BLOCK_SIZE - 1 {
-            let up = current.rows[y - 1];
-            let center = current.rows[y];
-            let down = current.rows[y + 1];
-            if up | center | down == 0 {
-                continue;
-            }
-            calc_row!(
-                y,
-                up,
-                center,
-                down,
-                bit_w(W, y - 1),
-                bit_w(W, y),
-                bit_w(W, y + 1),
-                bit_e(E, y - 1),
-                bit_e(E, y),
-                bit_e(E, y + 1)
-            );
+        if occupied[0] != 0 && current.neighbors[N].is_none() {
+            growth_flags |= 1 << N;
         }
-
-        {
-            let up = current.rows[BLOCK_SIZE - 2];
-            let center = current.rows[BLOCK_SIZE - 1];
-            let down = get_row(S, 0);
-            if center != 0 && current.neighbors[S].is_none() {
-                growth_flags |= 1 << S;
-            }
-            calc_row!(
-                BLOCK_SIZE - 1,
-                up,
-                center,
-                down,
-                bit_w(W, BLOCK_SIZE - 2),
-                bit_w(W, BLOCK_SIZE - 1),
-                bit_w(SW, 0),
-                bit_e(E, BLOCK_SIZE - 2),
-                bit_e(E, BLOCK_SIZE - 1),
-                bit_e(SE, 0)
-            );
+        if occupied[BLOCK_SIZE - 1] != 0 && current.neighbors[S].is_none() {
+            growth_flags |= 1 << S;
         }
 
         let mut all_or = 0u64;
-        for r in current.rows {
+        for r in occupied {
             all_or |= r;
         }
 
@@ -413,16 +312,16 @@ impl ArenaLife {
         if (all_or & 1) != 0 && current.neighbors[E].is_none() {
             growth_flags |= 1 << E;
         }
-        if (current.rows[0] >> 63) & 1 == 1 && current.neighbors[NW].is_none() {
+        if (occupied[0] >> 63) & 1 == 1 && current.neighbors[NW].is_none() {
             growth_flags |= 1 << NW;
         }
-        if (current.rows[0] & 1) == 1 && current.neighbors[NE].is_none() {
+        if (occupied[0] & 1) == 1 && current.neighbors[NE].is_none() {
             growth_flags |= 1 << NE;
         }
-        if (current.rows[BLOCK_SIZE - 1] >> 63) & 1 == 1 && current.neighbors[SW].is_none() {
+        if (occupied[BLOCK_SIZE - 1] >> 63) & 1 == 1 && current.neighbors[SW].is_none() {
             growth_flags |= 1 << SW;
         }
-        if (current.rows[BLOCK_SIZE - 1] & 1) == 1 && current.neighbors[SE].is_none() {
+        if (occupied[BLOCK_SIZE - 1] & 1) == 1 && current.neighbors[SE].is_none() {
             growth_flags |= 1 << SE;
         }
 
@@ -446,6 +345,31 @@ impl LifeEngine for ArenaLife {
             .sum()
     }
 
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn set_generation(&mut self, generation: u64) {
+        self.generation = generation;
+    }
+
+    fn memory_bytes(&self) -> usize {
+        self.arena.capacity() * std::mem::size_of::<Block>()
+            + self.lookup.capacity()
+                * (std::mem::size_of::<I64Vec2>() + std::mem::size_of::<Index>())
+    }
+
+    fn estimate_memory(&self, cells: u64, size: I64Vec2) -> u64 {
+        let entry = std::mem::size_of::<Block>()
+            + std::mem::size_of::<I64Vec2>()
+            + std::mem::size_of::<Index>();
+        blocks_touched(cells, size, BLOCK_SIZE as i64).saturating_mul(entry as u64)
+    }
+
+    fn block_stats(&self) -> Option<BlockStats> {
+        Some(self.block_stats)
+    }
+
     fn set_cell(&mut self, pos: I64Vec2, alive: bool) {
         self.set_cells(&[pos], alive);
     }
@@ -454,9 +378,9 @@ impl LifeEngine for ArenaLife {
         for &pos in coords {
             let (chunk_pos, lx, ly) = Self::get_coords(pos.x, pos.y);
             let idx = self.spawn_block(chunk_pos);
-            let block = &mut self.arena[idx];
+            let block = &mut Arc::make_mut(&mut self.arena)[idx];
             if alive {
-                block.rows[ly] |= 1u64 << lx;
+                block.rows[ly] |= (1u64 << lx) & !block.walls[ly];
                 block.alive = true;
             } else {
                 block.rows[ly] &= !(1u64 << lx);
@@ -474,15 +398,16 @@ impl LifeEngine for ArenaLife {
     }
 
     fn clear(&mut self) {
-        self.arena.clear();
-        self.lookup.clear();
+        self.arena = Arc::new(Arena::new());
+        self.lookup = Arc::default();
         self.active_indices.clear();
         self.generation = 0;
     }
 
     fn export(&self) -> Vec<I64Vec2> {
+        let _span = info_span!("arena_life::export").entered();
         let mut cells = Vec::new();
-        for (pos, &idx) in &self.lookup {
+        for (pos, &idx) in self.lookup.iter() {
             let block = &self.arena[idx];
             if !block.alive {
                 continue;
@@ -504,12 +429,37 @@ impl LifeEngine for ArenaLife {
         cells
     }
 
+    // Blocks are tiles
+    fn visit_tiles(&self, visit: &mut dyn FnMut(I64Vec2, &Tile)) {
+        for (pos, &idx) in self.lookup.iter() {
+            let block = &self.arena[idx];
+            if block.alive {
+                visit(*pos, &block.rows);
+            }
+        }
+    }
+
+    fn add_tile(&mut self, pos: I64Vec2, tile: &Tile) {
+        if tile.iter().all(|&row| row == 0) {
+            return;
+        }
+        let idx = self.spawn_block(pos);
+        let block = &mut Arc::make_mut(&mut self.arena)[idx];
+        for (row, (&cells, &walls)) in block.rows.iter_mut().zip(tile.iter().zip(&block.walls)) {
+            *row |= cells & !walls;
+        }
+        block.alive = true;
+    }
+
     fn import(&mut self, alive_cells: &[I64Vec2]) {
+        let _span = info_span!("arena_life::import", cells = alive_cells.len()).entered();
         self.clear();
         self.set_cells(alive_cells, true);
     }
 
     fn step(&mut self, steps: u64) -> u64 {
+        let _span = info_span!("arena_life::step", steps).entered();
+        self.block_stats = BlockStats::default();
         for _ in 0..steps {
             self.active_indices.clear();
             self.active_indices
@@ -517,15 +467,27 @@ impl LifeEngine for ArenaLife {
             self.growth_requests.clear();
             self.update_buffer.clear();
 
-            let arena_ref = &self.arena;
+            let arena_ref: &Arena<Block> = &self.arena;
+            let step_region = self.step_region;
+            let evolve_span = info_span!(
+                "arena_life::evolve_blocks",
+                blocks = self.active_indices.len()
+            )
+            .entered();
             let results: Vec<_> = self
                 .active_indices
                 .par_iter()
                 .map(|&(pos, idx)| {
-                    let (next_rows, alive, growth) = Self::evolve_block_internal(arena_ref, idx);
+                    let (mut next_rows, mut alive, growth) =
+                        Self::evolve_block_internal(arena_ref, idx);
+                    if let Some(region) = &step_region {
+                        alive =
+                            Self::freeze_outside(region, pos, &arena_ref[idx].rows, &mut next_rows);
+                    }
                     (idx, pos, next_rows, alive, growth)
                 })
                 .collect();
+            drop(evolve_span);
 
             for (idx, pos, next_rows, alive, growth_flags) in results {
                 self.update_buffer.push((idx, next_rows, alive));
@@ -557,26 +519,42 @@ impl LifeEngine for ArenaLife {
                 }
             }
 
+            let arena = Arc::make_mut(&mut self.arena);
             for (idx, rows, alive) in self.update_buffer.drain(..) {
-                let block = &mut self.arena[idx];
+                let block = &mut arena[idx];
                 block.rows = rows;
                 block.alive = alive;
             }
 
+            let _growth_span = info_span!("arena_life::spawn_growth").entered();
             self.growth_requests
                 .sort_unstable_by(|a, b| a.x.cmp(&b.x).then(a.y.cmp(&b.y)));
             self.growth_requests.dedup();
             let mut local_requests = std::mem::take(&mut self.growth_requests);
+            let blocks_before = self.lookup.len();
             for pos in local_requests.drain(..) {
                 self.spawn_block(pos);
             }
             self.growth_requests = local_requests;
+
+            // Every block is evaluated and none are freed, emptied ones included
+            let stats = &mut self.block_stats;
+            stats.generations += 1;
+            stats.evaluated += self.active_indices.len() as u64;
+            stats.spawned += (self.lookup.len() - blocks_before) as u64;
             self.generation += 1;
         }
         steps
     }
 
-    fn draw_to_buffer(&self, rect: Rect, buffer: &mut [u8], width: usize, height: usize) {
+    fn draw_to_buffer(
+        &self,
+        rect: Rect,
+        buffer: &mut [u8],
+        width: usize,
+        height: usize,
+        population: u64,
+    ) {
         let scale = width as f64 / rect.width() as f64;
 
         if scale <= 0.0001 || scale.is_infinite() || scale.is_nan() {
@@ -584,15 +562,93 @@ impl LifeEngine for ArenaLife {
         }
 
         let total_pixels = width * height;
-        let is_sparse = self.population() < (total_pixels as u64 / 10) || scale > 0.5;
+        let is_sparse = population < (total_pixels as u64 / 10) || scale > 0.5;
 
+        let _span = info_span!(
+            "arena_life::draw",
+            path = if is_sparse { "sparse" } else { "dense" }
+        )
+        .entered();
         if is_sparse {
-            self.draw_sparse(rect, buffer, width, height, scale);
+            self.draw_sparse(rect, buffer, width, scale);
         } else {
             self.draw_dense(rect, buffer, width, scale);
         }
     }
 
+    #[allow(clippy::needless_range_loop)]
+    fn paste(&mut self, region: CellRegion, cells: &[I64Vec2], mode: PasteMode) {
+        let _span = info_span!("arena_life::paste", cells = cells.len(), ?mode).entered();
+
+        // The pattern as bit rows per block
+        let mut pattern: FxHashMap<I64Vec2, [u64; BLOCK_SIZE]> = FxHashMap::default();
+        for &pos in cells {
+            let (chunk_pos, lx, ly) = Self::get_coords(pos.x, pos.y);
+            pattern.entry(chunk_pos).or_insert([0; BLOCK_SIZE])[ly] |= 1u64 << lx;
+        }
+        for &chunk_pos in pattern.keys() {
+            self.spawn_block(chunk_pos);
+        }
+
+        // And/Copy also clear cells in the region that the pattern doesn't cover
+        let clears_region = matches!(mode, PasteMode::And | PasteMode::Copy);
+        let empty = [0u64; BLOCK_SIZE];
+        let arena = Arc::make_mut(&mut self.arena);
+        for (chunk_pos, &idx) in self.lookup.iter() {
+            let bits = pattern.get(chunk_pos);
+            if bits.is_none() && !clears_region {
+                continue;
+            }
+            let bits = bits.unwrap_or(&empty);
+            let mask = if clears_region {
+                Self::region_mask(&region, *chunk_pos)
+            } else {
+                empty
+            };
+
+            let block = &mut arena[idx];
+            let mut alive = false;
+            for y in 0..BLOCK_SIZE {
+                let row = block.rows[y];
+                let next = match mode {
+                    PasteMode::Or => row | bits[y],
+                    PasteMode::Xor => row ^ bits[y],
+                    PasteMode::And => row & (bits[y] | !mask[y]),
+                    PasteMode::Copy => (row & !mask[y]) | bits[y],
+                };
+                block.rows[y] = next & !block.walls[y];
+                alive |= block.rows[y] != 0;
+            }
+            block.alive = alive;
+        }
+    }
+
+    fn supports_walls(&self) -> bool {
+        true
+    }
+
+    fn set_walls(&mut self, coords: &[I64Vec2], wall: bool) {
+        for &pos in coords {
+            let (chunk_pos, lx, ly) = Self::get_coords(pos.x, pos.y);
+            let idx = self.spawn_block(chunk_pos);
+            let block = &mut Arc::make_mut(&mut self.arena)[idx];
+            if wall {
+                block.walls[ly] |= 1u64 << lx;
+                block.rows[ly] &= !(1u64 << lx);
+            } else {
+                block.walls[ly] &= !(1u64 << lx);
+            }
+        }
+    }
+
+    fn supports_step_region(&self) -> bool {
+        true
+    }
+
+    fn set_step_region(&mut self, region: Option<CellRegion>) {
+        self.step_region = region;
+    }
+
     fn box_clone(&self) -> Box<dyn LifeEngine> {
         Box::new(self.clone())
     }
@@ -0,0 +1,336 @@
+//! The bit-parallel Life step shared by the block engines, compiled once per instruction
+//! set and picked at runtime, so one build runs the widest kernel the CPU has.
+//!
+//! Each row of a block is independent, so the vector kernels step 2 (SSE2, NEON) or 4
+//! (AVX2) rows at once: the scalar step written out with the instruction set's
+//! intrinsics on 128- or 256-bit vectors of rows. `LIFE_KERNEL=scalar|sse2|avx2|neon`
+//! forces a kernel the CPU supports, for comparing.
+
+use bevy::log::{info, warn};
+use std::sync::OnceLock;
+
+pub const ROWS: usize = 64;
+
+/// A block's rows with what its neighbours add at the edges.
+pub struct Window {
+    /// `rows[0]` is the last row of the block above, `rows[ROWS + 1]` the first row of
+    /// the block below.
+    pub rows: [u64; ROWS + 2],
+    /// Per row, the last column of the west neighbour, in bit 0.
+    pub west: [u64; ROWS + 2],
+    /// Per row, the first column of the east neighbour, in bit 63.
+    pub east: [u64; ROWS + 2],
+}
+
+impl Window {
+    /// `neighbour(dx, dy, y)` is row `y` of the block at offset (`dx`, `dy`), or 0 if
+    /// there is none.
+    pub fn gather(center: &[u64; ROWS], neighbour: impl Fn(i64, i64, usize) -> u64) -> Self {
+        let mut rows = [0; ROWS + 2];
+        rows[0] = neighbour(0, -1, ROWS - 1);
+        rows[1..=ROWS].copy_from_slice(center);
+        rows[ROWS + 1] = neighbour(0, 1, 0);
+
+        // Row i of the window is row i - 1 of the block, reaching into the corners
+        let edge = |dx: i64, i: usize| match i {
+            0 => neighbour(dx, -1, ROWS - 1),
+            i if i == ROWS + 1 => neighbour(dx, 1, 0),
+            i => neighbour(dx, 0, i - 1),
+        };
+        Self {
+            rows,
+            west: std::array::from_fn(|i| (edge(-1, i) >> 63) & 1),
+            east: std::array::from_fn(|i| (edge(1, i) & 1) << 63),
+        }
+    }
+}
+
+// Only the current architecture's kernels are ever picked
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kernel {
+    Scalar,
+    Sse2,
+    Avx2,
+    Neon,
+}
+
+impl Kernel {
+    pub fn name(self) -> &'static str {
+        match self {
+            Kernel::Scalar => "scalar",
+            Kernel::Sse2 => "sse2",
+            Kernel::Avx2 => "avx2",
+            Kernel::Neon => "neon",
+        }
+    }
+
+    /// The kernel steps run with, detected on first use.
+    pub fn selected() -> Self {
+        static SELECTED: OnceLock<Kernel> = OnceLock::new();
+        *SELECTED.get_or_init(|| {
+            let best = Self::supported().last().copied().unwrap_or(Kernel::Scalar);
+            let kernel = match std::env::var("LIFE_KERNEL") {
+                Ok(name) => match Self::supported().into_iter().find(|k| k.name() == name) {
+                    Some(kernel) => kernel,
+                    None => {
+                        warn!(
+                            "LIFE_KERNEL={name} isn't supported here, using {}",
+                            best.name()
+                        );
+                        best
+                    }
+                },
+                Err(_) => best,
+            };
+            info!("Block step kernel: {}", kernel.name());
+            kernel
+        })
+    }
+
+    /// Kernels this CPU can run, slowest first.
+    pub fn supported() -> Vec<Self> {
+        #[allow(unused_mut)]
+        let mut kernels = vec![Kernel::Scalar];
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            if std::arch::is_x86_feature_detected!("sse2") {
+                kernels.push(Kernel::Sse2);
+            }
+            if std::arch::is_x86_feature_detected!("avx2") {
+                kernels.push(Kernel::Avx2);
+            }
+        }
+        #[cfg(target_arch = "aarch64")]
+        if std::arch::is_aarch64_feature_detected!("neon") {
+            kernels.push(Kernel::Neon);
+        }
+        kernels
+    }
+}
+
+/// The next generation of the block in `window`, with the selected kernel.
+pub fn step(window: &Window) -> [u64; ROWS] {
+    match Kernel::selected() {
+        // SAFETY: selected() only returns kernels the CPU was detected to support
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        Kernel::Avx2 => unsafe { step_avx2(window) },
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        Kernel::Sse2 => unsafe { step_sse2(window) },
+        #[cfg(target_arch = "aarch64")]
+        Kernel::Neon => unsafe { step_neon(window) },
+        _ => step_rows(window),
+    }
+}
+
+/// [`step_rows`] on vectors of `$v`, as many rows as fit, with the instruction set's
+/// bitwise operations and 64-bit lane shifts. `clear(a, b)` is `a & !b`.
+macro_rules! step_vectors {
+    (
+        $window:expr,
+        $v:ty,
+        $and:ident,
+        $or:ident,
+        $xor:ident,
+        |$a:ident, $b:ident| $clear:expr,
+        $shl:ident,
+        $shr:ident $(,)?
+    ) => {{
+        const LANES: usize = size_of::<$v>() / 8;
+        let Window { rows, west, east } = $window;
+        let mut next = [0u64; ROWS];
+        for y in (0..ROWS).step_by(LANES) {
+            // SAFETY: the kernel's instruction set was detected before it was picked
+            unsafe {
+                let (up, center, down): ($v, $v, $v) =
+                    (load(rows, y), load(rows, y + 1), load(rows, y + 2));
+                let neighbours: [$v; 8] = [
+                    $or($shl::<1>(up), load(west, y)),
+                    up,
+                    $or($shr::<1>(up), load(east, y)),
+                    $or($shl::<1>(center), load(west, y + 1)),
+                    $or($shr::<1>(center), load(east, y + 1)),
+                    $or($shl::<1>(down), load(west, y + 2)),
+                    down,
+                    $or($shr::<1>(down), load(east, y + 2)),
+                ];
+
+                let zero = $xor(up, up);
+                let (mut s0, mut s1, mut s2) = (zero, zero, zero);
+                for x in neighbours {
+                    let c0 = $and(s0, x);
+                    s0 = $xor(s0, x);
+                    let c1 = $and(s1, c0);
+                    s1 = $xor(s1, c0);
+                    s2 = $or(s2, c1);
+                }
+                let ($a, $b) = (s1, s2);
+                store(&mut next, y, $and($clear, $or(center, s0)));
+            }
+        }
+        next
+    }};
+}
+
+/// `size_of::<V>() / 8` rows from `rows[at..]` as one vector.
+///
+/// # Safety
+/// Any bits must make a valid `V`, as they do for SIMD vectors.
+#[inline(always)]
+unsafe fn load<V>(rows: &[u64], at: usize) -> V {
+    let lanes = &rows[at..at + size_of::<V>() / 8];
+    // SAFETY: `lanes` is exactly as long as a V
+    unsafe { lanes.as_ptr().cast::<V>().read_unaligned() }
+}
+
+/// Writes `vector` over `size_of::<V>() / 8` rows from `rows[at..]`.
+#[inline(always)]
+fn store<V>(rows: &mut [u64], at: usize, vector: V) {
+    let lanes = &mut rows[at..at + size_of::<V>() / 8];
+    // SAFETY: `lanes` is exactly as long as a V
+    unsafe { lanes.as_mut_ptr().cast::<V>().write_unaligned(vector) }
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "avx2")]
+unsafe fn step_avx2(window: &Window) -> [u64; ROWS] {
+    #[cfg(target_arch = "x86")]
+    use std::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use std::arch::x86_64::*;
+    step_vectors!(
+        window,
+        __m256i,
+        _mm256_and_si256,
+        _mm256_or_si256,
+        _mm256_xor_si256,
+        |a, b| _mm256_andnot_si256(b, a),
+        _mm256_slli_epi64,
+        _mm256_srli_epi64,
+    )
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "sse2")]
+unsafe fn step_sse2(window: &Window) -> [u64; ROWS] {
+    #[cfg(target_arch = "x86")]
+    use std::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use std::arch::x86_64::*;
+    step_vectors!(
+        window,
+        __m128i,
+        _mm_and_si128,
+        _mm_or_si128,
+        _mm_xor_si128,
+        |a, b| _mm_andnot_si128(b, a),
+        _mm_slli_epi64,
+        _mm_srli_epi64,
+    )
+}
+
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "neon")]
+unsafe fn step_neon(window: &Window) -> [u64; ROWS] {
+    use std::arch::aarch64::*;
+    step_vectors!(
+        window,
+        uint64x2_t,
+        vandq_u64,
+        vorrq_u64,
+        veorq_u64,
+        |a, b| vbicq_u64(a, b),
+        vshlq_n_u64,
+        vshrq_n_u64,
+    )
+}
+
+fn step_rows(window: &Window) -> [u64; ROWS] {
+    let Window { rows, west, east } = window;
+    let mut next = [0u64; ROWS];
+    for (y, out) in next.iter_mut().enumerate() {
+        let (up, center, down) = (rows[y], rows[y + 1], rows[y + 2]);
+        let neighbours = [
+            (up << 1) | west[y],
+            up,
+            (up >> 1) | east[y],
+            (center << 1) | west[y + 1],
+            (center >> 1) | east[y + 1],
+            (down << 1) | west[y + 2],
+            down,
+            (down >> 1) | east[y + 2],
+        ];
+
+        // Count to three in bit planes, with s2 saturating at four or more
+        let (mut s0, mut s1, mut s2) = (0u64, 0u64, 0u64);
+        for x in neighbours {
+            let c0 = s0 & x;
+            s0 ^= x;
+            let c1 = s1 & c0;
+            s1 ^= c0;
+            s2 |= c1;
+        }
+        *out = (s1 & !s2) & (center | s0);
+    }
+    next
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+
+    fn run(kernel: Kernel, window: &Window) -> [u64; ROWS] {
+        match kernel {
+            // SAFETY: only kernels the CPU supports are run
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Kernel::Avx2 => unsafe { step_avx2(window) },
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Kernel::Sse2 => unsafe { step_sse2(window) },
+            #[cfg(target_arch = "aarch64")]
+            Kernel::Neon => unsafe { step_neon(window) },
+            _ => step_rows(window),
+        }
+    }
+
+    #[test]
+    fn kernels_agree() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for density in [0.0, 0.1, 0.5, 0.9, 1.0] {
+            let mut random = || -> u64 {
+                (0..64).fold(0, |row, x| row | ((rng.random_bool(density) as u64) << x))
+            };
+            let center: [u64; ROWS] = std::array::from_fn(|_| random());
+            let around: Vec<[u64; ROWS]> =
+                (0..9).map(|_| std::array::from_fn(|_| random())).collect();
+            let window = Window::gather(&center, |dx, dy, y| {
+                around[((dx + 1) * 3 + dy + 1) as usize][y]
+            });
+            let expected = step_rows(&window);
+            for kernel in Kernel::supported() {
+                assert_eq!(
+                    run(kernel, &window),
+                    expected,
+                    "{} at {density}",
+                    kernel.name()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn blinker_turns() {
+        let mut center = [0u64; ROWS];
+        center[10] = 0b111 << 20;
+        let window = Window::gather(&center, |_, _, _| 0);
+        let mut turned = [0u64; ROWS];
+        turned[9] = 0b010 << 20;
+        turned[10] = 0b010 << 20;
+        turned[11] = 0b010 << 20;
+        for kernel in Kernel::supported() {
+            assert_eq!(run(kernel, &window), turned, "{}", kernel.name());
+        }
+    }
+}
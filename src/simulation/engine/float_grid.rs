@@ -0,0 +1,187 @@
+//! Shared pieces of the continuous-state engines (Lenia, SmoothLife):
+//! a toroidal float grid with grayscale rendering and an FFT convolver.
+
+use std::sync::Arc;
+
+use crate::simulation::par::*;
+use bevy::math::{I64Vec2, Rect};
+use rustfft::num_complex::Complex32;
+use rustfft::{Fft, FftPlanner};
+
+/// States at or above this count as "alive" for the binary parts of `LifeEngine`.
+const ALIVE_THRESHOLD: f32 = 0.5;
+/// Rows handed to one FFT call; keeps per-call scratch allocation amortized.
+const ROWS_PER_JOB: usize = 16;
+
+/// Square grid of states in [0, 1], centered on the world origin.
+#[derive(Clone)]
+pub struct FloatGrid {
+    pub size: usize,
+    pub state: Vec<f32>,
+}
+
+impl FloatGrid {
+    pub fn new(size: usize) -> Self {
+        Self {
+            size,
+            state: vec![0.0; size * size],
+        }
+    }
+
+    /// Grid index for a world position, or `None` outside the grid.
+    #[inline]
+    pub fn index(&self, pos: I64Vec2) -> Option<usize> {
+        let half = (self.size / 2) as i64;
+        let x = pos.x + half;
+        let y = pos.y + half;
+        let size = self.size as i64;
+        if (0..size).contains(&x) && (0..size).contains(&y) {
+            Some(y as usize * self.size + x as usize)
+        } else {
+            None
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.state.fill(0.0);
+    }
+
+    pub fn set_cell(&mut self, pos: I64Vec2, alive: bool) {
+        if let Some(idx) = self.index(pos) {
+            self.state[idx] = if alive { 1.0 } else { 0.0 };
+        }
+    }
+
+    pub fn get_cell(&self, pos: I64Vec2) -> bool {
+        self.index(pos)
+            .is_some_and(|idx| self.state[idx] >= ALIVE_THRESHOLD)
+    }
+
+    pub fn population(&self) -> u64 {
+        self.state.iter().filter(|&&s| s >= ALIVE_THRESHOLD).count() as u64
+    }
+
+    pub fn memory_bytes(&self) -> usize {
+        self.state.capacity() * std::mem::size_of::<f32>()
+    }
+
+    pub fn export(&self) -> Vec<I64Vec2> {
+        let half = (self.size / 2) as i64;
+        self.state
+            .iter()
+            .enumerate()
+            .filter(|&(_, &s)| s >= ALIVE_THRESHOLD)
+            .map(|(idx, _)| {
+                I64Vec2::new(
+                    (idx % self.size) as i64 - half,
+                    (idx / self.size) as i64 - half,
+                )
+            })
+            .collect()
+    }
+
+    /// Grayscale: the buffer value is the state of the cell under each pixel center.
+    pub fn draw_to_buffer(&self, rect: Rect, buffer: &mut [u8], width: usize) {
+        let scale = width as f64 / rect.width() as f64;
+        if scale <= 0.0001 || scale.is_infinite() || scale.is_nan() {
+            buffer.fill(0);
+            return;
+        }
+        let inv_scale = 1.0 / scale;
+
+        buffer
+            .par_chunks_exact_mut(width)
+            .enumerate()
+            .for_each(|(y, pixel_row)| {
+                let world_y = (rect.min.y as f64 + (y as f64 + 0.5) * inv_scale).floor() as i64;
+                for (x, pixel) in pixel_row.iter_mut().enumerate() {
+                    let world_x = (rect.min.x as f64 + (x as f64 + 0.5) * inv_scale).floor() as i64;
+                    *pixel = match self.index(I64Vec2::new(world_x, world_y)) {
+                        Some(idx) => (self.state[idx] * 255.0) as u8,
+                        None => 0,
+                    };
+                }
+            });
+    }
+}
+
+/// Periodic 2D convolution of a [`FloatGrid`] with fixed kernels, done in frequency space
+/// so the cost doesn't depend on the kernel radius.
+#[derive(Clone)]
+pub struct FftConvolver {
+    size: usize,
+    forward: Arc<dyn Fft<f32>>,
+    inverse: Arc<dyn Fft<f32>>,
+}
+
+impl FftConvolver {
+    pub fn new(size: usize) -> Self {
+        let mut planner = FftPlanner::new();
+        Self {
+            size,
+            forward: planner.plan_fft_forward(size),
+            inverse: planner.plan_fft_inverse(size),
+        }
+    }
+
+    /// Spectrum of a radial kernel `weight(distance)` centered on (0, 0) with wrap-around,
+    /// normalized so its weights sum to one.
+    pub fn kernel(&self, weight: impl Fn(f32) -> f32) -> Arc<Vec<Complex32>> {
+        let n = self.size as i64;
+        let mut kernel = vec![Complex32::new(0.0, 0.0); self.size * self.size];
+        let mut total = 0.0;
+        for y in 0..n {
+            for x in 0..n {
+                // Shortest toroidal offset from the origin
+                let dx = if x > n / 2 { x - n } else { x };
+                let dy = if y > n / 2 { y - n } else { y };
+                let k = weight(((dx * dx + dy * dy) as f32).sqrt());
+                kernel[(y * n + x) as usize].re = k;
+                total += k;
+            }
+        }
+        if total > 0.0 {
+            for k in &mut kernel {
+                k.re /= total;
+            }
+        }
+        self.fft_2d(&mut kernel, &self.forward);
+        Arc::new(kernel)
+    }
+
+    /// Forward transform of the grid; feed it to [`FftConvolver::apply`] once per kernel.
+    pub fn spectrum(&self, grid: &FloatGrid) -> Vec<Complex32> {
+        let mut field: Vec<Complex32> =
+            grid.state.iter().map(|&s| Complex32::new(s, 0.0)).collect();
+        self.fft_2d(&mut field, &self.forward);
+        field
+    }
+
+    /// Convolution of the grid behind `spectrum` with `kernel`, back in grid layout.
+    pub fn apply(&self, spectrum: &[Complex32], kernel: &[Complex32]) -> Vec<f32> {
+        let mut field: Vec<Complex32> = spectrum
+            .par_iter()
+            .zip(kernel.par_iter())
+            .map(|(s, k)| s * k)
+            .collect();
+        self.fft_2d(&mut field, &self.inverse);
+
+        let norm = 1.0 / (self.size * self.size) as f32;
+        field.par_iter().map(|c| c.re * norm).collect()
+    }
+
+    /// Row FFTs, transpose, row FFTs. Leaves the result transposed; applying it twice
+    /// (forward then inverse) restores the original layout.
+    fn fft_2d(&self, data: &mut [Complex32], fft: &Arc<dyn Fft<f32>>) {
+        let size = self.size;
+        data.par_chunks_mut(size * ROWS_PER_JOB)
+            .for_each(|rows| fft.process(rows));
+        for y in 0..size {
+            for x in (y + 1)..size {
+                data.swap(y * size + x, x * size + y);
+            }
+        }
+        data.par_chunks_mut(size * ROWS_PER_JOB)
+            .for_each(|rows| fft.process(rows));
+    }
+}
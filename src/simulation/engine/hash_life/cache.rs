@@ -1,14 +1,24 @@
 use super::node::{Node, NodeData};
 use rustc_hash::{FxHashMap, FxHasher};
 use std::hash::{Hash, Hasher};
-use std::sync::{Arc, OnceLock};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError, RwLock};
 
-#[derive(Clone)]
+/// The node table is split into `1 << SHARD_BITS` maps, picked by the top bits of the
+/// node hash, so threads building nodes rarely wait for the same lock.
+const SHARD_BITS: u32 = 6;
+
+type Shard = FxHashMap<NodeData, Arc<Node>>;
+
+/// The canonical node table. Every method takes `&self`: lookups and inserts lock only
+/// the shard they touch, and only for the lookup itself, so engines sharing a cache can
+/// step one tree while others are read (drawn, queried) without waiting for each other.
 pub struct HashLifeCache {
-    map: FxHashMap<NodeData, Arc<Node>>,
-    pub empty_nodes: Vec<Arc<Node>>,
+    shards: Box<[Mutex<Shard>]>,
+    // Canonical empty node per level, from level 3 up
+    empty_nodes: RwLock<Vec<Arc<Node>>>,
 }
 
+#[allow(clippy::mutable_key_type)]
 impl HashLifeCache {
     /// Creates a new cache initialized with the base empty leaf node.
     pub fn new() -> Self {
@@ -26,17 +36,32 @@ impl HashLifeCache {
             result_step_1: OnceLock::new(),
         });
 
-        let mut map = FxHashMap::default();
-        map.insert(base_data, base_empty.clone());
+        let cache = Self {
+            shards: (0..1 << SHARD_BITS)
+                .map(|_| Mutex::new(Shard::default()))
+                .collect(),
+            empty_nodes: RwLock::new(vec![base_empty.clone()]),
+        };
+        cache.shard(base_hash).insert(base_data, base_empty);
+        cache
+    }
 
-        Self {
-            map,
-            empty_nodes: vec![base_empty],
-        }
+    /// Locks the shard holding nodes with this hash. A panic elsewhere can't leave a
+    /// shard half-updated (inserts are single calls), so poisoning is ignored.
+    fn shard(&self, hash: u64) -> MutexGuard<'_, Shard> {
+        self.shards[(hash >> (64 - SHARD_BITS)) as usize]
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn empty_nodes(&self) -> std::sync::RwLockReadGuard<'_, Vec<Arc<Node>>> {
+        self.empty_nodes
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
     }
 
     /// Advances the node by $2^{level-2}$ generations.
-    pub fn evolve(&mut self, node: Arc<Node>) -> Arc<Node> {
+    pub fn evolve(&self, node: Arc<Node>) -> Arc<Node> {
         if let Some(res) = node.result.get() {
             return res.clone();
         }
@@ -57,7 +82,7 @@ impl HashLifeCache {
     }
 
     /// Advances the node by exactly 1 generation.
-    pub fn evolve_1(&mut self, node: Arc<Node>) -> Arc<Node> {
+    pub fn evolve_1(&self, node: Arc<Node>) -> Arc<Node> {
         if let Some(res) = node.result_step_1.get() {
             return res.clone();
         }
@@ -111,34 +136,67 @@ impl HashLifeCache {
     }
 
     /// Returns a canonical empty node for the given level, creating it if necessary.
-    pub fn empty_node(&mut self, level: u8) -> Arc<Node> {
-        if level <= 3 {
-            return self.empty_nodes[0].clone();
-        }
-
-        let index = (level - 3) as usize;
-        if index < self.empty_nodes.len() {
-            return self.empty_nodes[index].clone();
+    pub fn empty_node(&self, level: u8) -> Arc<Node> {
+        let index = level.saturating_sub(3) as usize;
+        if let Some(node) = self.empty_nodes().get(index) {
+            return node.clone();
         }
 
         let child = self.empty_node(level - 1);
         let node = self.join(child.clone(), child.clone(), child.clone(), child.clone());
 
-        self.empty_nodes.push(node.clone());
+        // Another thread may have added it meanwhile; join returned the same node then
+        let mut empty_nodes = self
+            .empty_nodes
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        if empty_nodes.len() == index {
+            empty_nodes.push(node.clone());
+        }
         node
     }
 
+    /// Whether `node` is the cache's own node for its contents.
+    pub fn is_canonical(&self, node: &Arc<Node>) -> bool {
+        self.shard(node.hash)
+            .get(&node.data)
+            .is_some_and(|cached| Arc::ptr_eq(cached, node))
+    }
+
     #[allow(unused)]
     /// Removes unreferenced nodes from the internal map.
-    pub fn collect_garbage(&mut self) -> usize {
-        let before = self.map.len();
-        self.map.retain(|_, node| Arc::strong_count(node) > 1);
-        before - self.map.len()
+    pub fn collect_garbage(&self) -> usize {
+        let _span = bevy::log::info_span!("hash_life::collect_garbage").entered();
+        let mut removed = 0;
+        for shard in &self.shards {
+            let mut shard = shard.lock().unwrap_or_else(PoisonError::into_inner);
+            let before = shard.len();
+            shard.retain(|_, node| Arc::strong_count(node) > 1);
+            removed += before - shard.len();
+        }
+        removed
+    }
+
+    /// Approximate bytes held by the canonical node table (map entries plus node allocations).
+    pub fn memory_bytes(&self) -> usize {
+        let entry = std::mem::size_of::<NodeData>() + std::mem::size_of::<Arc<Node>>();
+        self.shards
+            .iter()
+            .map(|shard| {
+                let shard = shard.lock().unwrap_or_else(PoisonError::into_inner);
+                shard.capacity() * entry + shard.len() * std::mem::size_of::<Node>()
+            })
+            .sum()
     }
 
     /// Canonicalizes a node: returns an existing node from the cache or creates a new one.
-    pub fn get_node(&mut self, data: NodeData) -> Arc<Node> {
-        if let Some(node) = self.map.get(&data) {
+    pub fn get_node(&self, data: NodeData) -> Arc<Node> {
+        let mut hasher = FxHasher::default();
+        data.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        let mut shard = self.shard(hash);
+        if let Some(node) = shard.get(&data) {
             return node.clone();
         }
 
@@ -149,10 +207,6 @@ impl HashLifeCache {
             }
         };
 
-        let mut hasher = FxHasher::default();
-        data.hash(&mut hasher);
-        let hash = hasher.finish();
-
         let node = Arc::new(Node {
             data: data.clone(),
             population,
@@ -161,18 +215,12 @@ impl HashLifeCache {
             result_step_1: OnceLock::new(),
         });
 
-        self.map.insert(data, node.clone());
+        shard.insert(data, node.clone());
         node
     }
 
     /// Combines four children into a new branch node one level higher.
-    pub fn join(
-        &mut self,
-        nw: Arc<Node>,
-        ne: Arc<Node>,
-        sw: Arc<Node>,
-        se: Arc<Node>,
-    ) -> Arc<Node> {
+    pub fn join(&self, nw: Arc<Node>, ne: Arc<Node>, sw: Arc<Node>, se: Arc<Node>) -> Arc<Node> {
         let level = nw.level() + 1;
         debug_assert_eq!(nw.level(), ne.level());
         debug_assert_eq!(nw.level(), sw.level());
@@ -189,9 +237,9 @@ impl HashLifeCache {
 
     /// Calculates the next state for a Leaf node (8x8 grid).
     /// Uses SWAR (SIMD Within A Register) techniques for parallel counting.
-    fn calc_leaf(&mut self, input: u64) -> Arc<Node> {
+    fn calc_leaf(&self, input: u64) -> Arc<Node> {
         if input == 0 {
-            return self.empty_nodes[0].clone();
+            return self.empty_node(3);
         }
 
         let l = (input >> 1) & 0x7F7F7F7F7F7F7F7F;
@@ -267,7 +315,7 @@ impl HashLifeCache {
 
     /// Calculates the next state for a Branch node using 9-way decomposition.
     fn calc_branch(
-        &mut self,
+        &self,
         nw: &Arc<Node>,
         ne: &Arc<Node>,
         sw: &Arc<Node>,
@@ -315,7 +363,7 @@ impl HashLifeCache {
 
     /// Extracts the centered quarter node from 4 neighboring nodes.
     fn centered_sub(
-        &mut self,
+        &self,
         nw: &Arc<Node>,
         ne: &Arc<Node>,
         sw: &Arc<Node>,
@@ -339,7 +387,7 @@ impl HashLifeCache {
     }
 
     /// Extracts the horizontally centered half from two nodes.
-    fn centered_horizontal(&mut self, left: &Arc<Node>, right: &Arc<Node>) -> Arc<Node> {
+    fn centered_horizontal(&self, left: &Arc<Node>, right: &Arc<Node>) -> Arc<Node> {
         match (&left.data, &right.data) {
             (NodeData::Leaf(l_bits), NodeData::Leaf(r_bits)) => {
                 let mut res = 0u64;
@@ -370,7 +418,7 @@ impl HashLifeCache {
     }
 
     /// Extracts the vertically centered half from two nodes.
-    fn centered_vertical(&mut self, top: &Arc<Node>, bottom: &Arc<Node>) -> Arc<Node> {
+    fn centered_vertical(&self, top: &Arc<Node>, bottom: &Arc<Node>) -> Arc<Node> {
         match (&top.data, &bottom.data) {
             (NodeData::Leaf(t_bits), NodeData::Leaf(b_bits)) => {
                 let mut res = 0u64;
@@ -403,7 +451,7 @@ impl HashLifeCache {
     }
 
     /// Extracts the center 8x8 bits from four 8x8 Leaf nodes (forming a 16x16 grid).
-    fn centered_bits(&mut self, nw: u64, ne: u64, sw: u64, se: u64) -> Arc<Node> {
+    fn centered_bits(&self, nw: u64, ne: u64, sw: u64, se: u64) -> Arc<Node> {
         let mut res = 0u64;
         for y in 0..8 {
             for x in 0..8 {
@@ -431,7 +479,7 @@ impl HashLifeCache {
     /// Optimized calculation for Level 4 nodes (16x16 grid composed of 4 leaves).
     /// Uses packed `u64` operations to simulate the grid efficiently.
     fn calc_level_4_grid(
-        &mut self,
+        &self,
         nw: &Arc<Node>,
         ne: &Arc<Node>,
         sw: &Arc<Node>,
@@ -476,7 +524,7 @@ impl HashLifeCache {
     }
 
     /// Runs the SWAR Adder on 4 rows (packed in u64) simultaneously.
-    fn step_4_rows(&mut self, curr: u64, up_block: u64, down_block: u64) -> u64 {
+    fn step_4_rows(&self, curr: u64, up_block: u64, down_block: u64) -> u64 {
         // Vertical Neighbors
         // "Up" from Row 1 is Row 0. "Up" from Row 0 is last row of up_block.
         let u = (curr << 16) | (up_block >> 48);
@@ -513,7 +561,7 @@ impl HashLifeCache {
 
     /// Interleaves 4 bytes from left and right to create 4x 16-bit rows.
     /// `shift`: 0 for lower half of input, 32 for upper half.
-    fn zip_quadrants(&mut self, left: u64, right: u64, shift: usize) -> u64 {
+    fn zip_quadrants(&self, left: u64, right: u64, shift: usize) -> u64 {
         let l_part = left >> shift;
         let r_part = right >> shift;
 
@@ -536,7 +584,7 @@ impl HashLifeCache {
     }
 
     /// Extracts bits 4..11 from each 16-bit row and packs them into a 32-bit result.
-    fn compress_center(&mut self, block: u64) -> u64 {
+    fn compress_center(&self, block: u64) -> u64 {
         let r0 = (block >> 4) & 0xFF;
         let r1 = (block >> (16 + 4)) & 0xFF;
         let r2 = (block >> (32 + 4)) & 0xFF;
@@ -1,15 +1,36 @@
 mod cache;
 mod node;
 
-use crate::simulation::engine::LifeEngine;
+use crate::simulation::engine::{
+    COORD_LIMIT, CellRegion, Keyframe, LifeEngine, TILE_SIZE, Tile, TreeNodeInfo, blocks_touched,
+    in_supported_range, tile_cells,
+};
+use crate::simulation::error::LifeError;
+use crate::simulation::rules::LifeRule;
+use bevy::log::info_span;
 use bevy::math::{I64Vec2, Rect};
 use cache::HashLifeCache;
 use node::{Node, NodeData};
+use rustc_hash::FxHashMap;
+use std::io::Write;
 use std::sync::Arc;
 
+/// Tallest tree built for edits: twice the supported coordinate range, so any supported
+/// cell fits whichever way the root was grown.
+const MAX_LEVEL: u8 = 50;
+
+/// What a HashLife keyframe holds.
+struct RootKeyframe {
+    root: Arc<Node>,
+    origin_x: i64,
+    origin_y: i64,
+}
+
+/// Clones share the node cache, so stepping a clone while the original is drawn costs
+/// no copying and no waiting (see [`HashLifeCache`]).
 #[derive(Clone)]
 pub struct HashLife {
-    cache: HashLifeCache,
+    cache: Arc<HashLifeCache>,
     root: Arc<Node>,
     generation: u64,
     origin_x: i64,
@@ -19,7 +40,7 @@ pub struct HashLife {
 impl HashLife {
     /// Initializes a new Hashlife universe with a Level 4 (16x16) empty grid.
     pub fn new() -> Self {
-        let mut cache = HashLifeCache::new();
+        let cache = Arc::new(HashLifeCache::new());
         let root = cache.empty_node(4);
 
         HashLife {
@@ -45,6 +66,25 @@ impl LifeEngine for HashLife {
         self.root.population
     }
 
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn set_generation(&mut self, generation: u64) {
+        self.generation = generation;
+    }
+
+    fn memory_bytes(&self) -> usize {
+        self.cache.memory_bytes()
+    }
+
+    fn estimate_memory(&self, cells: u64, size: I64Vec2) -> u64 {
+        // At worst a distinct 8x8 node per occupied area, about doubled by its ancestors;
+        // repetitive patterns share nodes and need far less
+        let node = std::mem::size_of::<Node>() + 2 * std::mem::size_of::<NodeData>();
+        blocks_touched(cells, size, 8).saturating_mul(2 * node as u64)
+    }
+
     fn set_cell(&mut self, pos: I64Vec2, alive: bool) {
         self.set_cells(&[pos], alive);
     }
@@ -62,7 +102,7 @@ impl LifeEngine for HashLife {
         if rel_x < 0 || rel_y < 0 || rel_x >= size as i64 || rel_y >= size as i64 {
             return false;
         }
-        self.recursive_get(self.root.clone(), size as u64, rel_x as u64, rel_y as u64)
+        self.recursive_get(self.root.clone(), size, rel_x as u64, rel_y as u64)
     }
 
     fn clear(&mut self) {
@@ -73,6 +113,7 @@ impl LifeEngine for HashLife {
     }
 
     fn export(&self) -> Vec<I64Vec2> {
+        let _span = info_span!("hash_life::export").entered();
         let mut alive_cells = Vec::new();
         let size = 1u64 << self.root.level();
 
@@ -90,7 +131,83 @@ impl LifeEngine for HashLife {
             .collect()
     }
 
+    fn export_region(&self, region: CellRegion) -> Vec<I64Vec2> {
+        let _span = info_span!("hash_life::export_region").entered();
+        let mut alive_cells = Vec::new();
+        let size = 1u64 << self.root.level();
+
+        self.recursive_export_in(
+            &self.root,
+            self.origin_x,
+            self.origin_y,
+            size,
+            region,
+            &mut alive_cells,
+        );
+
+        alive_cells
+            .into_iter()
+            .map(|(x, y)| I64Vec2::new(x, y))
+            .collect()
+    }
+
+    fn inspect_tree(&self, path: &[usize]) -> Option<TreeNodeInfo> {
+        let mut node = &self.root;
+        let mut corner = I64Vec2::new(self.origin_x, self.origin_y);
+        for &index in path {
+            let NodeData::Branch { nw, ne, sw, se, .. } = &node.data else {
+                return None;
+            };
+            let half = 1i64 << (node.level() - 1);
+            // Same layout as export: nw at the lowest coordinates
+            (node, corner) = match index {
+                0 => (nw, corner),
+                1 => (ne, corner + I64Vec2::new(half, 0)),
+                2 => (sw, corner + I64Vec2::new(0, half)),
+                3 => (se, corner + I64Vec2::new(half, half)),
+                _ => return None,
+            };
+        }
+
+        let mut info = Self::node_info(node, corner);
+        if let NodeData::Branch { nw, ne, sw, se, .. } = &node.data {
+            let half = 1i64 << (node.level() - 1);
+            info.children = vec![
+                Self::node_info(nw, corner),
+                Self::node_info(ne, corner + I64Vec2::new(half, 0)),
+                Self::node_info(sw, corner + I64Vec2::new(0, half)),
+                Self::node_info(se, corner + I64Vec2::new(half, half)),
+            ];
+        }
+        Some(info)
+    }
+
+    fn visit_tiles(&self, visit: &mut dyn FnMut(I64Vec2, &Tile)) {
+        let _span = info_span!("hash_life::visit_tiles").entered();
+        let corner = I64Vec2::new(self.origin_x, self.origin_y);
+        Self::visit_node_tiles(&self.root, corner, visit);
+    }
+
+    fn add_tile(&mut self, pos: I64Vec2, tile: &Tile) {
+        let base = pos * TILE_SIZE;
+        let points = tile_cells(tile)
+            .map(|local| (base.x + local.x, base.y + local.y))
+            .collect();
+        self.apply_batch(points, true);
+    }
+
+    fn write_macrocell(&self, out: &mut dyn Write) -> Option<std::io::Result<()>> {
+        let _span = info_span!("hash_life::write_macrocell").entered();
+        Some(self.write_tree(out))
+    }
+
+    fn read_macrocell(&mut self, text: &str, center: I64Vec2) -> Option<Result<(), LifeError>> {
+        let _span = info_span!("hash_life::read_macrocell").entered();
+        Some(self.read_tree(text, center))
+    }
+
     fn import(&mut self, alive_cells: &[I64Vec2]) {
+        let _span = info_span!("hash_life::import", cells = alive_cells.len()).entered();
         self.clear();
         self.set_cells(alive_cells, true);
     }
@@ -105,10 +222,12 @@ impl LifeEngine for HashLife {
             return 0;
         }
 
+        let _span = info_span!("hash_life::step", steps).entered();
         let total_steps = steps;
 
         while steps > 0 {
             // 1. Ensure universe is padded with enough empty space
+            let expand_span = info_span!("hash_life::expand").entered();
             for _ in 0..60 {
                 let too_small = self.root.level() < 5;
                 if too_small || !self.is_padded() {
@@ -118,11 +237,14 @@ impl LifeEngine for HashLife {
                 }
             }
 
+            drop(expand_span);
+
             // 2. Determine max jump size (2^(level-2))
             let max_step_power = self.root.level() - 2;
             let max_jump = 1u64 << max_step_power;
 
             // 3. Evolve
+            let _evolve_span = info_span!("hash_life::evolve", level = self.root.level()).entered();
             let (next_node, steps_taken) = if steps >= max_jump {
                 (self.cache.evolve(self.root.clone()), max_jump)
             } else {
@@ -144,7 +266,15 @@ impl LifeEngine for HashLife {
         total_steps
     }
 
-    fn draw_to_buffer(&self, rect: Rect, buffer: &mut [u8], width: usize, height: usize) {
+    fn draw_to_buffer(
+        &self,
+        rect: Rect,
+        buffer: &mut [u8],
+        width: usize,
+        height: usize,
+        _population: u64,
+    ) {
+        let _span = info_span!("hash_life::draw").entered();
         buffer.fill(0);
         if rect.width() <= 0.0 {
             return;
@@ -172,6 +302,32 @@ impl LifeEngine for HashLife {
         );
     }
 
+    fn keyframe(&self) -> Option<Keyframe> {
+        // The root shares every node with the live tree; holding it also keeps the nodes
+        // in the cache through garbage collection
+        let state = RootKeyframe {
+            root: self.root.clone(),
+            origin_x: self.origin_x,
+            origin_y: self.origin_y,
+        };
+        Some(Keyframe::new(self.generation, state))
+    }
+
+    fn restore_keyframe(&mut self, keyframe: &Keyframe) -> bool {
+        let Some(state) = keyframe.state::<RootKeyframe>() else {
+            return false;
+        };
+        // A root from another cache would break hash-consing
+        if !self.cache.is_canonical(&state.root) {
+            return false;
+        }
+        self.root = state.root.clone();
+        self.origin_x = state.origin_x;
+        self.origin_y = state.origin_y;
+        self.generation = keyframe.generation;
+        true
+    }
+
     fn box_clone(&self) -> Box<dyn LifeEngine> {
         Box::new(self.clone())
     }
@@ -206,16 +362,22 @@ impl HashLife {
         self.root.population == inner_pop
     }
 
-    /// Expands the universe until the given coordinate fits within the bounds.
-    fn expand_to_fit(&mut self, x: i64, y: i64) {
-        // Safety cap to prevent infinite loops on extreme coordinates
-        for _ in 0..20 {
-            let size = 1u64 << self.root.level();
+    /// Grows the tree until it covers `(x, y)`. Returns false for cells outside the
+    /// supported range, which would need a tree too tall to address with `i64`.
+    fn expand_to_fit(&mut self, x: i64, y: i64) -> bool {
+        if !in_supported_range(I64Vec2::new(x, y)) {
+            return false;
+        }
+        loop {
+            let size = 1i64 << self.root.level();
             let rel_x = x - self.origin_x;
             let rel_y = y - self.origin_y;
 
-            if rel_x >= 0 && rel_y >= 0 && rel_x < size as i64 && rel_y < size as i64 {
-                return;
+            if rel_x >= 0 && rel_y >= 0 && rel_x < size && rel_y < size {
+                return true;
+            }
+            if self.root.level() >= MAX_LEVEL {
+                return false;
             }
             self.expand();
         }
@@ -272,8 +434,9 @@ impl HashLife {
         }
 
         // 1. Expand universe
-        for &(x, y) in &points {
-            self.expand_to_fit(x, y);
+        points.retain(|&(x, y)| self.expand_to_fit(x, y));
+        if points.is_empty() {
+            return;
         }
 
         // 2. Sort points (Y then X, or Morton order)
@@ -389,6 +552,283 @@ impl HashLife {
         unreachable!()
     }
 
+    /// Hands the cells under `node` (with its min corner at `corner`) to `visit` in tiles.
+    /// The origin isn't tile aligned, so each 64x64 node can straddle up to four tiles.
+    fn visit_node_tiles(node: &Arc<Node>, corner: I64Vec2, visit: &mut dyn FnMut(I64Vec2, &Tile)) {
+        if node.population == 0 {
+            return;
+        }
+        if let NodeData::Branch { nw, ne, sw, se, .. } = &node.data
+            && node.level() > 6
+        {
+            let half = 1i64 << (node.level() - 1);
+            Self::visit_node_tiles(nw, corner, visit);
+            Self::visit_node_tiles(ne, corner + I64Vec2::new(half, 0), visit);
+            Self::visit_node_tiles(sw, corner + I64Vec2::new(0, half), visit);
+            Self::visit_node_tiles(se, corner + I64Vec2::new(half, half), visit);
+            return;
+        }
+
+        let mut rows: Tile = [0; TILE_SIZE as usize];
+        Self::fill_rows(node, 0, 0, &mut rows);
+
+        let first = corner.div_euclid(I64Vec2::splat(TILE_SIZE));
+        let offset = corner.rem_euclid(I64Vec2::splat(TILE_SIZE));
+        // [row of tiles][column of tiles], starting at `first`
+        let mut tiles = [[[0u64; TILE_SIZE as usize]; 2]; 2];
+        for (y, &row) in rows.iter().enumerate() {
+            if row == 0 {
+                continue;
+            }
+            let y = offset.y as usize + y;
+            let (ty, ly) = (y / TILE_SIZE as usize, y % TILE_SIZE as usize);
+            tiles[ty][0][ly] |= row << offset.x;
+            if offset.x > 0 {
+                tiles[ty][1][ly] |= row >> (TILE_SIZE - offset.x);
+            }
+        }
+        for (ty, tile_row) in tiles.iter().enumerate() {
+            for (tx, tile) in tile_row.iter().enumerate() {
+                if tile.iter().any(|&row| row != 0) {
+                    visit(first + I64Vec2::new(tx as i64, ty as i64), tile);
+                }
+            }
+        }
+    }
+
+    /// Copies the cells of a node of at most 64x64 into `rows`, at (`x`, `y`).
+    fn fill_rows(node: &Arc<Node>, x: usize, y: usize, rows: &mut Tile) {
+        if node.population == 0 {
+            return;
+        }
+        match &node.data {
+            NodeData::Leaf(bits) => {
+                for row in 0..8 {
+                    rows[y + row] |= ((bits >> (row * 8)) & 0xFF) << x;
+                }
+            }
+            NodeData::Branch { nw, ne, sw, se, .. } => {
+                let half = 1usize << (node.level() - 1);
+                Self::fill_rows(nw, x, y, rows);
+                Self::fill_rows(ne, x + half, y, rows);
+                Self::fill_rows(sw, x, y + half, rows);
+                Self::fill_rows(se, x + half, y + half, rows);
+            }
+        }
+    }
+
+    fn write_tree(&self, out: &mut dyn Write) -> std::io::Result<()> {
+        writeln!(out, "[M2] (life.rs)")?;
+        writeln!(out, "#R B3/S23")?;
+        writeln!(out, "#G {}", self.generation)?;
+        if self.root.population == 0 {
+            // Readers expect at least one node
+            writeln!(out, "4 0 0 0 0")?;
+            return Ok(());
+        }
+        let mut ids = FxHashMap::default();
+        Self::write_node(&self.root, out, &mut ids)?;
+        Ok(())
+    }
+
+    /// Builds the tree of a macrocell file, mirrored back as in [`HashLife::write_node`],
+    /// and makes it the universe, centered on `center`, if the whole file is valid.
+    fn read_tree(&mut self, text: &str, center: I64Vec2) -> Result<(), LifeError> {
+        let invalid = |line: usize, detail: &str| {
+            LifeError::InvalidPattern(format!("macrocell line {}: {detail}", line + 1))
+        };
+        let mut generation = 0;
+        // Node `i` of the file is `nodes[i - 1]`; 0 stands for an empty node
+        let mut nodes: Vec<Arc<Node>> = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with("[M2]") {
+                continue;
+            }
+            if let Some(rule) = line.strip_prefix("#R") {
+                let rule = LifeRule::parse(rule.trim())?;
+                if rule != LifeRule::CONWAY {
+                    return Err(LifeError::InvalidPattern(format!(
+                        "HashLife only runs B3/S23, the file is for {rule}"
+                    )));
+                }
+                continue;
+            }
+            if let Some(value) = line.strip_prefix("#G") {
+                generation = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| invalid(number, "bad generation"))?;
+                continue;
+            }
+            if line.starts_with('#') {
+                continue;
+            }
+
+            if line.starts_with(['.', '*', '$']) {
+                let bits = Self::parse_leaf(line)
+                    .ok_or_else(|| invalid(number, "a leaf is 8 rows of 8 cells"))?;
+                nodes.push(self.cache.get_node(NodeData::Leaf(bits)));
+                continue;
+            }
+            let fields: Vec<usize> = line
+                .split_whitespace()
+                .map(str::parse)
+                .collect::<Result<_, _>>()
+                .map_err(|_| invalid(number, "expected a level and four node numbers"))?;
+            let [level, top_left, top_right, bottom_left, bottom_right] = fields[..] else {
+                return Err(invalid(number, "expected a level and four node numbers"));
+            };
+            if level < 4 {
+                return Err(invalid(
+                    number,
+                    "multi-state macrocell files aren't supported",
+                ));
+            }
+            if level > COORD_LIMIT.trailing_zeros() as usize + 1 {
+                return Err(LifeError::InvalidPattern(
+                    "pattern is larger than the supported coordinate range".to_string(),
+                ));
+            }
+            let level = level as u8;
+            let child = |id: usize| match id {
+                0 => Some(self.cache.empty_node(level - 1)),
+                id => nodes
+                    .get(id - 1)
+                    .filter(|node| node.level() == level - 1)
+                    .cloned(),
+            };
+            let (Some(sw), Some(se), Some(nw), Some(ne)) = (
+                child(top_left),
+                child(top_right),
+                child(bottom_left),
+                child(bottom_right),
+            ) else {
+                return Err(invalid(
+                    number,
+                    "children must be earlier nodes one level down",
+                ));
+            };
+            nodes.push(self.cache.join(nw, ne, sw, se));
+        }
+
+        let Some(root) = nodes.pop() else {
+            return Err(LifeError::InvalidPattern(
+                "macrocell file has no nodes".to_string(),
+            ));
+        };
+        let half = 1i64 << (root.level() - 1);
+        let origin = center - I64Vec2::splat(half);
+        if !in_supported_range(origin) || !in_supported_range(origin + I64Vec2::splat(2 * half - 1))
+        {
+            return Err(LifeError::InvalidPattern(
+                "pattern is larger than the supported coordinate range".to_string(),
+            ));
+        }
+        self.root = root;
+        self.origin_x = origin.x;
+        self.origin_y = origin.y;
+        self.generation = generation;
+        if self.root.level() < 4 {
+            self.expand();
+        }
+        Ok(())
+    }
+
+    /// The bits of a macrocell leaf line: rows top-down separated by `$`, `*` alive,
+    /// `.` dead and trailing dead cells left out.
+    fn parse_leaf(line: &str) -> Option<u64> {
+        let mut bits = 0u64;
+        let rows: Vec<&str> = line.split('$').collect();
+        // Every row ends in `$`, so the last piece is empty; empty rows at the end may be
+        // left out
+        if rows.iter().skip(8).any(|row| !row.is_empty()) {
+            return None;
+        }
+        for (r, row) in rows.iter().take(8).enumerate() {
+            if row.len() > 8 {
+                return None;
+            }
+            for (col, c) in row.chars().enumerate() {
+                match c {
+                    '*' => bits |= 1 << ((7 - r) * 8 + col),
+                    '.' => {}
+                    _ => return None,
+                }
+            }
+        }
+        Some(bits)
+    }
+
+    /// Writes `node` after its children unless it was written already and returns its
+    /// line number (0 for empty nodes, which the format leaves implicit).
+    /// Macrocell rows run top-down, so the tree is mirrored vertically: the file's
+    /// north is our highest y, as with RLE files.
+    fn write_node(
+        node: &Arc<Node>,
+        out: &mut dyn Write,
+        ids: &mut FxHashMap<*const Node, usize>,
+    ) -> std::io::Result<usize> {
+        if node.population == 0 {
+            return Ok(0);
+        }
+        if let Some(&id) = ids.get(&Arc::as_ptr(node)) {
+            return Ok(id);
+        }
+
+        match &node.data {
+            NodeData::Leaf(bits) => {
+                let mut line = String::with_capacity(72);
+                for row in (0..8).rev() {
+                    let start = line.len();
+                    for col in 0..8 {
+                        let alive = (bits >> (row * 8 + col)) & 1 == 1;
+                        line.push(if alive { '*' } else { '.' });
+                    }
+                    let trimmed = line[start..].trim_end_matches('.').len();
+                    line.truncate(start + trimmed);
+                    line.push('$');
+                }
+                writeln!(out, "{line}")?;
+            }
+            NodeData::Branch {
+                nw,
+                ne,
+                sw,
+                se,
+                level,
+            } => {
+                let top_left = Self::write_node(sw, out, ids)?;
+                let top_right = Self::write_node(se, out, ids)?;
+                let bottom_left = Self::write_node(nw, out, ids)?;
+                let bottom_right = Self::write_node(ne, out, ids)?;
+                writeln!(
+                    out,
+                    "{level} {top_left} {top_right} {bottom_left} {bottom_right}"
+                )?;
+            }
+        }
+
+        let id = ids.len() + 1;
+        ids.insert(Arc::as_ptr(node), id);
+        Ok(id)
+    }
+
+    fn node_info(node: &Node, corner: I64Vec2) -> TreeNodeInfo {
+        let size = 1i64 << node.level();
+        TreeNodeInfo {
+            level: node.level(),
+            population: node.population,
+            region: CellRegion {
+                min: corner,
+                max: corner + I64Vec2::splat(size),
+            },
+            cached_jump: node.result.get().is_some(),
+            cached_step: node.result_step_1.get().is_some(),
+            children: Vec::new(),
+        }
+    }
+
     fn recursive_get(&self, node: Arc<Node>, size: u64, x: u64, y: u64) -> bool {
         if node.population == 0 {
             return false;
@@ -450,6 +890,47 @@ impl HashLife {
         }
     }
 
+    /// [`Self::recursive_export`] for the cells inside `region`, skipping the nodes
+    /// that lie outside.
+    fn recursive_export_in(
+        &self,
+        node: &Arc<Node>,
+        x: i64,
+        y: i64,
+        size: u64,
+        region: CellRegion,
+        list: &mut Vec<(i64, i64)>,
+    ) {
+        let far = I64Vec2::new(x, y) + I64Vec2::splat(size as i64);
+        if node.population == 0
+            || far.cmple(region.min).any()
+            || I64Vec2::new(x, y).cmpge(region.max).any()
+        {
+            return;
+        }
+
+        match &node.data {
+            NodeData::Leaf(bits) => {
+                for row in 0..8 {
+                    for col in 0..8 {
+                        let pos = I64Vec2::new(x + col as i64, y + row as i64);
+                        if (bits >> (row * 8 + col)) & 1 == 1 && region.contains(pos) {
+                            list.push((pos.x, pos.y));
+                        }
+                    }
+                }
+            }
+            NodeData::Branch { nw, ne, sw, se, .. } => {
+                let half = (size / 2) as i64;
+                self.recursive_export_in(nw, x, y, size / 2, region, list);
+                self.recursive_export_in(ne, x + half, y, size / 2, region, list);
+                self.recursive_export_in(sw, x, y + half, size / 2, region, list);
+                self.recursive_export_in(se, x + half, y + half, size / 2, region, list);
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
     fn recursive_draw(
         &self,
         node: &Arc<Node>,
@@ -471,9 +952,18 @@ impl HashLife {
             return;
         }
 
-        // LOD: if a node is smaller than a pixel, draw it as a solid block
+        // LOD: a node of at most a pixel isn't descended, it shades the pixel by its density
         if size <= 1.0 {
-            self.fill_rect(buffer, width, height, x, y, size);
+            let area = 4f64.powi(node.level() as i32);
+            Self::shade(
+                buffer,
+                width,
+                height,
+                x,
+                y,
+                size,
+                node.population as f64 / area,
+            );
             return;
         }
 
@@ -485,7 +975,11 @@ impl HashLife {
                         if (bits >> (row * 8 + col)) & 1 == 1 {
                             let cx = x + (col as f64 * cell_size);
                             let cy = y + (row as f64 * cell_size);
-                            self.fill_rect(buffer, width, height, cx, cy, cell_size);
+                            if cell_size < 1.0 {
+                                Self::shade(buffer, width, height, cx, cy, cell_size, 1.0);
+                            } else {
+                                self.fill_rect(buffer, width, height, cx, cy, cell_size);
+                            }
                         }
                     }
                 }
@@ -510,6 +1004,31 @@ impl HashLife {
         }
     }
 
+    /// Adds a square of at most a pixel, with `density` live cells per cell, to the pixel
+    /// under its centre: gray in proportion to the density, weighted by how much of the
+    /// pixel the square covers. Half density, about the densest Life stays, is white;
+    /// anything alive shows at least faintly.
+    fn shade(
+        buffer: &mut [u8],
+        width: usize,
+        height: usize,
+        x: f64,
+        y: f64,
+        size: f64,
+        density: f64,
+    ) {
+        let cx = (x + size / 2.0).floor();
+        let cy = (y + size / 2.0).floor();
+        if cx < 0.0 || cy < 0.0 || cx >= width as f64 || cy >= height as f64 {
+            return;
+        }
+        let value = (density * 2.0 * size * size * 255.0)
+            .round()
+            .clamp(1.0, 255.0) as u8;
+        let pixel = &mut buffer[cy as usize * width + cx as usize];
+        *pixel = pixel.saturating_add(value);
+    }
+
     fn fill_rect(&self, buffer: &mut [u8], width: usize, height: usize, x: f64, y: f64, size: f64) {
         let start_x = x.round().max(0.0) as usize;
         let start_y = y.round().max(0.0) as usize;
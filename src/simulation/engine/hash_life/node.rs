@@ -3,6 +3,7 @@ use std::{
     sync::{Arc, OnceLock},
 };
 
+#[allow(clippy::derived_hash_with_manual_eq)]
 #[derive(Clone, Hash)]
 pub enum NodeData {
     Leaf(u64),
@@ -0,0 +1,341 @@
+use std::ops::RangeInclusive;
+
+use crate::simulation::engine::{LifeEngine, blocks_touched};
+use crate::simulation::par::*;
+use bevy::log::info_span;
+use bevy::math::{I64Vec2, Rect};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+const BLOCK_SIZE: usize = 64;
+/// Side of the 3x3-block window a block is evaluated in.
+const WINDOW: usize = BLOCK_SIZE * 3;
+/// The summed-area table has an extra zero row/column in front.
+const SAT_STRIDE: usize = WINDOW + 1;
+
+/// Larger than Life rule: a (2r+1)^2 box neighbourhood with birth/survival count ranges.
+#[derive(Clone, Debug)]
+pub struct LtlRule {
+    /// Neighbourhood radius, at most one block (64).
+    pub radius: usize,
+    /// Whether the cell itself counts towards its neighbourhood (`M1` in LtL notation).
+    pub include_center: bool,
+    pub survival: RangeInclusive<u32>,
+    pub birth: RangeInclusive<u32>,
+}
+
+impl LtlRule {
+    /// `R5,C0,M1,S34..58,B34..45`
+    pub fn bugs() -> Self {
+        Self {
+            radius: 5,
+            include_center: true,
+            survival: 34..=58,
+            birth: 34..=45,
+        }
+    }
+
+    /// `R8,C0,M0,S163..223,B74..252`
+    #[allow(unused)]
+    pub fn globe() -> Self {
+        Self {
+            radius: 8,
+            include_center: false,
+            survival: 163..=223,
+            birth: 74..=252,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Block {
+    rows: [u64; BLOCK_SIZE],
+}
+
+impl Default for Block {
+    fn default() -> Self {
+        Self {
+            rows: [0; BLOCK_SIZE],
+        }
+    }
+}
+
+/// Sparse block engine for big-radius totalistic rules.
+///
+/// Each block is evaluated inside the 192x192 window formed by itself and its eight
+/// neighbours. Per-row prefix sums over the window's bitmaps are accumulated into a
+/// summed-area table, after which any box count is four lookups, whatever the radius.
+#[derive(Clone)]
+pub struct LargerLife {
+    blocks: FxHashMap<I64Vec2, Block>,
+    rule: LtlRule,
+    generation: u64,
+}
+
+impl LargerLife {
+    pub fn new() -> Self {
+        Self::with_rule(LtlRule::bugs())
+    }
+
+    pub fn with_rule(mut rule: LtlRule) -> Self {
+        rule.radius = rule.radius.clamp(1, BLOCK_SIZE);
+        Self {
+            blocks: FxHashMap::default(),
+            rule,
+            generation: 0,
+        }
+    }
+
+    #[allow(unused)]
+    pub fn rule(&self) -> &LtlRule {
+        &self.rule
+    }
+
+    #[allow(unused)]
+    pub fn set_rule(&mut self, mut rule: LtlRule) {
+        rule.radius = rule.radius.clamp(1, BLOCK_SIZE);
+        self.rule = rule;
+    }
+
+    #[inline]
+    fn get_coords(x: i64, y: i64) -> (I64Vec2, usize, usize) {
+        let bs = BLOCK_SIZE as i64;
+        (
+            I64Vec2::new(x.div_euclid(bs), y.div_euclid(bs)),
+            x.rem_euclid(bs) as usize,
+            y.rem_euclid(bs) as usize,
+        )
+    }
+
+    /// Summed-area table of the 3x3 blocks around `pos`: `sat[y * SAT_STRIDE + x]`
+    /// is the number of live cells in the window rectangle `[0, x) x [0, y)`.
+    fn summed_area(&self, pos: I64Vec2) -> Vec<u32> {
+        let mut sat = vec![0u32; SAT_STRIDE * SAT_STRIDE];
+        for wy in 0..WINDOW {
+            let block_y = (wy / BLOCK_SIZE) as i64 - 1;
+            let ly = wy % BLOCK_SIZE;
+            let row_blocks = [-1i64, 0, 1].map(|bx| {
+                self.blocks
+                    .get(&(pos + I64Vec2::new(bx, block_y)))
+                    .map_or(0, |b| b.rows[ly])
+            });
+
+            // Prefix sum along the row, added to the row above
+            let mut running = 0u32;
+            let (above, current) = sat.split_at_mut((wy + 1) * SAT_STRIDE);
+            let above = &above[wy * SAT_STRIDE..];
+            for wx in 0..WINDOW {
+                running += ((row_blocks[wx / BLOCK_SIZE] >> (wx % BLOCK_SIZE)) & 1) as u32;
+                current[wx + 1] = above[wx + 1] + running;
+            }
+        }
+        sat
+    }
+
+    fn evolve_block(&self, pos: I64Vec2) -> Option<Block> {
+        let sat = self.summed_area(pos);
+        let r = self.rule.radius;
+        let center = self.blocks.get(&pos);
+
+        let mut next = Block::default();
+        let mut any = false;
+        for ly in 0..BLOCK_SIZE {
+            let wy = BLOCK_SIZE + ly;
+            let (y0, y1) = (wy - r, wy + r + 1);
+            let row = center.map_or(0, |b| b.rows[ly]);
+            for lx in 0..BLOCK_SIZE {
+                let wx = BLOCK_SIZE + lx;
+                let (x0, x1) = (wx - r, wx + r + 1);
+                let alive = (row >> lx) & 1 == 1;
+                let mut count = sat[y1 * SAT_STRIDE + x1] + sat[y0 * SAT_STRIDE + x0]
+                    - sat[y0 * SAT_STRIDE + x1]
+                    - sat[y1 * SAT_STRIDE + x0];
+                if alive && !self.rule.include_center {
+                    count -= 1;
+                }
+
+                let lives = if alive {
+                    self.rule.survival.contains(&count)
+                } else {
+                    self.rule.birth.contains(&count)
+                };
+                if lives {
+                    next.rows[ly] |= 1 << lx;
+                    any = true;
+                }
+            }
+        }
+        any.then_some(next)
+    }
+
+    fn fill_rect_safe(buffer: &mut [u8], width: usize, height: usize, x: f64, y: f64, size: f64) {
+        let effective_size = size.max(1.0);
+
+        let sx = (x.round() as isize).clamp(0, width as isize) as usize;
+        let sy = (y.round() as isize).clamp(0, height as isize) as usize;
+        let ex = ((x + effective_size).round() as isize).clamp(0, width as isize) as usize;
+        let ey = ((y + effective_size).round() as isize).clamp(0, height as isize) as usize;
+
+        for row in sy..ey {
+            let offset = row * width;
+            buffer[offset + sx..offset + ex].fill(255);
+        }
+    }
+}
+
+impl LifeEngine for LargerLife {
+    fn id(&self) -> &str {
+        "larger-life"
+    }
+
+    fn name(&self) -> &str {
+        "LargerThanLife"
+    }
+
+    fn step(&mut self, steps: u64) -> u64 {
+        let _span = info_span!("larger_life::step", steps).entered();
+        for _ in 0..steps {
+            // The radius never exceeds a block, so only direct neighbours can change
+            let mut candidates = FxHashSet::default();
+            for &pos in self.blocks.keys() {
+                for dy in -1..=1 {
+                    for dx in -1..=1 {
+                        candidates.insert(pos + I64Vec2::new(dx, dy));
+                    }
+                }
+            }
+            let candidates: Vec<I64Vec2> = candidates.into_iter().collect();
+
+            let _evolve_span =
+                info_span!("larger_life::evolve_blocks", blocks = candidates.len()).entered();
+            self.blocks = candidates
+                .par_iter()
+                .filter_map(|&pos| self.evolve_block(pos).map(|block| (pos, block)))
+                .collect();
+            self.generation += 1;
+        }
+        steps
+    }
+
+    fn clear(&mut self) {
+        self.blocks.clear();
+        self.generation = 0;
+    }
+
+    fn population(&self) -> u64 {
+        self.blocks
+            .values()
+            .map(|b| b.rows.iter().map(|r| r.count_ones() as u64).sum::<u64>())
+            .sum()
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn set_generation(&mut self, generation: u64) {
+        self.generation = generation;
+    }
+
+    fn memory_bytes(&self) -> usize {
+        self.blocks.capacity() * (std::mem::size_of::<I64Vec2>() + std::mem::size_of::<Block>())
+    }
+
+    fn estimate_memory(&self, cells: u64, size: I64Vec2) -> u64 {
+        let entry = std::mem::size_of::<I64Vec2>() + std::mem::size_of::<Block>();
+        blocks_touched(cells, size, BLOCK_SIZE as i64).saturating_mul(entry as u64)
+    }
+
+    fn set_cell(&mut self, pos: I64Vec2, alive: bool) {
+        let (chunk_pos, lx, ly) = Self::get_coords(pos.x, pos.y);
+        if alive {
+            self.blocks.entry(chunk_pos).or_default().rows[ly] |= 1 << lx;
+        } else if let Some(block) = self.blocks.get_mut(&chunk_pos) {
+            block.rows[ly] &= !(1 << lx);
+        }
+    }
+
+    fn get_cell(&self, pos: I64Vec2) -> bool {
+        let (chunk_pos, lx, ly) = Self::get_coords(pos.x, pos.y);
+        self.blocks
+            .get(&chunk_pos)
+            .is_some_and(|b| (b.rows[ly] >> lx) & 1 == 1)
+    }
+
+    fn set_cells(&mut self, coords: &[I64Vec2], alive: bool) {
+        for &pos in coords {
+            self.set_cell(pos, alive);
+        }
+    }
+
+    fn import(&mut self, alive_cells: &[I64Vec2]) {
+        let _span = info_span!("larger_life::import", cells = alive_cells.len()).entered();
+        self.clear();
+        self.set_cells(alive_cells, true);
+    }
+
+    fn export(&self) -> Vec<I64Vec2> {
+        let _span = info_span!("larger_life::export").entered();
+        let mut cells = Vec::new();
+        for (pos, block) in &self.blocks {
+            let base = *pos * BLOCK_SIZE as i64;
+            for (y, &row) in block.rows.iter().enumerate() {
+                for x in 0..BLOCK_SIZE {
+                    if (row >> x) & 1 == 1 {
+                        cells.push(base + I64Vec2::new(x as i64, y as i64));
+                    }
+                }
+            }
+        }
+        cells
+    }
+
+    fn draw_to_buffer(
+        &self,
+        rect: Rect,
+        buffer: &mut [u8],
+        width: usize,
+        height: usize,
+        _population: u64,
+    ) {
+        let _span = info_span!("larger_life::draw").entered();
+        buffer.fill(0);
+
+        let scale = width as f64 / rect.width() as f64;
+        if scale <= 0.0001 || scale.is_infinite() || scale.is_nan() {
+            return;
+        }
+
+        let view_min_x = rect.min.x as f64;
+        let view_min_y = rect.min.y as f64;
+        let block_screen_size = BLOCK_SIZE as f64 * scale;
+        for (pos, block) in &self.blocks {
+            let base = *pos * BLOCK_SIZE as i64;
+            let bx = (base.x as f64 - view_min_x) * scale;
+            let by = (base.y as f64 - view_min_y) * scale;
+            if bx > width as f64
+                || by > height as f64
+                || bx + block_screen_size < 0.0
+                || by + block_screen_size < 0.0
+            {
+                continue;
+            }
+
+            for (ly, &row) in block.rows.iter().enumerate() {
+                if row == 0 {
+                    continue;
+                }
+                let sy = by + ly as f64 * scale;
+                for lx in 0..BLOCK_SIZE {
+                    if (row >> lx) & 1 == 1 {
+                        let sx = bx + lx as f64 * scale;
+                        Self::fill_rect_safe(buffer, width, height, sx, sy, scale);
+                    }
+                }
+            }
+        }
+    }
+
+    fn box_clone(&self) -> Box<dyn LifeEngine> {
+        Box::new(self.clone())
+    }
+}
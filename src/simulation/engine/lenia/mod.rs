@@ -0,0 +1,199 @@
+use std::sync::Arc;
+
+use crate::simulation::engine::LifeEngine;
+use crate::simulation::engine::float_grid::{FftConvolver, FloatGrid};
+use crate::simulation::par::*;
+use bevy::log::info_span;
+use bevy::math::{I64Vec2, Rect};
+use rustfft::num_complex::Complex32;
+
+/// Edge length of the (toroidal) world. Centered on the origin.
+const GRID_SIZE: usize = 512;
+
+/// Kernel and growth parameters of a Lenia world.
+#[derive(Clone, Copy, Debug)]
+pub struct LeniaParams {
+    /// Kernel radius in cells.
+    pub radius: f32,
+    /// Center of the growth bell.
+    pub mu: f32,
+    /// Width of the growth bell.
+    pub sigma: f32,
+    /// Integration time step.
+    pub dt: f32,
+}
+
+impl Default for LeniaParams {
+    /// The parameters of the classic Orbium glider.
+    fn default() -> Self {
+        Self {
+            radius: 13.0,
+            mu: 0.15,
+            sigma: 0.015,
+            dt: 0.1,
+        }
+    }
+}
+
+/// Experimental continuous CA: float states in [0, 1], a smooth ring kernel and a
+/// bell-shaped growth function.
+///
+/// The neighbourhood sum is a convolution with a kernel hundreds of cells wide, so it
+/// is done in frequency space: one forward and one inverse 2D FFT per step regardless
+/// of the kernel radius.
+#[derive(Clone)]
+pub struct LeniaLife {
+    grid: FloatGrid,
+    params: LeniaParams,
+    convolver: FftConvolver,
+    // Shared between clones since it only changes with `params`.
+    kernel: Arc<Vec<Complex32>>,
+    generation: u64,
+}
+
+impl LeniaLife {
+    pub fn new() -> Self {
+        Self::with_params(LeniaParams::default())
+    }
+
+    pub fn with_params(params: LeniaParams) -> Self {
+        let convolver = FftConvolver::new(GRID_SIZE);
+        let kernel = Self::kernel(&params, &convolver);
+        Self {
+            grid: FloatGrid::new(GRID_SIZE),
+            params,
+            convolver,
+            kernel,
+            generation: 0,
+        }
+    }
+
+    #[allow(unused)]
+    pub fn params(&self) -> LeniaParams {
+        self.params
+    }
+
+    #[allow(unused)]
+    pub fn set_params(&mut self, params: LeniaParams) {
+        self.params = params;
+        self.kernel = Self::kernel(&params, &self.convolver);
+    }
+
+    /// Smooth ring kernel `exp(4 - 1 / (r (1 - r)))` with `r` relative to the radius.
+    fn kernel(params: &LeniaParams, convolver: &FftConvolver) -> Arc<Vec<Complex32>> {
+        let radius = params.radius;
+        convolver.kernel(|distance| {
+            let r = distance / radius;
+            if r > 0.0 && r < 1.0 {
+                (4.0 - 1.0 / (r * (1.0 - r))).exp()
+            } else {
+                0.0
+            }
+        })
+    }
+
+    #[inline]
+    fn growth(&self, u: f32) -> f32 {
+        let d = (u - self.params.mu) / self.params.sigma;
+        2.0 * (-0.5 * d * d).exp() - 1.0
+    }
+}
+
+impl LifeEngine for LeniaLife {
+    fn id(&self) -> &str {
+        "lenia"
+    }
+
+    fn name(&self) -> &str {
+        "Lenia"
+    }
+
+    fn step(&mut self, steps: u64) -> u64 {
+        let _span = info_span!("lenia::step", steps).entered();
+        for _ in 0..steps {
+            let potential = {
+                let _span = info_span!("lenia::convolve").entered();
+                let spectrum = self.convolver.spectrum(&self.grid);
+                self.convolver.apply(&spectrum, &self.kernel)
+            };
+
+            let dt = self.params.dt;
+            let updates: Vec<f32> = potential.par_iter().map(|&u| self.growth(u)).collect();
+            self.grid
+                .state
+                .par_iter_mut()
+                .zip(updates)
+                .for_each(|(s, g)| *s = (*s + dt * g).clamp(0.0, 1.0));
+
+            self.generation += 1;
+        }
+        steps
+    }
+
+    fn clear(&mut self) {
+        self.grid.clear();
+        self.generation = 0;
+    }
+
+    fn population(&self) -> u64 {
+        self.grid.population()
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn set_generation(&mut self, generation: u64) {
+        self.generation = generation;
+    }
+
+    fn memory_bytes(&self) -> usize {
+        self.grid.memory_bytes() + self.kernel.capacity() * std::mem::size_of::<Complex32>()
+    }
+
+    // The grid has a fixed size; cells outside it are dropped on import
+    fn estimate_memory(&self, _cells: u64, _size: I64Vec2) -> u64 {
+        self.memory_bytes() as u64
+    }
+
+    fn set_cell(&mut self, pos: I64Vec2, alive: bool) {
+        self.grid.set_cell(pos, alive);
+    }
+
+    fn get_cell(&self, pos: I64Vec2) -> bool {
+        self.grid.get_cell(pos)
+    }
+
+    fn set_cells(&mut self, coords: &[I64Vec2], alive: bool) {
+        for &pos in coords {
+            self.grid.set_cell(pos, alive);
+        }
+    }
+
+    fn import(&mut self, alive_cells: &[I64Vec2]) {
+        let _span = info_span!("lenia::import", cells = alive_cells.len()).entered();
+        self.clear();
+        self.set_cells(alive_cells, true);
+    }
+
+    fn export(&self) -> Vec<I64Vec2> {
+        let _span = info_span!("lenia::export").entered();
+        self.grid.export()
+    }
+
+    fn draw_to_buffer(
+        &self,
+        rect: Rect,
+        buffer: &mut [u8],
+        width: usize,
+        _height: usize,
+        _population: u64,
+    ) {
+        let _span = info_span!("lenia::draw").entered();
+        self.grid.draw_to_buffer(rect, buffer, width);
+    }
+
+    fn box_clone(&self) -> Box<dyn LifeEngine> {
+        Box::new(self.clone())
+    }
+}
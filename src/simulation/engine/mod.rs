@@ -1,18 +1,169 @@
 use bevy::math::{I64Vec2, Rect};
+use rustc_hash::{FxHashMap, FxHashSet};
 
-use crate::simulation::engine::{
-    arena_life::ArenaLife, hash_life::HashLife, sparse_life::SparseLife,
-};
+use crate::simulation::par::*;
 
+#[cfg(feature = "arena-life")]
+use crate::simulation::engine::arena_life::ArenaLife;
+use crate::simulation::engine::stochastic_life::{StochasticLife, StochasticRule};
+use crate::simulation::error::LifeError;
+use crate::simulation::rules::LifeRule;
+
+#[cfg(feature = "arena-life")]
 mod arena_life;
+#[cfg(any(feature = "arena-life", feature = "sparse-life"))]
+mod block_kernel;
+#[cfg(any(feature = "lenia", feature = "smooth-life"))]
+mod float_grid;
+#[cfg(feature = "hash-life")]
 mod hash_life;
+#[cfg(feature = "larger-life")]
+mod larger_life;
+#[cfg(feature = "lenia")]
+mod lenia;
+mod registry;
+#[cfg(feature = "smooth-life")]
+mod smooth_life;
+#[cfg(feature = "sparse-life")]
 mod sparse_life;
+mod stochastic_life;
+
+pub use registry::{EngineEntry, EngineRegistry, RegisterEngine};
+
+/// Cells must lie in `-COORD_LIMIT..COORD_LIMIT` on both axes. Engines do block and tree
+/// arithmetic on coordinates; the limit leaves them enough headroom to never overflow.
+pub const COORD_LIMIT: i64 = 1 << 48;
+
+/// Side of the square tiles engines hand cells over in (see [`LifeEngine::visit_tiles`]).
+pub const TILE_SIZE: i64 = 64;
+
+/// `TILE_SIZE` square of cells, one row per `u64` with bit `x` set for column `x`.
+/// Row 0 is the lowest y; the tile at `pos` starts at cell `pos * TILE_SIZE`.
+pub type Tile = [u64; TILE_SIZE as usize];
+
+/// Whether every engine can hold a cell at `pos` (see [`COORD_LIMIT`]).
+#[inline]
+pub fn in_supported_range(pos: I64Vec2) -> bool {
+    (-COORD_LIMIT..COORD_LIMIT).contains(&pos.x) && (-COORD_LIMIT..COORD_LIMIT).contains(&pos.y)
+}
 
+/// Half-open rectangle of cells: `min` is inside, `max` is just past the far corner.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub enum EngineMode {
-    ArenaLife,
-    SparseLife,
-    HashLife,
+pub struct CellRegion {
+    pub min: I64Vec2,
+    pub max: I64Vec2,
+}
+
+impl CellRegion {
+    /// Smallest region containing both corner cells.
+    pub fn from_corners(a: I64Vec2, b: I64Vec2) -> Self {
+        Self {
+            min: a.min(b),
+            max: a.max(b) + I64Vec2::ONE,
+        }
+    }
+
+    pub fn size(&self) -> I64Vec2 {
+        self.max - self.min
+    }
+
+    #[inline]
+    pub fn contains(&self, pos: I64Vec2) -> bool {
+        pos.x >= self.min.x && pos.y >= self.min.y && pos.x < self.max.x && pos.y < self.max.y
+    }
+}
+
+/// A saved state of an engine that can share it with the live one, so taking it costs
+/// next to nothing (see [`LifeEngine::keyframe`]). Only the engine that took it, or a
+/// clone of that engine, can go back to it.
+#[derive(Clone)]
+pub struct Keyframe {
+    pub generation: u64,
+    state: std::sync::Arc<dyn std::any::Any + Send + Sync>,
+}
+
+impl Keyframe {
+    pub fn new(generation: u64, state: impl std::any::Any + Send + Sync) -> Self {
+        Self {
+            generation,
+            state: std::sync::Arc::new(state),
+        }
+    }
+
+    /// The engine-specific state, if it is a `T`.
+    pub fn state<T: std::any::Any>(&self) -> Option<&T> {
+        self.state.downcast_ref()
+    }
+}
+
+/// One node of a quadtree engine, as shown by debugging tools.
+#[derive(Clone, Debug)]
+pub struct TreeNodeInfo {
+    pub level: u8,
+    pub population: u64,
+    pub region: CellRegion,
+    /// Whether the node's `2^(level-2)` generation jump is cached.
+    pub cached_jump: bool,
+    /// Whether its one-generation result is cached.
+    pub cached_step: bool,
+    /// NW, NE, SW, SE, with NW at the lowest coordinates; empty for leaves and for
+    /// the children themselves.
+    pub children: Vec<TreeNodeInfo>,
+}
+
+/// Which blocks an engine that skips quiet areas is tracking, for debugging overlays.
+/// Blocks are `block_size` cells square; `min` corner at `pos * block_size`.
+#[derive(Clone, Debug, Default)]
+pub struct BlockActivity {
+    pub block_size: i64,
+    /// Blocks that hold cells.
+    pub occupied: Vec<I64Vec2>,
+    /// Blocks that changed last step, so they and their neighbours get evaluated next.
+    pub active: Vec<I64Vec2>,
+}
+
+/// What an engine that works in blocks did during its last [`LifeEngine::step`] call,
+/// summed over the generations it ran.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BlockStats {
+    pub generations: u64,
+    /// Blocks whose next generation was computed.
+    pub evaluated: u64,
+    /// Blocks looked at but left alone, as nothing in or around them could change.
+    pub skipped: u64,
+    /// Blocks created for cells growing into them.
+    pub spawned: u64,
+    /// Blocks dropped after they emptied out.
+    pub freed: u64,
+    /// Blocks asleep after the last generation, kept packed as nothing around them
+    /// changes.
+    pub cold: u64,
+}
+
+/// How a pasted pattern combines with the cells already inside its bounding box
+/// (named after Golly's paste modes).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PasteMode {
+    /// Pattern cells are added, existing cells stay.
+    #[default]
+    Or,
+    /// Pattern cells toggle the cells they land on.
+    Xor,
+    /// Only cells alive in both survive.
+    And,
+    /// The bounding box is replaced by the pattern.
+    Copy,
+}
+
+impl PasteMode {
+    pub fn next(self) -> Self {
+        match self {
+            PasteMode::Or => PasteMode::Xor,
+            PasteMode::Xor => PasteMode::And,
+            PasteMode::And => PasteMode::Copy,
+            PasteMode::Copy => PasteMode::Or,
+        }
+    }
 }
 
 // 1. The Trait must be Object Safe.
@@ -26,6 +177,15 @@ pub trait LifeEngine: Send + Sync {
 
     fn population(&self) -> u64;
 
+    /// Number of generations stepped since the last clear/import.
+    fn generation(&self) -> u64;
+
+    /// Carries on counting from `generation`, e.g. when a saved session is restored.
+    fn set_generation(&mut self, generation: u64);
+
+    /// Rough estimate of the heap memory held by the engine, in bytes.
+    fn memory_bytes(&self) -> usize;
+
     fn set_cell(&mut self, pos: I64Vec2, alive: bool);
     fn get_cell(&self, pos: I64Vec2) -> bool;
 
@@ -34,9 +194,187 @@ pub trait LifeEngine: Send + Sync {
     fn import(&mut self, alive_cells: &[I64Vec2]);
     fn export(&self) -> Vec<I64Vec2>;
 
-    fn draw_to_buffer(&self, world_rect: Rect, buffer: &mut [u8], width: usize, height: usize);
+    /// The live cells inside `region`. The default skips tiles outside it (see
+    /// [`LifeEngine::visit_tiles`]); engines that can skip more override it.
+    fn export_region(&self, region: CellRegion) -> Vec<I64Vec2> {
+        let mut cells = Vec::new();
+        self.visit_tiles(&mut |pos, tile| {
+            let base = pos * TILE_SIZE;
+            let covered = CellRegion {
+                min: base,
+                max: base + I64Vec2::splat(TILE_SIZE),
+            };
+            if covered.max.cmpgt(region.min).all() && covered.min.cmplt(region.max).all() {
+                cells.extend(
+                    tile_cells(tile)
+                        .map(|local| base + local)
+                        .filter(|&pos| region.contains(pos)),
+                );
+            }
+        });
+        // Tiles can come up more than once
+        cells.sort_unstable_by_key(|pos| (pos.y, pos.x));
+        cells.dedup();
+        cells
+    }
+
+    /// Draws the cells in `world_rect` into a `width` × `height` buffer. `population` is
+    /// the live cell count as the caller last knew it, for choosing how to draw without a
+    /// recount every frame.
+    fn draw_to_buffer(
+        &self,
+        world_rect: Rect,
+        buffer: &mut [u8],
+        width: usize,
+        height: usize,
+        population: u64,
+    );
+
+    /// Seeds engines that use randomness. Deterministic engines ignore it.
+    fn set_seed(&mut self, _seed: u64) {}
+
+    /// Whether [`LifeEngine::set_torus`] does anything.
+    fn supports_torus(&self) -> bool {
+        false
+    }
+
+    /// Wraps the plane into a `size` torus with its corner at the origin (`None` unwraps),
+    /// re-mapping the current cells modulo the new size.
+    fn set_torus(&mut self, _size: Option<I64Vec2>) {}
+
+    /// Combines `cells` (all inside `region`) with the current cells in `region`.
+    /// The default goes cell by cell; engines with bit-level storage should override it.
+    fn paste(&mut self, region: CellRegion, cells: &[I64Vec2], mode: PasteMode) {
+        match mode {
+            PasteMode::Or => self.set_cells(cells, true),
+            PasteMode::Xor => {
+                for &pos in cells {
+                    let alive = self.get_cell(pos);
+                    self.set_cell(pos, !alive);
+                }
+            }
+            PasteMode::And | PasteMode::Copy => {
+                let pattern: FxHashSet<I64Vec2> = cells.iter().copied().collect();
+                for y in region.min.y..region.max.y {
+                    for x in region.min.x..region.max.x {
+                        let pos = I64Vec2::new(x, y);
+                        let keep = pattern.contains(&pos)
+                            && (mode == PasteMode::Copy || self.get_cell(pos));
+                        self.set_cell(pos, keep);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Calls `visit` with every tile holding live cells, so cells can be moved between
+    /// engines without a list of all of them. A tile may come up more than once with
+    /// parts of its cells; the union is what's alive. The default goes through `export`.
+    fn visit_tiles(&self, visit: &mut dyn FnMut(I64Vec2, &Tile)) {
+        let mut tiles: FxHashMap<I64Vec2, Tile> = FxHashMap::default();
+        for pos in self.export() {
+            let tile = tiles
+                .entry(pos.div_euclid(I64Vec2::splat(TILE_SIZE)))
+                .or_insert([0; TILE_SIZE as usize]);
+            let local = pos.rem_euclid(I64Vec2::splat(TILE_SIZE));
+            tile[local.y as usize] |= 1 << local.x;
+        }
+        for (pos, tile) in &tiles {
+            visit(*pos, tile);
+        }
+    }
+
+    /// Sets the live cells of `tile` (at tile position `pos`) alive, as `set_cells` would.
+    fn add_tile(&mut self, pos: I64Vec2, tile: &Tile) {
+        let base = pos * TILE_SIZE;
+        let cells: Vec<I64Vec2> = tile_cells(tile).map(|local| base + local).collect();
+        self.set_cells(&cells, true);
+    }
+
+    /// Whether [`LifeEngine::set_walls`] does anything.
+    fn supports_walls(&self) -> bool {
+        false
+    }
+
+    /// Turns cells into (or back from) walls: permanently occupied cells that count as
+    /// alive for their neighbours but are never born, never die and are not part of
+    /// the population. Walls survive `clear`/`import` only if re-applied afterwards.
+    fn set_walls(&mut self, _coords: &[I64Vec2], _wall: bool) {}
+
+    /// Whether [`LifeEngine::set_step_region`] does anything.
+    fn supports_step_region(&self) -> bool {
+        false
+    }
+
+    /// Confines stepping to `region` (`None` steps everything). Cells outside stay frozen
+    /// but still count as neighbours for the cells inside.
+    fn set_step_region(&mut self, _region: Option<CellRegion>) {}
 
-    // The Magic Method for cloning Box<dyn LifeEngine>
+    /// Describes the node reached from the root by taking the children at `path`
+    /// (indices into [`TreeNodeInfo::children`]). `None` for engines without a tree
+    /// and for paths that lead past a leaf.
+    fn inspect_tree(&self, _path: &[usize]) -> Option<TreeNodeInfo> {
+        None
+    }
+
+    /// The current state as a [`Keyframe`]; `None` for engines that can't take one
+    /// without copying their cells.
+    fn keyframe(&self) -> Option<Keyframe> {
+        None
+    }
+
+    /// Goes back (or forward) to a keyframe this engine took. Returns false, leaving the
+    /// engine as it is, for keyframes it can't restore.
+    fn restore_keyframe(&mut self, _keyframe: &Keyframe) -> bool {
+        false
+    }
+
+    /// The engine's block bookkeeping; `None` for engines that don't track activity.
+    fn block_activity(&self) -> Option<BlockActivity> {
+        None
+    }
+
+    /// Block counters of the last step; `None` for engines that don't work in blocks.
+    fn block_stats(&self) -> Option<BlockStats> {
+        None
+    }
+
+    /// Rough number of bytes the engine would hold after importing `cells` cells spread
+    /// over a `size` bounding box, so importers can refuse patterns that won't fit. The
+    /// default assumes a hash set entry plus neighbour counts per cell.
+    fn estimate_memory(&self, cells: u64, _size: I64Vec2) -> u64 {
+        cells.saturating_mul(96)
+    }
+
+    /// Streams the universe to `out` in Golly's macrocell format, one line per distinct
+    /// tree node, so repeated areas cost nothing extra. `None` for engines without a tree.
+    fn write_macrocell(&self, _out: &mut dyn std::io::Write) -> Option<std::io::Result<()>> {
+        None
+    }
+
+    /// Replaces the universe with a macrocell file (as written by
+    /// [`LifeEngine::write_macrocell`] or Golly), building the tree straight from its
+    /// nodes. The file's root is centered on `center`. On an error the universe is left
+    /// as it was. `None` for engines without a tree.
+    fn read_macrocell(&mut self, _text: &str, _center: I64Vec2) -> Option<Result<(), LifeError>> {
+        None
+    }
+
+    /// Fingerprint of the live cells (see [`hash_cells`]), equal across engines and
+    /// platforms for equal cells. Engines storing cells in blocks can override it by
+    /// summing [`cell_hash`] block by block.
+    fn state_hash(&self) -> u64 {
+        hash_cells(&self.export())
+    }
+
+    /// Whether stepping a clone while the original keeps rendering is worthwhile.
+    /// Engines whose clone is expensive compared to a step should return false.
+    fn prefers_pipelining(&self) -> bool {
+        true
+    }
+
+    // The Magic Method for cloning Box<dyn LifeEngine>. Should share state copy-on-write
+    // where the engine can, since forks and pipelined steps clone whole universes.
     fn box_clone(&self) -> Box<dyn LifeEngine>;
 }
 
@@ -47,11 +385,187 @@ impl Clone for Box<dyn LifeEngine> {
     }
 }
 
-// 3. Factory Function to create engines
-pub fn create_engine(mode: EngineMode) -> Box<dyn LifeEngine> {
-    match mode {
-        EngineMode::ArenaLife => Box::new(ArenaLife::new()),
-        EngineMode::SparseLife => Box::new(SparseLife::new()),
-        EngineMode::HashLife => Box::new(HashLife::new()),
+/// Local positions of the live cells in `tile`.
+pub fn tile_cells(tile: &Tile) -> impl Iterator<Item = I64Vec2> + '_ {
+    tile.iter().enumerate().flat_map(|(y, &row)| {
+        (0..TILE_SIZE)
+            .filter(move |&x| (row >> x) & 1 == 1)
+            .map(move |x| I64Vec2::new(x, y as i64))
+    })
+}
+
+/// Upper bound on the `block_size` square blocks that `cells` cells in a `size` bounding
+/// box can touch: one per cell when sparse, every block of the box when dense.
+pub fn blocks_touched(cells: u64, size: I64Vec2, block_size: i64) -> u64 {
+    // +1: the box rarely lines up with the block grid
+    let across = |len: i64| (len.max(0) / block_size + 1) as u64;
+    cells.min(across(size.x).saturating_mul(across(size.y)))
+}
+
+/// Calls `visit` for each block of a block map that overlaps `rect` (in cells). When the
+/// rect covers fewer blocks than the map holds, the blocks under it are looked up one by
+/// one, so drawing a small window into a huge universe doesn't walk every block.
+pub fn visit_blocks_in<'a, B>(
+    blocks: &'a FxHashMap<I64Vec2, B>,
+    rect: Rect,
+    block_size: i64,
+    mut visit: impl FnMut(I64Vec2, &'a B),
+) {
+    let to_block = |v: f32| (v.floor() as i64).div_euclid(block_size);
+    let min = I64Vec2::new(to_block(rect.min.x), to_block(rect.min.y));
+    let max = I64Vec2::new(to_block(rect.max.x), to_block(rect.max.y));
+    let covered = ((max.x - min.x + 1) as u64).saturating_mul((max.y - min.y + 1) as u64);
+
+    if covered < blocks.len() as u64 {
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                let pos = I64Vec2::new(x, y);
+                if let Some(block) = blocks.get(&pos) {
+                    visit(pos, block);
+                }
+            }
+        }
+    } else {
+        for (&pos, block) in blocks {
+            if pos.cmpge(min).all() && pos.cmple(max).all() {
+                visit(pos, block);
+            }
+        }
+    }
+}
+
+/// Pixel rows per band of [`draw_blocks_sparse`].
+const BAND_ROWS: usize = 32;
+
+/// Draws the live cells of the `TILE_SIZE` blocks under `rect` into `buffer` as squares
+/// of `scale` pixels (at least one), for views with few cells; `rows` gives a block's
+/// rows, or `None` to skip it. The buffer is cut into horizontal bands drawn in
+/// parallel, each from the blocks that reach into it.
+pub fn draw_blocks_sparse<'a, B>(
+    blocks: &'a FxHashMap<I64Vec2, B>,
+    rows: impl Fn(&'a B) -> Option<&'a Tile>,
+    rect: Rect,
+    buffer: &mut [u8],
+    width: usize,
+    scale: f64,
+) {
+    let mut visible: Vec<(I64Vec2, &Tile)> = Vec::new();
+    visit_blocks_in(blocks, rect, TILE_SIZE, |pos, block| {
+        if let Some(tile) = rows(block) {
+            visible.push((pos, tile));
+        }
+    });
+    draw_tiles_sparse(visible, rect, buffer, width, scale);
+}
+
+/// [`draw_blocks_sparse`] for tiles already picked, e.g. gathered from more than one
+/// table.
+pub fn draw_tiles_sparse(
+    mut visible: Vec<(I64Vec2, &Tile)>,
+    rect: Rect,
+    buffer: &mut [u8],
+    width: usize,
+    scale: f64,
+) {
+    if width == 0 {
+        return;
+    }
+    visible.sort_unstable_by_key(|(pos, _)| pos.y);
+
+    let view_min_x = rect.min.x as f64;
+    let view_min_y = rect.min.y as f64;
+    // A cell reaches into the band below by up to its size, which is at least a pixel
+    let margin = 1.0f64.max(1.0 / scale);
+
+    buffer
+        .par_chunks_mut(BAND_ROWS * width)
+        .enumerate()
+        .for_each(|(band, pixels)| {
+            pixels.fill(0);
+            let top = (band * BAND_ROWS) as f64;
+            let band_height = pixels.len() / width;
+            let first_cell = (view_min_y + top / scale - margin).floor() as i64;
+            let last_cell = (view_min_y + (top + band_height as f64) / scale).ceil() as i64;
+
+            let start =
+                visible.partition_point(|(pos, _)| pos.y < first_cell.div_euclid(TILE_SIZE));
+            let end = visible.partition_point(|(pos, _)| pos.y <= last_cell.div_euclid(TILE_SIZE));
+            for &(pos, tile) in &visible[start..end] {
+                let base = pos * TILE_SIZE;
+                let ly0 = (first_cell - base.y).clamp(0, TILE_SIZE) as usize;
+                let ly1 = (last_cell + 1 - base.y).clamp(0, TILE_SIZE) as usize;
+                for (ly, mut row) in tile.iter().copied().enumerate().take(ly1).skip(ly0) {
+                    let sy = ((base.y + ly as i64) as f64 - view_min_y) * scale - top;
+                    while row != 0 {
+                        let lx = row.trailing_zeros() as i64;
+                        row &= row - 1;
+                        let sx = ((base.x + lx) as f64 - view_min_x) * scale;
+                        fill_rect(pixels, width, band_height, sx, sy, scale);
+                    }
+                }
+            }
+        });
+}
+
+/// Fills the `size` square at (`x`, `y`) in pixels, rounding its edges to whole pixels
+/// so neighbouring cells neither overlap nor leave gaps, clipped to the buffer.
+fn fill_rect(buffer: &mut [u8], width: usize, height: usize, x: f64, y: f64, size: f64) {
+    let effective_size = size.max(1.0);
+
+    let start_x = x.round() as isize;
+    let start_y = y.round() as isize;
+    let end_x = (x + effective_size).round() as isize;
+    let end_y = (y + effective_size).round() as isize;
+
+    let sx = start_x.max(0).min(width as isize) as usize;
+    let sy = start_y.max(0).min(height as isize) as usize;
+    let ex = end_x.max(0).min(width as isize) as usize;
+    let ey = end_y.max(0).min(height as isize) as usize;
+
+    if sx >= ex || sy >= ey {
+        return;
+    }
+
+    for row in sy..ey {
+        let offset = row * width;
+        buffer[offset + sx..offset + ex].fill(255);
     }
 }
+
+/// Mixes a cell position into 64 bits (SplitMix64 finalizer), independent of the
+/// platform and the Rust version, unlike the std hashers.
+pub fn cell_hash(pos: I64Vec2) -> u64 {
+    let mut z = (pos.x as u64)
+        .wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        .wrapping_add(pos.y as u64)
+        .wrapping_add(0x632B_E59B_D9B4_E019);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Canonical hash of a set of live cells: the wrapping sum of their [`cell_hash`]es,
+/// so it doesn't depend on the order (or blocks) the cells come in.
+pub fn hash_cells(cells: &[I64Vec2]) -> u64 {
+    cells
+        .iter()
+        .fold(0u64, |sum, &pos| sum.wrapping_add(cell_hash(pos)))
+}
+
+/// The engine the universe starts on and returns to for Conway's rule.
+#[cfg(feature = "arena-life")]
+pub fn default_engine() -> Box<dyn LifeEngine> {
+    Box::new(ArenaLife::new())
+}
+
+/// Without ArenaLife, Conway's rule runs on the rule engine.
+#[cfg(not(feature = "arena-life"))]
+pub fn default_engine() -> Box<dyn LifeEngine> {
+    create_rule_engine(LifeRule::CONWAY)
+}
+
+/// A small general-purpose engine running an arbitrary Life-like rule.
+/// The bitwise engines are hardwired to B3/S23; this one trades speed for flexibility.
+pub fn create_rule_engine(rule: LifeRule) -> Box<dyn LifeEngine> {
+    Box::new(StochasticLife::with_rule(StochasticRule::exact(rule)))
+}
@@ -0,0 +1,148 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+
+use crate::simulation::engine::LifeEngine;
+#[cfg(feature = "arena-life")]
+use crate::simulation::engine::arena_life::ArenaLife;
+#[cfg(feature = "hash-life")]
+use crate::simulation::engine::hash_life::HashLife;
+#[cfg(feature = "larger-life")]
+use crate::simulation::engine::larger_life::LargerLife;
+#[cfg(feature = "lenia")]
+use crate::simulation::engine::lenia::LeniaLife;
+#[cfg(feature = "smooth-life")]
+use crate::simulation::engine::smooth_life::SmoothLife;
+#[cfg(feature = "sparse-life")]
+use crate::simulation::engine::sparse_life::SparseLife;
+use crate::simulation::engine::stochastic_life::StochasticLife;
+
+type EngineFactory = Arc<dyn Fn() -> Box<dyn LifeEngine> + Send + Sync>;
+
+/// An engine that can be selected at runtime.
+#[derive(Clone)]
+pub struct EngineEntry {
+    pub id: String,
+    pub name: String,
+    /// Whether the engine computes exactly B3/S23 (the continuous and stochastic ones don't).
+    pub exact_conway: bool,
+    factory: EngineFactory,
+}
+
+impl EngineEntry {
+    /// `id` should match [`LifeEngine::id`] of the engines `factory` creates.
+    pub fn new(
+        id: impl Into<String>,
+        name: impl Into<String>,
+        factory: impl Fn() -> Box<dyn LifeEngine> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            exact_conway: false,
+            factory: Arc::new(factory),
+        }
+    }
+
+    pub fn exact_conway(mut self) -> Self {
+        self.exact_conway = true;
+        self
+    }
+
+    pub fn create(&self) -> Box<dyn LifeEngine> {
+        (self.factory)()
+    }
+}
+
+/// The engines the universe can switch to, in the order of their number keys.
+/// Starts out with the engines compiled into this build; other crates add theirs
+/// with [`RegisterEngine::register_engine`].
+#[derive(Resource, Clone)]
+pub struct EngineRegistry {
+    entries: Vec<EngineEntry>,
+}
+
+impl Default for EngineRegistry {
+    fn default() -> Self {
+        Self::with_builtins()
+    }
+}
+
+impl EngineRegistry {
+    pub fn empty() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::empty();
+        #[cfg(feature = "arena-life")]
+        registry.register(
+            EngineEntry::new("arena-life", "ArenaLife", || Box::new(ArenaLife::new()))
+                .exact_conway(),
+        );
+        #[cfg(feature = "sparse-life")]
+        registry.register(
+            EngineEntry::new("sparse-life", "SparseLife", || Box::new(SparseLife::new()))
+                .exact_conway(),
+        );
+        #[cfg(feature = "hash-life")]
+        registry.register(
+            EngineEntry::new("hash-life", "HashLife", || Box::new(HashLife::new())).exact_conway(),
+        );
+        registry.register(EngineEntry::new(
+            "stochastic-life",
+            "StochasticLife",
+            || Box::new(StochasticLife::new()),
+        ));
+        #[cfg(feature = "lenia")]
+        registry.register(EngineEntry::new("lenia", "Lenia", || {
+            Box::new(LeniaLife::new())
+        }));
+        #[cfg(feature = "smooth-life")]
+        registry.register(EngineEntry::new("smooth-life", "SmoothLife", || {
+            Box::new(SmoothLife::new())
+        }));
+        #[cfg(feature = "larger-life")]
+        registry.register(EngineEntry::new("larger-life", "LargerThanLife", || {
+            Box::new(LargerLife::new())
+        }));
+        registry
+    }
+
+    /// Adds `entry` at the end, or replaces the engine already registered under its id.
+    pub fn register(&mut self, entry: EngineEntry) {
+        match self.entries.iter_mut().find(|e| e.id == entry.id) {
+            Some(existing) => *existing = entry,
+            None => self.entries.push(entry),
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Option<&EngineEntry> {
+        self.entries.iter().find(|e| e.id == id)
+    }
+
+    pub fn create(&self, id: &str) -> Option<Box<dyn LifeEngine>> {
+        self.get(id).map(EngineEntry::create)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &EngineEntry> {
+        self.entries.iter()
+    }
+}
+
+/// Lets plugins add engines to the [`EngineRegistry`], before or after the simulation
+/// plugin is added.
+pub trait RegisterEngine {
+    fn register_engine(&mut self, entry: EngineEntry) -> &mut Self;
+}
+
+impl RegisterEngine for App {
+    fn register_engine(&mut self, entry: EngineEntry) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<EngineRegistry>()
+            .register(entry);
+        self
+    }
+}
@@ -0,0 +1,220 @@
+use std::sync::Arc;
+
+use crate::simulation::engine::LifeEngine;
+use crate::simulation::engine::float_grid::{FftConvolver, FloatGrid};
+use crate::simulation::par::*;
+use bevy::log::info_span;
+use bevy::math::{I64Vec2, Rect};
+use rustfft::num_complex::Complex32;
+
+/// Edge length of the (toroidal) world. Centered on the origin.
+const GRID_SIZE: usize = 512;
+
+/// Parameters of Rafler's SmoothLife.
+#[derive(Clone, Copy, Debug)]
+pub struct SmoothLifeParams {
+    /// Radius of the outer annulus; the inner disc is a third of it.
+    pub outer_radius: f32,
+    /// Birth interval of the outer filling.
+    pub birth: (f32, f32),
+    /// Survival interval of the outer filling.
+    pub death: (f32, f32),
+    /// Smoothness of the outer filling thresholds.
+    pub alpha_n: f32,
+    /// Smoothness of the inner filling (alive/dead) threshold.
+    pub alpha_m: f32,
+    /// Integration time step.
+    pub dt: f32,
+}
+
+impl Default for SmoothLifeParams {
+    /// The parameters from the SmoothLife paper that produce gliders.
+    fn default() -> Self {
+        Self {
+            outer_radius: 21.0,
+            birth: (0.278, 0.365),
+            death: (0.267, 0.445),
+            alpha_n: 0.028,
+            alpha_m: 0.147,
+            dt: 0.1,
+        }
+    }
+}
+
+impl SmoothLifeParams {
+    #[inline]
+    fn sigmoid(x: f32, a: f32, alpha: f32) -> f32 {
+        1.0 / (1.0 + (-(x - a) * 4.0 / alpha).exp())
+    }
+
+    /// Continuous version of the Life rule: `n` is the outer (neighbourhood) filling,
+    /// `m` the inner (cell) filling. Returns the target state in [0, 1].
+    #[inline]
+    fn transition(&self, n: f32, m: f32) -> f32 {
+        let alive = Self::sigmoid(m, 0.5, self.alpha_m);
+        let lo = self.birth.0 * (1.0 - alive) + self.death.0 * alive;
+        let hi = self.birth.1 * (1.0 - alive) + self.death.1 * alive;
+        Self::sigmoid(n, lo, self.alpha_n) * (1.0 - Self::sigmoid(n, hi, self.alpha_n))
+    }
+}
+
+/// Continuous-space Life: a cell is a disc, its neighbourhood the surrounding annulus,
+/// and both are integrated by FFT convolution every step.
+#[derive(Clone)]
+pub struct SmoothLife {
+    grid: FloatGrid,
+    params: SmoothLifeParams,
+    convolver: FftConvolver,
+    // Shared between clones since they only change with `params`.
+    inner: Arc<Vec<Complex32>>,
+    outer: Arc<Vec<Complex32>>,
+    generation: u64,
+}
+
+impl SmoothLife {
+    pub fn new() -> Self {
+        Self::with_params(SmoothLifeParams::default())
+    }
+
+    pub fn with_params(params: SmoothLifeParams) -> Self {
+        let convolver = FftConvolver::new(GRID_SIZE);
+        let (inner, outer) = Self::kernels(&params, &convolver);
+        Self {
+            grid: FloatGrid::new(GRID_SIZE),
+            params,
+            convolver,
+            inner,
+            outer,
+            generation: 0,
+        }
+    }
+
+    #[allow(unused)]
+    pub fn params(&self) -> SmoothLifeParams {
+        self.params
+    }
+
+    #[allow(unused)]
+    pub fn set_params(&mut self, params: SmoothLifeParams) {
+        self.params = params;
+        (self.inner, self.outer) = Self::kernels(&params, &self.convolver);
+    }
+
+    /// Disc and annulus with anti-aliased (half-cell) edges.
+    fn kernels(
+        params: &SmoothLifeParams,
+        convolver: &FftConvolver,
+    ) -> (Arc<Vec<Complex32>>, Arc<Vec<Complex32>>) {
+        let ra = params.outer_radius;
+        let ri = ra / 3.0;
+        let disc = move |r: f32, d: f32| (r + 0.5 - d).clamp(0.0, 1.0);
+        let inner = convolver.kernel(|d| disc(ri, d));
+        let outer = convolver.kernel(|d| disc(ra, d) * (1.0 - disc(ri, d)));
+        (inner, outer)
+    }
+}
+
+impl LifeEngine for SmoothLife {
+    fn id(&self) -> &str {
+        "smooth-life"
+    }
+
+    fn name(&self) -> &str {
+        "SmoothLife"
+    }
+
+    fn step(&mut self, steps: u64) -> u64 {
+        let _span = info_span!("smooth_life::step", steps).entered();
+        for _ in 0..steps {
+            let (m, n) = {
+                let _span = info_span!("smooth_life::convolve").entered();
+                let spectrum = self.convolver.spectrum(&self.grid);
+                (
+                    self.convolver.apply(&spectrum, &self.inner),
+                    self.convolver.apply(&spectrum, &self.outer),
+                )
+            };
+
+            let params = self.params;
+            self.grid
+                .state
+                .par_iter_mut()
+                .zip(m.par_iter().zip(n.par_iter()))
+                .for_each(|(s, (&m, &n))| {
+                    let target = params.transition(n, m);
+                    *s = (*s + params.dt * (2.0 * target - 1.0)).clamp(0.0, 1.0);
+                });
+
+            self.generation += 1;
+        }
+        steps
+    }
+
+    fn clear(&mut self) {
+        self.grid.clear();
+        self.generation = 0;
+    }
+
+    fn population(&self) -> u64 {
+        self.grid.population()
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn set_generation(&mut self, generation: u64) {
+        self.generation = generation;
+    }
+
+    fn memory_bytes(&self) -> usize {
+        self.grid.memory_bytes()
+            + (self.inner.capacity() + self.outer.capacity()) * std::mem::size_of::<Complex32>()
+    }
+
+    // The grid has a fixed size; cells outside it are dropped on import
+    fn estimate_memory(&self, _cells: u64, _size: I64Vec2) -> u64 {
+        self.memory_bytes() as u64
+    }
+
+    fn set_cell(&mut self, pos: I64Vec2, alive: bool) {
+        self.grid.set_cell(pos, alive);
+    }
+
+    fn get_cell(&self, pos: I64Vec2) -> bool {
+        self.grid.get_cell(pos)
+    }
+
+    fn set_cells(&mut self, coords: &[I64Vec2], alive: bool) {
+        for &pos in coords {
+            self.grid.set_cell(pos, alive);
+        }
+    }
+
+    fn import(&mut self, alive_cells: &[I64Vec2]) {
+        let _span = info_span!("smooth_life::import", cells = alive_cells.len()).entered();
+        self.clear();
+        self.set_cells(alive_cells, true);
+    }
+
+    fn export(&self) -> Vec<I64Vec2> {
+        let _span = info_span!("smooth_life::export").entered();
+        self.grid.export()
+    }
+
+    fn draw_to_buffer(
+        &self,
+        rect: Rect,
+        buffer: &mut [u8],
+        width: usize,
+        _height: usize,
+        _population: u64,
+    ) {
+        let _span = info_span!("smooth_life::draw").entered();
+        self.grid.draw_to_buffer(rect, buffer, width);
+    }
+
+    fn box_clone(&self) -> Box<dyn LifeEngine> {
+        Box::new(self.clone())
+    }
+}
@@ -1,10 +1,18 @@
-use crate::simulation::engine::LifeEngine;
+use crate::simulation::engine::block_kernel::{self, Window};
+use crate::simulation::engine::{
+    BlockActivity, BlockStats, LifeEngine, Tile, blocks_touched, draw_tiles_sparse, visit_blocks_in,
+};
+use crate::simulation::par::*;
+use bevy::log::info_span;
 use bevy::math::{I64Vec2, Rect};
-use rayon::prelude::*;
 use rustc_hash::{FxHashMap, FxHashSet};
+use std::sync::{Arc, OnceLock};
 
 const BLOCK_SIZE: usize = 64;
 
+/// Side, in blocks, of the squares the cold blocks are sharded into.
+const SHARD_BLOCKS: i64 = 16;
+
 #[derive(Clone, Copy)]
 struct Block {
     rows: [u64; BLOCK_SIZE],
@@ -18,31 +26,191 @@ impl Default for Block {
     }
 }
 
+/// A block as kept while it sleeps: a mask of its non-empty rows and those rows in
+/// order. Ash is mostly empty rows, so this is a tenth of a [`Block`] or less.
+#[derive(Clone)]
+struct PackedBlock {
+    mask: u64,
+    rows: Box<[u64]>,
+}
+
+impl PackedBlock {
+    fn pack(block: &Block) -> Self {
+        let mask = block
+            .rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| **row != 0)
+            .fold(0, |mask, (y, _)| mask | (1 << y));
+        Self {
+            mask,
+            rows: block.rows.iter().copied().filter(|&row| row != 0).collect(),
+        }
+    }
+
+    #[inline]
+    fn row(&self, y: usize) -> u64 {
+        if (self.mask >> y) & 1 == 0 {
+            return 0;
+        }
+        // Kept rows before this one
+        self.rows[(self.mask & ((1 << y) - 1)).count_ones() as usize]
+    }
+
+    fn unpack(&self) -> Block {
+        Block {
+            rows: std::array::from_fn(|y| self.row(y)),
+        }
+    }
+
+    fn population(&self) -> u64 {
+        self.rows.iter().map(|r| r.count_ones() as u64).sum()
+    }
+}
+
+/// The sleeping blocks, sharded so that clones share them and waking a block copies
+/// only its shard, not all of them.
+#[derive(Clone, Default)]
+struct ColdStore {
+    shards: FxHashMap<I64Vec2, Arc<FxHashMap<I64Vec2, PackedBlock>>>,
+    len: usize,
+}
+
+impl ColdStore {
+    fn shard(pos: I64Vec2) -> I64Vec2 {
+        pos.div_euclid(I64Vec2::splat(SHARD_BLOCKS))
+    }
+
+    fn get(&self, pos: I64Vec2) -> Option<&PackedBlock> {
+        self.shards.get(&Self::shard(pos))?.get(&pos)
+    }
+
+    fn insert(&mut self, pos: I64Vec2, block: PackedBlock) {
+        let shard = self.shards.entry(Self::shard(pos)).or_default();
+        if Arc::make_mut(shard).insert(pos, block).is_none() {
+            self.len += 1;
+        }
+    }
+
+    fn remove(&mut self, pos: I64Vec2) -> Option<PackedBlock> {
+        let key = Self::shard(pos);
+        let shard = self.shards.get_mut(&key)?;
+        // Checked first, so looking for a block that isn't there copies nothing
+        if !shard.contains_key(&pos) {
+            return None;
+        }
+        let removed = Arc::make_mut(shard).remove(&pos);
+        if shard.is_empty() {
+            self.shards.remove(&key);
+        }
+        self.len -= 1;
+        removed
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (I64Vec2, &PackedBlock)> {
+        self.shards
+            .values()
+            .flat_map(|shard| shard.iter().map(|(&pos, block)| (pos, block)))
+    }
+
+    /// The blocks under `rect`, in world coordinates.
+    fn visit_in<'a>(&'a self, rect: Rect, mut visit: impl FnMut(I64Vec2, &'a PackedBlock)) {
+        let shard_size = BLOCK_SIZE as i64 * SHARD_BLOCKS;
+        let to_block = |v: f32| (v.floor() as i64).div_euclid(BLOCK_SIZE as i64);
+        let min = I64Vec2::new(to_block(rect.min.x), to_block(rect.min.y));
+        let max = I64Vec2::new(to_block(rect.max.x), to_block(rect.max.y));
+        visit_blocks_in(&self.shards, rect, shard_size, |_, shard| {
+            for (&pos, block) in shard.iter() {
+                if pos.cmpge(min).all() && pos.cmple(max).all() {
+                    visit(pos, block);
+                }
+            }
+        });
+    }
+
+    fn memory_bytes(&self) -> usize {
+        let entry = std::mem::size_of::<I64Vec2>() + std::mem::size_of::<PackedBlock>();
+        self.shards
+            .values()
+            .map(|shard| {
+                shard.capacity() * entry
+                    + shard
+                        .values()
+                        .map(|block| block.rows.len() * std::mem::size_of::<u64>())
+                        .sum::<usize>()
+            })
+            .sum()
+    }
+}
+
+/// A block wherever it is kept.
+#[derive(Clone, Copy)]
+enum BlockRef<'a> {
+    Hot(&'a Block),
+    Cold(&'a PackedBlock),
+}
+
+impl BlockRef<'_> {
+    #[inline]
+    fn row(self, y: usize) -> u64 {
+        match self {
+            BlockRef::Hot(block) => block.rows[y],
+            BlockRef::Cold(block) => block.row(y),
+        }
+    }
+
+    fn to_block(self) -> Block {
+        match self {
+            BlockRef::Hot(block) => *block,
+            BlockRef::Cold(block) => block.unpack(),
+        }
+    }
+}
+
+/// Whether sleeping blocks are packed; `LIFE_COLD_BLOCKS=off` keeps them whole, for
+/// comparing.
+fn cold_storage() -> bool {
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    *ENABLED.get_or_init(|| !std::env::var("LIFE_COLD_BLOCKS").is_ok_and(|value| value == "off"))
+}
+
+/// Steps only the blocks around the ones that changed. Blocks with nothing changing
+/// around them sleep packed (see [`PackedBlock`]), so the still lifes of a vast ash
+/// field take a fraction of the memory; they wake up when something reaches them.
+///
+/// Clones share the block table until either side edits it, so forking the universe
+/// copies no blocks; stepping builds a new table anyway. The sleeping blocks are shared
+/// shard by shard.
 #[derive(Clone)]
 pub struct SparseLife {
-    // Primary State
-    blocks: FxHashMap<I64Vec2, Block>,
+    // Primary State: blocks that may change, and the sleeping ones (never both)
+    blocks: Arc<FxHashMap<I64Vec2, Block>>,
+    cold: ColdStore,
+    // Blocks that changed last step (or were edited)
     active: FxHashSet<I64Vec2>,
 
     // Secondary State (Buffers for Double Buffering)
-    next_blocks: FxHashMap<I64Vec2, Block>,
+    next_blocks: Arc<FxHashMap<I64Vec2, Block>>,
     next_active: FxHashSet<I64Vec2>,
 
     // Scratchpad for step coordination
     to_evaluate: FxHashSet<I64Vec2>,
 
     generation: u64,
+    block_stats: BlockStats,
 }
 
 impl SparseLife {
     pub fn new() -> Self {
         Self {
-            blocks: FxHashMap::default(),
+            blocks: Arc::default(),
+            cold: ColdStore::default(),
             active: FxHashSet::default(),
-            next_blocks: FxHashMap::default(),
+            next_blocks: Arc::default(),
             next_active: FxHashSet::default(),
             to_evaluate: FxHashSet::default(),
             generation: 0,
+            block_stats: BlockStats::default(),
         }
     }
 
@@ -55,171 +223,80 @@ impl SparseLife {
         (I64Vec2::new(block_x, block_y), local_x, local_y)
     }
 
-    // Optimized: Unswitched loop to remove branches from the hot path
-    fn evolve_block(
-        current: &Block,
-        n: Option<&Block>,
-        s: Option<&Block>,
-        w: Option<&Block>,
-        e: Option<&Block>,
-        nw: Option<&Block>,
-        ne: Option<&Block>,
-        sw: Option<&Block>,
-        se: Option<&Block>,
-    ) -> (Block, bool) {
-        let mut next = Block::default();
-        let mut alive = false;
-
-        macro_rules! calc_row {
-            ($y_idx:expr, $up:expr, $center:expr, $down:expr, $w_bit_u:expr, $w_bit_c:expr, $w_bit_d:expr, $e_bit_u:expr, $e_bit_c:expr, $e_bit_d:expr) => {{
-                let l_up = ($up << 1) | $w_bit_u;
-                let r_up = ($up >> 1) | $e_bit_u;
-                let l_curr = ($center << 1) | $w_bit_c;
-                let r_curr = ($center >> 1) | $e_bit_c;
-                let l_down = ($down << 1) | $w_bit_d;
-                let r_down = ($down >> 1) | $e_bit_d;
-
-                let mut s0 = 0u64;
-                let mut s1 = 0u64;
-                let mut s2 = 0u64;
-
-                for x in [l_up, $up, r_up, l_curr, r_curr, l_down, $down, r_down] {
-                    let c0 = s0 & x;
-                    s0 ^= x;
-                    let c1 = s1 & c0;
-                    s1 ^= c0;
-                    s2 |= c1;
-                }
-
-                let res = (s1 & !s2) & ($center | s0);
-
-                next.rows[$y_idx] = res;
-                if res != 0 {
-                    alive = true;
-                }
-            }};
-        }
-
-        #[inline(always)]
-        fn bit_w(b: Option<&Block>, row: usize) -> u64 {
-            b.map(|x| (x.rows[row] >> 63) & 1).unwrap_or(0)
-        }
-        #[inline(always)]
-        fn bit_e(b: Option<&Block>, row: usize) -> u64 {
-            b.map(|x| (x.rows[row] & 1) << 63).unwrap_or(0)
-        }
-
-        // --- 1. Top Row (Y=0) ---
-        {
-            let up = n.map(|b| b.rows[BLOCK_SIZE - 1]).unwrap_or(0);
-            let center = current.rows[0];
-            let down = current.rows[1];
-
-            let w_u = bit_w(nw, BLOCK_SIZE - 1);
-            let w_c = bit_w(w, 0);
-            let w_d = bit_w(w, 1);
-            let e_u = bit_e(ne, BLOCK_SIZE - 1);
-            let e_c = bit_e(e, 0);
-            let e_d = bit_e(e, 1);
+    fn block(&self, pos: I64Vec2) -> Option<BlockRef<'_>> {
+        Self::lookup(&self.blocks, &self.cold, pos)
+    }
 
-            calc_row!(0, up, center, down, w_u, w_c, w_d, e_u, e_c, e_d);
+    // Takes the tables apart from `self`, for stepping while the next table is written
+    fn lookup<'a>(
+        blocks: &'a FxHashMap<I64Vec2, Block>,
+        cold: &'a ColdStore,
+        pos: I64Vec2,
+    ) -> Option<BlockRef<'a>> {
+        match blocks.get(&pos) {
+            Some(block) => Some(BlockRef::Hot(block)),
+            None => cold.get(pos).map(BlockRef::Cold),
         }
+    }
 
-        // --- 2. Middle Rows (Y=1..63) ---
-        for y in 1..BLOCK_SIZE - 1 {
-            let up = current.rows[y - 1];
-            let center = current.rows[y];
-            let down = current.rows[y + 1];
-
-            let w_u = bit_w(w, y - 1);
-            let w_c = bit_w(w, y);
-            let w_d = bit_w(w, y + 1);
-            let e_u = bit_e(e, y - 1);
-            let e_c = bit_e(e, y);
-            let e_d = bit_e(e, y + 1);
-
-            calc_row!(y, up, center, down, w_u, w_c, w_d, e_u, e_c, e_d);
-        }
+    /// The block at `pos` for editing, unpacked if it was asleep.
+    fn wake(&mut self, pos: I64Vec2) -> &mut Block {
+        let cold = self.cold.remove(pos);
+        Arc::make_mut(&mut self.blocks)
+            .entry(pos)
+            .or_insert_with(|| cold.map_or_else(Block::default, |block| block.unpack()))
+    }
 
-        // --- 3. Bottom Row (Y=63) ---
-        {
-            let up = current.rows[BLOCK_SIZE - 2];
-            let center = current.rows[BLOCK_SIZE - 1];
-            let down = s.map(|b| b.rows[0]).unwrap_or(0);
-
-            let w_u = bit_w(w, BLOCK_SIZE - 2);
-            let w_c = bit_w(w, BLOCK_SIZE - 1);
-            let w_d = bit_w(sw, 0);
-            let e_u = bit_e(e, BLOCK_SIZE - 2);
-            let e_c = bit_e(e, BLOCK_SIZE - 1);
-            let e_d = bit_e(se, 0);
-
-            calc_row!(
-                BLOCK_SIZE - 1,
-                up,
-                center,
-                down,
-                w_u,
-                w_c,
-                w_d,
-                e_u,
-                e_c,
-                e_d
-            );
-        }
+    /// Steps `current` given its neighbours, returning whether any cell is left alive.
+    #[allow(clippy::too_many_arguments)]
+    fn evolve_block(
+        current: &Block,
+        n: Option<BlockRef>,
+        s: Option<BlockRef>,
+        w: Option<BlockRef>,
+        e: Option<BlockRef>,
+        nw: Option<BlockRef>,
+        ne: Option<BlockRef>,
+        sw: Option<BlockRef>,
+        se: Option<BlockRef>,
+    ) -> (Block, bool) {
+        let window = Window::gather(&current.rows, |dx, dy, y| {
+            let block = match (dx, dy) {
+                (0, -1) => n,
+                (0, 1) => s,
+                (-1, 0) => w,
+                (1, 0) => e,
+                (-1, -1) => nw,
+                (1, -1) => ne,
+                (-1, 1) => sw,
+                _ => se,
+            };
+            block.map_or(0, |b| b.row(y))
+        });
+        let next = Block {
+            rows: block_kernel::step(&window),
+        };
+        let alive = next.rows.iter().any(|&row| row != 0);
         (next, alive)
     }
 
     // --- Rendering Helpers ---
 
     /// Path A: Sparse Rendering (World Space -> Screen Space)
-    /// Used when population is low. Iterates active blocks and draws rectangles.
-    fn draw_sparse(&self, rect: Rect, buffer: &mut [u8], width: usize, height: usize, scale: f64) {
-        // Clear buffer first (Essential, as we only draw "on" pixels)
-        buffer.fill(0);
-
-        let view_min_x = rect.min.x as f64;
-        let view_min_y = rect.min.y as f64;
-        let bs = BLOCK_SIZE as i64;
-        let block_screen_size = bs as f64 * scale;
-
-        // Iterate over BLOCKS that contain cells
-        for (&chunk_pos, block) in &self.blocks {
-            // Culling (Approximate AABB overlap check)
-            let block_world_x = chunk_pos.x * bs;
-            let block_world_y = chunk_pos.y * bs;
-            let screen_block_x = (block_world_x as f64 - view_min_x) * scale;
-            let screen_block_y = (block_world_y as f64 - view_min_y) * scale;
-
-            if screen_block_x > width as f64
-                || screen_block_x + block_screen_size < 0.0
-                || screen_block_y > height as f64
-                || screen_block_y + block_screen_size < 0.0
-            {
-                continue;
-            }
-
-            // Iterate active cells in this block
-            for ly in 0..BLOCK_SIZE {
-                let row = block.rows[ly];
-                if row == 0 {
-                    continue;
-                }
-
-                let world_y = (block_world_y + ly as i64) as f64;
-                let sy = (world_y - view_min_y) * scale;
-
-                for lx in 0..BLOCK_SIZE {
-                    if (row >> lx) & 1 == 1 {
-                        let world_x = (block_world_x + lx as i64) as f64;
-                        let sx = (world_x - view_min_x) * scale;
-
-                        // Draw the cell using the fixed rounding logic
-                        self.fill_rect_safe(buffer, width, height, sx, sy, scale);
-                    }
-                }
-            }
-        }
+    /// Used when population is low. Draws the cells of the blocks under the view, in
+    /// parallel bands.
+    fn draw_sparse(&self, rect: Rect, buffer: &mut [u8], width: usize, scale: f64) {
+        let mut unpacked = Vec::new();
+        self.cold
+            .visit_in(rect, |pos, block| unpacked.push((pos, block.unpack())));
+        let mut visible: Vec<(I64Vec2, &Tile)> = unpacked
+            .iter()
+            .map(|(pos, block)| (*pos, &block.rows))
+            .collect();
+        visit_blocks_in(&self.blocks, rect, BLOCK_SIZE as i64, |pos, block| {
+            visible.push((pos, &block.rows));
+        });
+        draw_tiles_sparse(visible, rect, buffer, width, scale);
     }
 
     /// Path B: Dense Rendering (Screen Space -> World Space)
@@ -239,7 +316,7 @@ impl SparseLife {
                 let global_y = center_y.floor() as i64;
 
                 let mut current_chunk_idx = I64Vec2::new(i64::MAX, i64::MAX);
-                let mut current_block: Option<&Block> = None;
+                let mut current_block: Option<BlockRef> = None;
 
                 for (x, pixel) in pixel_row.iter_mut().enumerate() {
                     let screen_x = x as f64;
@@ -252,7 +329,7 @@ impl SparseLife {
 
                     if chunk_pos != current_chunk_idx {
                         current_chunk_idx = chunk_pos;
-                        current_block = self.blocks.get(&chunk_pos);
+                        current_block = self.block(chunk_pos);
                     }
 
                     *pixel = 0;
@@ -262,7 +339,7 @@ impl SparseLife {
                             let local_x = global_x.rem_euclid(bs) as usize;
                             let local_y = global_y.rem_euclid(bs) as usize;
 
-                            if (block.rows[local_y] >> local_x) & 1 == 1 {
+                            if (block.row(local_y) >> local_x) & 1 == 1 {
                                 *pixel = 255;
                             }
                         } else {
@@ -294,7 +371,7 @@ impl SparseLife {
                                 let row_mask = mask_bits << lx_start;
 
                                 for r in ly_start..ly_end {
-                                    if (block.rows[r] & row_mask) != 0 {
+                                    if (block.row(r) & row_mask) != 0 {
                                         *pixel = 255;
                                         break;
                                     }
@@ -305,38 +382,6 @@ impl SparseLife {
                 }
             });
     }
-
-    /// Safe rectangle filler using rounding to avoid 'fat' blocks
-    fn fill_rect_safe(
-        &self,
-        buffer: &mut [u8],
-        width: usize,
-        height: usize,
-        x: f64,
-        y: f64,
-        size: f64,
-    ) {
-        let effective_size = size.max(1.0);
-
-        let start_x = x.round() as isize;
-        let start_y = y.round() as isize;
-        let end_x = (x + effective_size).round() as isize;
-        let end_y = (y + effective_size).round() as isize;
-
-        let sx = start_x.max(0).min(width as isize) as usize;
-        let sy = start_y.max(0).min(height as isize) as usize;
-        let ex = end_x.max(0).min(width as isize) as usize;
-        let ey = end_y.max(0).min(height as isize) as usize;
-
-        if sx >= ex || sy >= ey {
-            return;
-        }
-
-        for row in sy..ey {
-            let offset = row * width;
-            buffer[offset + sx..offset + ex].fill(255);
-        }
-    }
 }
 
 impl LifeEngine for SparseLife {
@@ -349,10 +394,58 @@ impl LifeEngine for SparseLife {
     }
 
     fn population(&self) -> u64 {
-        self.blocks
+        let hot: u64 = self
+            .blocks
             .values()
             .map(|b| b.rows.iter().map(|r| r.count_ones() as u64).sum::<u64>())
-            .sum()
+            .sum();
+        hot + self
+            .cold
+            .iter()
+            .map(|(_, block)| block.population())
+            .sum::<u64>()
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn set_generation(&mut self, generation: u64) {
+        self.generation = generation;
+    }
+
+    fn memory_bytes(&self) -> usize {
+        let block_entry = std::mem::size_of::<I64Vec2>() + std::mem::size_of::<Block>();
+        let key_entry = std::mem::size_of::<I64Vec2>();
+        (self.blocks.capacity() + self.next_blocks.capacity()) * block_entry
+            + (self.active.capacity() + self.next_active.capacity() + self.to_evaluate.capacity())
+                * key_entry
+            + self.cold.memory_bytes()
+    }
+
+    fn estimate_memory(&self, cells: u64, size: I64Vec2) -> u64 {
+        // Current and next generation, plus the activity sets
+        let entry = 2 * (std::mem::size_of::<I64Vec2>() + std::mem::size_of::<Block>())
+            + 3 * std::mem::size_of::<I64Vec2>();
+        blocks_touched(cells, size, BLOCK_SIZE as i64).saturating_mul(entry as u64)
+    }
+
+    fn block_activity(&self) -> Option<BlockActivity> {
+        Some(BlockActivity {
+            block_size: BLOCK_SIZE as i64,
+            occupied: self
+                .blocks
+                .iter()
+                .filter(|(_, b)| b.rows.iter().any(|&r| r != 0))
+                .map(|(&pos, _)| pos)
+                .chain(self.cold.iter().map(|(pos, _)| pos))
+                .collect(),
+            active: self.active.iter().copied().collect(),
+        })
+    }
+
+    fn block_stats(&self) -> Option<BlockStats> {
+        Some(self.block_stats)
     }
 
     fn set_cell(&mut self, pos: I64Vec2, alive: bool) {
@@ -362,7 +455,7 @@ impl LifeEngine for SparseLife {
     fn set_cells(&mut self, coords: &[I64Vec2], alive: bool) {
         for &pos in coords {
             let (chunk_pos, lx, ly) = Self::get_coords(pos.x, pos.y);
-            let block = self.blocks.entry(chunk_pos).or_insert_with(Block::default);
+            let block = self.wake(chunk_pos);
 
             if alive {
                 block.rows[ly] |= 1u64 << lx;
@@ -381,29 +474,36 @@ impl LifeEngine for SparseLife {
 
     fn get_cell(&self, pos: I64Vec2) -> bool {
         let (chunk_pos, lx, ly) = Self::get_coords(pos.x, pos.y);
-        if let Some(block) = self.blocks.get(&chunk_pos) {
-            (block.rows[ly] >> lx) & 1 == 1
-        } else {
-            false
-        }
+        self.block(chunk_pos)
+            .is_some_and(|block| (block.row(ly) >> lx) & 1 == 1)
     }
 
     fn clear(&mut self) {
-        self.blocks.clear();
+        self.blocks = Arc::default();
+        self.cold = ColdStore::default();
         self.active.clear();
-        self.next_blocks.clear();
+        self.next_blocks = Arc::default();
         self.next_active.clear();
         self.to_evaluate.clear();
         self.generation = 0;
     }
 
     fn export(&self) -> Vec<I64Vec2> {
+        let _span = info_span!("sparse_life::export").entered();
         let mut cells = Vec::new();
-        for (pos, block) in &self.blocks {
+        let blocks = self
+            .blocks
+            .iter()
+            .map(|(&pos, block)| (pos, BlockRef::Hot(block)));
+        let cold = self
+            .cold
+            .iter()
+            .map(|(pos, block)| (pos, BlockRef::Cold(block)));
+        for (pos, block) in blocks.chain(cold) {
             let base_x = pos.x * BLOCK_SIZE as i64;
             let base_y = pos.y * BLOCK_SIZE as i64;
             for y in 0..BLOCK_SIZE {
-                let row = block.rows[y];
+                let row = block.row(y);
                 if row == 0 {
                     continue;
                 }
@@ -417,12 +517,41 @@ impl LifeEngine for SparseLife {
         cells
     }
 
+    // Blocks are tiles
+    fn visit_tiles(&self, visit: &mut dyn FnMut(I64Vec2, &Tile)) {
+        for (pos, block) in self.blocks.iter() {
+            visit(*pos, &block.rows);
+        }
+        for (pos, block) in self.cold.iter() {
+            visit(pos, &block.unpack().rows);
+        }
+    }
+
+    fn add_tile(&mut self, pos: I64Vec2, tile: &Tile) {
+        if tile.iter().all(|&row| row == 0) {
+            return;
+        }
+        let block = self.wake(pos);
+        for (row, &cells) in block.rows.iter_mut().zip(tile) {
+            *row |= cells;
+        }
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                self.active.insert(pos + I64Vec2::new(dx, dy));
+            }
+        }
+    }
+
     fn import(&mut self, alive_cells: &[I64Vec2]) {
+        let _span = info_span!("sparse_life::import", cells = alive_cells.len()).entered();
         self.clear();
         self.set_cells(alive_cells, true);
     }
 
     fn step(&mut self, steps: u64) -> u64 {
+        let _span = info_span!("sparse_life::step", steps).entered();
+        self.block_stats = BlockStats::default();
+        let pack = cold_storage();
         for _ in 0..steps {
             self.to_evaluate.clear();
             for &pos in &self.active {
@@ -433,29 +562,34 @@ impl LifeEngine for SparseLife {
                 }
             }
             let eval_list: Vec<I64Vec2> = self.to_evaluate.iter().copied().collect();
-            self.next_blocks.clear();
+            // A clone may still share the table; it is overwritten, so never copy it
+            if Arc::get_mut(&mut self.next_blocks).is_none() {
+                self.next_blocks = Arc::default();
+            }
+            let next_blocks = Arc::make_mut(&mut self.next_blocks);
+            next_blocks.clear();
             self.next_active.clear();
 
-            let results: Vec<(I64Vec2, Block)> = eval_list
+            let _evolve_span =
+                info_span!("sparse_life::evolve_blocks", blocks = eval_list.len()).entered();
+            // (position, next state, alive, changed, existed) of every evaluated block
+            let results: Vec<(I64Vec2, Block, bool, bool, bool)> = eval_list
                 .par_iter()
                 .filter_map(|&pos| {
-                    let get_b = |dx, dy| self.blocks.get(&(pos + I64Vec2::new(dx, dy)));
+                    let get_b =
+                        |dx, dy| Self::lookup(&self.blocks, &self.cold, pos + I64Vec2::new(dx, dy));
                     let current = get_b(0, 0);
 
                     if current.is_none() {
                         let has_neighbor = (-1..=1).any(|dy| {
-                            (-1..=1).any(|dx| {
-                                (dx != 0 || dy != 0)
-                                    && self.blocks.contains_key(&(pos + I64Vec2::new(dx, dy)))
-                            })
+                            (-1..=1).any(|dx| (dx != 0 || dy != 0) && get_b(dx, dy).is_some())
                         });
                         if !has_neighbor {
                             return None;
                         }
                     }
 
-                    let default = Block::default();
-                    let curr_ref = current.unwrap_or(&default);
+                    let curr = current.map_or_else(Block::default, BlockRef::to_block);
 
                     let (n, s, w, e, nw, ne, sw, se) = (
                         get_b(0, -1),
@@ -468,29 +602,62 @@ impl LifeEngine for SparseLife {
                         get_b(1, 1),
                     );
                     let (next_block, is_alive) =
-                        Self::evolve_block(curr_ref, n, s, w, e, nw, ne, sw, se);
-
-                    if is_alive {
-                        Some((pos, next_block))
-                    } else {
-                        None
-                    }
+                        Self::evolve_block(&curr, n, s, w, e, nw, ne, sw, se);
+                    let changed = next_block.rows != curr.rows;
+                    Some((pos, next_block, is_alive, changed, current.is_some()))
                 })
                 .collect();
+            drop(_evolve_span);
+
+            let stats = &mut self.block_stats;
+            stats.generations += 1;
+            stats.evaluated += results.len() as u64;
+            stats.skipped += (eval_list.len() - results.len()) as u64;
+            for &(pos, block, is_alive, changed, existed) in &results {
+                if changed {
+                    self.next_active.insert(pos);
+                }
+                if existed {
+                    self.cold.remove(pos);
+                    if !is_alive {
+                        stats.freed += 1;
+                    }
+                } else if is_alive {
+                    stats.spawned += 1;
+                }
+                if is_alive {
+                    next_blocks.insert(pos, block);
+                }
+            }
 
-            for (pos, block) in results {
-                self.next_blocks.insert(pos, block);
-                self.next_active.insert(pos);
+            // Nothing around the blocks left out changed, so neither do they: they sleep
+            for (&pos, block) in self.blocks.iter() {
+                if self.to_evaluate.contains(&pos) {
+                    continue;
+                }
+                if pack {
+                    self.cold.insert(pos, PackedBlock::pack(block));
+                } else {
+                    next_blocks.insert(pos, *block);
+                }
             }
 
             std::mem::swap(&mut self.blocks, &mut self.next_blocks);
             std::mem::swap(&mut self.active, &mut self.next_active);
             self.generation += 1;
         }
+        self.block_stats.cold = self.cold.len as u64;
         steps
     }
 
-    fn draw_to_buffer(&self, rect: Rect, buffer: &mut [u8], width: usize, height: usize) {
+    fn draw_to_buffer(
+        &self,
+        rect: Rect,
+        buffer: &mut [u8],
+        width: usize,
+        height: usize,
+        population: u64,
+    ) {
         let scale = width as f64 / rect.width() as f64;
 
         if scale <= 0.0001 || scale.is_infinite() || scale.is_nan() {
@@ -499,10 +666,15 @@ impl LifeEngine for SparseLife {
 
         let total_pixels = width * height;
 
-        let is_sparse = self.population() < (total_pixels as u64 / 10);
+        let is_sparse = population < (total_pixels as u64 / 10);
 
+        let _span = info_span!(
+            "sparse_life::draw",
+            path = if is_sparse { "sparse" } else { "dense" }
+        )
+        .entered();
         if is_sparse {
-            self.draw_sparse(rect, buffer, width, height, scale);
+            self.draw_sparse(rect, buffer, width, scale);
         } else {
             self.draw_dense(rect, buffer, width, scale);
         }
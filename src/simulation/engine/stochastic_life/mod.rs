@@ -0,0 +1,319 @@
+use crate::simulation::engine::{CellRegion, LifeEngine};
+use crate::simulation::rules::LifeRule;
+use bevy::log::info_span;
+use bevy::math::{I64Vec2, Rect};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+/// Outer-totalistic rule where each birth/survival only happens with some probability.
+#[derive(Clone, Copy, Debug)]
+pub struct StochasticRule {
+    /// Which neighbour counts may lead to a birth or survival at all.
+    pub base: LifeRule,
+    /// Chance that an allowed birth actually happens.
+    pub birth_chance: f64,
+    /// Chance that an allowed survival actually happens.
+    pub survival_chance: f64,
+}
+
+impl Default for StochasticRule {
+    /// B3/S23 with a little noise, so still lifes slowly decay and oscillators misfire.
+    fn default() -> Self {
+        Self {
+            base: LifeRule::CONWAY,
+            birth_chance: 0.9,
+            survival_chance: 0.99,
+        }
+    }
+}
+
+impl StochasticRule {
+    /// `base` without any randomness.
+    pub fn exact(base: LifeRule) -> Self {
+        Self {
+            base,
+            birth_chance: 1.0,
+            survival_chance: 1.0,
+        }
+    }
+}
+
+/// Probabilistic CA on a plain cell set.
+///
+/// The bitwise kernels of the other engines evaluate 64 cells per instruction and
+/// can't roll a die per cell, so this engine trades speed for flexibility.
+/// Every roll is derived from `(seed, generation, position)`, which keeps steps
+/// reproducible regardless of iteration order or how often the engine is cloned.
+#[derive(Clone)]
+pub struct StochasticLife {
+    cells: FxHashSet<I64Vec2>,
+    // Scratchpad reused between steps
+    neighbours: FxHashMap<I64Vec2, u8>,
+    rule: StochasticRule,
+    seed: u64,
+    generation: u64,
+    // Wrap dimensions when running on a torus with its corner at the origin
+    torus: Option<I64Vec2>,
+    // Cells outside keep their state when stepping
+    step_region: Option<CellRegion>,
+    // Always count as live neighbours, never change
+    walls: FxHashSet<I64Vec2>,
+}
+
+impl StochasticLife {
+    pub fn new() -> Self {
+        Self::with_rule(StochasticRule::default())
+    }
+
+    pub fn with_rule(rule: StochasticRule) -> Self {
+        Self {
+            cells: FxHashSet::default(),
+            neighbours: FxHashMap::default(),
+            rule,
+            seed: 0,
+            generation: 0,
+            torus: None,
+            step_region: None,
+            walls: FxHashSet::default(),
+        }
+    }
+
+    #[allow(unused)]
+    pub fn rule(&self) -> StochasticRule {
+        self.rule
+    }
+
+    #[allow(unused)]
+    pub fn set_rule(&mut self, rule: StochasticRule) {
+        self.rule = rule;
+    }
+
+    #[inline]
+    fn wrap(torus: Option<I64Vec2>, pos: I64Vec2) -> I64Vec2 {
+        match torus {
+            Some(size) => I64Vec2::new(pos.x.rem_euclid(size.x), pos.y.rem_euclid(size.y)),
+            None => pos,
+        }
+    }
+
+    /// Uniform sample in [0, 1) for one cell in the current generation.
+    #[inline]
+    fn roll(&self, pos: I64Vec2) -> f64 {
+        let mut h = self.seed
+            ^ self.generation.wrapping_mul(0x9E37_79B9_7F4A_7C15)
+            ^ (pos.x as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F)
+            ^ (pos.y as u64).wrapping_mul(0x1656_67B1_9E37_79F9);
+        // splitmix64 finalizer
+        h = (h ^ (h >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        h = (h ^ (h >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        h ^= h >> 31;
+        (h >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn fill_rect_safe(buffer: &mut [u8], width: usize, height: usize, x: f64, y: f64, size: f64) {
+        let effective_size = size.max(1.0);
+
+        let sx = (x.round() as isize).clamp(0, width as isize) as usize;
+        let sy = (y.round() as isize).clamp(0, height as isize) as usize;
+        let ex = ((x + effective_size).round() as isize).clamp(0, width as isize) as usize;
+        let ey = ((y + effective_size).round() as isize).clamp(0, height as isize) as usize;
+
+        for row in sy..ey {
+            let offset = row * width;
+            buffer[offset + sx..offset + ex].fill(255);
+        }
+    }
+}
+
+impl LifeEngine for StochasticLife {
+    fn id(&self) -> &str {
+        "stochastic-life"
+    }
+
+    fn name(&self) -> &str {
+        "StochasticLife"
+    }
+
+    fn step(&mut self, steps: u64) -> u64 {
+        let _span = info_span!("stochastic_life::step", steps).entered();
+        let torus = self.torus;
+        for _ in 0..steps {
+            self.neighbours.clear();
+            for &pos in self.cells.iter().chain(&self.walls) {
+                for dy in -1..=1 {
+                    for dx in -1..=1 {
+                        if dx != 0 || dy != 0 {
+                            *self
+                                .neighbours
+                                .entry(Self::wrap(torus, pos + I64Vec2::new(dx, dy)))
+                                .or_default() += 1;
+                        }
+                    }
+                }
+            }
+
+            let mut next = FxHashSet::default();
+            let frozen = |pos: I64Vec2| self.step_region.is_some_and(|r| !r.contains(pos));
+            for (&pos, &count) in &self.neighbours {
+                if frozen(pos) || self.walls.contains(&pos) {
+                    continue;
+                }
+                let alive = self.cells.contains(&pos);
+                let (allowed, chance) = if alive {
+                    (
+                        self.rule.base.survives(count as u32),
+                        self.rule.survival_chance,
+                    )
+                } else {
+                    (self.rule.base.born(count as u32), self.rule.birth_chance)
+                };
+                if allowed && (chance >= 1.0 || self.roll(pos) < chance) {
+                    next.insert(pos);
+                }
+            }
+            // Isolated cells never appear in the neighbour map
+            if self.rule.base.survives(0) {
+                for &pos in &self.cells {
+                    if !self.neighbours.contains_key(&pos)
+                        && !frozen(pos)
+                        && self.roll(pos) < self.rule.survival_chance
+                    {
+                        next.insert(pos);
+                    }
+                }
+            }
+            next.extend(self.cells.iter().copied().filter(|&pos| frozen(pos)));
+
+            self.cells = next;
+            self.generation += 1;
+        }
+        steps
+    }
+
+    fn clear(&mut self) {
+        self.cells.clear();
+        self.walls.clear();
+        self.neighbours.clear();
+        self.generation = 0;
+    }
+
+    fn population(&self) -> u64 {
+        self.cells.len() as u64
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn set_generation(&mut self, generation: u64) {
+        self.generation = generation;
+    }
+
+    fn memory_bytes(&self) -> usize {
+        (self.cells.capacity() + self.walls.capacity()) * std::mem::size_of::<I64Vec2>()
+            + self.neighbours.capacity() * std::mem::size_of::<(I64Vec2, u8)>()
+    }
+
+    fn set_cell(&mut self, pos: I64Vec2, alive: bool) {
+        let pos = Self::wrap(self.torus, pos);
+        if alive && !self.walls.contains(&pos) {
+            self.cells.insert(pos);
+        } else {
+            self.cells.remove(&pos);
+        }
+    }
+
+    fn get_cell(&self, pos: I64Vec2) -> bool {
+        self.cells.contains(&Self::wrap(self.torus, pos))
+    }
+
+    fn set_cells(&mut self, coords: &[I64Vec2], alive: bool) {
+        for &pos in coords {
+            self.set_cell(pos, alive);
+        }
+    }
+
+    fn import(&mut self, alive_cells: &[I64Vec2]) {
+        let _span = info_span!("stochastic_life::import", cells = alive_cells.len()).entered();
+        self.clear();
+        let torus = self.torus;
+        self.cells
+            .extend(alive_cells.iter().map(|&pos| Self::wrap(torus, pos)));
+    }
+
+    fn export(&self) -> Vec<I64Vec2> {
+        let _span = info_span!("stochastic_life::export").entered();
+        self.cells.iter().copied().collect()
+    }
+
+    fn draw_to_buffer(
+        &self,
+        rect: Rect,
+        buffer: &mut [u8],
+        width: usize,
+        height: usize,
+        _population: u64,
+    ) {
+        let _span = info_span!("stochastic_life::draw").entered();
+        buffer.fill(0);
+
+        let scale = width as f64 / rect.width() as f64;
+        if scale <= 0.0001 || scale.is_infinite() || scale.is_nan() {
+            return;
+        }
+
+        let view_min_x = rect.min.x as f64;
+        let view_min_y = rect.min.y as f64;
+        for pos in &self.cells {
+            let sx = (pos.x as f64 - view_min_x) * scale;
+            let sy = (pos.y as f64 - view_min_y) * scale;
+            if sx + scale < 0.0 || sy + scale < 0.0 || sx >= width as f64 || sy >= height as f64 {
+                continue;
+            }
+            Self::fill_rect_safe(buffer, width, height, sx, sy, scale);
+        }
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    fn supports_torus(&self) -> bool {
+        true
+    }
+
+    fn supports_walls(&self) -> bool {
+        true
+    }
+
+    fn set_walls(&mut self, coords: &[I64Vec2], wall: bool) {
+        for &pos in coords {
+            let pos = Self::wrap(self.torus, pos);
+            if wall {
+                self.cells.remove(&pos);
+                self.walls.insert(pos);
+            } else {
+                self.walls.remove(&pos);
+            }
+        }
+    }
+
+    fn supports_step_region(&self) -> bool {
+        true
+    }
+
+    fn set_step_region(&mut self, region: Option<CellRegion>) {
+        self.step_region = region;
+    }
+
+    fn set_torus(&mut self, size: Option<I64Vec2>) {
+        self.torus = size;
+        let cells = std::mem::take(&mut self.cells);
+        self.cells = cells.into_iter().map(|pos| Self::wrap(size, pos)).collect();
+        let walls = std::mem::take(&mut self.walls);
+        self.walls = walls.into_iter().map(|pos| Self::wrap(size, pos)).collect();
+    }
+
+    fn box_clone(&self) -> Box<dyn LifeEngine> {
+        Box::new(self.clone())
+    }
+}
@@ -0,0 +1,75 @@
+use std::path::PathBuf;
+
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+use thiserror::Error;
+
+use crate::simulation::engine::COORD_LIMIT;
+
+/// Everything that can go wrong outside of a programming error.
+/// Written as a message so [`ToastPlugin`](crate::simulation::toasts::ToastPlugin)
+/// can show it to the user.
+#[derive(Message, Error, Debug)]
+pub enum LifeError {
+    #[error("{}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("invalid rule: {0}")]
+    InvalidRule(String),
+    #[error("invalid pattern: {0}")]
+    InvalidPattern(String),
+    #[error(
+        "{count} cells lie outside the supported range of ±{} and were ignored",
+        COORD_LIMIT
+    )]
+    OutOfRange { count: usize },
+    #[error(
+        "the {engine} engine crashed ({reason}); stepping is paused until the universe \
+         is cleared, loaded or switched to another engine"
+    )]
+    EngineCrashed { engine: String, reason: String },
+    #[error(
+        "{engine} diverged from {shadow} by generation {generation}, first at ({}, {}); \
+         stepping is paused",
+        cell.x,
+        cell.y
+    )]
+    Diverged {
+        engine: String,
+        shadow: String,
+        generation: u64,
+        cell: I64Vec2,
+    },
+    #[error(
+        "{name} has {cells} cells, about {} MB on {engine}, over the import budget of {} MB; \
+         {suggestion}",
+        estimate / 1_000_000,
+        budget / 1_000_000
+    )]
+    TooLarge {
+        name: String,
+        cells: u64,
+        engine: String,
+        estimate: u64,
+        budget: u64,
+        suggestion: String,
+    },
+    #[error("export failed: {0}")]
+    Export(String),
+    #[error("upload failed: {0}")]
+    Upload(String),
+    #[error("remote universe: {0}")]
+    Remote(String),
+}
+
+impl LifeError {
+    pub fn io(path: impl Into<PathBuf>, source: std::io::Error) -> Self {
+        Self::Io {
+            path: path.into(),
+            source,
+        }
+    }
+}
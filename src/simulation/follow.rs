@@ -0,0 +1,124 @@
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+
+use crate::simulation::SimulationInput;
+use crate::simulation::period::{PeriodDetector, Periodicity};
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::universe::Universe;
+use crate::simulation::view::SimulationView;
+
+/// L keeps the camera on a spaceship: once the universe repeats with a displacement,
+/// the view moves along at the measured velocity, shown in the stats.
+pub struct FollowPlugin;
+
+impl Plugin for FollowPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Follow>().add_systems(
+            Update,
+            (toggle_follow.in_set(SimulationInput), follow).chain(),
+        );
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Follow {
+    pub active: bool,
+    last_generation: Option<u64>,
+}
+
+// L: toggle follow mode (Ctrl+L shares a link on the web)
+fn toggle_follow(keys: Res<ButtonInput<KeyCode>>, mut follow: ResMut<Follow>) {
+    if keys.just_pressed(KeyCode::KeyL)
+        && !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+    {
+        follow.active = !follow.active;
+        follow.last_generation = None;
+    }
+}
+
+fn follow(
+    mut follow: ResMut<Follow>,
+    universe: Res<Universe>,
+    detector: Res<PeriodDetector>,
+    mut view: ResMut<SimulationView>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    if !follow.active {
+        if follow.is_changed() {
+            stats.remove("Velocity");
+        }
+        return;
+    }
+
+    let generation = universe.generation();
+    let last = follow.last_generation.replace(generation);
+    let moving = detector
+        .detected
+        .filter(|d| d.displacement != I64Vec2::ZERO);
+    let Some(periodicity) = moving else {
+        stats.insert("Velocity", "following, no spaceship yet".to_string());
+        return;
+    };
+
+    // Going back in time (loads, clears) is no reason to jump
+    if let Some(last) = last
+        && generation > last
+    {
+        let velocity = periodicity.displacement.as_dvec2() / periodicity.period as f64;
+        view.center += velocity * (generation - last) as f64;
+    }
+
+    if detector.is_changed() || !stats.contains("Velocity") {
+        stats.insert("Velocity", describe_velocity(periodicity));
+    }
+}
+
+/// Conwaylife-style speed, e.g. `c/4 diag` for the glider or `2c/5 orth`,
+/// followed by the velocity in cells per generation.
+fn describe_velocity(periodicity: Periodicity) -> String {
+    let velocity = periodicity.displacement.as_dvec2() / periodicity.period as f64;
+    format!(
+        "{} ({}, {}) cells/gen",
+        describe_speed(periodicity),
+        format_rate(velocity.x),
+        format_rate(velocity.y)
+    )
+}
+
+/// Just the speed and direction, e.g. `c/4 diag`.
+pub(crate) fn describe_speed(
+    Periodicity {
+        period,
+        displacement,
+    }: Periodicity,
+) -> String {
+    let (dx, dy) = (displacement.x.unsigned_abs(), displacement.y.unsigned_abs());
+    let distance = dx.max(dy);
+    if distance == 0 {
+        return "still".to_string();
+    }
+    let divisor = gcd(distance, period);
+    let speed = match (distance / divisor, period / divisor) {
+        (1, 1) => "c".to_string(),
+        (n, 1) => format!("{n}c"),
+        (1, d) => format!("c/{d}"),
+        (n, d) => format!("{n}c/{d}"),
+    };
+    let direction = if dx == 0 || dy == 0 {
+        "orth"
+    } else if dx == dy {
+        "diag"
+    } else {
+        "oblique"
+    };
+    format!("{speed} {direction}")
+}
+
+fn format_rate(rate: f64) -> String {
+    let text = format!("{rate:.3}");
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a.max(1) } else { gcd(b, a % b) }
+}
@@ -0,0 +1,250 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use bevy::asset::RenderAssetUsages;
+use bevy::math::Rect;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::tasks::{AsyncComputeTaskPool, Task};
+use bevy::window::PrimaryWindow;
+
+use crate::simulation::SimulationInput;
+use crate::simulation::batch::arg_value;
+use crate::simulation::engine::LifeEngine;
+use crate::simulation::error::LifeError;
+use crate::simulation::selection::Selection;
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::theme::Theme;
+use crate::simulation::universe::{Universe, poll_task_once};
+use crate::simulation::view::SimulationView;
+
+/// Frames are never wider or taller than this, whatever the region's aspect ratio.
+const MAX_SIZE: u32 = 8192;
+
+/// E writes the selection (or the visible area) as numbered PNG frames, stepping a copy
+/// of the universe in the background; E again cancels. Configured with
+/// `--frames-dir DIR`, `--frame-count N`, `--frame-stride N` and `--frame-width PX`,
+/// or through [`FrameExportSettings`]. Desktop only.
+pub struct FrameExportPlugin;
+
+impl Plugin for FrameExportPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FrameExportSettings>()
+            .init_resource::<FrameExport>()
+            .add_systems(
+                Update,
+                (toggle_export.in_set(SimulationInput), poll_export).chain(),
+            );
+    }
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct FrameExportSettings {
+    pub dir: PathBuf,
+    pub frames: u64,
+    /// Generations between two frames.
+    pub stride: u64,
+    /// Frame width in pixels; the height follows the region's aspect ratio.
+    pub width: u32,
+}
+
+impl Default for FrameExportSettings {
+    fn default() -> Self {
+        let number = |name: &str, default: u64| {
+            arg_value(name)
+                .and_then(|v| v.parse().ok())
+                .filter(|&n| n > 0)
+                .unwrap_or(default)
+        };
+        Self {
+            dir: arg_value("--frames-dir").map_or_else(|| PathBuf::from("frames"), PathBuf::from),
+            frames: number("--frame-count", 300),
+            stride: number("--frame-stride", 1),
+            width: number("--frame-width", 1920).min(MAX_SIZE as u64) as u32,
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct FrameExport {
+    task: Option<Task<Result<u64, LifeError>>>,
+    written: Arc<AtomicU64>,
+    cancel: Arc<AtomicBool>,
+    total: u64,
+}
+
+impl FrameExport {
+    pub fn is_running(&self) -> bool {
+        self.task.is_some()
+    }
+}
+
+/// What one export renders, captured when it starts.
+struct ExportJob {
+    engine: Box<dyn LifeEngine>,
+    rect: Rect,
+    width: u32,
+    height: u32,
+    // Linear ramp from dead to alive, indexed by the engine's cell intensity
+    colors: [[u8; 4]; 256],
+    settings: FrameExportSettings,
+}
+
+// E: start or cancel a frame export
+#[allow(clippy::too_many_arguments)]
+fn toggle_export(
+    keys: Res<ButtonInput<KeyCode>>,
+    settings: Res<FrameExportSettings>,
+    universe: Res<Universe>,
+    selection: Res<Selection>,
+    view: Res<SimulationView>,
+    theme: Res<Theme>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut export: ResMut<FrameExport>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    if !keys.just_pressed(KeyCode::KeyE) {
+        return;
+    }
+    if export.is_running() {
+        export.cancel.store(true, Ordering::Relaxed);
+        stats.insert("Frame Export", "cancelling...".to_string());
+        return;
+    }
+
+    let rect = match selection.region {
+        Some(region) => Rect::new(
+            region.min.x as f32,
+            region.min.y as f32,
+            region.max.x as f32,
+            region.max.y as f32,
+        ),
+        None => {
+            let Ok(window) = q_window.single() else {
+                return;
+            };
+            let half = Vec2::new(window.width(), window.height()) / (2.0 * view.zoom as f32);
+            Rect::from_center_half_size(view.center.as_vec2(), half)
+        }
+    };
+    if rect.width() <= 0.0 || rect.height() <= 0.0 {
+        return;
+    }
+    // Even sizes, as most video encoders require
+    let width = settings.width.max(2) & !1;
+    let height =
+        ((width as f32 * rect.height() / rect.width()).round() as u32).clamp(2, MAX_SIZE) & !1;
+
+    let job = ExportJob {
+        engine: universe.fork(),
+        rect,
+        width,
+        height,
+        colors: color_ramp(&theme),
+        settings: settings.clone(),
+    };
+    export.written = Arc::default();
+    export.cancel = Arc::default();
+    export.total = settings.frames;
+    let (written, cancel) = (export.written.clone(), export.cancel.clone());
+    export.task =
+        Some(AsyncComputeTaskPool::get().spawn(async move { run(job, &written, &cancel) }));
+    info!(
+        "Exporting {} frames of {width}x{height} to {}",
+        settings.frames,
+        settings.dir.display()
+    );
+}
+
+fn run(mut job: ExportJob, written: &AtomicU64, cancel: &AtomicBool) -> Result<u64, LifeError> {
+    let _span = info_span!("frame_export::run", frames = job.settings.frames).entered();
+    let dir = &job.settings.dir;
+    std::fs::create_dir_all(dir).map_err(|err| LifeError::io(dir, err))?;
+
+    let (width, height) = (job.width as usize, job.height as usize);
+    let mut cells = vec![0u8; width * height];
+    for frame in 0..job.settings.frames {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        cells.fill(0);
+        let population = job.engine.population();
+        job.engine
+            .draw_to_buffer(job.rect, &mut cells, width, height, population);
+        save_frame(&job, &cells, &dir.join(format!("frame_{frame:05}.png")))?;
+        written.store(frame + 1, Ordering::Relaxed);
+        job.engine.step(job.settings.stride);
+    }
+    Ok(written.load(Ordering::Relaxed))
+}
+
+/// Theme colors for every cell intensity an engine draws, from dead to alive.
+pub(crate) fn color_ramp(theme: &Theme) -> [[u8; 4]; 256] {
+    let mut colors = [[0; 4]; 256];
+    for (i, color) in colors.iter_mut().enumerate() {
+        let c = theme
+            .universe_dead
+            .lerp(theme.universe_alive, i as f32 / 255.0);
+        *color = (c * 255.0).round().to_array().map(|v| v as u8);
+    }
+    colors
+}
+
+/// Turns an engine buffer into top-down RGBA rows.
+pub(crate) fn to_rgba(cells: &[u8], width: usize, colors: &[[u8; 4]; 256]) -> Vec<u8> {
+    // The buffer starts at the bottom row, images at the top
+    cells
+        .chunks_exact(width)
+        .rev()
+        .flatten()
+        .flat_map(|&v| colors[v as usize])
+        .collect()
+}
+
+fn save_frame(job: &ExportJob, cells: &[u8], path: &Path) -> Result<(), LifeError> {
+    let pixels = to_rgba(cells, job.width as usize, &job.colors);
+    let image = Image::new(
+        Extent3d {
+            width: job.width,
+            height: job.height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        pixels,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::MAIN_WORLD,
+    );
+    let image = image
+        .try_into_dynamic()
+        .map_err(|err| LifeError::Export(err.to_string()))?;
+    image
+        .save(path)
+        .map_err(|err| LifeError::Export(format!("{}: {err}", path.display())))
+}
+
+fn poll_export(
+    mut export: ResMut<FrameExport>,
+    mut stats: ResMut<StatsBoard>,
+    mut errors: MessageWriter<LifeError>,
+) {
+    let Some(task) = export.task.as_mut() else {
+        return;
+    };
+    let Some(result) = poll_task_once(task) else {
+        let written = export.written.load(Ordering::Relaxed);
+        if !export.cancel.load(Ordering::Relaxed) {
+            stats.insert("Frame Export", format!("{written}/{} frames", export.total));
+        }
+        return;
+    };
+    export.task = None;
+
+    match result {
+        Ok(written) => stats.insert("Frame Export", format!("wrote {written} frames")),
+        Err(err) => {
+            stats.remove("Frame Export");
+            errors.write(err);
+        }
+    }
+}
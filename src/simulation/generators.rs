@@ -0,0 +1,385 @@
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::simulation::SimulationInput;
+use crate::simulation::engine::create_rule_engine;
+use crate::simulation::locale::Localization;
+use crate::simulation::rle;
+use crate::simulation::rules::LifeRule;
+use crate::simulation::seed::{SimulationRng, view_center};
+use crate::simulation::universe::{PatternLoaded, Universe};
+use crate::simulation::view::SimulationView;
+
+const GOSPER_GUN: &str = "24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$\
+                          2o8bo3bob2o4bobo$10bo5bo7bo$11bo3bo$12b2o!";
+const LWSS: &str = "bo2bo$o4b$o3bo$4o!";
+/// The Gosper gun fires a glider every this many generations.
+const GUN_PERIOD: i64 = 30;
+/// Blinkers this far apart (or more) never touch, whichever way they point.
+const BLINKER_PITCH: i64 = 5;
+
+/// A setting of a generator, adjusted in steps of `step` between `min` and `max`.
+#[derive(Clone, Copy, Debug)]
+pub struct Param {
+    pub name: &'static str,
+    pub default: i64,
+    pub min: i64,
+    pub max: i64,
+    pub step: i64,
+}
+
+/// A composite pattern built from its settings, with its top-left cell at the origin
+/// (rows going down, as in RLE).
+#[derive(Clone, Copy, Debug)]
+pub struct Generator {
+    pub name: &'static str,
+    pub params: &'static [Param],
+    pub build: fn(&[i64], &mut SimulationRng) -> Vec<I64Vec2>,
+}
+
+pub const GENERATORS: [Generator; 3] = [
+    Generator {
+        name: "Glider gun array",
+        params: &[
+            Param {
+                name: "guns",
+                default: 4,
+                min: 1,
+                max: 32,
+                step: 1,
+            },
+            Param {
+                name: "spacing",
+                default: 60,
+                min: 40,
+                max: 400,
+                step: 10,
+            },
+            Param {
+                name: "phase offset",
+                default: 0,
+                min: 0,
+                max: GUN_PERIOD - 1,
+                step: 1,
+            },
+        ],
+        build: gun_array,
+    },
+    Generator {
+        name: "LWSS fleet",
+        params: &[
+            Param {
+                name: "ships",
+                default: 8,
+                min: 1,
+                max: 64,
+                step: 1,
+            },
+            Param {
+                name: "spacing",
+                default: 8,
+                min: 6,
+                max: 100,
+                step: 1,
+            },
+            Param {
+                name: "stagger",
+                default: 0,
+                min: 0,
+                max: 20,
+                step: 1,
+            },
+        ],
+        build: lwss_fleet,
+    },
+    Generator {
+        name: "Blinker field",
+        params: &[
+            Param {
+                name: "size",
+                default: 100,
+                min: 10,
+                max: 2000,
+                step: 10,
+            },
+            Param {
+                name: "density %",
+                default: 50,
+                min: 1,
+                max: 100,
+                step: 1,
+            },
+        ],
+        build: blinker_field,
+    },
+];
+
+/// Gosper guns side by side, `spacing` cells apart, each `phase offset` generations
+/// further along than the one before, so their streams interleave as wanted.
+fn gun_array(values: &[i64], _: &mut SimulationRng) -> Vec<I64Vec2> {
+    let [count, spacing, offset] = values else {
+        return Vec::new();
+    };
+    let gun = decode(GOSPER_GUN);
+    (0..*count)
+        .flat_map(|i| {
+            let cells = advanced(&gun, (i * offset).rem_euclid(GUN_PERIOD));
+            let shift = I64Vec2::new(i * spacing, 0);
+            cells.into_iter().map(move |pos| pos + shift)
+        })
+        .collect()
+}
+
+/// Lightweight spaceships in a column `spacing` rows apart, each `stagger` cells behind
+/// the one above.
+fn lwss_fleet(values: &[i64], _: &mut SimulationRng) -> Vec<I64Vec2> {
+    let [count, spacing, stagger] = values else {
+        return Vec::new();
+    };
+    let ship = decode(LWSS);
+    (0..*count)
+        .flat_map(|i| {
+            let shift = I64Vec2::new(i * stagger, i * spacing);
+            ship.iter().map(move |&pos| pos + shift)
+        })
+        .collect()
+}
+
+/// A square of `size` cells with a blinker, pointing either way, on `density` percent
+/// of the spots [`BLINKER_PITCH`] apart.
+fn blinker_field(values: &[i64], rng: &mut SimulationRng) -> Vec<I64Vec2> {
+    let [size, density] = values else {
+        return Vec::new();
+    };
+    let spots = size / BLINKER_PITCH;
+    let mut cells = Vec::new();
+    for y in 0..spots {
+        for x in 0..spots {
+            if rng.rng().random_range(0..100) >= *density {
+                continue;
+            }
+            let middle = I64Vec2::new(x, y) * BLINKER_PITCH + I64Vec2::ONE;
+            let arm = if rng.rng().random_bool(0.5) {
+                I64Vec2::X
+            } else {
+                I64Vec2::Y
+            };
+            cells.extend([middle - arm, middle, middle + arm]);
+        }
+    }
+    cells
+}
+
+fn decode(text: &str) -> Vec<I64Vec2> {
+    rle::decode(text)
+        .expect("built-in patterns are valid")
+        .cells
+}
+
+/// `cells` run for `generations` under Conway's rule, kept in place.
+fn advanced(cells: &[I64Vec2], generations: i64) -> Vec<I64Vec2> {
+    if generations == 0 {
+        return cells.to_vec();
+    }
+    let mut engine = create_rule_engine(LifeRule::CONWAY);
+    engine.import(cells);
+    for _ in 0..generations {
+        engine.step(1);
+    }
+    engine.export()
+}
+
+/// F12 opens the generator menu: Up/Down pick a generator or one of its settings,
+/// Left/Right change the setting and Enter adds the pattern, centered on the view.
+/// The patterns are Conway's Life constructions; other rules take them as they are.
+pub struct GeneratorPlugin;
+
+impl Plugin for GeneratorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GeneratorMenu>()
+            .add_systems(Startup, setup_generator_menu)
+            .add_systems(
+                Update,
+                (
+                    navigate_generator_menu.in_set(SimulationInput),
+                    update_generator_menu,
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Resource)]
+pub struct GeneratorMenu {
+    pub open: bool,
+    /// Index into [`GeneratorMenu::rows`].
+    pub selected: usize,
+    /// Current settings, per generator.
+    pub values: Vec<Vec<i64>>,
+}
+
+impl Default for GeneratorMenu {
+    fn default() -> Self {
+        Self {
+            open: false,
+            selected: 0,
+            values: GENERATORS
+                .iter()
+                .map(|generator| generator.params.iter().map(|p| p.default).collect())
+                .collect(),
+        }
+    }
+}
+
+impl GeneratorMenu {
+    /// The menu's lines: each generator, then each of its settings.
+    pub fn rows() -> Vec<(usize, Option<usize>)> {
+        GENERATORS
+            .iter()
+            .enumerate()
+            .flat_map(|(g, generator)| {
+                std::iter::once((g, None))
+                    .chain((0..generator.params.len()).map(move |p| (g, Some(p))))
+            })
+            .collect()
+    }
+}
+
+#[derive(Component)]
+struct GeneratorMenuPanel;
+
+fn setup_generator_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(10.0),
+                right: Val::Px(10.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+            BackgroundColor(Color::BLACK.with_alpha(0.7)),
+            GlobalZIndex(100),
+            Visibility::Hidden,
+            GeneratorMenuPanel,
+        ))
+        .with_child((
+            Text::default(),
+            TextFont {
+                font,
+                font_size: 20.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+        ));
+}
+
+// F12: open or close, Up/Down: pick a line, Left/Right: change a setting, Enter: generate
+fn navigate_generator_menu(
+    keys: Res<ButtonInput<KeyCode>>,
+    view: Res<SimulationView>,
+    mut menu: ResMut<GeneratorMenu>,
+    mut rng: ResMut<SimulationRng>,
+    mut universe: ResMut<Universe>,
+    mut patterns_loaded: MessageWriter<PatternLoaded>,
+) {
+    if keys.just_pressed(KeyCode::F12) || (menu.open && keys.just_pressed(KeyCode::Escape)) {
+        menu.open = !menu.open;
+    }
+    if !menu.open {
+        return;
+    }
+
+    let rows = GeneratorMenu::rows();
+    if keys.just_pressed(KeyCode::ArrowUp) {
+        menu.selected = (menu.selected + rows.len() - 1) % rows.len();
+    }
+    if keys.just_pressed(KeyCode::ArrowDown) {
+        menu.selected = (menu.selected + 1) % rows.len();
+    }
+    let (g, param) = rows[menu.selected];
+    if let Some(p) = param {
+        let spec = GENERATORS[g].params[p];
+        let delta = if keys.just_pressed(KeyCode::ArrowRight) {
+            spec.step
+        } else if keys.just_pressed(KeyCode::ArrowLeft) {
+            -spec.step
+        } else {
+            0
+        };
+        if delta != 0 {
+            let value = &mut menu.values[g][p];
+            *value = (*value + delta).clamp(spec.min, spec.max);
+        }
+    }
+    if !keys.just_pressed(KeyCode::Enter) {
+        return;
+    }
+
+    let generator = &GENERATORS[g];
+    let cells = (generator.build)(&menu.values[g], &mut rng);
+    let Some(min) = cells.iter().copied().reduce(I64Vec2::min) else {
+        return;
+    };
+    let max = cells.iter().copied().reduce(I64Vec2::max).unwrap_or(min);
+    // Rows run down in the generators and up on screen
+    let center = view_center(&view);
+    let middle = (min + max) / 2;
+    let cells: Vec<I64Vec2> = cells
+        .into_iter()
+        .map(|pos| center + I64Vec2::new(pos.x - middle.x, middle.y - pos.y))
+        .collect();
+
+    info!("Generated a {} of {} cells", generator.name, cells.len());
+    universe.add_cells(cells);
+    patterns_loaded.write(PatternLoaded {
+        name: generator.name.to_string(),
+    });
+    menu.open = false;
+}
+
+fn update_generator_menu(
+    menu: Res<GeneratorMenu>,
+    locale: Res<Localization>,
+    mut q_panel: Query<(&mut Visibility, &Children), With<GeneratorMenuPanel>>,
+    mut q_text: Query<&mut Text>,
+) {
+    if !menu.is_changed() {
+        return;
+    }
+    let Ok((mut visibility, children)) = q_panel.single_mut() else {
+        return;
+    };
+
+    *visibility = if menu.open {
+        Visibility::Visible
+    } else {
+        Visibility::Hidden
+    };
+
+    let mut output = locale.get("menu-generators").to_string();
+    for (i, (g, param)) in GeneratorMenu::rows().into_iter().enumerate() {
+        let marker = if i == menu.selected { ">" } else { " " };
+        match param {
+            None => output.push_str(&format!(
+                "
+{marker} {}",
+                locale.label("generator", GENERATORS[g].name)
+            )),
+            Some(p) => output.push_str(&format!(
+                "\n{marker}     {}: {}",
+                locale.label("param", GENERATORS[g].params[p].name),
+                menu.values[g][p]
+            )),
+        }
+    }
+
+    for &child in children {
+        if let Ok(mut text) = q_text.get_mut(child) {
+            **text = output.clone();
+        }
+    }
+}
@@ -1,10 +1,21 @@
+use std::collections::BTreeMap;
+use std::ops::Range;
+
 use bevy::asset::RenderAssetUsages;
+use bevy::ecs::system::{EntityCommands, SystemParam};
+use bevy::mesh::MeshVertexBufferLayoutRef;
 use bevy::prelude::*;
-use bevy::render::render_resource::{AsBindGroup, Extent3d, TextureDimension, TextureFormat};
+use bevy::render::render_resource::{
+    AsBindGroup, BlendComponent, BlendFactor, BlendOperation, BlendState, Extent3d,
+    RenderPipelineDescriptor, SpecializedMeshPipelineError, TextureDimension, TextureFormat,
+};
 use bevy::shader::ShaderRef;
-use bevy::sprite_render::{AlphaMode2d, Material2d, Material2dPlugin, MeshMaterial2d};
+use bevy::sprite_render::{
+    AlphaMode2d, Material2d, Material2dKey, Material2dPlugin, MeshMaterial2d,
+};
 use bevy::window::PrimaryWindow;
 
+use crate::simulation::stats_boards::StatsBoard;
 use crate::simulation::view::SimulationView;
 
 pub struct GraphicsPlugin;
@@ -12,88 +23,397 @@ pub struct GraphicsPlugin;
 impl Plugin for GraphicsPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(Material2dPlugin::<GridLayerMaterial>::default())
+            .init_resource::<PixelLayers>()
+            .init_resource::<LayerZRange>()
+            .add_observer(forget_removed_layer)
             // This system handles scaling and refreshing for EVERY pixel layer automatically
-            .add_systems(PostUpdate, manage_pixel_layers);
+            .add_systems(
+                PostUpdate,
+                (upload_dirty_tiles, manage_pixel_layers).chain(),
+            );
+    }
+}
+
+// --- 1. Layer Description ---
+
+/// How a layer's colors are combined with whatever has been drawn beneath it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum LayerBlend {
+    /// Standard alpha blending.
+    #[default]
+    Alpha,
+    /// Adds the layer color (weighted by alpha) on top. Good for glows and heatmaps.
+    #[allow(unused)]
+    Additive,
+    /// Multiplies the destination by the layer color. Good for shading/dimming.
+    #[allow(unused)]
+    Multiply,
+}
+
+/// Which fragment shader variant a layer is drawn with.
+/// All variants live in `chunk_shader.wgsl` and are selected through shader defs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum LayerShader {
+    /// Hard alive/dead colors, any coverage counts as alive.
+    Binary,
+    /// Blends between the dead and alive colors by coverage.
+    #[default]
+    Gradient,
+    /// Maps the buffer value through a heat palette (for age/multi-state buffers).
+    AgePalette,
+    /// Gradient plus cell grid lines when zoomed in far enough.
+    GridLines,
+    /// Treats the buffer value as a state index into the layer's [`PixelLayer::palette`].
+    /// Used by multi-state layers (Generations, Wireworld, colored variants).
+    Palette,
+}
+
+impl LayerShader {
+    /// The variants that make sense for any coverage buffer, in cycling order.
+    pub const ALL: [LayerShader; 4] = [
+        LayerShader::Binary,
+        LayerShader::Gradient,
+        LayerShader::AgePalette,
+        LayerShader::GridLines,
+    ];
+
+    /// The next variant in [`LayerShader::ALL`], wrapping around.
+    pub fn next(self) -> Self {
+        let idx = Self::ALL.iter().position(|&s| s == self).unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+
+    fn shader_def(self) -> Option<&'static str> {
+        match self {
+            LayerShader::Binary => Some("SHADER_BINARY"),
+            LayerShader::Gradient => None,
+            LayerShader::AgePalette => Some("SHADER_AGE_PALETTE"),
+            LayerShader::GridLines => Some("SHADER_GRID_LINES"),
+            LayerShader::Palette => Some("SHADER_PALETTE"),
+        }
+    }
+}
+
+/// The part of the window a layer covers.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum LayerRegion {
+    /// Covers the whole window.
+    #[default]
+    FullScreen,
+    /// A fixed rectangle in logical window pixels (origin top-left), e.g. a minimap.
+    Screen(Rect),
+}
+
+/// Storage of one canvas pixel. The value is a coverage (0 = empty, max = full) or,
+/// with [`LayerShader::Palette`], a state index.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum LayerFormat {
+    /// 8-bit values, written through [`LayerCanvas::buffer`].
+    #[default]
+    R8,
+    /// 16-bit values, written through [`LayerCanvas::buffer_u16`].
+    /// For rules with more than 256 states or fine-grained ages.
+    #[allow(unused)]
+    R16,
+}
+
+impl LayerFormat {
+    fn bytes_per_pixel(self) -> usize {
+        match self {
+            LayerFormat::R8 => 1,
+            LayerFormat::R16 => 2,
+        }
+    }
+
+    fn max_value(self) -> u32 {
+        match self {
+            LayerFormat::R8 => u8::MAX as u32,
+            LayerFormat::R16 => u16::MAX as u32,
+        }
+    }
+
+    fn texture_format(self) -> TextureFormat {
+        match self {
+            LayerFormat::R8 => TextureFormat::R8Uint,
+            LayerFormat::R16 => TextureFormat::R16Uint,
+        }
+    }
+}
+
+/// Number of entries in the palette uniform. Longer palettes are truncated.
+pub const PALETTE_SIZE: usize = 16;
+
+/// Everything needed to register a new pixel layer.
+/// Build one with [`LayerDesc::new`] and pass it to [`LayerSpawner::spawn`].
+#[derive(Clone, Debug)]
+pub struct LayerDesc {
+    pub name: String,
+    pub z_index: f32,
+    pub color_alive: Vec4,
+    pub color_dead: Vec4,
+    pub shader: LayerShader,
+    pub blend: LayerBlend,
+    pub visible: bool,
+    pub region: LayerRegion,
+    pub view: Option<SimulationView>,
+    pub tile_size: Option<u32>,
+    pub format: LayerFormat,
+    pub palette: Vec<Vec4>,
+}
+
+impl LayerDesc {
+    pub fn new(name: impl Into<String>, z_index: f32) -> Self {
+        Self {
+            name: name.into(),
+            z_index,
+            color_alive: Vec4::ONE,
+            color_dead: Vec4::ZERO,
+            shader: LayerShader::default(),
+            blend: LayerBlend::default(),
+            visible: true,
+            region: LayerRegion::default(),
+            view: None,
+            tile_size: None,
+            format: LayerFormat::default(),
+            palette: Vec::new(),
+        }
+    }
+
+    pub fn colors(mut self, color_alive: Vec4, color_dead: Vec4) -> Self {
+        self.color_alive = color_alive;
+        self.color_dead = color_dead;
+        self
+    }
+
+    pub fn shader(mut self, shader: LayerShader) -> Self {
+        self.shader = shader;
+        self
+    }
+
+    #[allow(unused)]
+    pub fn blend(mut self, blend: LayerBlend) -> Self {
+        self.blend = blend;
+        self
+    }
+
+    pub fn hidden(mut self) -> Self {
+        self.visible = false;
+        self
+    }
+
+    pub fn region(mut self, region: LayerRegion) -> Self {
+        self.region = region;
+        self
+    }
+
+    /// Splits the layer into square GPU textures of `size` pixels so a frame
+    /// only re-uploads the tiles that changed. Worth it for large, mostly static layers.
+    pub fn tiled(mut self, size: u32) -> Self {
+        self.tile_size = Some(size.max(1));
+        self
+    }
+
+    #[allow(unused)]
+    pub fn format(mut self, format: LayerFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Draws the layer with [`LayerShader::Palette`]: buffer value `i` gets `colors[i]`.
+    pub fn palette(mut self, colors: impl Into<Vec<Vec4>>) -> Self {
+        self.palette = colors.into();
+        self.shader = LayerShader::Palette;
+        self
+    }
+
+    /// Pins the layer to its own camera instead of following the shared [`SimulationView`].
+    pub fn view(mut self, view: SimulationView) -> Self {
+        self.view = Some(view);
+        self
     }
 }
 
-// --- 1. The Component & Bundle ---
+// --- 2. The Component & Registry ---
 
-/// Tag component for any entity that renders a pixel buffer.
-/// Holds the image handle so we can refresh the material automatically.
+/// Component for any entity that renders a pixel buffer.
+/// Holds the compositing settings applied by `manage_pixel_layers` every frame.
+/// The pixels themselves live in the sibling [`LayerCanvas`] and are shown by
+/// [`LayerTile`] children.
 #[derive(Component)]
 pub struct PixelLayer {
-    pub image_handle: Handle<Image>,
+    pub name: String,
+    pub z_index: f32,
+    pub color_alive: Vec4,
+    pub color_dead: Vec4,
+    pub shader: LayerShader,
+    pub blend: LayerBlend,
+    pub visible: bool,
+    pub region: LayerRegion,
+    /// Camera override. `None` follows the shared [`SimulationView`].
+    pub view: Option<SimulationView>,
+    /// Edge length of the GPU tiles in pixels. `None` uses a single texture for the whole layer.
+    pub tile_size: Option<u32>,
+    /// State colors for [`LayerShader::Palette`], at most [`PALETTE_SIZE`] are used.
+    pub palette: Vec<Vec4>,
 }
 
-/// Spawn this bundle to create a fully managed fullscreen drawing layer.
-#[derive(Bundle)]
-pub struct PixelLayerBundle {
-    pub layer: PixelLayer,
-    pub mesh: Mesh2d,
-    pub material: MeshMaterial2d<GridLayerMaterial>,
-    pub transform: Transform,
-    pub visibility: Visibility,
-    pub inherited_visibility: InheritedVisibility,
-    pub view_visibility: ViewVisibility,
-}
-
-impl PixelLayerBundle {
-    pub fn new(
-        images: &mut Assets<Image>,
-        meshes: &mut Assets<Mesh>,
-        materials: &mut Assets<GridLayerMaterial>,
-        z_index: f32,
-        color_alive: Vec4,
-        color_dead: Vec4,
-    ) -> Self {
-        let width = 32;
-        let height = 32;
-
-        let size = Extent3d {
-            width,
-            height,
-            depth_or_array_layers: 1,
-        };
-        let mut image = Image::new_fill(
-            size,
-            TextureDimension::D2,
-            &vec![0u8; (width * height) as usize],
-            TextureFormat::R8Uint,
-            RenderAssetUsages::RENDER_WORLD | RenderAssetUsages::MAIN_WORLD,
-        );
-        image.sampler = bevy::image::ImageSampler::nearest();
-        let image_handle = images.add(image);
-
-        let material_handle = materials.add(GridLayerMaterial {
-            color_alive,
-            color_dead,
-            image: image_handle.clone(),
-        });
+/// CPU-side pixel buffer of a layer. Draw systems write here every frame;
+/// `upload_dirty_tiles` then copies only the tiles whose content actually changed to the GPU.
+#[derive(Component, Default)]
+pub struct LayerCanvas {
+    pub width: usize,
+    pub height: usize,
+    pub format: LayerFormat,
+    // u16 backing keeps R16 access aligned; R8 canvases use it as plain bytes.
+    data: Vec<u16>,
+}
 
-        Self {
-            layer: PixelLayer { image_handle },
-            mesh: Mesh2d(meshes.add(Rectangle::new(1.0, 1.0))),
-            material: MeshMaterial2d(material_handle),
-            transform: Transform::from_xyz(0.0, 0.0, z_index),
-            visibility: Visibility::default(),
-            inherited_visibility: InheritedVisibility::default(),
-            view_visibility: ViewVisibility::default(),
+impl LayerCanvas {
+    /// Returns the 8-bit buffer sized for `viewport`, reallocating (zeroed) if the size changed.
+    pub fn buffer(&mut self, viewport: &LayerViewport) -> &mut [u8] {
+        debug_assert_eq!(self.format, LayerFormat::R8);
+        self.resize(viewport);
+        let len = self.width * self.height;
+        &mut bytemuck::cast_slice_mut(&mut self.data)[..len]
+    }
+
+    /// Like [`LayerCanvas::buffer`], for [`LayerFormat::R16`] canvases.
+    #[allow(unused)]
+    pub fn buffer_u16(&mut self, viewport: &LayerViewport) -> &mut [u16] {
+        debug_assert_eq!(self.format, LayerFormat::R16);
+        self.resize(viewport);
+        &mut self.data
+    }
+
+    /// The pixels as raw texture bytes, row by row.
+    pub fn bytes(&self) -> &[u8] {
+        let len = self.width * self.height * self.format.bytes_per_pixel();
+        &bytemuck::cast_slice(&self.data)[..len]
+    }
+
+    fn resize(&mut self, viewport: &LayerViewport) {
+        if self.width != viewport.screen_w || self.height != viewport.screen_h {
+            self.width = viewport.screen_w;
+            self.height = viewport.screen_h;
+            let bytes = self.width * self.height * self.format.bytes_per_pixel();
+            self.data = vec![0u16; bytes.div_ceil(2)];
         }
     }
 }
 
-// --- 2. The Infrastructure System ---
+/// One GPU texture covering a rectangle of its parent layer's canvas.
+#[derive(Component)]
+pub struct LayerTile {
+    pub image_handle: Handle<Image>,
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+    /// Size of the canvas the tile was cut from; another one needs new tiles.
+    pub canvas_size: (usize, usize),
+}
+
+/// Name -> entity lookup for every spawned pixel layer.
+#[derive(Resource, Default)]
+pub struct PixelLayers {
+    by_name: BTreeMap<String, Entity>,
+}
+
+impl PixelLayers {
+    #[allow(unused)]
+    pub fn get(&self, name: &str) -> Option<Entity> {
+        self.by_name.get(name).copied()
+    }
+}
+
+/// World z range the layers are placed in. Layer descriptions use `0.0..1.0`, which is
+/// mapped onto this range so an embedding app can keep its own sprites above or below.
+#[derive(Resource, Clone, Debug)]
+pub struct LayerZRange(pub Range<f32>);
+
+impl Default for LayerZRange {
+    fn default() -> Self {
+        Self(0.0..1.0)
+    }
+}
+
+impl LayerZRange {
+    pub fn map(&self, z: f32) -> f32 {
+        self.0.start + z * (self.0.end - self.0.start)
+    }
+}
+
+/// Bundles what is needed to create a layer, so plugins only need one system param.
+#[derive(SystemParam)]
+pub struct LayerSpawner<'w, 's> {
+    commands: Commands<'w, 's>,
+    layers: ResMut<'w, PixelLayers>,
+    z_range: Res<'w, LayerZRange>,
+}
+
+impl<'w, 's> LayerSpawner<'w, 's> {
+    /// Creates the layer entity and registers it by name. Its tiles are created
+    /// lazily once the first frame has been drawn into the canvas.
+    /// Registering a name twice replaces the lookup entry but keeps the old entity alive.
+    pub fn spawn(&mut self, desc: LayerDesc) -> EntityCommands<'_> {
+        let z_index = self.z_range.map(desc.z_index);
+        let entity = self
+            .commands
+            .spawn((
+                PixelLayer {
+                    name: desc.name.clone(),
+                    z_index,
+                    color_alive: desc.color_alive,
+                    color_dead: desc.color_dead,
+                    shader: desc.shader,
+                    blend: desc.blend,
+                    visible: desc.visible,
+                    region: desc.region,
+                    view: desc.view,
+                    tile_size: desc.tile_size,
+                    palette: desc.palette,
+                },
+                LayerCanvas {
+                    format: desc.format,
+                    ..default()
+                },
+                Transform::from_xyz(0.0, 0.0, z_index),
+                Visibility::default(),
+            ))
+            .id();
+
+        self.layers.by_name.insert(desc.name, entity);
+        self.commands.entity(entity)
+    }
+}
+
+fn forget_removed_layer(
+    trigger: On<Remove, PixelLayer>,
+    q_layers: Query<&PixelLayer>,
+    mut layers: ResMut<PixelLayers>,
+) {
+    let Ok(layer) = q_layers.get(trigger.entity) else {
+        return;
+    };
+    if layers.by_name.get(&layer.name) == Some(&trigger.entity) {
+        layers.by_name.remove(&layer.name);
+    }
+}
+
+// --- 3. The Infrastructure Systems ---
 
 fn manage_pixel_layers(
     q_window: Query<&Window, With<PrimaryWindow>>,
     // Query ALL layers (Universe, Draw, etc.)
     mut q_layers: Query<(
         &mut Transform,
-        &MeshMaterial2d<GridLayerMaterial>,
+        &mut Visibility,
         &PixelLayer,
+        Option<&Children>,
     )>,
+    q_tiles: Query<(&LayerTile, &MeshMaterial2d<GridLayerMaterial>)>,
     mut materials: ResMut<Assets<GridLayerMaterial>>,
+    view: Res<SimulationView>,
 ) {
     let Ok(window) = q_window.single() else {
         return;
@@ -101,30 +421,295 @@ fn manage_pixel_layers(
     let width = window.width();
     let height = window.height();
 
-    // Scale 1.0 -> Screen Dimensions
-    let scale = Vec3::new(width, height, 1.0);
+    for (mut transform, mut visibility, layer, children) in q_layers.iter_mut() {
+        // 1. Place the quad over its region (the 2D camera maps 1 unit to 1 logical pixel)
+        let (translation, scale) = match layer.region {
+            LayerRegion::FullScreen => (
+                Vec3::new(0.0, 0.0, layer.z_index),
+                Vec3::new(width, height, 1.0),
+            ),
+            LayerRegion::Screen(rect) => {
+                let center = rect.center();
+                (
+                    Vec3::new(
+                        center.x - width / 2.0,
+                        height / 2.0 - center.y,
+                        layer.z_index,
+                    ),
+                    Vec3::new(rect.width(), rect.height(), 1.0),
+                )
+            }
+        };
+        if transform.translation != translation || transform.scale != scale {
+            transform.translation = translation;
+            transform.scale = scale;
+        }
 
-    for (mut transform, mat_handle, layer) in q_layers.iter_mut() {
-        // 1. Auto-Scale the mesh to fit the window
-        transform.scale = scale;
+        // 2. Visibility toggle
+        let wanted = if layer.visible {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        visibility.set_if_neq(wanted);
+
+        // 3. Push settings to the tile materials, touching only those that differ
+        let Some(children) = children else {
+            continue;
+        };
+        let viewport = LayerViewport::new(window, &view, layer);
+        for (tile, mat_handle) in q_tiles.iter_many(children) {
+            let grid = viewport
+                .as_ref()
+                .map(|vp| {
+                    Vec4::new(
+                        vp.scale as f32,
+                        (vp.min_x + tile.x as f64 / vp.scale).rem_euclid(1.0) as f32,
+                        (vp.min_y + tile.y as f64 / vp.scale).rem_euclid(1.0) as f32,
+                        0.0,
+                    )
+                })
+                .unwrap_or(Vec4::ZERO);
+            let wanted_grid = if layer.shader == LayerShader::GridLines {
+                grid
+            } else {
+                Vec4::ZERO
+            };
 
-        // 2. Auto-Refresh the material (Fixes Bevy not updating texture content)
-        if let Some(material) = materials.get_mut(&mat_handle.0) {
-            material.image = layer.image_handle.clone();
+            let (palette, palette_len) = palette_uniform(&layer.palette);
+
+            let Some(material) = materials.get(&mat_handle.0) else {
+                continue;
+            };
+            if material.color_alive == layer.color_alive
+                && material.color_dead == layer.color_dead
+                && material.shader == layer.shader
+                && material.blend == layer.blend
+                && material.grid == wanted_grid
+                && material.palette == palette
+                && material.palette_len == palette_len
+            {
+                continue;
+            }
+            if let Some(material) = materials.get_mut(&mat_handle.0) {
+                material.color_alive = layer.color_alive;
+                material.color_dead = layer.color_dead;
+                material.shader = layer.shader;
+                material.blend = layer.blend;
+                material.grid = wanted_grid;
+                material.palette = palette;
+                material.palette_len = palette_len;
+            }
         }
     }
 }
 
-// --- 3. Shared Resources ---
+/// Splits each canvas into tiles (respawning them when the canvas size changes)
+/// and re-uploads only the tiles whose pixels differ from what the GPU already has.
+#[allow(clippy::too_many_arguments)]
+fn upload_dirty_tiles(
+    mut commands: Commands,
+    q_layers: Query<(Entity, &PixelLayer, &LayerCanvas, Option<&Children>)>,
+    q_tiles: Query<(&LayerTile, &MeshMaterial2d<GridLayerMaterial>)>,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<GridLayerMaterial>>,
+    mut stats: ResMut<StatsBoard>,
+    mut quad: Local<Option<Handle<Mesh>>>,
+) {
+    let quad = quad
+        .get_or_insert_with(|| meshes.add(Rectangle::new(1.0, 1.0)))
+        .clone();
+
+    let mut uploaded = 0usize;
+    let mut total = 0usize;
+
+    for (entity, layer, canvas, children) in &q_layers {
+        if canvas.width == 0 || canvas.height == 0 {
+            continue;
+        }
+
+        let tiles: Vec<_> = children
+            .map(|c| q_tiles.iter_many(c).collect())
+            .unwrap_or_default();
+        let canvas_size = (canvas.width, canvas.height);
+
+        // Canvas resized (or first frame): rebuild the tile grid with fresh textures.
+        // Comparing the shape, not the area: 800x600 and 600x800 cut into other tiles.
+        if tiles.is_empty() || tiles.iter().any(|(t, _)| t.canvas_size != canvas_size) {
+            commands.entity(entity).despawn_related::<Children>();
+            let tile_w = layer.tile_size.map_or(canvas.width, |s| s as usize);
+            let tile_h = layer.tile_size.map_or(canvas.height, |s| s as usize);
+
+            for y in (0..canvas.height).step_by(tile_h) {
+                for x in (0..canvas.width).step_by(tile_w) {
+                    let tile = LayerTile {
+                        image_handle: Handle::default(),
+                        x,
+                        y,
+                        width: tile_w.min(canvas.width - x),
+                        height: tile_h.min(canvas.height - y),
+                        canvas_size,
+                    };
+                    let image_handle = images.add(tile_image(&tile, canvas));
+                    let (palette, palette_len) = palette_uniform(&layer.palette);
+                    let material = materials.add(GridLayerMaterial {
+                        color_alive: layer.color_alive,
+                        color_dead: layer.color_dead,
+                        grid: Vec4::ZERO,
+                        palette,
+                        palette_len,
+                        max_value: canvas.format.max_value(),
+                        image: image_handle.clone(),
+                        shader: layer.shader,
+                        blend: layer.blend,
+                    });
+                    let transform = tile_transform(&tile, canvas);
+                    commands.entity(entity).with_child((
+                        LayerTile {
+                            image_handle,
+                            ..tile
+                        },
+                        Mesh2d(quad.clone()),
+                        MeshMaterial2d(material),
+                        transform,
+                    ));
+                    uploaded += 1;
+                    total += 1;
+                }
+            }
+            continue;
+        }
+
+        for (tile, mat_handle) in tiles {
+            total += 1;
+            let unchanged = images
+                .get(&tile.image_handle)
+                .and_then(|image| image.data.as_ref())
+                .is_some_and(|data| tile_matches(tile, canvas, data));
+            if unchanged {
+                continue;
+            }
+
+            if let Some(image) = images.get_mut(&tile.image_handle) {
+                let data = image.data.get_or_insert_with(Vec::new);
+                data.clear();
+                for row in tile_rows(tile, canvas) {
+                    data.extend_from_slice(row);
+                }
+                uploaded += 1;
+            }
+            // Touch the material so its bind group picks up the new texture
+            let _ = materials.get_mut(&mat_handle.0);
+        }
+    }
+
+    stats.insert("Tile Uploads", format!("{uploaded}/{total}"));
+}
+
+/// The canvas bytes covered by `tile`, one slice per row.
+fn tile_rows<'a>(tile: &LayerTile, canvas: &'a LayerCanvas) -> impl Iterator<Item = &'a [u8]> {
+    let bpp = canvas.format.bytes_per_pixel();
+    let bytes = canvas.bytes();
+    let (x, y, width, stride) = (tile.x * bpp, tile.y, tile.width * bpp, canvas.width * bpp);
+    (0..tile.height).map(move |row| {
+        let src = (y + row) * stride + x;
+        &bytes[src..src + width]
+    })
+}
+
+fn tile_matches(tile: &LayerTile, canvas: &LayerCanvas, data: &[u8]) -> bool {
+    let row_bytes = tile.width * canvas.format.bytes_per_pixel();
+    if data.len() != row_bytes * tile.height {
+        return false;
+    }
+    tile_rows(tile, canvas)
+        .zip(data.chunks_exact(row_bytes))
+        .all(|(canvas_row, gpu_row)| canvas_row == gpu_row)
+}
+
+fn tile_image(tile: &LayerTile, canvas: &LayerCanvas) -> Image {
+    let mut data = Vec::with_capacity(tile.width * tile.height * canvas.format.bytes_per_pixel());
+    for row in tile_rows(tile, canvas) {
+        data.extend_from_slice(row);
+    }
+    let mut image = Image::new(
+        Extent3d {
+            width: tile.width as u32,
+            height: tile.height as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        canvas.format.texture_format(),
+        RenderAssetUsages::RENDER_WORLD | RenderAssetUsages::MAIN_WORLD,
+    );
+    image.sampler = bevy::image::ImageSampler::nearest();
+    image
+}
+
+/// Places a tile inside the parent's unit quad. Canvas row 0 is the bottom edge (world Y up).
+fn tile_transform(tile: &LayerTile, canvas: &LayerCanvas) -> Transform {
+    let w = canvas.width as f32;
+    let h = canvas.height as f32;
+    Transform {
+        translation: Vec3::new(
+            (tile.x as f32 + tile.width as f32 / 2.0) / w - 0.5,
+            (tile.y as f32 + tile.height as f32 / 2.0) / h - 0.5,
+            0.0,
+        ),
+        scale: Vec3::new(tile.width as f32 / w, tile.height as f32 / h, 1.0),
+        ..default()
+    }
+}
+
+// --- 4. Shared Resources ---
 
 #[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+#[bind_group_data(GridLayerMaterialKey)]
 pub struct GridLayerMaterial {
     #[uniform(0)]
     pub color_alive: Vec4,
     #[uniform(0)]
     pub color_dead: Vec4,
+    /// x: buffer pixels per cell, y/z: fractional world offset of the buffer origin.
+    #[uniform(0)]
+    pub grid: Vec4,
+    #[uniform(0)]
+    pub palette: [Vec4; PALETTE_SIZE],
+    #[uniform(0)]
+    pub palette_len: u32,
+    /// Buffer value that means "full coverage" (255 for R8, 65535 for R16).
+    #[uniform(0)]
+    pub max_value: u32,
     #[texture(1, sample_type = "u_int")]
     pub image: Handle<Image>,
+    pub shader: LayerShader,
+    pub blend: LayerBlend,
+}
+
+/// Pads/truncates a layer palette to the fixed-size uniform array.
+fn palette_uniform(colors: &[Vec4]) -> ([Vec4; PALETTE_SIZE], u32) {
+    let mut palette = [Vec4::ZERO; PALETTE_SIZE];
+    let len = colors.len().min(PALETTE_SIZE);
+    palette[..len].copy_from_slice(&colors[..len]);
+    (palette, len as u32)
+}
+
+/// Pipeline key: everything that changes the render pipeline rather than the bind group.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GridLayerMaterialKey {
+    shader: LayerShader,
+    blend: LayerBlend,
+}
+
+impl From<&GridLayerMaterial> for GridLayerMaterialKey {
+    fn from(material: &GridLayerMaterial) -> Self {
+        Self {
+            shader: material.shader,
+            blend: material.blend,
+        }
+    }
 }
 
 impl Material2d for GridLayerMaterial {
@@ -134,6 +719,42 @@ impl Material2d for GridLayerMaterial {
     fn alpha_mode(&self) -> AlphaMode2d {
         AlphaMode2d::Blend
     }
+
+    fn specialize(
+        descriptor: &mut RenderPipelineDescriptor,
+        _layout: &MeshVertexBufferLayoutRef,
+        key: Material2dKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        let blend = match key.bind_group_data.blend {
+            LayerBlend::Alpha => BlendState::ALPHA_BLENDING,
+            LayerBlend::Additive => BlendState {
+                color: BlendComponent {
+                    src_factor: BlendFactor::SrcAlpha,
+                    dst_factor: BlendFactor::One,
+                    operation: BlendOperation::Add,
+                },
+                alpha: BlendComponent::OVER,
+            },
+            LayerBlend::Multiply => BlendState {
+                color: BlendComponent {
+                    src_factor: BlendFactor::Dst,
+                    dst_factor: BlendFactor::OneMinusSrcAlpha,
+                    operation: BlendOperation::Add,
+                },
+                alpha: BlendComponent::OVER,
+            },
+        };
+
+        if let Some(fragment) = descriptor.fragment.as_mut() {
+            if let Some(def) = key.bind_group_data.shader.shader_def() {
+                fragment.shader_defs.push(def.into());
+            }
+            for target in fragment.targets.iter_mut().flatten() {
+                target.blend = Some(blend);
+            }
+        }
+        Ok(())
+    }
 }
 
 pub struct LayerViewport {
@@ -145,17 +766,35 @@ pub struct LayerViewport {
 }
 
 impl LayerViewport {
-    pub fn new(window: &Window, view: &SimulationView) -> Option<Self> {
-        let screen_w = window.physical_width() as usize;
-        let screen_h = window.physical_height() as usize;
+    /// Maps the layer's region of the window onto the world, using the layer's own camera
+    /// if it has one and the shared `view` otherwise.
+    pub fn new(window: &Window, view: &SimulationView, layer: &PixelLayer) -> Option<Self> {
+        let (logical_w, logical_h, screen_w, screen_h) = match layer.region {
+            LayerRegion::FullScreen => (
+                window.width() as f64,
+                window.height() as f64,
+                window.physical_width() as usize,
+                window.physical_height() as usize,
+            ),
+            LayerRegion::Screen(rect) => {
+                let pixel_ratio = window.scale_factor();
+                (
+                    rect.width() as f64,
+                    rect.height() as f64,
+                    (rect.width() * pixel_ratio).round() as usize,
+                    (rect.height() * pixel_ratio).round() as usize,
+                )
+            }
+        };
         if screen_w == 0 || screen_h == 0 {
             return None;
         }
 
-        let world_w = window.width() as f64 / view.zoom as f64;
-        let world_h = window.height() as f64 / view.zoom as f64;
-        let min_x = view.center.x as f64 - (world_w / 2.0);
-        let min_y = view.center.y as f64 - (world_h / 2.0);
+        let view = layer.view.as_ref().unwrap_or(view);
+        let world_w = logical_w / view.zoom;
+        let world_h = logical_h / view.zoom;
+        let min_x = view.center.x - (world_w / 2.0);
+        let min_y = view.center.y - (world_h / 2.0);
         let scale = screen_w as f64 / world_w;
 
         Some(Self {
@@ -167,23 +806,6 @@ impl LayerViewport {
         })
     }
 
-    pub fn get_buffer<'a>(&self, image: &'a mut Image) -> &'a mut [u8] {
-        let width = self.screen_w as u32;
-        let height = self.screen_h as u32;
-        if image.width() != width || image.height() != height {
-            image.resize(Extent3d {
-                width,
-                height,
-                depth_or_array_layers: 1,
-            });
-        }
-        let len = self.screen_w * self.screen_h;
-        if image.data.is_none() || image.data.as_ref().map(|d| d.len()).unwrap_or(0) != len {
-            image.data = Some(vec![0u8; len]);
-        }
-        image.data.as_mut().unwrap()
-    }
-
     pub fn get_world_rect(&self) -> Rect {
         let width = self.screen_w as f64 / self.scale;
         let height = self.screen_h as f64 / self.scale;
@@ -193,21 +815,28 @@ impl LayerViewport {
         }
     }
 
-    pub fn draw_cell(&self, buffer: &mut [u8], gx: i64, gy: i64, value: u8) {
+    pub fn draw_cell<T: Copy>(&self, buffer: &mut [T], gx: i64, gy: i64, value: T) {
+        self.draw_rect(buffer, gx, gy, 1, 1, value);
+    }
+
+    /// Fills the `w` x `h` cell rectangle whose lower corner is the cell (`gx`, `gy`).
+    pub fn draw_rect<T: Copy>(&self, buffer: &mut [T], gx: i64, gy: i64, w: i64, h: i64, value: T) {
         let screen_x = (gx as f64 - self.min_x) * self.scale;
         let screen_y = (gy as f64 - self.min_y) * self.scale;
+        let screen_w = w as f64 * self.scale;
+        let screen_h = h as f64 * self.scale;
 
         if screen_x >= self.screen_w as f64 || screen_y >= self.screen_h as f64 {
             return;
         }
-        if screen_x + self.scale <= 0.0 || screen_y + self.scale <= 0.0 {
+        if screen_x + screen_w <= 0.0 || screen_y + screen_h <= 0.0 {
             return;
         }
 
         let start_x = screen_x.floor().max(0.0) as usize;
         let start_y = screen_y.floor().max(0.0) as usize;
-        let end_x = (screen_x + self.scale).ceil().min(self.screen_w as f64) as usize;
-        let end_y = (screen_y + self.scale).ceil().min(self.screen_h as f64) as usize;
+        let end_x = (screen_x + screen_w).ceil().min(self.screen_w as f64) as usize;
+        let end_y = (screen_y + screen_h).ceil().min(self.screen_h as f64) as usize;
 
         for y in start_y..end_y {
             let row_offset = y * self.screen_w;
@@ -0,0 +1,270 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::simulation::SimulationInput;
+use crate::simulation::graphics::{
+    LayerCanvas, LayerDesc, LayerSpawner, LayerViewport, PixelLayer,
+};
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::view::SimulationView;
+
+/// Gridlines are at least this many logical pixels apart.
+const MIN_SPACING_PX: f64 = 80.0;
+/// Labels kept around for the gridlines; lines past this many go unlabeled.
+#[cfg(feature = "ui")]
+const MAX_LABELS: usize = 64;
+
+const AXIS: u8 = 1;
+const GRID: u8 = 2;
+
+pub struct GuidesPlugin;
+
+impl Plugin for GuidesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Guides>()
+            .add_systems(Startup, setup_guides_layer)
+            .add_systems(
+                Update,
+                (toggle_guides.in_set(SimulationInput), render_guides).chain(),
+            );
+        #[cfg(feature = "ui")]
+        app.add_systems(Startup, setup_guide_labels)
+            .add_systems(Update, update_guide_labels.after(toggle_guides));
+    }
+}
+
+/// Orientation aids drawn over the plane: the axes through the origin, and gridlines at
+/// a power of ten cells apart, labeled with their coordinate, once zoomed out far enough
+/// that cells don't show the way.
+#[derive(Resource, Clone, Copy, Default)]
+pub struct Guides {
+    pub axes: bool,
+    pub grid: bool,
+}
+
+impl Guides {
+    fn describe(&self) -> Option<&'static str> {
+        match (self.axes, self.grid) {
+            (false, false) => None,
+            (true, false) => Some("axes (F8: grid)"),
+            (_, true) => Some("axes and grid (F8: off)"),
+        }
+    }
+}
+
+/// Cells between gridlines at `zoom` (logical pixels per cell), or `None` when zoomed in
+/// so far that they would be closer than ten cells.
+fn grid_spacing(zoom: f64) -> Option<i64> {
+    let exponent = (MIN_SPACING_PX / zoom).log10().ceil().max(0.0) as u32;
+    (exponent >= 1)
+        .then(|| 10i64.checked_pow(exponent))
+        .flatten()
+}
+
+/// `value` as a label: plain up to a million, then as a multiple of a power of ten.
+#[cfg(feature = "ui")]
+fn format_coordinate(value: i64, spacing: i64) -> String {
+    let exponent = spacing.ilog10();
+    if exponent >= 6 {
+        format!("{}e{exponent}", value / spacing)
+    } else {
+        value.to_string()
+    }
+}
+
+#[derive(Component)]
+struct GuidesLayer;
+
+fn setup_guides_layer(mut layers: LayerSpawner) {
+    layers
+        .spawn(
+            LayerDesc::new("guides", 0.02)
+                .palette([
+                    Vec4::ZERO,
+                    Vec4::new(0.9, 0.3, 0.3, 0.6),
+                    Vec4::new(1.0, 1.0, 1.0, 0.12),
+                ])
+                .hidden(),
+        )
+        .insert(GuidesLayer);
+}
+
+// F8: off, axes, axes and grid
+fn toggle_guides(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut guides: ResMut<Guides>,
+    mut q_layer: Query<&mut PixelLayer, With<GuidesLayer>>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    if keys.just_pressed(KeyCode::F8) {
+        *guides = match (guides.axes, guides.grid) {
+            (false, false) => Guides {
+                axes: true,
+                grid: false,
+            },
+            (true, false) => Guides {
+                axes: true,
+                grid: true,
+            },
+            _ => Guides::default(),
+        };
+    }
+    if !guides.is_changed() {
+        return;
+    }
+
+    let shown = guides.axes || guides.grid;
+    for mut layer in &mut q_layer {
+        if layer.visible != shown {
+            layer.visible = shown;
+        }
+    }
+    match guides.describe() {
+        Some(text) => stats.insert("Guides", text),
+        None => stats.remove("Guides"),
+    }
+}
+
+fn render_guides(
+    guides: Res<Guides>,
+    view: Res<SimulationView>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut q_layer: Query<(&PixelLayer, &mut LayerCanvas), With<GuidesLayer>>,
+) {
+    if !guides.axes && !guides.grid {
+        return;
+    }
+    let Ok((layer, mut canvas)) = q_layer.single_mut() else {
+        return;
+    };
+    let Ok(window) = q_window.single() else {
+        return;
+    };
+    let Some(viewport) = LayerViewport::new(window, &view, layer) else {
+        return;
+    };
+    let buffer = canvas.buffer(&viewport);
+    buffer.fill(0);
+
+    // One pixel wide lines along the left and bottom edges of the cells at a coordinate
+    let (w, h) = (viewport.screen_w, viewport.screen_h);
+    let column = |x: i64| {
+        let px = ((x as f64 - viewport.min_x) * viewport.scale).round();
+        (px >= 0.0 && px < w as f64).then_some(px as usize)
+    };
+    let row = |y: i64| {
+        let py = ((y as f64 - viewport.min_y) * viewport.scale).round();
+        (py >= 0.0 && py < h as f64).then_some(py as usize)
+    };
+    let vertical = |buffer: &mut [u8], x: usize, value: u8| {
+        for y in 0..h {
+            buffer[y * w + x] = value;
+        }
+    };
+
+    let rect = viewport.get_world_rect();
+    if guides.grid
+        && let Some(spacing) = grid_spacing(view.zoom)
+    {
+        let first = |min: f32| (min as f64 / spacing as f64).ceil() as i64;
+        let last = |max: f32| (max as f64 / spacing as f64).floor() as i64;
+        for k in first(rect.min.x)..=last(rect.max.x) {
+            if let Some(x) = column(k * spacing) {
+                vertical(buffer, x, GRID);
+            }
+        }
+        for k in first(rect.min.y)..=last(rect.max.y) {
+            if let Some(y) = row(k * spacing) {
+                buffer[y * w..(y + 1) * w].fill(GRID);
+            }
+        }
+    }
+    if guides.axes {
+        if let Some(x) = column(0) {
+            vertical(buffer, x, AXIS);
+        }
+        if let Some(y) = row(0) {
+            buffer[y * w..(y + 1) * w].fill(AXIS);
+        }
+    }
+}
+
+#[cfg(feature = "ui")]
+#[derive(Component)]
+struct GuideLabel;
+
+#[cfg(feature = "ui")]
+fn setup_guide_labels(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+    for _ in 0..MAX_LABELS {
+        commands.spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            Text::new(""),
+            TextFont {
+                font: font.clone(),
+                font_size: 13.0,
+                ..default()
+            },
+            TextColor(Color::WHITE.with_alpha(0.6)),
+            Visibility::Hidden,
+            GlobalZIndex(50),
+            GuideLabel,
+        ));
+    }
+}
+
+/// Puts the coordinates of the gridlines in view along the top and left window edges.
+#[cfg(feature = "ui")]
+fn update_guide_labels(
+    guides: Res<Guides>,
+    view: Res<SimulationView>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut q_labels: Query<(&mut Node, &mut Text, &mut Visibility), With<GuideLabel>>,
+) {
+    let Ok(window) = q_window.single() else {
+        return;
+    };
+    let spacing = grid_spacing(view.zoom).filter(|_| guides.grid);
+    let mut labels = q_labels.iter_mut();
+
+    if let Some(spacing) = spacing {
+        let (width, height) = (window.width() as f64, window.height() as f64);
+        // Logical window position of a world coordinate, y down
+        let screen_x = |x: i64| (x as f64 - view.center.x) * view.zoom + width / 2.0;
+        let screen_y = |y: i64| height / 2.0 - (y as f64 - view.center.y) * view.zoom;
+        let range = |center: f64, extent: f64| {
+            let half = extent / 2.0 / view.zoom;
+            let first = ((center - half) / spacing as f64).ceil() as i64;
+            let last = ((center + half) / spacing as f64).floor() as i64;
+            first..=last
+        };
+
+        let columns = range(view.center.x, width).map(|k| {
+            let x = k * spacing;
+            (x, Val::Px(screen_x(x) as f32 + 3.0), Val::Px(3.0))
+        });
+        let rows = range(view.center.y, height).map(|k| {
+            let y = k * spacing;
+            (y, Val::Px(3.0), Val::Px(screen_y(y) as f32 + 1.0))
+        });
+        for ((value, left, top), (mut node, mut text, mut visibility)) in
+            columns.chain(rows).zip(&mut labels)
+        {
+            if node.left != left || node.top != top {
+                node.left = left;
+                node.top = top;
+            }
+            let label = format_coordinate(value, spacing);
+            if text.0 != label {
+                text.0 = label;
+            }
+            visibility.set_if_neq(Visibility::Inherited);
+        }
+    }
+    for (_, _, mut visibility) in labels {
+        visibility.set_if_neq(Visibility::Hidden);
+    }
+}
@@ -0,0 +1,224 @@
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::simulation::SimulationInput;
+use crate::simulation::engine::{TILE_SIZE, Tile};
+use crate::simulation::graphics::{
+    LayerCanvas, LayerDesc, LayerSpawner, LayerViewport, PixelLayer,
+};
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::universe::Universe;
+use crate::simulation::view::SimulationView;
+
+/// Buckets along the longer side of the bounding box.
+const BUCKETS: i64 = 64;
+/// Shades of the palette, the first one for empty buckets.
+const SHADES: usize = 8;
+
+/// Shift+A lays a coarse heatmap over the population's bounding box: about 64×64 square
+/// buckets, shaded by how many cells each holds on a log scale, from faint blue to
+/// yellow. Zoomed out on a huge universe it shows at a glance where far away regions
+/// are still alive, long after single cells are too small to see. The buckets are
+/// counted again whenever the generation changes.
+pub struct HeatmapPlugin;
+
+impl Plugin for HeatmapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Heatmap>()
+            .add_systems(Startup, setup_heatmap_layer)
+            .add_systems(
+                Update,
+                (
+                    toggle_heatmap.in_set(SimulationInput),
+                    count_heatmap,
+                    render_heatmap,
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Heatmap {
+    pub enabled: bool,
+    /// Lower corner of the first bucket.
+    pub origin: I64Vec2,
+    /// Side of a bucket in cells, a power of two.
+    pub bucket_size: i64,
+    /// Buckets across and up.
+    pub dims: I64Vec2,
+    /// Live cells per bucket, row by row from the bottom.
+    pub counts: Vec<u64>,
+    // Generation the buckets were counted at
+    counted_at: Option<u64>,
+}
+
+impl Heatmap {
+    fn count(&mut self, tiles: &[(I64Vec2, Tile)]) {
+        self.counts.clear();
+        self.dims = I64Vec2::ZERO;
+        let Some(min) = tiles.iter().map(|(pos, _)| *pos).reduce(I64Vec2::min) else {
+            return;
+        };
+        let max = tiles.iter().map(|(pos, _)| *pos).fold(min, I64Vec2::max);
+        let extent = (max - min + I64Vec2::ONE) * TILE_SIZE;
+
+        // Powers of two either split tiles evenly or hold whole ones
+        let size = (extent.max_element() as u64).div_ceil(BUCKETS as u64);
+        self.bucket_size = size.next_power_of_two() as i64;
+        let bucket = self.bucket_size;
+        self.origin = (min * TILE_SIZE).div_euclid(I64Vec2::splat(bucket)) * bucket;
+        let end =
+            ((max + I64Vec2::ONE) * TILE_SIZE - self.origin + I64Vec2::splat(bucket - 1)) / bucket;
+        self.dims = end;
+        self.counts = vec![0; (end.x * end.y) as usize];
+
+        for (pos, tile) in tiles {
+            let corner = *pos * TILE_SIZE - self.origin;
+            if bucket >= TILE_SIZE {
+                let cell = corner / bucket;
+                let count = tile.iter().map(|row| row.count_ones() as u64).sum::<u64>();
+                self.counts[(cell.y * end.x + cell.x) as usize] += count;
+                continue;
+            }
+            let mask = (1u64 << bucket) - 1;
+            for (y, row) in tile.iter().enumerate().filter(|(_, row)| **row != 0) {
+                let by = (corner.y + y as i64) / bucket;
+                for bx in 0..TILE_SIZE / bucket {
+                    let count = ((row >> (bx * bucket)) & mask).count_ones() as u64;
+                    let x = corner.x / bucket + bx;
+                    self.counts[(by * end.x + x) as usize] += count;
+                }
+            }
+        }
+    }
+
+    /// The fullest bucket's lower corner and count.
+    pub fn densest(&self) -> Option<(I64Vec2, u64)> {
+        let (index, &count) = self
+            .counts
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, count)| **count)?;
+        let cell = I64Vec2::new(index as i64 % self.dims.x, index as i64 / self.dims.x);
+        Some((self.origin + cell * self.bucket_size, count))
+    }
+}
+
+#[derive(Component)]
+struct HeatmapLayer;
+
+fn setup_heatmap_layer(mut layers: LayerSpawner) {
+    let shades: Vec<Vec4> = (0..SHADES)
+        .map(|i| {
+            if i == 0 {
+                return Vec4::ZERO;
+            }
+            let t = i as f32 / (SHADES - 1) as f32;
+            Vec4::new(
+                0.2 + 0.8 * t,
+                0.3 + 0.6 * t * t,
+                1.0 - 0.8 * t,
+                0.2 + 0.35 * t,
+            )
+        })
+        .collect();
+    layers
+        .spawn(LayerDesc::new("heatmap", 0.03).palette(shades).hidden())
+        .insert(HeatmapLayer);
+}
+
+// Shift+A: toggle the population heatmap
+fn toggle_heatmap(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut heatmap: ResMut<Heatmap>,
+    mut q_layer: Query<&mut PixelLayer, With<HeatmapLayer>>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    if !keys.just_pressed(KeyCode::KeyA)
+        || !keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight])
+    {
+        return;
+    }
+    heatmap.enabled = !heatmap.enabled;
+    heatmap.counted_at = None;
+    for mut layer in &mut q_layer {
+        layer.visible = heatmap.enabled;
+    }
+    if !heatmap.enabled {
+        stats.remove("Heatmap");
+    }
+}
+
+fn count_heatmap(
+    universe: Res<Universe>,
+    mut heatmap: ResMut<Heatmap>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    let generation = universe.generation();
+    if !heatmap.enabled || heatmap.counted_at == Some(generation) {
+        return;
+    }
+    heatmap.counted_at = Some(generation);
+
+    let mut tiles = Vec::new();
+    universe
+        .read_engine()
+        .visit_tiles(&mut |pos, tile| tiles.push((pos, *tile)));
+    heatmap.count(&tiles);
+
+    match heatmap.densest() {
+        Some((corner, count)) => stats.insert(
+            "Heatmap",
+            format!(
+                "{}×{} buckets of {} cells, densest at ({}, {}) with {count}",
+                heatmap.dims.x, heatmap.dims.y, heatmap.bucket_size, corner.x, corner.y
+            ),
+        ),
+        None => stats.insert("Heatmap", "empty universe"),
+    }
+}
+
+fn render_heatmap(
+    heatmap: Res<Heatmap>,
+    view: Res<SimulationView>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut q_layer: Query<(&PixelLayer, &mut LayerCanvas), With<HeatmapLayer>>,
+) {
+    if !heatmap.enabled {
+        return;
+    }
+    let Ok((layer, mut canvas)) = q_layer.single_mut() else {
+        return;
+    };
+    let Ok(window) = q_window.single() else {
+        return;
+    };
+    let Some(viewport) = LayerViewport::new(window, &view, layer) else {
+        return;
+    };
+    let buffer = canvas.buffer(&viewport);
+    buffer.fill(0);
+
+    // Shades step up with each doubling of the count, relative to the fullest bucket
+    let peak = heatmap.counts.iter().copied().max().unwrap_or(0);
+    let scale = (SHADES - 1) as f64 / ((peak + 1) as f64).log2().max(1.0);
+    let size = heatmap.bucket_size;
+    for (index, &count) in heatmap.counts.iter().enumerate() {
+        if count == 0 {
+            continue;
+        }
+        let shade = (((count + 1) as f64).log2() * scale).ceil() as usize;
+        let cell = I64Vec2::new(index as i64 % heatmap.dims.x, index as i64 / heatmap.dims.x);
+        let corner = heatmap.origin + cell * size;
+        viewport.draw_rect(
+            buffer,
+            corner.x,
+            corner.y,
+            size,
+            size,
+            shade.clamp(1, SHADES - 1) as u8,
+        );
+    }
+}
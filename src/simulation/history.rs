@@ -0,0 +1,238 @@
+use std::collections::VecDeque;
+
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+use rustc_hash::FxHashMap;
+
+use crate::simulation::SimulationInput;
+use crate::simulation::codec::{BlockDiff, apply_diff, collect_tiles, diff_tiles};
+use crate::simulation::engine::{Keyframe, Tile};
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::universe::Universe;
+
+/// Recording exports every cell each generation, so big universes aren't recorded.
+const MAX_POPULATION: u64 = 500_000;
+/// Memory the deltas may take up; the oldest are dropped beyond it.
+const MAX_BYTES: usize = 256 << 20;
+/// Keyframes kept; they share nodes, so this is about bookkeeping rather than memory.
+const MAX_KEYFRAMES: usize = 100_000;
+
+/// Records each state the universe is drawn in (generations and edits alike) as the
+/// tiles that changed, XORed with their previous contents and kept encoded as a
+/// [`BlockDiff`], which stays tiny once a pattern settles. Engines that take keyframes
+/// (HashLife) keep those instead: a shared root per state, with no size limit and
+/// rewinding far deeper. Home steps back through the states (pausing the universe), End
+/// forward again; resuming from an earlier state forgets the later ones.
+pub struct HistoryPlugin;
+
+impl Plugin for HistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<History>().add_systems(
+            Update,
+            (record_history, history_input.in_set(SimulationInput)).chain(),
+        );
+    }
+}
+
+/// The tiles that differ between two consecutive states, XORed and encoded.
+struct Delta {
+    /// Generation of the earlier state.
+    generation: u64,
+    diff: Vec<u8>,
+}
+
+impl Delta {
+    fn bytes(&self) -> usize {
+        self.diff.len()
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct History {
+    deltas: VecDeque<Delta>,
+    bytes: usize,
+    // The state after the last delta, or the one shown while stepping back
+    tiles: FxHashMap<I64Vec2, Tile>,
+    // Every state, for engines that take keyframes; the deltas stay empty then
+    keyframes: VecDeque<Keyframe>,
+    // How many states back the shown one is
+    back: usize,
+    // (generation, revision) of the universe the tiles match
+    synced_at: Option<(u64, u64)>,
+    // Generation of the newest state, to step forward to it again
+    newest: u64,
+}
+
+impl History {
+    /// States that can be stepped back to.
+    pub fn len(&self) -> usize {
+        self.deltas.len() + self.keyframes.len().saturating_sub(1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Records a keyframe of the universe's current state as the newest one.
+    fn record_keyframe(&mut self, keyframe: Keyframe) {
+        if !self.deltas.is_empty() || !self.tiles.is_empty() {
+            // Switched to an engine that takes keyframes
+            self.deltas.clear();
+            self.tiles.clear();
+            self.bytes = 0;
+        }
+        self.keyframes.push_back(keyframe);
+        if self.keyframes.len() > MAX_KEYFRAMES {
+            self.keyframes.pop_front();
+        }
+    }
+
+    /// Records the universe's current state as the newest one.
+    fn record(&mut self, tiles: FxHashMap<I64Vec2, Tile>, generation: u64) {
+        self.keyframes.clear();
+        if self.synced_at.is_some() {
+            let diff = BlockDiff {
+                generation,
+                tiles: diff_tiles(&self.tiles, &tiles, |_| true),
+            };
+            let delta = Delta {
+                generation,
+                diff: diff.encode(),
+            };
+            self.bytes += delta.bytes();
+            self.deltas.push_back(delta);
+            while self.bytes > MAX_BYTES
+                && let Some(oldest) = self.deltas.pop_front()
+            {
+                self.bytes -= oldest.bytes();
+            }
+        }
+        self.tiles = tiles;
+    }
+
+    /// Applies delta `index` to the shown tiles (undoing or redoing it), returning the
+    /// cells that were born and died by that.
+    fn apply(&mut self, index: usize) -> (Vec<I64Vec2>, Vec<I64Vec2>) {
+        // Encoded right here, so it decodes
+        let diff = BlockDiff::decode(&self.deltas[index].diff).unwrap_or_default();
+        apply_diff(&mut self.tiles, &diff.tiles)
+    }
+}
+
+fn record_history(
+    mut history: ResMut<History>,
+    universe: Res<Universe>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    let now = (universe.generation(), universe.revision());
+    if history.synced_at == Some(now) {
+        return;
+    }
+    let keyframe = universe.keyframe();
+    if keyframe.is_none() && universe.population() > MAX_POPULATION {
+        if history.synced_at.is_some() {
+            history.clear();
+            stats.insert("History", format!("off above {MAX_POPULATION} cells"));
+        }
+        return;
+    }
+
+    // Moving on from an earlier state: the later ones are no longer where this leads
+    let history = &mut *history;
+    if history.back > 0 {
+        let keep = history.deltas.len().saturating_sub(history.back);
+        for dropped in history.deltas.drain(keep..) {
+            history.bytes -= dropped.bytes();
+        }
+        let keep = history.keyframes.len().saturating_sub(history.back);
+        history.keyframes.truncate(keep);
+        history.back = 0;
+    }
+    match keyframe {
+        Some(keyframe) => history.record_keyframe(keyframe),
+        None => {
+            let generation = history
+                .synced_at
+                .map_or(now.0, |(generation, _)| generation);
+            history.record(collect_tiles(universe.read_engine().as_ref()), generation);
+            history.newest = now.0;
+        }
+    }
+    history.synced_at = Some(now);
+    if history.is_empty() {
+        return;
+    }
+    let size = if history.keyframes.is_empty() {
+        format!("{} KB", history.bytes / 1024)
+    } else {
+        "keyframes".to_string()
+    };
+    stats.insert(
+        "History",
+        format!("{} states, {size} (Home: back)", history.len()),
+    );
+}
+
+// Home: step back through the history, End: forward again
+fn history_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut history: ResMut<History>,
+    mut universe: ResMut<Universe>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    let forward = if keys.just_pressed(KeyCode::Home) && history.back < history.len() {
+        false
+    } else if keys.just_pressed(KeyCode::End) && history.back > 0 {
+        true
+    } else {
+        return;
+    };
+    universe.paused = true;
+
+    let generation = if history.keyframes.is_empty() {
+        let index = if forward {
+            history.back -= 1;
+            history.len() - history.back - 1
+        } else {
+            history.back += 1;
+            history.len() - history.back
+        };
+        // Each delta leads on from the generation it holds
+        let generation = history
+            .deltas
+            .get(history.len() - history.back)
+            .map_or(history.newest, |delta| delta.generation);
+        universe.set_generation(generation);
+        let (born, died) = history.apply(index);
+        universe.remove_cells(died);
+        universe.add_cells(born);
+        Some(generation)
+    } else {
+        let back = if forward {
+            history.back - 1
+        } else {
+            history.back + 1
+        };
+        let keyframe = history.keyframes[history.keyframes.len() - 1 - back].clone();
+        if !universe.restore_keyframe(&keyframe) {
+            // Taken by an engine that is gone
+            history.clear();
+            stats.remove("History");
+            return;
+        }
+        history.back = back;
+        Some(keyframe.generation)
+    };
+    // Our own change; not a new state
+    history.synced_at = Some((universe.generation(), universe.revision()));
+
+    let shown = match (history.back, generation) {
+        (0, _) | (_, None) => "latest state".to_string(),
+        (back, Some(generation)) => format!("generation {generation}, {back} back"),
+    };
+    stats.insert("History", format!("{shown} (Home: back, End: forward)"));
+}
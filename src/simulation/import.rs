@@ -0,0 +1,329 @@
+use std::path::Path;
+
+#[cfg(not(target_arch = "wasm32"))]
+use bevy::math::DVec2;
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+
+use crate::simulation::engine::{EngineRegistry, LifeEngine, create_rule_engine, default_engine};
+use crate::simulation::error::LifeError;
+use crate::simulation::rle::{PatternSize, RlePattern};
+use crate::simulation::rules::LifeRule;
+use crate::simulation::universe::Universe;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::simulation::{
+    camera_script::CameraScript, import_ghost::PendingImport, stats_boards::StatsBoard,
+    view::SimulationView,
+};
+use crate::simulation::{plaintext, rle};
+
+const HASH_LIFE: &str = "hash-life";
+
+/// Turns pattern text into cells for every importer, each checked against the
+/// [`ImportBudget`] first: a pattern is measured, which only adds up its run counts, and
+/// refused if it would take more memory than the budget on the engine it would load
+/// into.
+///
+/// Dropping an `.rle` or `.cells` file on the window opens it centered on the view and
+/// zooms to fit it; a click puts it down (see `ImportGhostPlugin`). A rule in its header
+/// and a `STOP n` generation in its camera script are only suggestions: Enter applies
+/// them, Escape keeps the current rule. ZIPs and `.rule` files, with the patterns dropped
+/// along with them, are opened as rule bundles instead (see `BundlePlugin`).
+pub struct ImportPlugin;
+
+impl Plugin for ImportPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ImportBudget>();
+        #[cfg(not(target_arch = "wasm32"))]
+        app.init_resource::<PatternHints>().add_systems(
+            Update,
+            (
+                load_dropped_patterns,
+                confirm_hints.in_set(crate::simulation::SimulationInput),
+            ),
+        );
+    }
+}
+
+/// Memory a pattern read from text may take up on the engine it loads into, in bytes.
+/// Set with `--import-budget MB`; 2 GB by default, 1 GB on the web, where a page gets
+/// 4 GB at most.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct ImportBudget(pub u64);
+
+impl Default for ImportBudget {
+    fn default() -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        let megabytes = crate::simulation::batch::arg_value("--import-budget")
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(2000);
+        #[cfg(target_arch = "wasm32")]
+        let megabytes = 1000u64;
+        Self(megabytes.saturating_mul(1_000_000))
+    }
+}
+
+impl ImportBudget {
+    /// Refuses a pattern of `size` that would take more than the budget on `engine`.
+    /// With a `registry` to switch engines in, the error points at HashLife, which
+    /// shares the memory of repeated areas.
+    pub fn check(
+        self,
+        size: &PatternSize,
+        name: &str,
+        engine: &dyn LifeEngine,
+        registry: Option<&EngineRegistry>,
+    ) -> Result<(), LifeError> {
+        let estimate = engine.estimate_memory(size.cells, size.size);
+        if estimate <= self.0 {
+            return Ok(());
+        }
+
+        let hash_life_key =
+            registry.and_then(|registry| registry.iter().position(|entry| entry.id == HASH_LIFE));
+        let suggestion = match hash_life_key {
+            Some(index) if engine.id() != HASH_LIFE => format!(
+                "switch to HashLife (key {}) to load it, or raise --import-budget",
+                index + 1
+            ),
+            _ => "raise --import-budget to load it anyway".to_string(),
+        };
+        Err(LifeError::TooLarge {
+            name: name.to_string(),
+            cells: size.cells,
+            engine: engine.name().to_string(),
+            estimate,
+            budget: self.0,
+            suggestion,
+        })
+    }
+
+    /// [`ImportBudget::check`] on the engine a pattern would load into in `universe`:
+    /// its own, or the one a pattern with another rule switches to first.
+    pub fn check_in(
+        self,
+        size: &PatternSize,
+        name: &str,
+        registry: &EngineRegistry,
+        universe: &Universe,
+    ) -> Result<(), LifeError> {
+        match size.rule {
+            Some(rule) if rule != universe.rule() && rule == LifeRule::CONWAY => {
+                self.check(size, name, default_engine().as_ref(), Some(registry))
+            }
+            Some(rule) if rule != universe.rule() => self.check(
+                size,
+                name,
+                create_rule_engine(rule).as_ref(),
+                Some(registry),
+            ),
+            _ => self.check(size, name, universe.read_engine().as_ref(), Some(registry)),
+        }
+    }
+
+    /// `text` decoded, once it passed [`ImportBudget::check_in`].
+    pub fn decode_in(
+        self,
+        text: &str,
+        format: PatternFormat,
+        name: &str,
+        registry: &EngineRegistry,
+        universe: &Universe,
+    ) -> Result<RlePattern, LifeError> {
+        self.check_in(&format.measure(text)?, name, registry, universe)?;
+        format.decode(text)
+    }
+}
+
+/// The text formats patterns are read from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PatternFormat {
+    Rle,
+    /// LifeWiki's `.cells`.
+    Plaintext,
+}
+
+impl PatternFormat {
+    /// The format a file is in, going by its extension.
+    pub fn of(path: &Path) -> Self {
+        if plaintext::is_plaintext(path) {
+            Self::Plaintext
+        } else {
+            Self::Rle
+        }
+    }
+
+    pub fn measure(self, text: &str) -> Result<PatternSize, LifeError> {
+        match self {
+            Self::Rle => rle::measure(text),
+            Self::Plaintext => plaintext::measure(text),
+        }
+    }
+
+    /// The pattern with its top-left cell at the origin. Unchecked: go through
+    /// [`ImportBudget`] for text from outside.
+    pub fn decode(self, text: &str) -> Result<RlePattern, LifeError> {
+        match self {
+            Self::Rle => rle::decode(text),
+            Self::Plaintext => plaintext::decode(text),
+        }
+    }
+}
+
+/// Suggestions from the last dropped pattern, waiting for Enter (apply) or Escape.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Resource, Default)]
+pub struct PatternHints {
+    pub rule: Option<LifeRule>,
+    pub stop: Option<u64>,
+}
+
+// Dropping an .rle or .cells file on the window opens it centered on the view, with its
+// camera script if it has one, for a click to put down
+#[cfg(not(target_arch = "wasm32"))]
+#[allow(clippy::too_many_arguments)]
+fn load_dropped_patterns(
+    mut drops: MessageReader<bevy::window::FileDragAndDrop>,
+    budget: Res<ImportBudget>,
+    registry: Res<EngineRegistry>,
+    q_window: Query<&Window, With<bevy::window::PrimaryWindow>>,
+    universe: Res<Universe>,
+    mut pending: ResMut<PendingImport>,
+    mut hints: ResMut<PatternHints>,
+    mut view: ResMut<SimulationView>,
+    mut stats: ResMut<StatsBoard>,
+    mut errors: MessageWriter<LifeError>,
+) {
+    let dropped: Vec<std::path::PathBuf> = drops
+        .read()
+        .filter_map(|drop| match drop {
+            bevy::window::FileDragAndDrop::DroppedFile { path_buf, .. } => Some(path_buf.clone()),
+            _ => None,
+        })
+        .collect();
+    for path_buf in &dropped {
+        // Workspaces, rule bundles and macrocell files have their own loaders
+        if crate::simulation::workspace::is_workspace(path_buf)
+            || crate::simulation::bundle::opens(&dropped, path_buf)
+            || crate::simulation::macrocell::is_macrocell(path_buf)
+        {
+            continue;
+        }
+        let name = path_buf
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned();
+        let loaded = std::fs::read_to_string(path_buf)
+            .map_err(|err| LifeError::io(path_buf, err))
+            .and_then(|text| {
+                budget.decode_in(
+                    &text,
+                    PatternFormat::of(path_buf),
+                    &name,
+                    &registry,
+                    &universe,
+                )
+            })
+            .and_then(|pattern| Ok((CameraScript::parse(&pattern.comments)?, pattern)));
+        let (mut new_script, pattern) = match loaded {
+            Ok(loaded) => loaded,
+            Err(err) => {
+                errors.write(err);
+                continue;
+            }
+        };
+
+        // Rows run down in the file and up on screen
+        let cells: Vec<_> = pattern
+            .cells
+            .into_iter()
+            .map(|p| I64Vec2::new(p.x, -p.y))
+            .collect();
+        let offset = centering_offset(&cells, crate::simulation::seed::view_center(&view));
+        let cells: Vec<_> = cells.into_iter().map(|pos| pos + offset).collect();
+        if let Ok(window) = q_window.single()
+            && let Some(fitted) = fit_view(&cells, window)
+        {
+            *view = fitted;
+        }
+
+        *hints = PatternHints {
+            rule: pattern.rule.filter(|&rule| rule != universe.rule()),
+            stop: new_script.stop.filter(|&stop| stop > 0),
+        };
+        match describe_hints(&hints) {
+            Some(hint) => stats.insert(
+                "Pattern Hints",
+                format!("{hint}: Enter applies, Esc ignores"),
+            ),
+            None => stats.remove("Pattern Hints"),
+        }
+        info!(
+            "Opened {} with {} camera waypoints",
+            path_buf.display(),
+            new_script.waypoints.len()
+        );
+        new_script.translate(offset);
+        pending.hold(name, cells, Some(new_script));
+    }
+}
+
+/// How far `cells` have to move for the middle of their bounding box to be at `center`.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn centering_offset(cells: &[I64Vec2], center: I64Vec2) -> I64Vec2 {
+    let Some(min) = cells.iter().copied().reduce(I64Vec2::min) else {
+        return I64Vec2::ZERO;
+    };
+    let max = cells.iter().copied().reduce(I64Vec2::max).unwrap_or(min);
+    center - (min + max) / 2
+}
+
+/// A view centered on `cells` with all of them in the window.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn fit_view(cells: &[I64Vec2], window: &Window) -> Option<SimulationView> {
+    let min = cells.iter().copied().reduce(I64Vec2::min)?;
+    let max = cells.iter().copied().reduce(I64Vec2::max)?;
+    let size = (max - min).as_dvec2() + DVec2::ONE;
+    let fit = (window.width() as f64 / size.x).min(window.height() as f64 / size.y) * 0.9;
+    Some(SimulationView {
+        center: min.as_dvec2() + size / 2.0,
+        zoom: fit.clamp(0.01, 500.0),
+    })
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn describe_hints(hints: &PatternHints) -> Option<String> {
+    match (hints.rule, hints.stop) {
+        (Some(rule), Some(stop)) => Some(format!("rule {rule}, run to generation {stop}")),
+        (Some(rule), None) => Some(format!("rule {rule}")),
+        (None, Some(stop)) => Some(format!("run to generation {stop}")),
+        (None, None) => None,
+    }
+}
+
+// Enter: apply the dropped pattern's suggested rule and generations, Esc: ignore them
+#[cfg(not(target_arch = "wasm32"))]
+fn confirm_hints(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut hints: ResMut<PatternHints>,
+    mut universe: ResMut<Universe>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    if hints.rule.is_none() && hints.stop.is_none() {
+        return;
+    }
+    if keys.just_pressed(KeyCode::Enter) {
+        if let Some(rule) = hints.rule {
+            universe.set_rule(rule);
+        }
+        if let Some(stop) = hints.stop {
+            let generation = universe.generation();
+            universe.queue_steps(stop.saturating_sub(generation));
+        }
+    } else if !keys.just_pressed(KeyCode::Escape) {
+        return;
+    }
+    *hints = PatternHints::default();
+    stats.remove("Pattern Hints");
+}
@@ -0,0 +1,192 @@
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::simulation::SimulationInput;
+use crate::simulation::camera_script::CameraScript;
+use crate::simulation::engine::CellRegion;
+use crate::simulation::graphics::{
+    LayerCanvas, LayerDesc, LayerSpawner, LayerViewport, PixelLayer,
+};
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::universe::Universe;
+use crate::simulation::view::{MouseWorldPosition, SimulationView};
+
+const GHOST: u8 = 1;
+const FOOTPRINT: u8 = 2;
+
+/// A pattern opened from a file doesn't go into the universe right away: it follows the
+/// mouse as a ghost, centered on it, until a click puts it down there in place of what
+/// the universe holds (see [`MouseDrawPlugin`]). Escape drops it.
+///
+/// [`MouseDrawPlugin`]: crate::simulation::draw::MouseDrawPlugin
+pub struct ImportGhostPlugin;
+
+impl Plugin for ImportGhostPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PendingImport>()
+            .add_systems(Startup, setup_import_layer)
+            .add_systems(
+                Update,
+                (
+                    import_input.in_set(SimulationInput),
+                    show_import,
+                    render_import,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// The opened pattern waiting for a click, if any.
+#[derive(Resource, Default)]
+pub struct PendingImport {
+    pattern: Option<PendingPattern>,
+}
+
+struct PendingPattern {
+    name: String,
+    // Where the pattern was opened; placing moves all of it by one offset
+    cells: Vec<I64Vec2>,
+    bounds: CellRegion,
+    script: Option<CameraScript>,
+}
+
+impl PendingPattern {
+    /// How far the pattern moves to be centered on `pos`.
+    fn offset_to(&self, pos: I64Vec2) -> I64Vec2 {
+        pos - (self.bounds.min + self.bounds.max).div_euclid(I64Vec2::splat(2))
+    }
+}
+
+impl PendingImport {
+    /// Holds `cells` until a click puts them down, replacing a pattern still waiting.
+    /// `script` is the pattern's camera script, moved along and started once it is down.
+    pub fn hold(&mut self, name: String, cells: Vec<I64Vec2>, script: Option<CameraScript>) {
+        let bounds = cells
+            .iter()
+            .copied()
+            .reduce(I64Vec2::min)
+            .zip(cells.iter().copied().reduce(I64Vec2::max))
+            .map_or(
+                CellRegion {
+                    min: I64Vec2::ZERO,
+                    max: I64Vec2::ZERO,
+                },
+                |(min, max)| CellRegion::from_corners(min, max),
+            );
+        self.pattern = Some(PendingPattern {
+            name,
+            cells,
+            bounds,
+            script,
+        });
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.pattern.is_some()
+    }
+
+    /// Puts the waiting pattern down centered on `pos` in place of the universe's cells,
+    /// returning its name. `None` if nothing was waiting.
+    pub fn place(
+        &mut self,
+        pos: I64Vec2,
+        universe: &mut Universe,
+        script: &mut CameraScript,
+    ) -> Option<String> {
+        let pattern = self.pattern.take()?;
+        let offset = pattern.offset_to(pos);
+        universe.import(
+            pattern
+                .cells
+                .into_iter()
+                .map(|cell| cell + offset)
+                .collect(),
+        );
+        if let Some(mut placed) = pattern.script {
+            placed.translate(offset);
+            *script = placed;
+        }
+        info!("Placed {} at {pos}", pattern.name);
+        Some(pattern.name)
+    }
+}
+
+#[derive(Component)]
+struct ImportLayer;
+
+fn setup_import_layer(mut layers: LayerSpawner) {
+    layers
+        .spawn(
+            LayerDesc::new("import", 0.13)
+                .palette([
+                    Vec4::ZERO,
+                    Vec4::new(1.0, 1.0, 1.0, 0.5),
+                    Vec4::new(1.0, 0.8, 0.4, 0.12),
+                ])
+                .hidden(),
+        )
+        .insert(ImportLayer);
+}
+
+// Esc: drop the waiting pattern
+fn import_input(keys: Res<ButtonInput<KeyCode>>, mut pending: ResMut<PendingImport>) {
+    if pending.is_pending() && keys.just_pressed(KeyCode::Escape) {
+        info!("Dropped the opened pattern");
+        pending.pattern = None;
+    }
+}
+
+fn show_import(pending: Res<PendingImport>, mut stats: ResMut<StatsBoard>) {
+    if !pending.is_changed() {
+        return;
+    }
+    match &pending.pattern {
+        Some(pattern) => stats.insert(
+            "Import",
+            format!(
+                "{}, {} cells: click to place, Esc drops it",
+                pattern.name,
+                pattern.cells.len()
+            ),
+        ),
+        None => stats.remove("Import"),
+    }
+}
+
+fn render_import(
+    pending: Res<PendingImport>,
+    mouse_res: Res<MouseWorldPosition>,
+    view: Res<SimulationView>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut q_layer: Query<(&mut PixelLayer, &mut LayerCanvas), With<ImportLayer>>,
+) {
+    let Ok((mut layer, mut canvas)) = q_layer.single_mut() else {
+        return;
+    };
+    let target = pending.pattern.as_ref().zip(mouse_res.grid_pos);
+    if layer.visible != target.is_some() {
+        layer.visible = target.is_some();
+    }
+    let Some((pattern, pos)) = target else {
+        return;
+    };
+    let Ok(window) = q_window.single() else {
+        return;
+    };
+    let Some(viewport) = LayerViewport::new(window, &view, &layer) else {
+        return;
+    };
+    let buffer = canvas.buffer(&viewport);
+    buffer.fill(0);
+
+    let offset = pattern.offset_to(pos);
+    let min = pattern.bounds.min + offset;
+    let size = pattern.bounds.size();
+    viewport.draw_rect(buffer, min.x, min.y, size.x, size.y, FOOTPRINT);
+    for &cell in &pattern.cells {
+        let cell = cell + offset;
+        viewport.draw_cell(buffer, cell.x, cell.y, GHOST);
+    }
+}
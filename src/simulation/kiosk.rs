@@ -0,0 +1,205 @@
+use std::collections::VecDeque;
+use std::f64::consts::TAU;
+use std::time::Duration;
+
+use bevy::math::DVec2;
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use rand::Rng;
+
+use crate::simulation::accessibility::Accessibility;
+use crate::simulation::batch::arg_value;
+use crate::simulation::period::PeriodDetector;
+use crate::simulation::presentation::Presentation;
+use crate::simulation::seed::{SimulationRng, SoupNoise};
+use crate::simulation::theme::Theme;
+use crate::simulation::universe::Universe;
+use crate::simulation::view::SimulationView;
+
+/// Generations whose populations are compared to call a soup settled.
+const SETTLE_WINDOW: usize = 500;
+/// Population swing (a fraction of the population) still counted as settled; escaping
+/// gliders and small oscillators stay below it.
+const SETTLE_TOLERANCE: f64 = 0.02;
+/// Soups still going after this many generations are moved on from anyway.
+const MAX_GENERATIONS: u64 = 30_000;
+/// How long the camera takes to glide to the next soup.
+const DRIFT_TIME: Duration = Duration::from_secs(4);
+/// Soup sides between one soup and the next, so the camera has somewhere to drift.
+const DRIFT_DISTANCE: f64 = 4.0;
+
+/// `--kiosk` runs the simulation unattended, for displays and as a screensaver: soups
+/// are seeded (with the current soup noise), run until they settle, linger for
+/// `--kiosk-linger SECONDS` (10 by default) and are replaced by a fresh one, the camera
+/// gliding over to it. `--kiosk-soup SIZE` and `--kiosk-density D` shape the soups
+/// (96 and 0.4). Starts in presentation mode. Desktop only.
+pub struct KioskPlugin;
+
+impl Plugin for KioskPlugin {
+    fn build(&self, app: &mut App) {
+        if !std::env::args().any(|arg| arg == "--kiosk") {
+            return;
+        }
+        let linger = parsed_arg("--kiosk-linger", 10.0_f64).max(0.0);
+        app.insert_resource(Kiosk {
+            linger: Duration::from_secs_f64(linger),
+            soup_size: parsed_arg("--kiosk-soup", 96_i64).clamp(8, 4096),
+            density: parsed_arg("--kiosk-density", 0.4_f64).clamp(0.0, 1.0),
+            phase: KioskPhase::Starting,
+            populations: VecDeque::new(),
+            last_generation: None,
+            drift: None,
+        })
+        .add_systems(Startup, start_presenting)
+        .add_systems(Update, (run_kiosk, drift_camera).chain());
+    }
+}
+
+fn parsed_arg<T: std::str::FromStr + Copy + std::fmt::Display>(name: &str, default: T) -> T {
+    let Some(value) = arg_value(name) else {
+        return default;
+    };
+    value.parse().unwrap_or_else(|_| {
+        warn!("Ignoring invalid {name} value {value:?}, using {default}");
+        default
+    })
+}
+
+#[derive(Resource)]
+pub struct Kiosk {
+    pub linger: Duration,
+    pub soup_size: i64,
+    pub density: f64,
+    phase: KioskPhase,
+    // Population of each generation seen since the soup was seeded, newest last
+    populations: VecDeque<u64>,
+    last_generation: Option<u64>,
+    drift: Option<Drift>,
+}
+
+enum KioskPhase {
+    Starting,
+    Running,
+    Lingering { until: Instant },
+}
+
+/// A camera move from one view to another.
+struct Drift {
+    from: SimulationView,
+    to: SimulationView,
+    started: Instant,
+}
+
+impl Kiosk {
+    /// Whether the soup stopped changing: it repeats, died out, keeps its population
+    /// within [`SETTLE_TOLERANCE`] over [`SETTLE_WINDOW`] generations or ran too long.
+    fn settled(&self, generation: u64, population: u64, repeating: bool) -> bool {
+        // The period detector only notices the new soup once it saw it step
+        if self.populations.len() < 2 {
+            return false;
+        }
+        if repeating || population == 0 || generation >= MAX_GENERATIONS {
+            return true;
+        }
+        if self.populations.len() < SETTLE_WINDOW {
+            return false;
+        }
+        let min = self.populations.iter().copied().min().unwrap_or(0);
+        let max = self.populations.iter().copied().max().unwrap_or(0);
+        (max - min) as f64 <= max as f64 * SETTLE_TOLERANCE
+    }
+}
+
+fn start_presenting(mut presentation: ResMut<Presentation>, mut theme: ResMut<Theme>) {
+    presentation.set_active(true, &mut theme);
+}
+
+fn run_kiosk(
+    mut kiosk: ResMut<Kiosk>,
+    mut universe: ResMut<Universe>,
+    mut rng: ResMut<SimulationRng>,
+    noise: Res<SoupNoise>,
+    detector: Res<PeriodDetector>,
+    view: Res<SimulationView>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+) {
+    let kiosk = &mut *kiosk;
+    match kiosk.phase {
+        KioskPhase::Running => {
+            let generation = universe.generation();
+            if kiosk.last_generation == Some(generation) {
+                return;
+            }
+            kiosk.last_generation = Some(generation);
+            let population = universe.population();
+            kiosk.populations.push_back(population);
+            if kiosk.populations.len() > SETTLE_WINDOW {
+                kiosk.populations.pop_front();
+            }
+            if kiosk.settled(generation, population, detector.detected.is_some()) {
+                info!("Kiosk: soup settled at generation {generation} with {population} cells");
+                kiosk.phase = KioskPhase::Lingering {
+                    until: Instant::now() + kiosk.linger,
+                };
+            }
+            return;
+        }
+        KioskPhase::Lingering { until } if Instant::now() < until => return,
+        KioskPhase::Starting | KioskPhase::Lingering { .. } => {}
+    }
+
+    // The next soup goes a few soup sides away in a random direction
+    let from = kiosk.drift.as_ref().map_or(*view, |drift| drift.to);
+    let center = if matches!(kiosk.phase, KioskPhase::Starting) {
+        from.center
+    } else {
+        let angle = rng.rng().random_range(0.0..TAU);
+        from.center + DVec2::from_angle(angle) * kiosk.soup_size as f64 * DRIFT_DISTANCE
+    };
+    let cell = center.floor().as_i64vec2();
+    let cells = rng.noise_soup(cell, kiosk.soup_size, kiosk.density, *noise);
+    universe.clear();
+    universe.add_cells(cells);
+    universe.paused = false;
+
+    // Room for the soup to spread to three times its size
+    let zoom = q_window.single().map_or(view.zoom, |window| {
+        let fit = window.width().min(window.height()) as f64 / (kiosk.soup_size * 3) as f64;
+        fit.clamp(0.01, 500.0)
+    });
+    kiosk.drift = Some(Drift {
+        from: *view,
+        to: SimulationView {
+            center: cell.as_dvec2() + DVec2::splat(0.5),
+            zoom,
+        },
+        started: Instant::now(),
+    });
+    kiosk.populations.clear();
+    kiosk.last_generation = None;
+    kiosk.phase = KioskPhase::Running;
+}
+
+fn drift_camera(
+    accessibility: Res<Accessibility>,
+    mut kiosk: ResMut<Kiosk>,
+    mut view: ResMut<SimulationView>,
+) {
+    let Some(drift) = &kiosk.drift else {
+        return;
+    };
+    // With reduced motion the camera cuts straight to the new soup
+    let t = if accessibility.reduced_motion {
+        1.0
+    } else {
+        (drift.started.elapsed().as_secs_f64() / DRIFT_TIME.as_secs_f64()).min(1.0)
+    };
+    let eased = t * t * (3.0 - 2.0 * t);
+    view.center = drift.from.center.lerp(drift.to.center, eased);
+    // Zooming geometrically looks even, whatever the scale
+    view.zoom = drift.from.zoom * (drift.to.zoom / drift.from.zoom).powf(eased);
+    if t >= 1.0 {
+        kiosk.drift = None;
+    }
+}
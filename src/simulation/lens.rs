@@ -0,0 +1,183 @@
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::simulation::SimulationInput;
+use crate::simulation::engine::{CellRegion, LifeEngine, PasteMode, create_rule_engine};
+use crate::simulation::graphics::{
+    LayerCanvas, LayerDesc, LayerSpawner, LayerViewport, PixelLayer,
+};
+use crate::simulation::selection::Selection;
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::universe::Universe;
+use crate::simulation::view::SimulationView;
+
+const ALIVE: u8 = 1;
+const BACKDROP: u8 = 2;
+
+/// N opens a lens on the selection: its cells are copied into a scratch engine that runs
+/// ahead [`Lens::generations`] generations and is drawn over the region, while the real
+/// universe carries on untouched. Ctrl+N writes the preview back as one undoable edit;
+/// N again throws it away.
+pub struct LensPlugin;
+
+impl Plugin for LensPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Lens>()
+            .add_systems(Startup, setup_lens_layer)
+            .add_systems(
+                Update,
+                (lens_input.in_set(SimulationInput), step_lens, render_lens).chain(),
+            );
+    }
+}
+
+#[derive(Resource)]
+pub struct Lens {
+    /// How far ahead the preview runs.
+    pub generations: u64,
+    /// Generations stepped per frame until the preview gets there.
+    pub speed: u64,
+    preview: Option<Preview>,
+}
+
+impl Default for Lens {
+    fn default() -> Self {
+        Self {
+            generations: 100,
+            speed: 1,
+            preview: None,
+        }
+    }
+}
+
+impl Lens {
+    pub fn is_open(&self) -> bool {
+        self.preview.is_some()
+    }
+}
+
+struct Preview {
+    region: CellRegion,
+    engine: Box<dyn LifeEngine>,
+}
+
+#[derive(Component)]
+struct LensLayer;
+
+fn setup_lens_layer(mut layers: LayerSpawner) {
+    layers
+        .spawn(
+            LayerDesc::new("lens", 0.12)
+                .palette([
+                    Vec4::ZERO,
+                    Vec4::new(0.3, 0.9, 1.0, 1.0),
+                    Vec4::new(0.0, 0.0, 0.0, 0.75),
+                ])
+                .hidden(),
+        )
+        .insert(LensLayer);
+}
+
+// N: open/close the lens on the selection, Ctrl+N: commit the preview
+fn lens_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    selection: Res<Selection>,
+    mut lens: ResMut<Lens>,
+    mut universe: ResMut<Universe>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    if !keys.just_pressed(KeyCode::KeyN) {
+        return;
+    }
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+
+    if let Some(preview) = lens.preview.take() {
+        if ctrl {
+            let cells = preview.engine.export();
+            let cells = cells
+                .into_iter()
+                .filter(|&pos| preview.region.contains(pos))
+                .collect();
+            universe.paste(preview.region, cells, PasteMode::Copy);
+            info!(
+                "Committed the lens preview ({} generations ahead)",
+                preview.engine.generation()
+            );
+        }
+        stats.remove("Lens");
+        return;
+    }
+    if ctrl {
+        return;
+    }
+
+    let Some(region) = selection.region else {
+        stats.insert("Lens", "select a region first (Shift + drag)");
+        return;
+    };
+    // A ring of frozen cells around the region keeps its edges as they are in the universe
+    let surrounding = CellRegion {
+        min: region.min - I64Vec2::ONE,
+        max: region.max + I64Vec2::ONE,
+    };
+    let mut engine = create_rule_engine(universe.rule());
+    engine.set_step_region(Some(region));
+    engine.import(&universe.cells_in(surrounding));
+    lens.preview = Some(Preview { region, engine });
+}
+
+fn step_lens(mut lens: ResMut<Lens>, mut stats: ResMut<StatsBoard>) {
+    let (generations, speed) = (lens.generations, lens.speed);
+    let Some(preview) = lens.preview.as_mut() else {
+        return;
+    };
+    let generation = preview.engine.generation();
+    if generation < generations {
+        preview
+            .engine
+            .step(speed.max(1).min(generations - generation));
+    }
+
+    stats.insert(
+        "Lens",
+        format!(
+            "{}/{generations} generations ahead, Ctrl+N commits, N discards",
+            preview.engine.generation()
+        ),
+    );
+}
+
+fn render_lens(
+    lens: Res<Lens>,
+    view: Res<SimulationView>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut q_layer: Query<(&mut PixelLayer, &mut LayerCanvas), With<LensLayer>>,
+) {
+    let Ok((mut layer, mut canvas)) = q_layer.single_mut() else {
+        return;
+    };
+    if layer.visible != lens.is_open() {
+        layer.visible = lens.is_open();
+    }
+    let Some(preview) = &lens.preview else {
+        return;
+    };
+    let Ok(window) = q_window.single() else {
+        return;
+    };
+    let Some(viewport) = LayerViewport::new(window, &view, &layer) else {
+        return;
+    };
+    let buffer = canvas.buffer(&viewport);
+    buffer.fill(0);
+
+    // Hide the real cells under the region, then draw the preview on top
+    let (min, size) = (preview.region.min, preview.region.size());
+    viewport.draw_rect(buffer, min.x, min.y, size.x, size.y, BACKDROP);
+    for pos in preview.engine.export() {
+        if preview.region.contains(pos) {
+            viewport.draw_cell(buffer, pos.x, pos.y, ALIVE);
+        }
+    }
+}
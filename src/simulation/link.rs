@@ -0,0 +1,276 @@
+use bevy::math::{DVec2, I64Vec2};
+use bevy::prelude::*;
+
+use crate::simulation::SimulationInput;
+use crate::simulation::engine::EngineRegistry;
+use crate::simulation::error::LifeError;
+use crate::simulation::import::{ImportBudget, PatternFormat};
+use crate::simulation::rle;
+use crate::simulation::rule_presets::{PRESETS, PresetSeed};
+use crate::simulation::rules::LifeRule;
+use crate::simulation::seed::SimulationRng;
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::universe::{PatternLoaded, Universe};
+use crate::simulation::view::SimulationView;
+
+/// Longest RLE put into a link; browsers and chat apps cut off long URLs.
+const MAX_RLE_LEN: usize = 4000;
+
+/// Shareable links for the web build. Ctrl+L writes the scene into the page's URL
+/// fragment: the rule, the camera and the cells as RLE, or, when they are too many and
+/// came from a rule preset, the preset's number instead. Opening such a link starts the
+/// app with that scene, e.g. `#rule=B3/S23&view=0,0,50&at=-1,1&rle=bo$2o$obo!`.
+pub struct LinkPlugin;
+
+impl Plugin for LinkPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LastPreset>()
+            // A link's scene goes over the workspace the browser restores
+            .add_systems(
+                PostStartup,
+                open_link.after(crate::simulation::workspace::load_startup_workspace),
+            )
+            .add_systems(
+                Update,
+                (track_preset, write_link.in_set(SimulationInput)).chain(),
+            );
+    }
+}
+
+/// What a link puts on screen.
+#[derive(Clone, Debug, Default)]
+pub struct Scene {
+    pub rule: Option<LifeRule>,
+    pub view: Option<SimulationView>,
+    pub pattern: Option<ScenePattern>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScenePattern {
+    /// Cells in RLE, rows running down from the top-left cell `at`.
+    Rle { at: I64Vec2, rle: String },
+    /// The seed of an entry in [`PRESETS`], placed at the view center.
+    Preset(usize),
+}
+
+impl Scene {
+    /// Reads a URL fragment, with or without its `#`. Unknown fields are skipped so
+    /// newer links still open.
+    pub fn parse(fragment: &str) -> Result<Self, LifeError> {
+        let invalid = |what: &str| LifeError::InvalidPattern(format!("damaged link: {what}"));
+        let numbers = |value: &str| -> Option<Vec<f64>> {
+            value.split(',').map(|n| n.parse().ok()).collect()
+        };
+
+        let mut scene = Scene::default();
+        let mut at = None;
+        for field in fragment.trim_start_matches('#').split('&') {
+            let Some((key, value)) = field.split_once('=') else {
+                continue;
+            };
+            match key {
+                "rule" => scene.rule = Some(LifeRule::parse(value)?),
+                "view" => match numbers(value).as_deref() {
+                    Some(&[x, y, zoom]) if zoom > 0.0 => {
+                        scene.view = Some(SimulationView {
+                            center: DVec2::new(x, y),
+                            zoom,
+                        });
+                    }
+                    _ => return Err(invalid("view")),
+                },
+                "at" => match numbers(value).as_deref() {
+                    Some(&[x, y]) => at = Some(I64Vec2::new(x as i64, y as i64)),
+                    _ => return Err(invalid("at")),
+                },
+                "rle" => {
+                    scene.pattern = Some(ScenePattern::Rle {
+                        at: I64Vec2::ZERO,
+                        rle: value.to_string(),
+                    });
+                }
+                "preset" => match value.parse() {
+                    Ok(index) if index < PRESETS.len() => {
+                        scene.pattern = Some(ScenePattern::Preset(index));
+                    }
+                    _ => return Err(invalid("preset")),
+                },
+                _ => {}
+            }
+        }
+        if let Some(ScenePattern::Rle { at: anchor, .. }) = &mut scene.pattern {
+            *anchor = at.unwrap_or_default();
+        }
+        Ok(scene)
+    }
+
+    /// The URL fragment, without the `#`.
+    pub fn fragment(&self) -> String {
+        let mut fields = Vec::new();
+        if let Some(rule) = self.rule {
+            fields.push(format!("rule={rule}"));
+        }
+        if let Some(view) = self.view {
+            fields.push(format!(
+                "view={:.1},{:.1},{}",
+                view.center.x, view.center.y, view.zoom
+            ));
+        }
+        match &self.pattern {
+            Some(ScenePattern::Rle { at, rle }) => {
+                fields.push(format!("at={},{}", at.x, at.y));
+                fields.push(format!("rle={rle}"));
+            }
+            Some(ScenePattern::Preset(index)) => fields.push(format!("preset={index}")),
+            None => {}
+        }
+        fields.join("&")
+    }
+}
+
+/// The RLE runs of `cells` on one line, without header, and the top-left cell.
+fn rle_body(cells: &[I64Vec2], rule: LifeRule) -> Option<(I64Vec2, String)> {
+    let min_x = cells.iter().map(|pos| pos.x).min()?;
+    let max_y = cells.iter().map(|pos| pos.y).max()?;
+    // Rows run down in the file and up on screen
+    let flipped: Vec<_> = cells
+        .iter()
+        .map(|pos| I64Vec2::new(pos.x, -pos.y))
+        .collect();
+    let body = rle::encode(&flipped, rule, "")
+        .lines()
+        .skip(1)
+        .collect::<String>();
+    Some((I64Vec2::new(min_x, max_y), body))
+}
+
+/// The preset most recently applied, while its cells are still the ones on screen.
+#[derive(Resource, Default)]
+struct LastPreset(Option<usize>);
+
+fn track_preset(mut loaded: MessageReader<PatternLoaded>, mut last: ResMut<LastPreset>) {
+    if let Some(pattern) = loaded.read().last() {
+        last.0 = PRESETS
+            .iter()
+            .position(|preset| preset.name == pattern.name);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn open_link(
+    budget: Res<ImportBudget>,
+    registry: Res<EngineRegistry>,
+    mut universe: ResMut<Universe>,
+    mut view: ResMut<SimulationView>,
+    mut rng: ResMut<SimulationRng>,
+    mut patterns_loaded: MessageWriter<PatternLoaded>,
+    mut errors: MessageWriter<LifeError>,
+) {
+    let Some(fragment) = read_fragment().filter(|fragment| !fragment.is_empty()) else {
+        return;
+    };
+    let scene = match Scene::parse(&fragment) {
+        Ok(scene) => scene,
+        Err(err) => {
+            errors.write(err);
+            return;
+        }
+    };
+
+    if let Some(rule) = scene.rule {
+        universe.set_rule(rule);
+    }
+    if let Some(shown) = scene.view {
+        *view = shown;
+    }
+    let center = crate::simulation::seed::view_center(&view);
+    let (cells, name) = match scene.pattern {
+        // The link may come from anywhere; measure it before building its cells
+        Some(ScenePattern::Rle { at, rle }) => {
+            match budget.decode_in(&rle, PatternFormat::Rle, "link", &registry, &universe) {
+                Ok(pattern) => {
+                    let cells = pattern
+                        .cells
+                        .into_iter()
+                        .map(|pos| at + I64Vec2::new(pos.x, -pos.y))
+                        .collect();
+                    (cells, "link".to_string())
+                }
+                Err(err) => {
+                    errors.write(err);
+                    return;
+                }
+            }
+        }
+        Some(ScenePattern::Preset(index)) => {
+            let preset = &PRESETS[index];
+            let cells = match preset.seed {
+                PresetSeed::Pattern(cells) => cells
+                    .iter()
+                    .map(|&(x, y)| center + I64Vec2::new(x, y))
+                    .collect(),
+                PresetSeed::Soup { size, density } => rng.soup(center, size, density),
+            };
+            (cells, preset.name.to_string())
+        }
+        None => return,
+    };
+    info!("Opened a link with {} cells", cells.len());
+    universe.import(cells);
+    patterns_loaded.write(PatternLoaded { name });
+}
+
+// Ctrl+L: put the current scene into the URL
+fn write_link(
+    keys: Res<ButtonInput<KeyCode>>,
+    universe: Res<Universe>,
+    view: Res<SimulationView>,
+    last_preset: Res<LastPreset>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    if !keys.just_pressed(KeyCode::KeyL)
+        || !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+    {
+        return;
+    }
+
+    let cells = universe.read_engine().export();
+    let pattern = match rle_body(&cells, universe.rule()) {
+        Some((at, rle)) if rle.len() <= MAX_RLE_LEN => Some(ScenePattern::Rle { at, rle }),
+        Some((_, rle)) => match last_preset.0 {
+            Some(index) => Some(ScenePattern::Preset(index)),
+            None => {
+                stats.insert(
+                    "Link",
+                    format!("{} characters of RLE are too many to share", rle.len()),
+                );
+                return;
+            }
+        },
+        None => None,
+    };
+    let scene = Scene {
+        rule: Some(universe.rule()),
+        view: Some(*view),
+        pattern,
+    };
+    let fragment = scene.fragment();
+    write_fragment(&fragment);
+    stats.insert(
+        "Link",
+        format!("{} characters in the address bar", fragment.len()),
+    );
+}
+
+fn read_fragment() -> Option<String> {
+    web_sys::window()?.location().hash().ok()
+}
+
+fn write_fragment(fragment: &str) {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    if let Err(err) = window.location().set_hash(fragment) {
+        warn!("Couldn't update the address: {err:?}");
+    }
+}
@@ -0,0 +1,233 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use bevy::prelude::*;
+
+use crate::simulation::error::LifeError;
+use crate::simulation::settings::Settings;
+
+const ENGLISH: &str = include_str!("../../assets/locales/en.ftl");
+const GERMAN: &str = include_str!("../../assets/locales/de.ftl");
+
+/// Settings key holding the chosen language.
+const LANGUAGE_KEY: &str = "language";
+
+/// Translates the text of the stats board, menus, error toasts and the tutorial. The
+/// messages are in `assets/locales/<code>.ftl`, built into the binary; keys missing in a
+/// language fall back to English. `--lang CODE` picks (and remembers) a language,
+/// otherwise the one in the settings or the system's `LANG` is used.
+pub struct LocalePlugin;
+
+impl Plugin for LocalePlugin {
+    fn build(&self, app: &mut App) {
+        #[cfg(not(target_arch = "wasm32"))]
+        let chosen = crate::simulation::batch::arg_value("--lang");
+        #[cfg(target_arch = "wasm32")]
+        let chosen: Option<String> = None;
+
+        let language = {
+            let mut settings = app.world_mut().get_resource_mut::<Settings>();
+            match chosen.as_deref().and_then(Language::parse) {
+                Some(language) => {
+                    if let Some(settings) = settings.as_mut()
+                        && let Err(err) = settings.set(LANGUAGE_KEY, language.code())
+                    {
+                        warn!("Couldn't remember the language: {err}");
+                    }
+                    language
+                }
+                None => settings
+                    .and_then(|settings| settings.get(LANGUAGE_KEY).and_then(Language::parse))
+                    .or_else(|| {
+                        std::env::var("LANG")
+                            .ok()
+                            .as_deref()
+                            .and_then(Language::parse)
+                    })
+                    .unwrap_or_default(),
+            }
+        };
+        if let Some(code) = chosen
+            && Language::parse(&code).is_none()
+        {
+            warn!(
+                "No messages for language '{code}', using {}",
+                language.code()
+            );
+        }
+        app.insert_resource(Localization::new(language));
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+}
+
+impl Language {
+    /// Reads a language code such as `de` or a locale such as `de_DE.UTF-8`.
+    pub fn parse(text: &str) -> Option<Self> {
+        let code = text.split(['_', '-', '.']).next()?.to_ascii_lowercase();
+        match code.as_str() {
+            "en" => Some(Language::English),
+            "de" => Some(Language::German),
+            _ => None,
+        }
+    }
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::German => "de",
+        }
+    }
+
+    fn source(self) -> &'static str {
+        match self {
+            Language::English => ENGLISH,
+            Language::German => GERMAN,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct Localization {
+    language: Language,
+    messages: HashMap<&'static str, &'static str>,
+    fallback: HashMap<&'static str, &'static str>,
+}
+
+impl Localization {
+    pub fn new(language: Language) -> Self {
+        Self {
+            language,
+            messages: parse(language.source()),
+            fallback: parse(ENGLISH),
+        }
+    }
+
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    /// The message `key`, in English if this language lacks it, or the key itself.
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.messages
+            .get(key)
+            .or_else(|| self.fallback.get(key))
+            .copied()
+            .unwrap_or(key)
+    }
+
+    /// Like [`Localization::get`], with each `{ $name }` filled in from `args`.
+    pub fn format(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let mut text = self.get(key).to_string();
+        for (name, value) in args {
+            let value = value.to_string();
+            text = text
+                .replace(&format!("{{ ${name} }}"), &value)
+                .replace(&format!("{{${name}}}"), &value);
+        }
+        text
+    }
+
+    /// A label shown as is in English, like a stat name: the message
+    /// `<prefix>-<label in lowercase, words joined by dashes>`, or `label` itself.
+    pub fn label<'a>(&self, prefix: &str, label: &'a str) -> &'a str {
+        let key = format!("{prefix}-{}", slug(label));
+        match self.messages.get(key.as_str()) {
+            Some(text) => text,
+            None => label,
+        }
+    }
+
+    /// `error` as shown to the user.
+    pub fn error(&self, error: &LifeError) -> String {
+        match error {
+            LifeError::Io { path, source } => {
+                self.format("error-io", &[("path", &path.display()), ("reason", source)])
+            }
+            LifeError::InvalidRule(detail) => {
+                self.format("error-invalid-rule", &[("detail", detail)])
+            }
+            LifeError::InvalidPattern(detail) => {
+                self.format("error-invalid-pattern", &[("detail", detail)])
+            }
+            LifeError::OutOfRange { count } => self.format(
+                "error-out-of-range",
+                &[
+                    ("count", count),
+                    ("limit", &crate::simulation::engine::COORD_LIMIT),
+                ],
+            ),
+            LifeError::EngineCrashed { engine, reason } => self.format(
+                "error-engine-crashed",
+                &[("engine", engine), ("reason", reason)],
+            ),
+            LifeError::Diverged {
+                engine,
+                shadow,
+                generation,
+                cell,
+            } => self.format(
+                "error-diverged",
+                &[
+                    ("engine", engine),
+                    ("shadow", shadow),
+                    ("generation", generation),
+                    ("x", &cell.x),
+                    ("y", &cell.y),
+                ],
+            ),
+            LifeError::TooLarge {
+                name,
+                cells,
+                engine,
+                estimate,
+                budget,
+                suggestion,
+            } => self.format(
+                "error-too-large",
+                &[
+                    ("name", name),
+                    ("cells", cells),
+                    ("engine", engine),
+                    ("estimate", &(estimate / 1_000_000)),
+                    ("budget", &(budget / 1_000_000)),
+                    ("suggestion", suggestion),
+                ],
+            ),
+            LifeError::Export(detail) => self.format("error-export", &[("detail", detail)]),
+            LifeError::Upload(detail) => self.format("error-upload", &[("detail", detail)]),
+            LifeError::Remote(detail) => self.format("error-remote", &[("detail", detail)]),
+        }
+    }
+}
+
+impl Default for Localization {
+    fn default() -> Self {
+        Self::new(Language::English)
+    }
+}
+
+/// The `key = text` lines of a message file; `#` starts a comment line.
+fn parse(source: &'static str) -> HashMap<&'static str, &'static str> {
+    source
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, text)| (key.trim(), text.trim()))
+        .collect()
+}
+
+/// `Tile Uploads` as `tile-uploads`.
+fn slug(label: &str) -> String {
+    label
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
@@ -0,0 +1,250 @@
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+
+use crate::simulation::SimulationInput;
+use crate::simulation::batch::arg_value;
+use crate::simulation::engine::EngineRegistry;
+use crate::simulation::error::LifeError;
+use crate::simulation::seed::view_center;
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::universe::{PatternLoaded, Universe};
+use crate::simulation::view::SimulationView;
+
+/// Ctrl+S saves the universe as a Golly macrocell (`.mc`) file into `--save-dir DIR`
+/// (the working directory by default), and dropping an `.mc` file on the window loads
+/// it centered on the view. Only tree engines (HashLife) can read and write them, but
+/// then universes far too big for a cell list fit in a few megabytes; loading switches
+/// to HashLife first. Gzipped files (`.mc.gz`, as Golly writes them) load the same way,
+/// and saves over [`COMPRESS_OVER`] bytes are gzipped, which shrinks them about
+/// tenfold. Desktop only.
+pub struct MacrocellPlugin;
+
+/// Saves larger than this are gzipped; smaller ones stay readable in a text editor.
+pub const COMPRESS_OVER: usize = 1 << 20;
+/// Largest macrocell text a gzipped file may inflate to.
+const MAX_INFLATED_SIZE: usize = 1 << 30;
+
+impl Plugin for MacrocellPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                save_macrocell.in_set(SimulationInput),
+                load_dropped_macrocells,
+            ),
+        );
+    }
+}
+
+/// Whether `path` is a macrocell file, going by its `.mc` or `.mc.gz` extension.
+pub fn is_macrocell(path: &Path) -> bool {
+    let is_mc = |path: &Path| {
+        path.extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("mc"))
+    };
+    is_mc(path) || (is_gzip(path) && is_mc(&path.with_extension("")))
+}
+
+fn is_gzip(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("gz"))
+}
+
+/// Reads a macrocell file, inflating it if it is gzipped (told apart by its first
+/// bytes, not the name).
+pub fn read_file(path: &Path) -> Result<String, LifeError> {
+    let bytes = std::fs::read(path).map_err(|err| LifeError::io(path, err))?;
+    let bytes = if bytes.starts_with(&GZIP_MAGIC) {
+        gunzip(&bytes)?
+    } else {
+        bytes
+    };
+    String::from_utf8(bytes)
+        .map_err(|_| LifeError::InvalidPattern(format!("{} isn't text", path.display())))
+}
+
+// Ctrl+S: save the universe as a macrocell file
+fn save_macrocell(
+    keys: Res<ButtonInput<KeyCode>>,
+    universe: Res<Universe>,
+    registry: Res<EngineRegistry>,
+    mut stats: ResMut<StatsBoard>,
+    mut errors: MessageWriter<LifeError>,
+) {
+    // Ctrl+Shift+S saves the whole workspace instead
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+        || keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight])
+        || !keys.just_pressed(KeyCode::KeyS)
+    {
+        return;
+    }
+
+    let dir = arg_value("--save-dir").map_or_else(|| PathBuf::from("."), PathBuf::from);
+    let path = dir.join(format!("universe-gen{}.mc", universe.generation()));
+    match write_file(&universe, &path) {
+        Some(Ok(path)) => {
+            info!("Saved {}", path.display());
+            stats.insert("Saved", path.display());
+        }
+        Some(Err(err)) => {
+            errors.write(LifeError::io(path, err));
+        }
+        None => {
+            let hint = match registry.iter().position(|entry| entry.id == "hash-life") {
+                Some(index) => format!("; switch to HashLife (key {}) first", index + 1),
+                None => String::new(),
+            };
+            errors.write(LifeError::Export(format!(
+                "{} can't write macrocell files{hint}",
+                universe.engine_name()
+            )));
+        }
+    }
+}
+
+/// Writes the universe to `path`, or gzipped next to it with `.gz` appended if it is
+/// big, and returns the path written. `None` if the current engine has no tree to write.
+fn write_file(universe: &Universe, path: &Path) -> Option<std::io::Result<PathBuf>> {
+    let mut text = Vec::new();
+    if let Err(err) = universe.read_engine().write_macrocell(&mut text)? {
+        return Some(Err(err));
+    }
+
+    let (path, bytes) = if text.len() > COMPRESS_OVER {
+        let mut name = path.as_os_str().to_owned();
+        name.push(".gz");
+        (PathBuf::from(name), gzip(&text))
+    } else {
+        (path.to_path_buf(), text)
+    };
+    let written = std::fs::create_dir_all(path.parent()?)
+        .and_then(|()| std::fs::write(&path, bytes))
+        .map(|()| path);
+    Some(written)
+}
+
+const GZIP_MAGIC: [u8; 2] = [0x1F, 0x8B];
+
+/// A gzip member holding `data`: header, raw deflate stream, CRC-32 and length.
+fn gzip(data: &[u8]) -> Vec<u8> {
+    // No flags, no timestamp, maximum compression, unknown OS
+    let mut out = vec![0x1F, 0x8B, 8, 0, 0, 0, 0, 0, 2, 0xFF];
+    out.extend(miniz_oxide::deflate::compress_to_vec(data, 9));
+    out.extend(crc32(data).to_le_bytes());
+    out.extend((data.len() as u32).to_le_bytes());
+    out
+}
+
+/// The contents of the first gzip member in `bytes`.
+fn gunzip(bytes: &[u8]) -> Result<Vec<u8>, LifeError> {
+    const FHCRC: u8 = 0x02;
+    const FEXTRA: u8 = 0x04;
+    const FNAME: u8 = 0x08;
+    const FCOMMENT: u8 = 0x10;
+    let error = |what: &str| LifeError::InvalidPattern(format!("damaged gzip file: {what}"));
+
+    if bytes.len() < 18 || bytes[..2] != GZIP_MAGIC || bytes[2] != 8 {
+        return Err(error("not deflate compressed"));
+    }
+    let flags = bytes[3];
+    let mut at = 10;
+    if flags & FEXTRA != 0 {
+        let len = bytes
+            .get(at..at + 2)
+            .ok_or_else(|| error("truncated header"))?;
+        at += 2 + u16::from_le_bytes([len[0], len[1]]) as usize;
+    }
+    // The original name and a comment, both zero-terminated
+    for flag in [FNAME, FCOMMENT] {
+        if flags & flag != 0 {
+            let len = bytes
+                .get(at..)
+                .and_then(|rest| rest.iter().position(|&b| b == 0))
+                .ok_or_else(|| error("truncated header"))?;
+            at += len + 1;
+        }
+    }
+    if flags & FHCRC != 0 {
+        at += 2;
+    }
+
+    let trailer = bytes.len() - 8;
+    let data = bytes.get(at..trailer).ok_or_else(|| error("truncated"))?;
+    let inflated = miniz_oxide::inflate::decompress_to_vec_with_limit(data, MAX_INFLATED_SIZE)
+        .map_err(|_| error("can't inflate"))?;
+    let crc = u32::from_le_bytes(bytes[trailer..trailer + 4].try_into().expect("4 bytes"));
+    if crc != crc32(&inflated) {
+        return Err(error("checksum mismatch"));
+    }
+    Ok(inflated)
+}
+
+/// The CRC-32 gzip checks contents with (IEEE polynomial, reflected).
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+// Dropping an .mc or .mc.gz file on the window loads it into HashLife, centered on the view
+fn load_dropped_macrocells(
+    mut drops: MessageReader<bevy::window::FileDragAndDrop>,
+    registry: Res<EngineRegistry>,
+    view: Res<SimulationView>,
+    mut universe: ResMut<Universe>,
+    mut errors: MessageWriter<LifeError>,
+    mut patterns_loaded: MessageWriter<PatternLoaded>,
+) {
+    for drop in drops.read() {
+        let bevy::window::FileDragAndDrop::DroppedFile { path_buf, .. } = drop else {
+            continue;
+        };
+        if !is_macrocell(path_buf) {
+            continue;
+        }
+        let text = match read_file(path_buf) {
+            Ok(text) => text,
+            Err(err) => {
+                errors.write(err);
+                continue;
+            }
+        };
+
+        if universe.read_engine().inspect_tree(&[]).is_none()
+            && let Some(entry) = registry.iter().find(|entry| entry.id == "hash-life")
+        {
+            universe.switch_engine(entry);
+        }
+        match universe.load_macrocell(&text, view_center(&view)) {
+            Some(Ok(())) => {
+                info!(
+                    "Loaded {} at generation {}",
+                    path_buf.display(),
+                    universe.generation()
+                );
+                patterns_loaded.write(PatternLoaded {
+                    name: path_buf
+                        .file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .into_owned(),
+                });
+            }
+            Some(Err(err)) => {
+                errors.write(err);
+            }
+            None => {
+                errors.write(LifeError::InvalidPattern(format!(
+                    "{} can't load macrocell files, and this build has no HashLife",
+                    universe.engine_name()
+                )));
+            }
+        }
+    }
+}
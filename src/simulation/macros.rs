@@ -0,0 +1,109 @@
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+
+use crate::simulation::SimulationInput;
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::universe::{Edit, Universe};
+use crate::simulation::view::MouseWorldPosition;
+
+/// F1 starts recording edits (drawing, fills, walls, pastes and stamps in whatever
+/// rotation and phase) into a macro, relative to the cell under the mouse; F1 again
+/// keeps it. F2 replays the current macro at the mouse, Shift+F2 picks the next one.
+/// Macros are saved with the workspace.
+pub struct MacroPlugin;
+
+impl Plugin for MacroPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Macros>()
+            .add_systems(Update, macro_input.in_set(SimulationInput));
+    }
+}
+
+/// A named sequence of edits, with cells relative to where recording started.
+#[derive(Clone, Debug)]
+pub struct Macro {
+    pub name: String,
+    pub edits: Vec<Edit>,
+}
+
+#[derive(Resource, Default)]
+pub struct Macros {
+    pub list: Vec<Macro>,
+    /// Index into `list` of the macro F2 replays.
+    pub current: usize,
+    // Cell under the mouse when recording started
+    anchor: Option<I64Vec2>,
+}
+
+impl Macros {
+    /// A name no macro has yet.
+    fn next_name(&self) -> String {
+        (1..)
+            .map(|n| format!("macro{n}"))
+            .find(|name| self.list.iter().all(|m| &m.name != name))
+            .unwrap_or_default()
+    }
+
+    fn describe(&self) -> Option<String> {
+        if self.anchor.is_some() {
+            return Some("recording (F1: stop)".to_string());
+        }
+        let current = self.list.get(self.current)?;
+        Some(format!(
+            "{} ({} edits, {}/{}; F2: replay, Shift+F2: next)",
+            current.name,
+            current.edits.len(),
+            self.current + 1,
+            self.list.len()
+        ))
+    }
+}
+
+// F1: start or stop recording, F2: replay at the mouse, Shift+F2: next macro
+fn macro_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<MouseWorldPosition>,
+    mut universe: ResMut<Universe>,
+    mut macros: ResMut<Macros>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if keys.just_pressed(KeyCode::F1) {
+        if let Some(anchor) = macros.anchor.take() {
+            let edits: Vec<Edit> = universe
+                .stop_recording()
+                .into_iter()
+                .map(|edit| edit.translated(-anchor))
+                .collect();
+            if edits.is_empty() {
+                info!("Nothing was recorded");
+            } else {
+                let name = macros.next_name();
+                info!("Recorded {name} ({} edits)", edits.len());
+                macros.list.push(Macro { name, edits });
+                macros.current = macros.list.len() - 1;
+            }
+        } else if let Some(pos) = mouse.grid_pos {
+            universe.start_recording();
+            macros.anchor = Some(pos);
+        }
+    }
+    if keys.just_pressed(KeyCode::F2) && macros.anchor.is_none() && !macros.list.is_empty() {
+        if shift {
+            macros.current = (macros.current + 1) % macros.list.len();
+        } else if let Some(pos) = mouse.grid_pos
+            && let Some(recorded) = macros.list.get(macros.current)
+        {
+            for edit in recorded.edits.iter().cloned() {
+                universe.apply(edit.translated(pos));
+            }
+        }
+    }
+
+    if macros.is_changed() {
+        match macros.describe() {
+            Some(text) => stats.insert("Macro", text),
+            None => stats.remove("Macro"),
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::universe::Universe;
+
+pub struct MetricsPlugin;
+
+impl Plugin for MetricsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PopulationHistory>()
+            .init_resource::<RunMetrics>()
+            .add_systems(Update, sample_population);
+    }
+}
+
+/// How often the population is sampled into the history.
+const SAMPLE_INTERVAL_SECS: f32 = 0.25;
+
+/// Latest whole-run counters, refreshed every sample interval.
+/// This is what external exporters (e.g. the metrics endpoint) read.
+#[derive(Resource, Default, Clone, Copy, Debug)]
+pub struct RunMetrics {
+    pub generation: u64,
+    pub population: u64,
+    pub steps_per_sec: f64,
+    pub memory_bytes: usize,
+}
+
+/// Fixed-size ring buffer of recent population samples, oldest first.
+#[derive(Resource)]
+pub struct PopulationHistory {
+    samples: VecDeque<u64>,
+    capacity: usize,
+}
+
+impl Default for PopulationHistory {
+    fn default() -> Self {
+        Self::with_capacity(256)
+    }
+}
+
+impl PopulationHistory {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Appends a sample, evicting the oldest one once the buffer is full.
+    pub fn push(&mut self, value: u64) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(value);
+    }
+
+    /// The newest `count` samples (or fewer), oldest first.
+    pub fn latest(&self, count: usize) -> impl Iterator<Item = u64> + '_ {
+        let skip = self.samples.len().saturating_sub(count);
+        self.samples.iter().skip(skip).copied()
+    }
+
+    #[allow(unused)]
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    #[allow(unused)]
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+}
+
+fn sample_population(
+    time: Res<Time>,
+    universe: Res<Universe>,
+    mut history: ResMut<PopulationHistory>,
+    mut metrics: ResMut<RunMetrics>,
+    mut stats: ResMut<StatsBoard>,
+    mut elapsed: Local<f32>,
+) {
+    *elapsed += time.delta_secs();
+    if *elapsed < SAMPLE_INTERVAL_SECS {
+        return;
+    }
+
+    let population = universe.population();
+    let generation = universe.generation();
+    // A clear/import resets the generation; don't report a negative rate for it
+    let stepped = generation.saturating_sub(metrics.generation);
+
+    *metrics = RunMetrics {
+        generation,
+        population,
+        steps_per_sec: stepped as f64 / *elapsed as f64,
+        memory_bytes: universe.memory_bytes(),
+    };
+    *elapsed = 0.0;
+
+    history.push(metrics.population);
+    stats.insert("Generation", generation);
+    stats.insert("Steps/s", format!("{:.0}", metrics.steps_per_sec));
+    stats.insert(
+        "Memory",
+        format!("{:.1} MB", metrics.memory_bytes as f64 / (1024.0 * 1024.0)),
+    );
+}
@@ -0,0 +1,89 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+
+use bevy::prelude::*;
+
+use crate::simulation::metrics::RunMetrics;
+
+/// Address the endpoint binds to unless overridden by `LIFE_METRICS_ADDR`.
+const DEFAULT_ADDR: &str = "127.0.0.1:9898";
+
+/// Serves [`RunMetrics`] in the Prometheus text exposition format on `/metrics`,
+/// so long headless runs can be scraped by standard monitoring tooling.
+pub struct MetricsServerPlugin;
+
+impl Plugin for MetricsServerPlugin {
+    fn build(&self, app: &mut App) {
+        let shared = SharedMetrics::default();
+        let addr = std::env::var("LIFE_METRICS_ADDR").unwrap_or_else(|_| DEFAULT_ADDR.to_string());
+
+        match TcpListener::bind(&addr) {
+            Ok(listener) => {
+                info!("Metrics endpoint listening on http://{addr}/metrics");
+                let served = shared.clone();
+                std::thread::spawn(move || serve(listener, served));
+            }
+            Err(err) => warn!("Metrics endpoint disabled, cannot bind {addr}: {err}"),
+        }
+
+        app.insert_resource(shared)
+            .add_systems(Update, publish_metrics);
+    }
+}
+
+#[derive(Resource, Clone, Default)]
+struct SharedMetrics(Arc<Mutex<RunMetrics>>);
+
+fn publish_metrics(metrics: Res<RunMetrics>, shared: Res<SharedMetrics>) {
+    if metrics.is_changed()
+        && let Ok(mut slot) = shared.0.lock()
+    {
+        *slot = *metrics;
+    }
+}
+
+fn serve(listener: TcpListener, shared: SharedMetrics) {
+    for stream in listener.incoming().flatten() {
+        let snapshot = shared.0.lock().map(|m| *m).unwrap_or_default();
+        if let Err(err) = respond(stream, &snapshot) {
+            debug!("Metrics request failed: {err}");
+        }
+    }
+}
+
+fn respond(mut stream: TcpStream, metrics: &RunMetrics) -> std::io::Result<()> {
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+    let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+
+    let (status, body) = if path == "/metrics" {
+        ("200 OK", render(metrics))
+    } else {
+        ("404 Not Found", String::from("not found\n"))
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+fn render(metrics: &RunMetrics) -> String {
+    format!(
+        "# HELP life_generation Generations stepped since the last clear or import.\n\
+         # TYPE life_generation counter\n\
+         life_generation {}\n\
+         # HELP life_population Live cells in the universe.\n\
+         # TYPE life_population gauge\n\
+         life_population {}\n\
+         # HELP life_steps_per_second Generations computed per second.\n\
+         # TYPE life_steps_per_second gauge\n\
+         life_steps_per_second {}\n\
+         # HELP life_engine_memory_bytes Estimated heap memory held by the engine.\n\
+         # TYPE life_engine_memory_bytes gauge\n\
+         life_engine_memory_bytes {}\n",
+        metrics.generation, metrics.population, metrics.steps_per_sec, metrics.memory_bytes
+    )
+}
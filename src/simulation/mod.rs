@@ -1,30 +1,315 @@
+use std::ops::Range;
+
+use bevy::math::I64Vec2;
 use bevy::prelude::*;
 
+pub mod accessibility;
+pub mod activity;
+pub mod announcements;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod autosave;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod batch;
+pub mod benchmark;
+pub mod birth_death;
+#[cfg(target_arch = "wasm32")]
+pub mod browser_storage;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod bundle;
+pub mod calibration;
+pub mod camera_script;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod capture;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod census;
+pub mod clipboard;
+pub mod codec;
+pub mod collision;
+pub mod cursor;
+pub mod determinism;
 pub mod draw;
+pub mod energy;
 pub mod engine;
+pub mod error;
+pub mod follow;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod frame_export;
+#[cfg(feature = "ui")]
+pub mod generators;
 pub mod graphics;
+pub mod guides;
+pub mod heatmap;
+pub mod history;
+pub mod import;
+pub mod import_ghost;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod kiosk;
+pub mod lens;
+#[cfg(target_arch = "wasm32")]
+pub mod link;
+pub mod locale;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod macrocell;
+pub mod macros;
+pub mod metrics;
+#[cfg(feature = "metrics-server")]
+pub mod metrics_server;
+pub mod par;
+pub mod period;
+pub mod plaintext;
+pub mod power;
+pub mod presentation;
+#[cfg(feature = "ui")]
+pub mod presets;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod recording;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod remote;
 pub mod render;
+pub mod rle;
+pub mod rule_presets;
+pub mod rules;
+pub mod sandbox;
+pub mod seed;
+pub mod selection;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod session;
+pub mod settings;
+pub mod stamp;
 pub mod stats_boards;
+#[cfg(target_arch = "wasm32")]
+pub mod step_worker;
+pub mod system_clipboard;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod taskbar_icon;
+pub mod territory;
+pub mod theme;
+pub mod toasts;
+pub mod torus;
+#[cfg(feature = "ui")]
+pub mod tree_explorer;
+pub mod turbo;
+#[cfg(feature = "ui")]
+pub mod tutorial;
 pub mod universe;
+pub mod versus;
 pub mod view;
+pub mod workspace;
 
+use crate::simulation::accessibility::AccessibilityPlugin;
+use crate::simulation::activity::ActivityPlugin;
+use crate::simulation::announcements::AnnouncementPlugin;
+use crate::simulation::benchmark::BenchmarkPlugin;
+use crate::simulation::birth_death::BirthDeathPlugin;
+use crate::simulation::calibration::CalibrationPlugin;
+use crate::simulation::camera_script::CameraScriptPlugin;
+use crate::simulation::clipboard::ClipboardPlugin;
+use crate::simulation::collision::CollisionPlugin;
+use crate::simulation::cursor::CursorPlugin;
+use crate::simulation::determinism::DeterminismPlugin;
 use crate::simulation::draw::MouseDrawPlugin;
+use crate::simulation::energy::EnergyPlugin;
 use crate::simulation::stats_boards::StatsBoardPlugin;
 
-use self::graphics::GraphicsPlugin;
+use self::engine::EngineRegistry;
+use self::follow::FollowPlugin;
+use self::graphics::{GraphicsPlugin, LayerZRange};
+use self::guides::GuidesPlugin;
+use self::heatmap::HeatmapPlugin;
+use self::history::HistoryPlugin;
+use self::import::ImportPlugin;
+use self::import_ghost::ImportGhostPlugin;
+use self::lens::LensPlugin;
+use self::locale::LocalePlugin;
+use self::metrics::MetricsPlugin;
+use self::period::PeriodPlugin;
+use self::power::LowPowerPlugin;
+use self::presentation::PresentationPlugin;
 use self::render::SimulationRenderPlugin;
+use self::sandbox::SandboxPlugin;
+use self::seed::SeededRngPlugin;
+use self::selection::SelectionPlugin;
+use self::settings::SettingsPlugin;
+use self::stamp::StampPlugin;
+use self::territory::TerritoryPlugin;
+use self::theme::ThemePlugin;
+use self::toasts::ToastPlugin;
+use self::torus::TorusPlugin;
+use self::turbo::TurboPlugin;
 use self::universe::UniversePlugin;
+use self::versus::VersusPlugin;
 use self::view::ViewPlugin;
 
-pub struct SimulationPlugin;
+/// Systems that react to keyboard and mouse input; see [`SimulationPlugin::without_input`].
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SimulationInput;
+
+/// Input that only moves the view (pan, zoom); unlike [`SimulationInput`] it stays
+/// available in presentation mode.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ViewInput;
+
+/// The whole simulation: universe, rendering, overlays and tools.
+/// Configure it with the builder methods when embedding it in another app:
+///
+/// ```ignore
+/// app.add_plugins(
+///     SimulationPlugin::default()
+///         .with_engine("hash-life")
+///         .with_initial_pattern(cells)
+///         .without_input()
+///         .with_layer_z_range(-10.0..-5.0),
+/// );
+/// ```
+#[derive(Clone)]
+pub struct SimulationPlugin {
+    engine: Option<String>,
+    initial_pattern: Vec<I64Vec2>,
+    input: bool,
+    layer_z_range: Range<f32>,
+}
+
+impl Default for SimulationPlugin {
+    fn default() -> Self {
+        Self {
+            engine: None,
+            initial_pattern: Vec::new(),
+            input: true,
+            layer_z_range: LayerZRange::default().0,
+        }
+    }
+}
+
+impl SimulationPlugin {
+    /// Starts on the engine registered as `id` instead of the default engine.
+    pub fn with_engine(mut self, id: impl Into<String>) -> Self {
+        self.engine = Some(id.into());
+        self
+    }
+
+    /// Cells that are alive when the app starts.
+    pub fn with_initial_pattern(mut self, cells: impl IntoIterator<Item = I64Vec2>) -> Self {
+        self.initial_pattern = cells.into_iter().collect();
+        self
+    }
+
+    /// Leaves keyboard and mouse to the host app; the simulation is then only
+    /// driven through its resources (e.g. [`universe::Universe`]).
+    pub fn without_input(mut self) -> Self {
+        self.input = false;
+        self
+    }
+
+    /// World z range the simulation layers are placed in (`0.0..1.0` by default).
+    pub fn with_layer_z_range(mut self, range: Range<f32>) -> Self {
+        self.layer_z_range = range;
+        self
+    }
+}
 
 impl Plugin for SimulationPlugin {
     fn build(&self, app: &mut App) {
+        app.insert_resource(LayerZRange(self.layer_z_range.clone()));
+        if !self.input {
+            app.configure_sets(PreUpdate, (SimulationInput, ViewInput).run_if(never))
+                .configure_sets(Update, (SimulationInput, ViewInput).run_if(never));
+        }
+
+        let engine = self.engine.clone();
+        let pattern = self.initial_pattern.clone();
+        app.add_systems(
+            Startup,
+            move |mut universe: ResMut<universe::Universe>, registry: Res<EngineRegistry>| {
+                if let Some(id) = &engine {
+                    match registry.get(id) {
+                        Some(entry) => universe.switch_engine(entry),
+                        None => warn!("No engine registered as {id}"),
+                    }
+                }
+                if !pattern.is_empty() {
+                    universe.add_cells(pattern.clone());
+                }
+            },
+        );
+
         app.add_plugins(ViewPlugin);
+        app.add_plugins(ThemePlugin);
+        app.add_plugins(PresentationPlugin);
+        app.add_plugins(SettingsPlugin);
+        app.add_plugins(LocalePlugin);
+        app.add_plugins(AccessibilityPlugin);
+        app.add_plugins(ToastPlugin);
+        app.add_plugins(AnnouncementPlugin);
         app.add_plugins(GraphicsPlugin);
+        app.add_plugins(SeededRngPlugin);
         app.add_plugins(UniversePlugin);
+        app.add_plugins(TurboPlugin);
+        app.add_plugins(LowPowerPlugin);
         app.add_plugins(SimulationRenderPlugin);
         app.add_plugins(MouseDrawPlugin);
+        app.add_plugins(BirthDeathPlugin);
+        app.add_plugins(ActivityPlugin);
+        app.add_plugins(EnergyPlugin);
+        app.add_plugins(HeatmapPlugin);
         app.add_plugins(StatsBoardPlugin);
+        app.add_plugins(VersusPlugin);
+        app.add_plugins(TerritoryPlugin);
+        app.add_plugins(SandboxPlugin);
+        #[cfg(feature = "ui")]
+        app.add_plugins(presets::RulePresetPlugin);
+        #[cfg(feature = "ui")]
+        app.add_plugins(generators::GeneratorPlugin);
+        #[cfg(feature = "ui")]
+        app.add_plugins(tutorial::TutorialPlugin);
+        #[cfg(feature = "ui")]
+        app.add_plugins(tree_explorer::TreeExplorerPlugin);
+        app.add_plugins(TorusPlugin);
+        app.add_plugins(GuidesPlugin);
+        app.add_plugins(SelectionPlugin);
+        app.add_plugins(CursorPlugin);
+        app.add_plugins(ClipboardPlugin);
+        app.add_plugins(StampPlugin);
+        app.add_plugins(LensPlugin);
+        app.add_plugins(PeriodPlugin);
+        app.add_plugins(HistoryPlugin);
+        app.add_plugins(FollowPlugin);
+        app.add_plugins(CollisionPlugin);
+        app.add_plugins(CameraScriptPlugin);
+        app.add_plugins(ImportPlugin);
+        app.add_plugins(ImportGhostPlugin);
+        app.add_plugins(BenchmarkPlugin);
+        app.add_plugins(CalibrationPlugin);
+        app.add_plugins(DeterminismPlugin);
+        app.add_plugins(MetricsPlugin);
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_plugins(capture::AutoCapturePlugin);
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_plugins(frame_export::FrameExportPlugin);
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_plugins(recording::RecordingPlugin);
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_plugins(macrocell::MacrocellPlugin);
+        app.add_plugins(workspace::WorkspacePlugin);
+        app.add_plugins(macros::MacroPlugin);
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_plugins(autosave::AutosavePlugin);
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_plugins(kiosk::KioskPlugin);
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_plugins(bundle::BundlePlugin);
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_plugins(remote::RemotePlugin);
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_plugins(session::SessionPlugin);
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_plugins(taskbar_icon::TaskbarIconPlugin);
+        #[cfg(target_arch = "wasm32")]
+        app.add_plugins(link::LinkPlugin);
+        #[cfg(feature = "metrics-server")]
+        app.add_plugins(metrics_server::MetricsServerPlugin);
     }
 }
+
+fn never() -> bool {
+    false
+}
@@ -0,0 +1,37 @@
+//! `rayon`'s parallel iterators with the `parallel` feature, plain slice iterators
+//! without it, so engines can call `par_iter()` either way.
+
+#[cfg(feature = "parallel")]
+pub use rayon::prelude::*;
+
+#[cfg(not(feature = "parallel"))]
+pub use self::sequential::ParallelSlice;
+
+#[cfg(not(feature = "parallel"))]
+mod sequential {
+    /// The parts of rayon's slice API this crate uses, running on the calling thread.
+    pub trait ParallelSlice<T> {
+        fn par_iter(&self) -> std::slice::Iter<'_, T>;
+        fn par_iter_mut(&mut self) -> std::slice::IterMut<'_, T>;
+        fn par_chunks_mut(&mut self, size: usize) -> std::slice::ChunksMut<'_, T>;
+        fn par_chunks_exact_mut(&mut self, size: usize) -> std::slice::ChunksExactMut<'_, T>;
+    }
+
+    impl<T> ParallelSlice<T> for [T] {
+        fn par_iter(&self) -> std::slice::Iter<'_, T> {
+            self.iter()
+        }
+
+        fn par_iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+            self.iter_mut()
+        }
+
+        fn par_chunks_mut(&mut self, size: usize) -> std::slice::ChunksMut<'_, T> {
+            self.chunks_mut(size)
+        }
+
+        fn par_chunks_exact_mut(&mut self, size: usize) -> std::slice::ChunksExactMut<'_, T> {
+            self.chunks_exact_mut(size)
+        }
+    }
+}
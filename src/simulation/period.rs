@@ -0,0 +1,246 @@
+use std::collections::VecDeque;
+
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+use rustc_hash::FxHashMap;
+
+use crate::simulation::SimulationInput;
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::universe::{RuleChanged, Universe};
+
+/// Longest period looked for; older generations are forgotten.
+pub const MAX_PERIOD: u64 = 1024;
+/// Fingerprinting walks every cell each generation, so big universes are skipped.
+pub const MAX_POPULATION: u64 = 200_000;
+
+pub struct PeriodPlugin;
+
+impl Plugin for PeriodPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PeriodDetector>()
+            .init_resource::<PhaseStepping>()
+            .add_systems(
+                Update,
+                (
+                    detect_period,
+                    phase_stepping_input.in_set(SimulationInput),
+                    apply_phase_stepping,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// The universe repeats every `period` generations, shifted by `displacement`
+/// (zero for oscillators and still lifes).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Periodicity {
+    pub period: u64,
+    pub displacement: I64Vec2,
+}
+
+/// Spots the universe returning to an earlier state, up to translation.
+#[derive(Resource, Default)]
+pub struct PeriodDetector {
+    pub detected: Option<Periodicity>,
+    // Fingerprint -> latest generation with that state and its bounding box corner
+    seen: FxHashMap<u64, (u64, I64Vec2)>,
+    // (generation, fingerprint) in recording order, for forgetting old states
+    order: VecDeque<(u64, u64)>,
+    last_generation: Option<u64>,
+    last_match: u64,
+}
+
+impl PeriodDetector {
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Feeds the cells of one generation; generations must be observed in order.
+    pub fn observe(&mut self, generation: u64, cells: &[I64Vec2]) {
+        let (hash, corner) = fingerprint(cells);
+        self.record(generation, hash, corner);
+    }
+
+    fn record(&mut self, generation: u64, fingerprint: u64, corner: I64Vec2) {
+        if let Some(&(earlier, earlier_corner)) = self.seen.get(&fingerprint) {
+            let period = generation - earlier;
+            // Phase shifts can match a multiple of the period; keep the shorter one
+            let confirms = self
+                .detected
+                .is_some_and(|d| period.is_multiple_of(d.period));
+            if !confirms && period > 0 {
+                self.detected = Some(Periodicity {
+                    period,
+                    displacement: corner - earlier_corner,
+                });
+            }
+            self.last_match = generation;
+        } else if let Some(detected) = self.detected
+            && generation >= self.last_match + detected.period
+        {
+            // A whole period went by without a repeat: the pattern changed
+            self.detected = None;
+        }
+
+        self.seen.insert(fingerprint, (generation, corner));
+        self.order.push_back((generation, fingerprint));
+        while let Some(&(oldest, old_fingerprint)) = self.order.front()
+            && oldest + MAX_PERIOD < generation
+        {
+            self.order.pop_front();
+            if self
+                .seen
+                .get(&old_fingerprint)
+                .is_some_and(|&(g, _)| g == oldest)
+            {
+                self.seen.remove(&old_fingerprint);
+            }
+        }
+    }
+}
+
+/// Order-independent hash of `cells` relative to their bounding box corner,
+/// so a spaceship hashes the same wherever it is.
+fn fingerprint(cells: &[I64Vec2]) -> (u64, I64Vec2) {
+    let corner = cells
+        .iter()
+        .copied()
+        .reduce(I64Vec2::min)
+        .unwrap_or(I64Vec2::ZERO);
+    let mut hash = cells.len() as u64;
+    for &pos in cells {
+        let local = pos - corner;
+        let mut h = (local.x as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F)
+            ^ (local.y as u64).wrapping_mul(0x1656_67B1_9E37_79F9);
+        // splitmix64 finalizer
+        h = (h ^ (h >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        h = (h ^ (h >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        hash = hash.wrapping_add(h ^ (h >> 31));
+    }
+    (hash, corner)
+}
+
+fn detect_period(
+    universe: Res<Universe>,
+    mut detector: ResMut<PeriodDetector>,
+    mut rule_changed: MessageReader<RuleChanged>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    if rule_changed.read().last().is_some() {
+        detector.reset();
+    }
+
+    let generation = universe.generation();
+    match detector.last_generation {
+        Some(last) if last == generation => return,
+        // Cleared, imported or switched engines
+        Some(last) if last > generation => detector.reset(),
+        _ => {}
+    }
+    detector.last_generation = Some(generation);
+
+    if universe.population() > MAX_POPULATION {
+        detector.reset();
+        detector.last_generation = Some(generation);
+        stats.remove("Period");
+        return;
+    }
+
+    let cells = universe.read_engine().export();
+    let before = detector.detected;
+    detector.observe(generation, &cells);
+
+    if detector.detected != before {
+        match detector.detected {
+            Some(Periodicity {
+                period,
+                displacement,
+            }) if displacement == I64Vec2::ZERO => stats.insert("Period", format!("p{period}")),
+            Some(Periodicity {
+                period,
+                displacement,
+            }) => stats.insert(
+                "Period",
+                format!("p{period}, moving ({}, {})", displacement.x, displacement.y),
+            ),
+            None => stats.remove("Period"),
+        }
+    }
+}
+
+/// Steps a whole period per tick so oscillators and guns stand still,
+/// while the phase can still be shifted one generation at a time.
+#[derive(Resource, Default)]
+pub struct PhaseStepping {
+    pub enabled: bool,
+    /// Overrides the detected period when set.
+    pub manual_period: Option<u64>,
+}
+
+impl PhaseStepping {
+    pub fn period(&self, detector: &PeriodDetector) -> Option<u64> {
+        self.manual_period.or(detector.detected.map(|d| d.period))
+    }
+}
+
+// K: toggle phase stepping, ; and ': manual period down/up (0 = detected), J: shift phase
+fn phase_stepping_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut phase: ResMut<PhaseStepping>,
+    mut universe: ResMut<Universe>,
+) {
+    if keys.just_pressed(KeyCode::KeyK) {
+        phase.enabled = !phase.enabled;
+    }
+    if keys.just_pressed(KeyCode::Quote) {
+        phase.manual_period = Some(phase.manual_period.unwrap_or(0) + 1);
+    }
+    if keys.just_pressed(KeyCode::Semicolon) {
+        phase.manual_period = phase
+            .manual_period
+            .and_then(|p| p.checked_sub(1))
+            .filter(|&p| p > 0);
+    }
+    if keys.just_pressed(KeyCode::KeyJ) && phase.enabled {
+        universe.queue_steps(1);
+    }
+}
+
+fn apply_phase_stepping(
+    phase: Res<PhaseStepping>,
+    detector: Res<PeriodDetector>,
+    mut universe: ResMut<Universe>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    if !phase.is_changed() && !detector.is_changed() {
+        return;
+    }
+
+    let period = phase.period(&detector);
+    // Only hand the step size back once, when phase stepping is switched off
+    if phase.enabled || phase.is_changed() {
+        let steps = match period {
+            Some(period) if phase.enabled => period,
+            _ => 1,
+        };
+        if universe.steps_per_tick != steps {
+            universe.steps_per_tick = steps;
+        }
+    }
+
+    if phase.enabled {
+        let source = if phase.manual_period.is_some() {
+            "manual"
+        } else {
+            "detected"
+        };
+        let status = match period {
+            Some(period) => format!("p{period} ({source}), J shifts phase"),
+            None => "waiting for a period".to_string(),
+        };
+        stats.insert("Phase Stepping", status);
+    } else {
+        stats.remove("Phase Stepping");
+    }
+}
@@ -0,0 +1,104 @@
+use std::path::Path;
+
+use bevy::math::I64Vec2;
+
+use crate::simulation::engine::COORD_LIMIT;
+use crate::simulation::error::LifeError;
+use crate::simulation::rle::{PatternSize, RlePattern};
+
+/// Whether `path` is a plaintext pattern, going by its `.cells` extension.
+pub fn is_plaintext(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("cells"))
+}
+
+/// Writes `cells` in the plaintext `.cells` format used on LifeWiki: `!` comment lines,
+/// then a row of `.` and `O` per line, anchored at the bounding box corner.
+pub fn encode(cells: &[I64Vec2], comment: &str) -> String {
+    let mut out = String::new();
+    for line in comment.lines() {
+        out.push_str(&format!("!{line}\n"));
+    }
+
+    let Some(min) = cells.iter().copied().reduce(I64Vec2::min) else {
+        return out;
+    };
+    let max = cells.iter().copied().reduce(I64Vec2::max).unwrap_or(min);
+    let size = max - min + I64Vec2::ONE;
+
+    let mut sorted = cells.to_vec();
+    sorted.sort_unstable_by_key(|p| (p.y, p.x));
+    sorted.dedup();
+
+    let mut row = vec![b'.'; size.x as usize];
+    let mut cells = sorted.into_iter().peekable();
+    for y in min.y..=max.y {
+        row.fill(b'.');
+        while let Some(pos) = cells.next_if(|pos| pos.y == y) {
+            row[(pos.x - min.x) as usize] = b'O';
+        }
+        out.push_str(std::str::from_utf8(&row).expect("row is ASCII"));
+        out.push('\n');
+    }
+    out
+}
+
+/// Reads a plaintext pattern with its top-left cell at the origin. `O` and `*` are
+/// alive, `.` is dead; rows may stop short of the widest one.
+pub fn decode(text: &str) -> Result<RlePattern, LifeError> {
+    let mut cells = Vec::new();
+    let comments = parse(text, |pos| cells.push(pos))?;
+    Ok(RlePattern {
+        cells,
+        rule: None,
+        comments,
+    })
+}
+
+/// Counts the live cells of a plaintext pattern and their extent, like [`rle::measure`].
+///
+/// [`rle::measure`]: crate::simulation::rle::measure
+pub fn measure(text: &str) -> Result<PatternSize, LifeError> {
+    let mut cells = 0u64;
+    let mut max = I64Vec2::ZERO;
+    parse(text, |pos| {
+        cells += 1;
+        max = max.max(pos + I64Vec2::ONE);
+    })?;
+    Ok(PatternSize {
+        cells,
+        size: max,
+        rule: None,
+    })
+}
+
+/// Walks the rows of a plaintext pattern, calling `alive` with each live cell.
+/// Returns the text of the `!` comment lines.
+fn parse(text: &str, mut alive: impl FnMut(I64Vec2)) -> Result<Vec<String>, LifeError> {
+    let mut comments = Vec::new();
+    let mut y = 0;
+    for line in text.lines() {
+        if let Some(comment) = line.strip_prefix('!') {
+            comments.push(comment.trim().to_string());
+            continue;
+        }
+        if y >= COORD_LIMIT {
+            return Err(LifeError::InvalidPattern(
+                "pattern is larger than the supported coordinate range".to_string(),
+            ));
+        }
+        for (x, c) in line.trim_end().chars().enumerate() {
+            match c {
+                '.' => {}
+                'O' | 'o' | '*' => alive(I64Vec2::new(x as i64, y)),
+                c => {
+                    return Err(LifeError::InvalidPattern(format!(
+                        "unexpected character '{c}'"
+                    )));
+                }
+            }
+        }
+        y += 1;
+    }
+    Ok(comments)
+}
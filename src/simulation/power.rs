@@ -0,0 +1,96 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy::winit::{UpdateMode, WinitSettings};
+
+use crate::simulation::SimulationInput;
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::universe::{MAX_OWED_TICKS, Universe};
+
+/// Frame rate low power mode keeps to while the window has focus and nothing happens.
+const LOW_POWER_FPS: f64 = 30.0;
+/// How often an unfocused or minimized window wakes up in low power mode.
+const BACKGROUND_WAKE: Duration = Duration::from_secs(1);
+
+pub struct LowPowerPlugin;
+
+impl Plugin for LowPowerPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(LowPower {
+            active: std::env::args().any(|arg| arg == "--low-power"),
+            ..default()
+        })
+        .add_systems(
+            Update,
+            (toggle_low_power.in_set(SimulationInput), apply_low_power).chain(),
+        );
+    }
+}
+
+/// Saves battery: frames are redrawn at most [`LOW_POWER_FPS`] times a second unless
+/// there's input, the universe steps without pipelining and without catching up on
+/// missed ticks in bigger steps, and while the window is unfocused or minimized the
+/// app wakes up once every [`BACKGROUND_WAKE`] and doesn't draw the universe.
+/// `--low-power` starts in it; F4 toggles it.
+#[derive(Resource, Default)]
+pub struct LowPower {
+    pub active: bool,
+    // Whether the settings below are in effect, and what they replaced
+    applied: bool,
+    saved: Option<(WinitSettings, bool, u64)>,
+}
+
+/// Run condition for systems that only draw: false while low power mode waits in the
+/// background.
+pub fn window_shown(power: Res<LowPower>, q_window: Query<&Window, With<PrimaryWindow>>) -> bool {
+    !power.active || !matches!(q_window.single(), Ok(window) if !window.focused)
+}
+
+// F4: toggle low power mode
+fn toggle_low_power(keys: Res<ButtonInput<KeyCode>>, mut power: ResMut<LowPower>) {
+    if keys.just_pressed(KeyCode::F4) {
+        power.active = !power.active;
+    }
+}
+
+fn apply_low_power(
+    mut power: ResMut<LowPower>,
+    mut winit: Option<ResMut<WinitSettings>>,
+    mut universe: ResMut<Universe>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    if power.active == power.applied {
+        return;
+    }
+    power.applied = power.active;
+
+    if power.active {
+        power.saved = Some((
+            winit.as_deref().cloned().unwrap_or_default(),
+            universe.pipelined,
+            universe.max_owed_ticks,
+        ));
+        if let Some(winit) = winit.as_mut() {
+            **winit = WinitSettings {
+                focused_mode: UpdateMode::reactive(Duration::from_secs_f64(1.0 / LOW_POWER_FPS)),
+                unfocused_mode: UpdateMode::reactive_low_power(BACKGROUND_WAKE),
+            };
+        }
+        universe.pipelined = false;
+        universe.max_owed_ticks = 1;
+        stats.insert("Low Power", "on (F4: off)");
+    } else {
+        let (settings, pipelined, max_owed) =
+            power
+                .saved
+                .take()
+                .unwrap_or((WinitSettings::default(), true, MAX_OWED_TICKS));
+        if let Some(winit) = winit.as_mut() {
+            **winit = settings;
+        }
+        universe.pipelined = pipelined;
+        universe.max_owed_ticks = max_owed;
+        stats.remove("Low Power");
+    }
+}
@@ -0,0 +1,108 @@
+use bevy::prelude::*;
+use rustc_hash::FxHashMap;
+
+use crate::simulation::theme::Theme;
+use crate::simulation::{SimulationInput, ViewInput};
+
+/// F10 toggles a clean view for streaming and screen recording: every UI panel is
+/// hidden, the universe switches to [`Presentation::theme`] and only panning and
+/// zooming still react to input.
+pub struct PresentationPlugin;
+
+impl Plugin for PresentationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Presentation>()
+            .configure_sets(PreUpdate, SimulationInput.run_if(not_presenting))
+            .configure_sets(Update, SimulationInput.run_if(not_presenting))
+            .add_systems(Update, toggle_presentation.in_set(ViewInput))
+            .add_systems(PostUpdate, hide_ui);
+    }
+}
+
+// A UI panel: a node without a parent
+type TopLevelNode = (With<Node>, Without<ChildOf>);
+
+#[derive(Resource)]
+pub struct Presentation {
+    pub active: bool,
+    /// Theme used while presenting.
+    pub theme: Theme,
+    saved_theme: Option<Theme>,
+    // Visibility each UI panel had (or was given meanwhile), restored on exit
+    saved_visibility: FxHashMap<Entity, Visibility>,
+}
+
+impl Default for Presentation {
+    fn default() -> Self {
+        Self {
+            active: false,
+            theme: Theme {
+                universe_dead: Vec4::new(0.0, 0.0, 0.0, 1.0),
+                ..default()
+            },
+            saved_theme: None,
+            saved_visibility: FxHashMap::default(),
+        }
+    }
+}
+
+impl Presentation {
+    /// Enters or leaves presentation mode, swapping the theme in or back out.
+    pub fn set_active(&mut self, active: bool, theme: &mut Theme) {
+        if self.active == active {
+            return;
+        }
+        self.active = active;
+        if active {
+            let shown = std::mem::replace(theme, self.theme.clone());
+            self.saved_theme = Some(shown);
+        } else if let Some(saved) = self.saved_theme.take() {
+            *theme = saved;
+        }
+    }
+}
+
+/// Run condition for input that is off in presentation mode.
+pub fn not_presenting(presentation: Res<Presentation>) -> bool {
+    !presentation.active
+}
+
+// F10: toggle presentation mode
+fn toggle_presentation(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut presentation: ResMut<Presentation>,
+    mut theme: ResMut<Theme>,
+) {
+    if !keys.just_pressed(KeyCode::F10) {
+        return;
+    }
+    let active = !presentation.active;
+    presentation.set_active(active, &mut theme);
+}
+
+fn hide_ui(
+    mut presentation: ResMut<Presentation>,
+    mut q_panels: Query<(Entity, &mut Visibility), TopLevelNode>,
+) {
+    if !presentation.active {
+        if presentation.is_changed() {
+            let saved = std::mem::take(&mut presentation.saved_visibility);
+            for (entity, visibility) in saved {
+                if let Ok((_, mut current)) = q_panels.get_mut(entity) {
+                    *current = visibility;
+                }
+            }
+        }
+        return;
+    }
+
+    for (entity, mut visibility) in &mut q_panels {
+        // Panels keep updating their own visibility; remember the latest wish
+        if visibility.is_changed() || !presentation.saved_visibility.contains_key(&entity) {
+            presentation.saved_visibility.insert(entity, *visibility);
+        }
+        if *visibility != Visibility::Hidden {
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+
+use crate::simulation::SimulationInput;
+use crate::simulation::locale::Localization;
+use crate::simulation::rule_presets::{PRESETS, PresetSeed};
+use crate::simulation::sandbox::no_sandbox_running;
+use crate::simulation::seed::{SimulationRng, view_center};
+use crate::simulation::universe::{PatternLoaded, RuleChanged, Universe};
+use crate::simulation::versus::no_match_running;
+use crate::simulation::view::SimulationView;
+
+pub struct RulePresetPlugin;
+
+impl Plugin for RulePresetPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RulePresetMenu>()
+            .add_systems(Startup, setup_preset_menu)
+            .add_systems(
+                Update,
+                (
+                    (
+                        toggle_preset_menu.in_set(SimulationInput),
+                        navigate_preset_menu.in_set(SimulationInput),
+                    )
+                        .chain()
+                        .run_if(no_match_running)
+                        .run_if(no_sandbox_running),
+                    select_current_rule,
+                    update_preset_menu,
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct RulePresetMenu {
+    pub open: bool,
+    pub selected: usize,
+}
+
+#[derive(Component)]
+struct PresetMenuPanel;
+
+fn setup_preset_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(10.0),
+                left: Val::Px(10.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+            BackgroundColor(Color::BLACK.with_alpha(0.7)),
+            GlobalZIndex(100),
+            Visibility::Hidden,
+            PresetMenuPanel,
+        ))
+        .with_child((
+            Text::default(),
+            TextFont {
+                font,
+                font_size: 20.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+        ));
+}
+
+// P: open or close the rule presets
+fn toggle_preset_menu(keys: Res<ButtonInput<KeyCode>>, mut menu: ResMut<RulePresetMenu>) {
+    if keys.just_pressed(KeyCode::KeyP) || (menu.open && keys.just_pressed(KeyCode::Escape)) {
+        menu.open = !menu.open;
+    }
+}
+
+// Up/Down: pick a preset, Enter: apply its rule and seed
+fn navigate_preset_menu(
+    keys: Res<ButtonInput<KeyCode>>,
+    view: Res<SimulationView>,
+    mut menu: ResMut<RulePresetMenu>,
+    mut rng: ResMut<SimulationRng>,
+    mut universe: ResMut<Universe>,
+    mut patterns_loaded: MessageWriter<PatternLoaded>,
+) {
+    if !menu.open {
+        return;
+    }
+
+    if keys.just_pressed(KeyCode::ArrowUp) {
+        menu.selected = (menu.selected + PRESETS.len() - 1) % PRESETS.len();
+    }
+    if keys.just_pressed(KeyCode::ArrowDown) {
+        menu.selected = (menu.selected + 1) % PRESETS.len();
+    }
+    if !keys.just_pressed(KeyCode::Enter) {
+        return;
+    }
+
+    let preset = &PRESETS[menu.selected];
+    let center = view_center(&view);
+    let cells = match preset.seed {
+        PresetSeed::Pattern(cells) => cells
+            .iter()
+            .map(|&(x, y)| center + I64Vec2::new(x, y))
+            .collect(),
+        PresetSeed::Soup { size, density } => rng.soup(center, size, density),
+    };
+
+    info!("Applying preset {} ({})", preset.name, preset.rulestring);
+    universe.set_rule(preset.rule());
+    universe.import(cells);
+    patterns_loaded.write(PatternLoaded {
+        name: preset.name.to_string(),
+    });
+    menu.open = false;
+}
+
+// Keep the highlight on the rule that is actually running
+fn select_current_rule(mut changed: MessageReader<RuleChanged>, mut menu: ResMut<RulePresetMenu>) {
+    let Some(RuleChanged { rule }) = changed.read().last() else {
+        return;
+    };
+    if let Some(i) = PRESETS.iter().position(|p| p.rule() == *rule) {
+        menu.selected = i;
+    }
+}
+
+fn update_preset_menu(
+    menu: Res<RulePresetMenu>,
+    locale: Res<Localization>,
+    mut q_panel: Query<(&mut Visibility, &Children), With<PresetMenuPanel>>,
+    mut q_text: Query<&mut Text>,
+) {
+    if !menu.is_changed() {
+        return;
+    }
+    let Ok((mut visibility, children)) = q_panel.single_mut() else {
+        return;
+    };
+
+    *visibility = if menu.open {
+        Visibility::Visible
+    } else {
+        Visibility::Hidden
+    };
+
+    let mut output = locale.get("menu-presets").to_string();
+    for (i, preset) in PRESETS.iter().enumerate() {
+        let marker = if i == menu.selected { ">" } else { " " };
+        output.push_str(&format!("\n{marker} {} {}", preset.name, preset.rulestring));
+    }
+
+    for &child in children {
+        if let Ok(mut text) = q_text.get_mut(child) {
+            **text = output.clone();
+        }
+    }
+}
@@ -0,0 +1,400 @@
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{Receiver, SyncSender, TrySendError, sync_channel};
+use std::thread::JoinHandle;
+
+use bevy::math::Rect;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::simulation::SimulationInput;
+use crate::simulation::batch::arg_value;
+use crate::simulation::error::LifeError;
+use crate::simulation::frame_export::{color_ramp, to_rgba};
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::theme::Theme;
+use crate::simulation::universe::Universe;
+use crate::simulation::view::SimulationView;
+
+/// Frames waiting for ffmpeg; beyond this new frames are dropped rather than
+/// stalling the app.
+const QUEUED_FRAMES: usize = 16;
+
+/// Feeds ffmpeg and waits for it; yields the finished file.
+type Encoder = JoinHandle<Result<PathBuf, LifeError>>;
+
+/// F9 starts and stops recording the view into a video by piping raw frames to
+/// `ffmpeg`, which has to be on the `PATH`. Configured with `--record-dir DIR`,
+/// `--record-fps N`, `--record-stride N` (generations per frame), `--record-width PX`
+/// and `--record-format mp4|webm`, or through [`RecordingSettings`]. Desktop only.
+pub struct RecordingPlugin;
+
+impl Plugin for RecordingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RecordingSettings>()
+            .init_resource::<Recording>()
+            .add_systems(
+                Update,
+                (
+                    toggle_recording.in_set(SimulationInput),
+                    record_frame,
+                    finish_recording,
+                )
+                    .chain(),
+            );
+        #[cfg(feature = "ui")]
+        app.add_systems(Startup, setup_indicator)
+            .add_systems(Update, update_indicator.after(finish_recording));
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VideoFormat {
+    Mp4,
+    WebM,
+}
+
+impl VideoFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            VideoFormat::Mp4 => "mp4",
+            VideoFormat::WebM => "webm",
+        }
+    }
+
+    fn codec_args(self) -> &'static [&'static str] {
+        match self {
+            VideoFormat::Mp4 => &["-c:v", "libx264", "-pix_fmt", "yuv420p"],
+            VideoFormat::WebM => &["-c:v", "libvpx-vp9", "-b:v", "0", "-crf", "32"],
+        }
+    }
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct RecordingSettings {
+    pub dir: PathBuf,
+    pub fps: u32,
+    /// Generations between two frames.
+    pub stride: u64,
+    /// Video width in pixels; the height follows the window's aspect ratio.
+    pub width: u32,
+    pub format: VideoFormat,
+}
+
+impl Default for RecordingSettings {
+    fn default() -> Self {
+        let number = |name: &str, default: u64| {
+            arg_value(name)
+                .and_then(|v| v.parse().ok())
+                .filter(|&n| n > 0)
+                .unwrap_or(default)
+        };
+        let format = match arg_value("--record-format").as_deref() {
+            Some("webm") => VideoFormat::WebM,
+            _ => VideoFormat::Mp4,
+        };
+        Self {
+            dir: arg_value("--record-dir").map_or_else(|| PathBuf::from("."), PathBuf::from),
+            fps: number("--record-fps", 30) as u32,
+            stride: number("--record-stride", 1),
+            width: number("--record-width", 1280).min(8192) as u32,
+            format,
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Recording {
+    session: Option<Session>,
+    // Encoders that were stopped and are still writing out the file
+    finishing: Vec<Encoder>,
+}
+
+impl Recording {
+    pub fn is_recording(&self) -> bool {
+        self.session.is_some()
+    }
+}
+
+struct Session {
+    frames: SyncSender<Vec<u8>>,
+    encoder: Encoder,
+    width: u32,
+    height: u32,
+    colors: [[u8; 4]; 256],
+    last_generation: Option<u64>,
+    recorded: u64,
+    dropped: u64,
+    started: f64,
+}
+
+impl Session {
+    fn elapsed(&self, time: &Time) -> String {
+        let seconds = (time.elapsed_secs_f64() - self.started) as u64;
+        format!("{:02}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+// F9: start/stop recording
+#[allow(clippy::too_many_arguments)]
+fn toggle_recording(
+    keys: Res<ButtonInput<KeyCode>>,
+    settings: Res<RecordingSettings>,
+    theme: Res<Theme>,
+    time: Res<Time>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut recording: ResMut<Recording>,
+    mut stats: ResMut<StatsBoard>,
+    mut errors: MessageWriter<LifeError>,
+) {
+    if !keys.just_pressed(KeyCode::F9) {
+        return;
+    }
+    if let Some(session) = recording.session.take() {
+        // Closing the channel lets the encoder finish the file in the background
+        drop(session.frames);
+        recording.finishing.push(session.encoder);
+        stats.insert(
+            "Recording",
+            format!("stopped after {} frames, finishing...", session.recorded),
+        );
+        return;
+    }
+
+    let Ok(window) = q_window.single() else {
+        return;
+    };
+    // Even sizes, as most video encoders require
+    let width = settings.width.max(2) & !1;
+    let height = ((width as f32 * window.height() / window.width().max(1.0)).round() as u32)
+        .clamp(2, 8192)
+        & !1;
+
+    match start_encoder(&settings, width, height) {
+        Ok((frames, encoder)) => {
+            recording.session = Some(Session {
+                frames,
+                encoder,
+                width,
+                height,
+                colors: color_ramp(&theme),
+                last_generation: None,
+                recorded: 0,
+                dropped: 0,
+                started: time.elapsed_secs_f64(),
+            });
+        }
+        Err(err) => {
+            errors.write(err);
+        }
+    }
+}
+
+/// Spawns ffmpeg and a thread that feeds it the frames sent over the returned channel.
+fn start_encoder(
+    settings: &RecordingSettings,
+    width: u32,
+    height: u32,
+) -> Result<(SyncSender<Vec<u8>>, Encoder), LifeError> {
+    std::fs::create_dir_all(&settings.dir).map_err(|err| LifeError::io(&settings.dir, err))?;
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let path = settings
+        .dir
+        .join(format!("recording-{stamp}.{}", settings.format.extension()));
+
+    let mut child = Command::new("ffmpeg")
+        .args([
+            "-y",
+            "-loglevel",
+            "error",
+            "-f",
+            "rawvideo",
+            "-pix_fmt",
+            "rgba",
+        ])
+        .args(["-s", &format!("{width}x{height}")])
+        .args(["-r", &settings.fps.to_string()])
+        .args(["-i", "-"])
+        .args(settings.format.codec_args())
+        .arg(&path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .map_err(|err| {
+            LifeError::Export(format!(
+                "cannot start ffmpeg ({err}); install it or export frames with E instead"
+            ))
+        })?;
+    let stdin = child.stdin.take().expect("stdin is piped");
+    info!("Recording {width}x{height} to {}", path.display());
+
+    let (frames, received) = sync_channel(QUEUED_FRAMES);
+    let encoder = std::thread::spawn(move || encode(child, stdin, received, path));
+    Ok((frames, encoder))
+}
+
+fn encode(
+    mut child: Child,
+    mut stdin: ChildStdin,
+    frames: Receiver<Vec<u8>>,
+    path: PathBuf,
+) -> Result<PathBuf, LifeError> {
+    for frame in frames {
+        if let Err(err) = stdin.write_all(&frame) {
+            // ffmpeg quit; its exit status below says why
+            warn!("Recording stopped early: {err}");
+            break;
+        }
+    }
+    drop(stdin);
+    let status = child.wait().map_err(|err| LifeError::io(&path, err))?;
+    if status.success() {
+        Ok(path)
+    } else {
+        Err(LifeError::Export(format!(
+            "ffmpeg failed on {} ({status})",
+            path.display()
+        )))
+    }
+}
+
+fn record_frame(
+    universe: Res<Universe>,
+    settings: Res<RecordingSettings>,
+    view: Res<SimulationView>,
+    time: Res<Time>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut recording: ResMut<Recording>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    let Some(session) = recording.session.as_mut() else {
+        return;
+    };
+    let Ok(window) = q_window.single() else {
+        return;
+    };
+
+    let generation = universe.generation();
+    let due = match session.last_generation {
+        // Loads and clears restart the count
+        Some(last) if generation >= last => generation - last >= settings.stride,
+        _ => true,
+    };
+    if !due {
+        return;
+    }
+    session.last_generation = Some(generation);
+
+    let (width, height) = (session.width as usize, session.height as usize);
+    // What the window shows, keeping the video's aspect ratio if the window is resized
+    let half_width = window.width() / (2.0 * view.zoom as f32);
+    let half = Vec2::new(half_width, half_width * height as f32 / width as f32);
+    let rect = Rect::from_center_half_size(view.center.as_vec2(), half);
+    let mut cells = vec![0u8; width * height];
+    universe.draw_to_buffer(rect, &mut cells, width, height);
+
+    match session
+        .frames
+        .try_send(to_rgba(&cells, width, &session.colors))
+    {
+        Ok(()) => session.recorded += 1,
+        Err(TrySendError::Full(_)) => session.dropped += 1,
+        // The encoder gave up; stopping reports why
+        Err(TrySendError::Disconnected(_)) => {}
+    }
+
+    let mut status = format!("{}, {} frames", session.elapsed(&time), session.recorded);
+    if session.dropped > 0 {
+        status.push_str(&format!(", {} dropped", session.dropped));
+    }
+    stats.insert("Recording", status);
+}
+
+fn finish_recording(
+    mut recording: ResMut<Recording>,
+    mut stats: ResMut<StatsBoard>,
+    mut errors: MessageWriter<LifeError>,
+) {
+    if !recording.finishing.iter().any(JoinHandle::is_finished) {
+        return;
+    }
+    let (done, pending) = std::mem::take(&mut recording.finishing)
+        .into_iter()
+        .partition::<Vec<_>, _>(JoinHandle::is_finished);
+    recording.finishing = pending;
+
+    for handle in done {
+        match handle.join() {
+            Ok(Ok(path)) => {
+                info!("Saved recording {}", path.display());
+                stats.insert("Recording", format!("saved {}", path.display()));
+            }
+            Ok(Err(err)) => {
+                stats.remove("Recording");
+                errors.write(err);
+            }
+            Err(_) => {
+                stats.remove("Recording");
+                errors.write(LifeError::Export("the encoder thread panicked".to_string()));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "ui")]
+#[derive(Component)]
+struct RecordingIndicator;
+
+#[cfg(feature = "ui")]
+fn setup_indicator(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(10.0),
+            right: Val::Px(10.0),
+            padding: UiRect::all(Val::Px(10.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.6, 0.0, 0.0, 0.85)),
+        GlobalZIndex(100),
+        Visibility::Hidden,
+        RecordingIndicator,
+        Text::default(),
+        TextFont {
+            font,
+            font_size: 20.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+    ));
+}
+
+#[cfg(feature = "ui")]
+fn update_indicator(
+    recording: Res<Recording>,
+    time: Res<Time>,
+    mut q_indicator: Query<(&mut Visibility, &mut Text), With<RecordingIndicator>>,
+) {
+    let Ok((mut visibility, mut text)) = q_indicator.single_mut() else {
+        return;
+    };
+    let Some(session) = &recording.session else {
+        if *visibility != Visibility::Hidden {
+            *visibility = Visibility::Hidden;
+        }
+        return;
+    };
+
+    let label = format!("REC {}", session.elapsed(&time));
+    if **text != label {
+        **text = label;
+    }
+    if *visibility != Visibility::Visible {
+        *visibility = Visibility::Visible;
+    }
+}
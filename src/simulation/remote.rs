@@ -0,0 +1,607 @@
+use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use bevy::math::{DVec2, I64Vec2};
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use rustc_hash::FxHashMap;
+
+use crate::simulation::batch::arg_value;
+use crate::simulation::codec::{BlockDiff, apply_diff, base64, collect_tiles, diff_tiles};
+use crate::simulation::engine::{
+    CellRegion, EngineRegistry, LifeEngine, TILE_SIZE, Tile, create_rule_engine, default_engine,
+};
+use crate::simulation::error::LifeError;
+use crate::simulation::import::ImportBudget;
+use crate::simulation::rle;
+use crate::simulation::rules::LifeRule;
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::universe::Universe;
+use crate::simulation::view::SimulationView;
+
+/// Shortest time between two frames sent to viewers.
+const FRAME_TIME: Duration = Duration::from_millis(33);
+/// A viewer that can't take a frame for this long is dropped.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(2);
+/// Largest message accepted, compressed or not.
+const MAX_MESSAGE: usize = 256 << 20;
+/// A viewer that hasn't finished its handshake by then is refused.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+/// Longest header line, and most header lines, a handshake may send.
+const MAX_HEADER_LINE: u64 = 8 << 10;
+const MAX_HEADERS: usize = 100;
+
+/// `--serve ADDR` runs a universe without a window, as fast as `--serve-step N`
+/// generations (1 by default) per frame allow, and streams it over WebSocket to viewers
+/// attached with `--attach ADDR`, so huge simulations can run on a server and be explored
+/// from elsewhere. It starts from `--serve-pattern FILE` (RLE, its rule included) on
+/// `--serve-engine ID`, or the default engine. Each viewer only gets the tiles in its
+/// view, XORed with what it was sent before, as a deflated [`BlockDiff`]. Runs until
+/// killed; returns false when no server was requested.
+pub fn serve_from_args() -> bool {
+    let Some(addr) = arg_value("--serve") else {
+        return false;
+    };
+    let mut engine = match load_engine() {
+        Ok(engine) => engine,
+        Err(err) => {
+            eprintln!("{err}");
+            std::process::exit(2);
+        }
+    };
+    let steps = match arg_value("--serve-step").map(|v| v.parse::<u64>()) {
+        None => 1,
+        Some(Ok(steps)) => steps,
+        Some(Err(err)) => {
+            eprintln!("Invalid --serve-step: {err}");
+            std::process::exit(2);
+        }
+    };
+    let listener = match TcpListener::bind(&addr) {
+        Ok(listener) => listener,
+        Err(err) => {
+            eprintln!("Cannot listen on {addr}: {err}");
+            std::process::exit(2);
+        }
+    };
+    eprintln!("Serving {} on ws://{addr}", engine.name());
+
+    let viewers: Arc<Mutex<Vec<Viewer>>> = Arc::default();
+    let accepted = viewers.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            // A viewer stalling its handshake holds up only its own thread
+            let accepted = Arc::clone(&accepted);
+            std::thread::spawn(move || match Viewer::accept(stream) {
+                Ok(viewer) => {
+                    eprintln!("Viewer {} attached", viewer.addr);
+                    accepted
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .push(viewer);
+                }
+                Err(err) => eprintln!("Refused a viewer: {err}"),
+            });
+        }
+    });
+
+    loop {
+        let started = Instant::now();
+        engine.step(steps);
+        let mut viewers = viewers.lock().unwrap_or_else(PoisonError::into_inner);
+        if !viewers.is_empty() {
+            let tiles = collect_tiles(engine.as_ref());
+            let (generation, population) = (engine.generation(), engine.population());
+            viewers.retain_mut(|viewer| match viewer.send(&tiles, generation, population) {
+                Ok(()) => true,
+                Err(err) => {
+                    eprintln!("Viewer {} left: {err}", viewer.addr);
+                    false
+                }
+            });
+        }
+        drop(viewers);
+        std::thread::sleep(FRAME_TIME.saturating_sub(started.elapsed()));
+    }
+}
+
+fn load_engine() -> Result<Box<dyn LifeEngine>, LifeError> {
+    let text = match arg_value("--serve-pattern").map(PathBuf::from) {
+        Some(path) => {
+            let text = std::fs::read_to_string(&path).map_err(|err| LifeError::io(&path, err))?;
+            Some((path, text))
+        }
+        None => None,
+    };
+    let size = text
+        .as_ref()
+        .map(|(_, text)| rle::measure(text))
+        .transpose()?;
+    let rule = size.and_then(|size| size.rule).unwrap_or(LifeRule::CONWAY);
+    let mut engine = match arg_value("--serve-engine") {
+        // The other engines are hardwired to Conway's rule
+        Some(id) if rule == LifeRule::CONWAY => EngineRegistry::with_builtins()
+            .create(&id)
+            .ok_or_else(|| LifeError::InvalidRule(format!("no engine called {id}")))?,
+        _ if rule == LifeRule::CONWAY => default_engine(),
+        _ => create_rule_engine(rule),
+    };
+    if let (Some((path, text)), Some(size)) = (text, size) {
+        ImportBudget::default().check(&size, &path.display().to_string(), engine.as_ref(), None)?;
+        let pattern = rle::decode(&text)?;
+        // Rows run down in the file and up on screen
+        let cells: Vec<I64Vec2> = pattern
+            .cells
+            .iter()
+            .map(|p| I64Vec2::new(p.x, -p.y))
+            .collect();
+        engine.import(&cells);
+    }
+    Ok(engine)
+}
+
+/// A viewer attached to the server, and the tiles it was sent so far.
+struct Viewer {
+    addr: String,
+    stream: TcpStream,
+    /// The region it shows, in tiles; set by its reader thread.
+    view: Arc<Mutex<Option<CellRegion>>>,
+    sent: FxHashMap<I64Vec2, Tile>,
+}
+
+impl Viewer {
+    fn accept(stream: TcpStream) -> std::io::Result<Self> {
+        let addr = stream.peer_addr()?.to_string();
+        stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+        let reader = server_handshake(&stream)?;
+        // Views only come in when the viewer moves
+        stream.set_read_timeout(None)?;
+        stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+        let view = Arc::default();
+        let updated = Arc::clone(&view);
+        std::thread::spawn(move || read_views(reader, updated));
+        Ok(Self {
+            addr,
+            stream,
+            view,
+            sent: FxHashMap::default(),
+        })
+    }
+
+    /// Sends the tiles in view that changed since the last frame, and the ones that left.
+    fn send(
+        &mut self,
+        tiles: &FxHashMap<I64Vec2, Tile>,
+        generation: u64,
+        population: u64,
+    ) -> std::io::Result<()> {
+        let view = *self.view.lock().unwrap_or_else(PoisonError::into_inner);
+        // Nothing until the viewer says what it looks at
+        let Some(view) = view else {
+            return Ok(());
+        };
+        let changed = diff_tiles(&self.sent, tiles, |pos| view.contains(pos));
+        apply_diff(&mut self.sent, &changed);
+
+        let diff = BlockDiff {
+            generation,
+            tiles: changed,
+        };
+        let frame = encode_frame(population, &diff);
+        write_message(&mut self.stream, BINARY, &frame, false)
+    }
+}
+
+/// Takes `view X0 Y0 X1 Y1` (cells, the maximum exclusive) messages from a viewer.
+fn read_views(mut reader: BufReader<TcpStream>, view: Arc<Mutex<Option<CellRegion>>>) {
+    while let Ok((opcode, payload)) = read_message(&mut reader) {
+        if opcode != TEXT {
+            continue;
+        }
+        let text = String::from_utf8_lossy(&payload);
+        let numbers: Option<Vec<i64>> = text.strip_prefix("view ").map(|rest| {
+            rest.split_whitespace()
+                .filter_map(|n| n.parse().ok())
+                .collect()
+        });
+        let Some([x0, y0, x1, y1]) = numbers
+            .as_deref()
+            .and_then(|n| <[i64; 4]>::try_from(n).ok())
+        else {
+            continue;
+        };
+        let tile = I64Vec2::splat(TILE_SIZE);
+        let region = CellRegion {
+            min: I64Vec2::new(x0, y0).div_euclid(tile),
+            max: (I64Vec2::new(x1, y1) - I64Vec2::ONE).div_euclid(tile) + I64Vec2::ONE,
+        };
+        *view.lock().unwrap_or_else(PoisonError::into_inner) = Some(region);
+    }
+}
+
+/// The population (of the whole universe; viewers only see part of it) and the diff,
+/// deflated.
+fn encode_frame(population: u64, diff: &BlockDiff) -> Vec<u8> {
+    let mut bytes = population.to_le_bytes().to_vec();
+    bytes.extend(diff.encode());
+    miniz_oxide::deflate::compress_to_vec(&bytes, 1)
+}
+
+/// A frame from the server.
+struct RemoteFrame {
+    population: u64,
+    diff: BlockDiff,
+}
+
+fn decode_frame(data: &[u8]) -> Result<RemoteFrame, String> {
+    let bytes = miniz_oxide::inflate::decompress_to_vec_with_limit(data, MAX_MESSAGE)
+        .map_err(|_| "not deflated".to_string())?;
+    let (population, diff) = bytes
+        .split_first_chunk::<8>()
+        .ok_or_else(|| "truncated".to_string())?;
+    Ok(RemoteFrame {
+        population: u64::from_le_bytes(*population),
+        diff: BlockDiff::decode(diff).map_err(|err| err.to_string())?,
+    })
+}
+
+/// `--attach ADDR` turns the app into a viewer of a universe served with `--serve ADDR`:
+/// the cells in view are mirrored into the local universe, which stays paused, and the
+/// remote generation and population show on the stats board. Local edits aren't sent
+/// back. Desktop only.
+pub struct RemotePlugin;
+
+impl Plugin for RemotePlugin {
+    fn build(&self, app: &mut App) {
+        let Some(addr) = arg_value("--attach") else {
+            return;
+        };
+        let (events, received) = channel();
+        let writer = Arc::new(Mutex::new(None));
+        let (target, shared) = (addr.clone(), Arc::clone(&writer));
+        std::thread::spawn(move || receive(&target, &shared, &events));
+        app.insert_resource(RemoteView {
+            addr,
+            events: Mutex::new(received),
+            writer,
+            tiles: FxHashMap::default(),
+            sent_view: None,
+            attached: false,
+        })
+        .add_systems(Update, (send_view, apply_frames).chain());
+    }
+}
+
+enum RemoteEvent {
+    Frame(RemoteFrame),
+    Lost(String),
+}
+
+#[derive(Resource)]
+pub struct RemoteView {
+    pub addr: String,
+    events: Mutex<Receiver<RemoteEvent>>,
+    // Set once connected; the view is sent through it
+    writer: Arc<Mutex<Option<TcpStream>>>,
+    // The tiles mirrored so far
+    tiles: FxHashMap<I64Vec2, Tile>,
+    sent_view: Option<CellRegion>,
+    attached: bool,
+}
+
+/// Connects to the server and forwards its frames until the connection drops.
+fn receive(addr: &str, writer: &Mutex<Option<TcpStream>>, events: &Sender<RemoteEvent>) {
+    let connected = TcpStream::connect(addr).and_then(|stream| {
+        let reader = client_handshake(&stream, addr)?;
+        Ok((stream, reader))
+    });
+    let mut reader = match connected {
+        Ok((stream, reader)) => {
+            *writer.lock().unwrap_or_else(PoisonError::into_inner) = Some(stream);
+            reader
+        }
+        Err(err) => {
+            let _ = events.send(RemoteEvent::Lost(format!("cannot attach to {addr}: {err}")));
+            return;
+        }
+    };
+    loop {
+        let event = match read_message(&mut reader) {
+            Ok((BINARY, payload)) => match decode_frame(&payload) {
+                Ok(frame) => RemoteEvent::Frame(frame),
+                Err(err) => RemoteEvent::Lost(format!("{addr} sent a damaged frame ({err})")),
+            },
+            Ok(_) => continue,
+            Err(err) => RemoteEvent::Lost(format!("lost {addr}: {err}")),
+        };
+        let lost = matches!(event, RemoteEvent::Lost(_));
+        if events.send(event).is_err() || lost {
+            return;
+        }
+    }
+}
+
+// Tells the server which cells are on screen (with a tile to spare around them)
+fn send_view(
+    mut remote: ResMut<RemoteView>,
+    view: Res<SimulationView>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+) {
+    let Ok(window) = q_window.single() else {
+        return;
+    };
+    let half = DVec2::new(window.width() as f64, window.height() as f64) / (2.0 * view.zoom);
+    let tile = I64Vec2::splat(TILE_SIZE);
+    let min = (view.center - half).floor().as_i64vec2().div_euclid(tile) - I64Vec2::ONE;
+    let max = (view.center + half).ceil().as_i64vec2().div_euclid(tile) + I64Vec2::splat(2);
+    let region = CellRegion {
+        min: min * tile,
+        max: max * tile,
+    };
+    if remote.sent_view == Some(region) {
+        return;
+    }
+    let mut writer = remote.writer.lock().unwrap_or_else(PoisonError::into_inner);
+    let Some(stream) = writer.as_mut() else {
+        return;
+    };
+    let message = format!(
+        "view {} {} {} {}",
+        region.min.x, region.min.y, region.max.x, region.max.y
+    );
+    if write_message(stream, TEXT, message.as_bytes(), true).is_err() {
+        // The receiving side notices and reports it
+        return;
+    }
+    drop(writer);
+    remote.sent_view = Some(region);
+}
+
+fn apply_frames(
+    mut remote: ResMut<RemoteView>,
+    mut universe: ResMut<Universe>,
+    mut stats: ResMut<StatsBoard>,
+    mut errors: MessageWriter<LifeError>,
+) {
+    let remote = &mut *remote;
+    let events: Vec<RemoteEvent> = remote
+        .events
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .try_iter()
+        .collect();
+    for event in events {
+        let frame = match event {
+            RemoteEvent::Frame(frame) => frame,
+            RemoteEvent::Lost(reason) => {
+                errors.write(LifeError::Remote(reason));
+                stats.insert("Remote", format!("{} (disconnected)", remote.addr));
+                continue;
+            }
+        };
+        if !remote.attached {
+            universe.clear();
+            remote.attached = true;
+        }
+
+        let (born, died) = apply_diff(&mut remote.tiles, &frame.diff.tiles);
+        universe.remove_cells(died);
+        universe.add_cells(born);
+        stats.insert(
+            "Remote",
+            format!(
+                "{}: generation {}, {} cells",
+                remote.addr, frame.diff.generation, frame.population
+            ),
+        );
+    }
+    // The server steps; stepping here would only drift away from it
+    if remote.attached && !universe.paused {
+        universe.paused = true;
+    }
+}
+
+// A minimal WebSocket (RFC 6455): unfragmented messages, no extensions.
+
+const TEXT: u8 = 0x1;
+const BINARY: u8 = 0x2;
+const CLOSE: u8 = 0x8;
+const HANDSHAKE_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+fn invalid(message: &str) -> std::io::Error {
+    std::io::Error::new(ErrorKind::InvalidData, message)
+}
+
+/// Reads the header lines of an HTTP request or response, up to the blank line.
+fn read_headers(reader: &mut BufReader<TcpStream>) -> std::io::Result<Vec<String>> {
+    let mut lines = Vec::new();
+    loop {
+        let mut line = String::new();
+        let read = reader.by_ref().take(MAX_HEADER_LINE).read_line(&mut line)?;
+        if !line.ends_with('\n') {
+            return Err(if read as u64 == MAX_HEADER_LINE {
+                invalid("header line too long")
+            } else {
+                ErrorKind::UnexpectedEof.into()
+            });
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            return Ok(lines);
+        }
+        if lines.len() == MAX_HEADERS {
+            return Err(invalid("too many headers"));
+        }
+        lines.push(line.to_string());
+    }
+}
+
+fn header<'a>(lines: &'a [String], name: &str) -> Option<&'a str> {
+    lines.iter().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        key.trim()
+            .eq_ignore_ascii_case(name)
+            .then_some(value.trim())
+    })
+}
+
+fn accept_key(key: &str) -> String {
+    base64(&sha1(format!("{key}{HANDSHAKE_GUID}").as_bytes()))
+}
+
+fn server_handshake(stream: &TcpStream) -> std::io::Result<BufReader<TcpStream>> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let lines = read_headers(&mut reader)?;
+    let key =
+        header(&lines, "Sec-WebSocket-Key").ok_or_else(|| invalid("not a WebSocket request"))?;
+    write!(
+        &*stream,
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+         Sec-WebSocket-Accept: {}\r\n\r\n",
+        accept_key(key)
+    )?;
+    Ok(reader)
+}
+
+fn client_handshake(stream: &TcpStream, host: &str) -> std::io::Result<BufReader<TcpStream>> {
+    let key = base64(&rand::random::<[u8; 16]>());
+    write!(
+        &*stream,
+        "GET / HTTP/1.1\r\nHost: {host}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+         Sec-WebSocket-Key: {key}\r\nSec-WebSocket-Version: 13\r\n\r\n"
+    )?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let lines = read_headers(&mut reader)?;
+    let switched = lines.first().is_some_and(|status| status.contains(" 101 "));
+    if !switched || header(&lines, "Sec-WebSocket-Accept") != Some(accept_key(&key).as_str()) {
+        return Err(invalid("not a life.rs server"));
+    }
+    Ok(reader)
+}
+
+fn read_message(reader: &mut impl Read) -> std::io::Result<(u8, Vec<u8>)> {
+    let mut head = [0; 2];
+    reader.read_exact(&mut head)?;
+    if head[0] & 0x80 == 0 {
+        return Err(invalid("fragmented messages aren't supported"));
+    }
+    let opcode = head[0] & 0x0F;
+    let len = match head[1] & 0x7F {
+        126 => {
+            let mut len = [0; 2];
+            reader.read_exact(&mut len)?;
+            u16::from_be_bytes(len) as usize
+        }
+        127 => {
+            let mut len = [0; 8];
+            reader.read_exact(&mut len)?;
+            u64::from_be_bytes(len).try_into().unwrap_or(usize::MAX)
+        }
+        len => len as usize,
+    };
+    if len > MAX_MESSAGE {
+        return Err(invalid("message too large"));
+    }
+    let mut mask = [0; 4];
+    if head[1] & 0x80 != 0 {
+        reader.read_exact(&mut mask)?;
+    }
+    let mut payload = vec![0; len];
+    reader.read_exact(&mut payload)?;
+    for (i, byte) in payload.iter_mut().enumerate() {
+        *byte ^= mask[i % 4];
+    }
+    if opcode == CLOSE {
+        return Err(ErrorKind::ConnectionAborted.into());
+    }
+    Ok((opcode, payload))
+}
+
+/// Writes one message; clients have to mask theirs.
+fn write_message(
+    stream: &mut impl Write,
+    opcode: u8,
+    payload: &[u8],
+    masked: bool,
+) -> std::io::Result<()> {
+    let mut frame = Vec::with_capacity(payload.len() + 14);
+    frame.push(0x80 | opcode);
+    let mask_bit = if masked { 0x80 } else { 0 };
+    match payload.len() {
+        len @ 0..=125 => frame.push(mask_bit | len as u8),
+        len @ 126..=0xFFFF => {
+            frame.push(mask_bit | 126);
+            frame.extend((len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(mask_bit | 127);
+            frame.extend((len as u64).to_be_bytes());
+        }
+    }
+    if masked {
+        let mask: [u8; 4] = rand::random();
+        frame.extend(mask);
+        frame.extend(
+            payload
+                .iter()
+                .enumerate()
+                .map(|(i, byte)| byte ^ mask[i % 4]),
+        );
+    } else {
+        frame.extend_from_slice(payload);
+    }
+    stream.write_all(&frame)
+}
+
+/// SHA-1, which the handshake needs.
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend((data.len() as u64 * 8).to_be_bytes());
+
+    let mut state: [u32; 5] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+    for block in message.chunks_exact(64) {
+        let mut w = [0u32; 80];
+        for (i, word) in block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e] = state;
+        for (i, word) in w.into_iter().enumerate() {
+            let (f, k) = match i / 20 {
+                0 => ((b & c) | (!b & d), 0x5a827999),
+                1 => (b ^ c ^ d, 0x6ed9eba1),
+                2 => ((b & c) | (b & d) | (c & d), 0x8f1bbcdc),
+                _ => (b ^ c ^ d, 0xca62c1d6),
+            };
+            let t = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = t;
+        }
+        for (word, value) in state.iter_mut().zip([a, b, c, d, e]) {
+            *word = word.wrapping_add(value);
+        }
+    }
+    let mut digest = [0; 20];
+    for (bytes, word) in digest.chunks_exact_mut(4).zip(state) {
+        bytes.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
@@ -1,8 +1,15 @@
+use bevy::diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic};
+use bevy::platform::time::Instant;
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
 
-use crate::simulation::graphics::{GridLayerMaterial, LayerViewport, PixelLayer, PixelLayerBundle};
+use crate::simulation::graphics::{
+    LayerCanvas, LayerDesc, LayerSpawner, LayerViewport, PixelLayer,
+};
+use crate::simulation::power::window_shown;
 use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::theme::Theme;
+use crate::simulation::turbo::no_turbo;
 use crate::simulation::universe::Universe;
 use crate::simulation::view::SimulationView;
 
@@ -10,56 +17,64 @@ pub struct SimulationRenderPlugin;
 
 impl Plugin for SimulationRenderPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, setup_universe_layer)
-            .add_systems(Update, render_universe);
+        app.register_diagnostic(Diagnostic::new(DRAW_TIME).with_suffix("ms"))
+            .add_systems(Startup, setup_universe_layer)
+            .add_systems(
+                Update,
+                (render_universe, render_walls)
+                    .run_if(no_turbo)
+                    .run_if(window_shown),
+            );
     }
 }
 
+/// Time spent rasterizing the universe into the layer canvas each frame.
+pub const DRAW_TIME: DiagnosticPath = DiagnosticPath::const_new("engine/draw_time");
+
 #[derive(Component)]
 struct UniverseLayer;
 
-fn setup_universe_layer(
-    mut commands: Commands,
-    mut images: ResMut<Assets<Image>>,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<GridLayerMaterial>>,
-) {
-    commands.spawn((
-        PixelLayerBundle::new(
-            &mut images,
-            &mut meshes,
-            &mut materials,
-            0.0,
-            Vec4::new(1.0, 1.0, 1.0, 1.0),
-            Vec4::new(0.1, 0.1, 0.1, 1.0),
-        ),
-        UniverseLayer,
-    ));
+#[derive(Component)]
+struct WallLayer;
+
+fn setup_universe_layer(mut layers: LayerSpawner, theme: Res<Theme>) {
+    layers
+        .spawn(
+            LayerDesc::new("universe", 0.0)
+                .colors(theme.universe_alive, theme.universe_dead)
+                .shader(theme.universe_shader)
+                .tiled(256),
+        )
+        .insert(UniverseLayer);
+
+    // Just above the cells so walls stand out from live cells
+    layers
+        .spawn(LayerDesc::new("walls", 0.01).colors(theme.wall_color, Vec4::ZERO))
+        .insert(WallLayer);
 }
 
 fn render_universe(
     universe: Res<Universe>,
     view: Res<SimulationView>,
-    mut images: ResMut<Assets<Image>>,
     q_window: Query<&Window, With<PrimaryWindow>>,
-    q_layer: Query<&PixelLayer, With<UniverseLayer>>,
+    mut q_layer: Query<(&PixelLayer, &mut LayerCanvas), With<UniverseLayer>>,
     mut stats: ResMut<StatsBoard>,
+    mut diagnostics: Diagnostics,
 ) {
-    let Ok(layer) = q_layer.single() else { return };
-    let Some(image) = images.get_mut(&layer.image_handle) else {
+    let Ok((layer, mut canvas)) = q_layer.single_mut() else {
         return;
     };
     let Ok(window) = q_window.single() else {
         return;
     };
 
-    let Some(viewport) = LayerViewport::new(window, &view) else {
+    let Some(viewport) = LayerViewport::new(window, &view, layer) else {
         return;
     };
-    let buffer = viewport.get_buffer(image);
+    let buffer = canvas.buffer(&viewport);
 
     // Draw
-    // let draw_start = Time<Real>
+    let draw_start = Instant::now();
 
     universe.draw_to_buffer(
         viewport.get_world_rect(),
@@ -68,16 +83,40 @@ fn render_universe(
         viewport.screen_h,
     );
 
-    // let draw_duration = draw_start.elapsed();
+    let draw_duration = draw_start.elapsed();
+    diagnostics.add_measurement(&DRAW_TIME, || draw_duration.as_secs_f64() * 1000.0);
 
     stats.insert("Population", format_metric(universe.population()));
-    // stats.insert(
-    //     "Draw Time",
-    //     format!("{:.2} ms", draw_duration.as_micros() as f64 / 1000.0),
-    // );
+    stats.insert(
+        "Draw Time",
+        format!("{:.2} ms", draw_duration.as_micros() as f64 / 1000.0),
+    );
+}
+
+fn render_walls(
+    universe: Res<Universe>,
+    view: Res<SimulationView>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut q_layer: Query<(&PixelLayer, &mut LayerCanvas), With<WallLayer>>,
+) {
+    let Ok((layer, mut canvas)) = q_layer.single_mut() else {
+        return;
+    };
+    let Ok(window) = q_window.single() else {
+        return;
+    };
+    let Some(viewport) = LayerViewport::new(window, &view, layer) else {
+        return;
+    };
+    let buffer = canvas.buffer(&viewport);
+    buffer.fill(0);
+
+    for pos in universe.walls() {
+        viewport.draw_cell(buffer, pos.x, pos.y, 255);
+    }
 }
 
-fn format_metric(count: u64) -> String {
+pub fn format_metric(count: u64) -> String {
     if count < 1_000 {
         return count.to_string();
     }
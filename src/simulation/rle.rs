@@ -0,0 +1,213 @@
+use bevy::math::I64Vec2;
+
+use crate::simulation::engine::{COORD_LIMIT, CellRegion};
+use crate::simulation::error::LifeError;
+use crate::simulation::rules::LifeRule;
+
+/// RLE lines are kept under this many characters, as most readers expect.
+const LINE_WIDTH: usize = 70;
+
+/// Writes `cells` as an RLE pattern, anchored at their bounding box corner.
+pub fn encode(cells: &[I64Vec2], rule: LifeRule, comment: &str) -> String {
+    let min = cells
+        .iter()
+        .copied()
+        .reduce(I64Vec2::min)
+        .unwrap_or_default();
+    let max = cells
+        .iter()
+        .copied()
+        .reduce(I64Vec2::max)
+        .map_or(min, |max| max + I64Vec2::ONE);
+    encode_region(cells, CellRegion { min, max }, rule, comment)
+}
+
+/// Writes the cells inside `region` as an RLE pattern the size of the region, anchored
+/// at its `min` corner, so empty margins stay part of the pattern. Cells outside are
+/// left out.
+pub fn encode_region(
+    cells: &[I64Vec2],
+    region: CellRegion,
+    rule: LifeRule,
+    comment: &str,
+) -> String {
+    let mut out = String::new();
+    for line in comment.lines() {
+        out.push_str(&format!("#C {line}\n"));
+    }
+    let size = region.size();
+    out.push_str(&format!("x = {}, y = {}, rule = {rule}\n", size.x, size.y));
+
+    let mut sorted: Vec<I64Vec2> = cells
+        .iter()
+        .copied()
+        .filter(|&pos| region.contains(pos))
+        .collect();
+    sorted.sort_unstable_by_key(|p| (p.y, p.x));
+    sorted.dedup();
+
+    // (count, tag) runs; trailing dead cells in a row are left implicit
+    let mut runs: Vec<(i64, char)> = Vec::new();
+    let mut push = |count: i64, tag: char| {
+        if count == 0 {
+            return;
+        }
+        match runs.last_mut() {
+            Some((n, last)) if *last == tag => *n += count,
+            _ => runs.push((count, tag)),
+        }
+    };
+    let min = region.min;
+    let mut cursor = min;
+    for pos in sorted {
+        let local = pos - min;
+        if local.y > cursor.y - min.y {
+            push(local.y - (cursor.y - min.y), '$');
+            cursor = I64Vec2::new(min.x, pos.y);
+        }
+        push(pos.x - cursor.x, 'b');
+        push(1, 'o');
+        cursor.x = pos.x + 1;
+    }
+    push(1, '!');
+
+    let mut line = String::new();
+    for (count, tag) in runs {
+        let token = if count == 1 {
+            tag.to_string()
+        } else {
+            format!("{count}{tag}")
+        };
+        if line.len() + token.len() > LINE_WIDTH {
+            out.push_str(&line);
+            out.push('\n');
+            line.clear();
+        }
+        line.push_str(&token);
+    }
+    out.push_str(&line);
+    out.push('\n');
+    out
+}
+
+/// A pattern read from an RLE file.
+pub struct RlePattern {
+    pub cells: Vec<I64Vec2>,
+    /// The rule from the header, if it names one.
+    pub rule: Option<LifeRule>,
+    /// Text of the `#C` comment lines, in order.
+    pub comments: Vec<String>,
+}
+
+/// How big a pattern is, found without materializing its cells.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PatternSize {
+    pub cells: u64,
+    /// Bounding box of the live cells, from the top-left corner.
+    pub size: I64Vec2,
+    pub rule: Option<LifeRule>,
+}
+
+/// Reads an RLE pattern with its top-left cell at the origin.
+/// Any state other than `b`/`.` counts as alive, so multi-state files load as two-state.
+pub fn decode(text: &str) -> Result<RlePattern, LifeError> {
+    let mut cells = Vec::new();
+    let (rule, comments) = parse(text, |pos, n| {
+        cells.extend((0..n).map(|i| pos + I64Vec2::new(i, 0)));
+    })?;
+    Ok(RlePattern {
+        cells,
+        rule,
+        comments,
+    })
+}
+
+/// Counts the live cells of an RLE pattern and their extent, so importers can refuse
+/// a pattern before it takes up any memory.
+pub fn measure(text: &str) -> Result<PatternSize, LifeError> {
+    let mut cells = 0u64;
+    let mut max = I64Vec2::ZERO;
+    let (rule, _) = parse(text, |pos, n| {
+        cells += n as u64;
+        max = max.max(pos + I64Vec2::new(n, 1));
+    })?;
+    Ok(PatternSize {
+        cells,
+        size: max,
+        rule,
+    })
+}
+
+/// Walks the runs of an RLE pattern, calling `alive` with the start and length of each
+/// run of live cells. Returns the header's rule and the comments.
+fn parse(
+    text: &str,
+    mut alive: impl FnMut(I64Vec2, i64),
+) -> Result<(Option<LifeRule>, Vec<String>), LifeError> {
+    let mut rule = None;
+    let mut comments = Vec::new();
+    let mut pos = I64Vec2::ZERO;
+    let mut count: Option<i64> = None;
+    let mut seen_header = false;
+
+    'lines: for line in text.lines() {
+        let line = line.trim();
+        if let Some(comment) = line.strip_prefix("#C").or_else(|| line.strip_prefix("#c")) {
+            comments.push(comment.trim().to_string());
+            continue;
+        }
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if !seen_header && line.starts_with('x') {
+            seen_header = true;
+            for field in line.split(',') {
+                if let Some((key, value)) = field.split_once('=')
+                    && key.trim() == "rule"
+                {
+                    rule = Some(LifeRule::parse(value)?);
+                }
+            }
+            continue;
+        }
+
+        for c in line.chars() {
+            if let Some(digit) = c.to_digit(10) {
+                let run = count
+                    .unwrap_or(0)
+                    .checked_mul(10)
+                    .and_then(|v| v.checked_add(digit as i64))
+                    .filter(|&v| v < COORD_LIMIT)
+                    .ok_or_else(|| LifeError::InvalidPattern("run count too large".to_string()))?;
+                count = Some(run);
+                continue;
+            }
+            if c.is_whitespace() {
+                continue;
+            }
+
+            let n = count.take().unwrap_or(1);
+            match c {
+                'b' | '.' => pos.x += n,
+                '$' => pos = I64Vec2::new(0, pos.y + n),
+                '!' => break 'lines,
+                c if c.is_ascii_alphabetic() => {
+                    alive(pos, n);
+                    pos.x += n;
+                }
+                c => {
+                    return Err(LifeError::InvalidPattern(format!(
+                        "unexpected character '{c}'"
+                    )));
+                }
+            }
+            if pos.x >= COORD_LIMIT || pos.y >= COORD_LIMIT {
+                return Err(LifeError::InvalidPattern(
+                    "pattern is larger than the supported coordinate range".to_string(),
+                ));
+            }
+        }
+    }
+
+    Ok((rule, comments))
+}
@@ -0,0 +1,109 @@
+//! The built-in rule presets: the menu (`RulePresetPlugin`, with the `ui` feature) lists
+//! them and shared links (`LinkPlugin`) name them by index.
+
+use crate::simulation::rules::LifeRule;
+
+/// What a preset puts on the board when it is applied.
+#[derive(Clone, Copy, Debug)]
+pub enum PresetSeed {
+    /// A fixed pattern, in cells relative to the view center.
+    Pattern(&'static [(i64, i64)]),
+    /// A random square soup centered on the view.
+    Soup { size: i64, density: f64 },
+}
+
+/// A named Life-like rule together with a seed that shows it off.
+#[derive(Clone, Copy, Debug)]
+pub struct RulePreset {
+    pub name: &'static str,
+    pub rulestring: &'static str,
+    pub seed: PresetSeed,
+}
+
+impl RulePreset {
+    pub fn rule(&self) -> LifeRule {
+        LifeRule::parse(self.rulestring).expect("built-in rules are valid")
+    }
+}
+
+const R_PENTOMINO: &[(i64, i64)] = &[(0, -1), (1, -1), (-1, 0), (0, 0), (0, 1)];
+const REPLICATOR: &[(i64, i64)] = &[
+    (0, -2),
+    (1, -2),
+    (2, -2),
+    (-1, -1),
+    (2, -1),
+    (-2, 0),
+    (2, 0),
+    (-2, 1),
+    (1, 1),
+    (-2, 2),
+    (-1, 2),
+    (0, 2),
+];
+
+pub const PRESETS: [RulePreset; 9] = [
+    RulePreset {
+        name: "Conway's Life",
+        rulestring: "B3/S23",
+        seed: PresetSeed::Pattern(R_PENTOMINO),
+    },
+    RulePreset {
+        name: "HighLife",
+        rulestring: "B36/S23",
+        seed: PresetSeed::Pattern(REPLICATOR),
+    },
+    RulePreset {
+        name: "Day & Night",
+        rulestring: "B3678/S34678",
+        seed: PresetSeed::Soup {
+            size: 64,
+            density: 0.5,
+        },
+    },
+    RulePreset {
+        name: "Seeds",
+        rulestring: "B2/S",
+        seed: PresetSeed::Soup {
+            size: 8,
+            density: 0.2,
+        },
+    },
+    RulePreset {
+        name: "Life without Death",
+        rulestring: "B3/S012345678",
+        seed: PresetSeed::Soup {
+            size: 16,
+            density: 0.25,
+        },
+    },
+    RulePreset {
+        name: "Maze",
+        rulestring: "B3/S12345",
+        seed: PresetSeed::Soup {
+            size: 16,
+            density: 0.4,
+        },
+    },
+    RulePreset {
+        name: "2x2",
+        rulestring: "B36/S125",
+        seed: PresetSeed::Soup {
+            size: 32,
+            density: 0.5,
+        },
+    },
+    RulePreset {
+        name: "Morley",
+        rulestring: "B368/S245",
+        seed: PresetSeed::Soup {
+            size: 32,
+            density: 0.5,
+        },
+    },
+    RulePreset {
+        name: "Replicator",
+        rulestring: "B1357/S1357",
+        seed: PresetSeed::Pattern(R_PENTOMINO),
+    },
+];
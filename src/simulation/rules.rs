@@ -0,0 +1,80 @@
+use std::fmt;
+
+use crate::simulation::error::LifeError;
+
+/// Outer-totalistic Life-like rule in B/S notation, e.g. `B3/S23` for Conway's Life.
+/// Bit `n` of `birth` (`survival`) set means a dead (live) cell with `n` live neighbours
+/// is alive in the next generation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LifeRule {
+    pub birth: u16,
+    pub survival: u16,
+}
+
+impl LifeRule {
+    pub const CONWAY: LifeRule = LifeRule {
+        birth: 1 << 3,
+        survival: (1 << 2) | (1 << 3),
+    };
+
+    /// Parses `B3/S23`-style rulestrings (case-insensitive, either half may be empty).
+    /// `B0` rules are rejected since they would fill the infinite plane in one step.
+    pub fn parse(rulestring: &str) -> Result<Self, LifeError> {
+        Self::parse_parts(rulestring).map_err(LifeError::InvalidRule)
+    }
+
+    fn parse_parts(rulestring: &str) -> Result<Self, String> {
+        let upper = rulestring.trim().to_ascii_uppercase();
+        let (b, s) = upper
+            .split_once('/')
+            .ok_or_else(|| format!("'{rulestring}' is not of the form B.../S..."))?;
+        let birth = Self::parse_counts(b, 'B')?;
+        let survival = Self::parse_counts(s, 'S')?;
+        if birth & 1 == 1 {
+            return Err("B0 rules are not supported".to_string());
+        }
+        Ok(Self { birth, survival })
+    }
+
+    fn parse_counts(part: &str, prefix: char) -> Result<u16, String> {
+        let digits = part
+            .strip_prefix(prefix)
+            .ok_or_else(|| format!("expected '{prefix}' in '{part}'"))?;
+        let mut mask = 0u16;
+        for c in digits.chars() {
+            match c.to_digit(10) {
+                Some(n) if n <= 8 => mask |= 1 << n,
+                _ => return Err(format!("invalid neighbour count '{c}'")),
+            }
+        }
+        Ok(mask)
+    }
+
+    #[inline]
+    pub fn born(&self, neighbours: u32) -> bool {
+        (self.birth >> neighbours) & 1 == 1
+    }
+
+    #[inline]
+    pub fn survives(&self, neighbours: u32) -> bool {
+        (self.survival >> neighbours) & 1 == 1
+    }
+}
+
+impl Default for LifeRule {
+    fn default() -> Self {
+        Self::CONWAY
+    }
+}
+
+impl fmt::Display for LifeRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts = |mask: u16| -> String {
+            (0..=8)
+                .filter(|n| (mask >> n) & 1 == 1)
+                .map(|n| char::from(b'0' + n as u8))
+                .collect()
+        };
+        write!(f, "B{}/S{}", counts(self.birth), counts(self.survival))
+    }
+}
@@ -0,0 +1,314 @@
+use crate::simulation::par::*;
+use bevy::math::{DVec2, I64Vec2};
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use rand::Rng;
+
+use crate::simulation::SimulationInput;
+use crate::simulation::engine::{LifeEngine, create_rule_engine};
+use crate::simulation::graphics::{
+    LayerCanvas, LayerDesc, LayerRegion, LayerSpawner, LayerViewport, PixelLayer,
+};
+use crate::simulation::rules::LifeRule;
+use crate::simulation::seed::SimulationRng;
+use crate::simulation::theme::Theme;
+use crate::simulation::view::SimulationView;
+
+const COLUMNS: usize = 4;
+const ROWS: usize = 3;
+const TILE_COUNT: usize = COLUMNS * ROWS;
+/// Logical pixels between (and around) the tiles.
+const GAP: f32 = 6.0;
+/// Cells across the width of a tile.
+const TILE_WORLD_SIZE: f64 = 128.0;
+/// Side of the random soup every tile starts from.
+const SOUP_SIZE: i64 = 32;
+const SOUP_DENSITY: f64 = 0.5;
+
+/// Rules compared side by side in [`SandboxMode::Rules`].
+const RULES: [&str; TILE_COUNT] = [
+    "B3/S23",
+    "B36/S23",
+    "B3678/S34678",
+    "B2/S",
+    "B3/S012345678",
+    "B3/S12345",
+    "B36/S125",
+    "B368/S245",
+    "B1357/S1357",
+    "B35678/S5678",
+    "B3/S45678",
+    "B4678/S35678",
+];
+/// Layers hidden while the sandbox covers the window.
+const HIDDEN_LAYERS: [&str; 3] = ["universe", "walls", "draw"];
+
+pub struct SandboxPlugin;
+
+impl Plugin for SandboxPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RuleSandbox>()
+            .add_systems(Startup, setup_sandbox_tiles)
+            .add_systems(
+                Update,
+                (
+                    toggle_sandbox.in_set(SimulationInput),
+                    layout_tiles,
+                    step_tiles,
+                    render_tiles,
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SandboxMode {
+    /// The normal single universe is shown.
+    #[default]
+    Off,
+    /// One soup, a different rule per tile.
+    Rules,
+    /// One rule (Conway), a different soup per tile.
+    Seeds,
+}
+
+impl SandboxMode {
+    fn next(self) -> Self {
+        match self {
+            SandboxMode::Off => SandboxMode::Rules,
+            SandboxMode::Rules => SandboxMode::Seeds,
+            SandboxMode::Seeds => SandboxMode::Off,
+        }
+    }
+}
+
+struct SandboxTile {
+    label: String,
+    engine: Box<dyn LifeEngine>,
+    // Counted once per step rather than on every draw
+    population: u64,
+}
+
+/// A grid of small independent universes for comparing rules or seeds at a glance.
+#[derive(Resource, Default)]
+pub struct RuleSandbox {
+    pub mode: SandboxMode,
+    tiles: Vec<SandboxTile>,
+}
+
+impl RuleSandbox {
+    pub fn is_active(&self) -> bool {
+        self.mode != SandboxMode::Off
+    }
+
+    fn rebuild(&mut self, rng: &mut SimulationRng) {
+        let soup =
+            |seed: u64| SimulationRng::new(seed).soup(I64Vec2::ZERO, SOUP_SIZE, SOUP_DENSITY);
+        self.tiles = match self.mode {
+            SandboxMode::Off => Vec::new(),
+            SandboxMode::Rules => {
+                let seed = rng.rng().random();
+                let cells = soup(seed);
+                RULES
+                    .iter()
+                    .map(|rulestring| {
+                        let rule = LifeRule::parse(rulestring).expect("built-in rules are valid");
+                        SandboxTile::new(format!("{rule}\nseed {seed}"), rule, &cells)
+                    })
+                    .collect()
+            }
+            SandboxMode::Seeds => (0..TILE_COUNT)
+                .map(|_| {
+                    let seed = rng.rng().random();
+                    SandboxTile::new(
+                        format!("{}\nseed {seed}", LifeRule::CONWAY),
+                        LifeRule::CONWAY,
+                        &soup(seed),
+                    )
+                })
+                .collect(),
+        };
+    }
+}
+
+impl SandboxTile {
+    fn new(label: String, rule: LifeRule, cells: &[I64Vec2]) -> Self {
+        let mut engine = create_rule_engine(rule);
+        engine.import(cells);
+        Self {
+            label,
+            population: engine.population(),
+            engine,
+        }
+    }
+}
+
+/// Run condition for sandbox input that must not fire while the tiles cover the window.
+pub fn no_sandbox_running(sandbox: Res<RuleSandbox>) -> bool {
+    !sandbox.is_active()
+}
+
+#[derive(Component)]
+struct SandboxLayer(usize);
+
+#[derive(Component)]
+struct SandboxLabel(usize);
+
+fn setup_sandbox_tiles(
+    mut commands: Commands,
+    mut layers: LayerSpawner,
+    theme: Res<Theme>,
+    asset_server: Res<AssetServer>,
+) {
+    let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+
+    for i in 0..TILE_COUNT {
+        layers
+            .spawn(
+                LayerDesc::new(format!("sandbox_{i}"), 0.3)
+                    .colors(theme.universe_alive, theme.universe_dead)
+                    .shader(theme.universe_shader)
+                    .region(LayerRegion::Screen(Rect::new(0.0, 0.0, 1.0, 1.0)))
+                    .view(SimulationView::default())
+                    .hidden(),
+            )
+            .insert(SandboxLayer(i));
+
+        commands.spawn((
+            Text::default(),
+            TextFont {
+                font: font.clone(),
+                font_size: 14.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            Node {
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            GlobalZIndex(100),
+            Visibility::Hidden,
+            SandboxLabel(i),
+        ));
+    }
+}
+
+// X: cycle between the normal view, the rule comparison and the seed comparison
+fn toggle_sandbox(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut sandbox: ResMut<RuleSandbox>,
+    mut rng: ResMut<SimulationRng>,
+    mut q_layers: Query<(&mut PixelLayer, Option<&SandboxLayer>)>,
+    mut q_labels: Query<(&mut Visibility, &mut Text, &SandboxLabel)>,
+) {
+    if !keys.just_pressed(KeyCode::KeyX) {
+        return;
+    }
+
+    sandbox.mode = sandbox.mode.next();
+    sandbox.rebuild(&mut rng);
+
+    let active = sandbox.is_active();
+    for (mut layer, tile) in &mut q_layers {
+        if tile.is_some() {
+            layer.visible = active;
+        } else if HIDDEN_LAYERS.contains(&layer.name.as_str()) {
+            layer.visible = !active;
+        }
+    }
+    for (mut visibility, mut text, label) in &mut q_labels {
+        *visibility = if active {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+        if let Some(tile) = sandbox.tiles.get(label.0) {
+            **text = tile.label.clone();
+        }
+    }
+}
+
+/// Screen rectangle (logical pixels, origin top-left) of tile `i`.
+fn tile_rect(window: &Window, i: usize) -> Rect {
+    let tile_w = (window.width() - GAP * (COLUMNS + 1) as f32) / COLUMNS as f32;
+    let tile_h = (window.height() - GAP * (ROWS + 1) as f32) / ROWS as f32;
+    let (column, row) = (i % COLUMNS, i / COLUMNS);
+    let min = Vec2::new(
+        GAP + column as f32 * (tile_w + GAP),
+        GAP + row as f32 * (tile_h + GAP),
+    );
+    Rect::from_corners(min, min + Vec2::new(tile_w, tile_h).max(Vec2::ONE))
+}
+
+fn layout_tiles(
+    sandbox: Res<RuleSandbox>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut q_layers: Query<(&mut PixelLayer, &SandboxLayer)>,
+    mut q_labels: Query<(&mut Node, &SandboxLabel)>,
+) {
+    if !sandbox.is_active() {
+        return;
+    }
+    let Ok(window) = q_window.single() else {
+        return;
+    };
+
+    for (mut layer, tile) in &mut q_layers {
+        let rect = tile_rect(window, tile.0);
+        if layer.region != LayerRegion::Screen(rect) {
+            layer.region = LayerRegion::Screen(rect);
+            layer.view = Some(SimulationView {
+                center: DVec2::ZERO,
+                zoom: (rect.width() as f64 / TILE_WORLD_SIZE).max(0.1),
+            });
+        }
+    }
+    for (mut node, label) in &mut q_labels {
+        let rect = tile_rect(window, label.0);
+        node.left = Val::Px(rect.min.x + 4.0);
+        node.top = Val::Px(rect.min.y + 2.0);
+    }
+}
+
+fn step_tiles(mut sandbox: ResMut<RuleSandbox>) {
+    if !sandbox.is_active() {
+        return;
+    }
+    let _span = info_span!("sandbox::step", tiles = sandbox.tiles.len()).entered();
+    sandbox.tiles.par_iter_mut().for_each(|tile| {
+        tile.engine.step(1);
+        tile.population = tile.engine.population();
+    });
+}
+
+fn render_tiles(
+    sandbox: Res<RuleSandbox>,
+    view: Res<SimulationView>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut q_layers: Query<(&PixelLayer, &mut LayerCanvas, &SandboxLayer)>,
+) {
+    if !sandbox.is_active() {
+        return;
+    }
+    let Ok(window) = q_window.single() else {
+        return;
+    };
+
+    for (layer, mut canvas, tile) in &mut q_layers {
+        let Some(tile) = sandbox.tiles.get(tile.0) else {
+            continue;
+        };
+        let Some(viewport) = LayerViewport::new(window, &view, layer) else {
+            continue;
+        };
+        let (width, height) = (viewport.screen_w, viewport.screen_h);
+        tile.engine.draw_to_buffer(
+            viewport.get_world_rect(),
+            canvas.buffer(&viewport),
+            width,
+            height,
+            tile.population,
+        );
+    }
+}
@@ -0,0 +1,272 @@
+use std::f64::consts::TAU;
+use std::fmt;
+
+use bevy::math::{DVec2, I64Vec2};
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::simulation::SimulationInput;
+use crate::simulation::engine::cell_hash;
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::universe::Universe;
+use crate::simulation::view::SimulationView;
+
+/// Largest side length (in cells) the random fill will cover.
+const MAX_FILL_SIZE: i64 = 512;
+/// Cells per lattice step of the blob noise; about the size of one blob.
+const BLOB_SCALE: f64 = 12.0;
+/// Width of one stripe or ring, in cells.
+const BAND_WIDTH: f64 = 6.0;
+
+pub struct SeededRngPlugin;
+
+impl Plugin for SeededRngPlugin {
+    fn build(&self, app: &mut App) {
+        let seed = seed_from_args().unwrap_or_else(rand::random);
+        info!("Using seed {seed} (pass --seed {seed} to reproduce this run)");
+
+        app.insert_resource(SimulationRng::new(seed))
+            .init_resource::<SoupNoise>()
+            .add_systems(Startup, (show_seed, seed_universe))
+            .add_systems(
+                Update,
+                (
+                    cycle_noise.in_set(SimulationInput),
+                    spawn_soup.in_set(SimulationInput),
+                    random_fill.in_set(SimulationInput),
+                ),
+            );
+    }
+}
+
+/// The single source of randomness for the simulation.
+/// Everything random (soups, fills, stochastic rules) draws from here so that
+/// the same `--seed` reproduces the same run.
+#[derive(Resource)]
+pub struct SimulationRng {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl SimulationRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn rng(&mut self) -> &mut StdRng {
+        &mut self.rng
+    }
+
+    /// Derives an independent generator, e.g. for work running off the main thread.
+    /// Forks taken in the same order always produce the same streams.
+    #[allow(unused)]
+    pub fn fork(&mut self) -> StdRng {
+        StdRng::seed_from_u64(self.rng.random())
+    }
+
+    /// Random cells inside the square of `size` cells centered on `center`.
+    pub fn soup(&mut self, center: I64Vec2, size: i64, density: f64) -> Vec<I64Vec2> {
+        self.noise_soup(center, size, density, SoupNoise::Uniform)
+    }
+
+    /// Like [`SimulationRng::soup`], with `noise` deciding where the cells go.
+    pub fn noise_soup(
+        &mut self,
+        center: I64Vec2,
+        size: i64,
+        density: f64,
+        noise: SoupNoise,
+    ) -> Vec<I64Vec2> {
+        // Per soup, so no two soups share their blobs or stripe direction
+        let salt: u64 = self.rng.random();
+        let direction = DVec2::from_angle(self.rng.random_range(0.0..TAU));
+        let half = size / 2;
+        let mut cells = Vec::new();
+        for y in -half..size - half {
+            for x in -half..size - half {
+                let local = DVec2::new(x as f64, y as f64);
+                let p = match noise {
+                    SoupNoise::Uniform => density,
+                    SoupNoise::Blobs if gradient_noise(local / BLOB_SCALE, salt) > 0.1 => density,
+                    SoupNoise::Blobs => 0.0,
+                    SoupNoise::Stripes if banded(local.dot(direction)) => density,
+                    SoupNoise::Rings if banded(local.length()) => density,
+                    SoupNoise::Stripes | SoupNoise::Rings => 0.0,
+                    // Empty at one edge, twice as dense at the other
+                    SoupNoise::Gradient => {
+                        let t = local.dot(direction) / size as f64 + 0.5;
+                        density * 2.0 * t
+                    }
+                };
+                if self.rng.random_bool(p.clamp(0.0, 1.0)) {
+                    cells.push(center + I64Vec2::new(x, y));
+                }
+            }
+        }
+        cells
+    }
+}
+
+/// How soups decide where their cells go. Uniform soups are the classic; the others
+/// give structure to start from.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SoupNoise {
+    /// Every cell is alive with the same probability.
+    #[default]
+    Uniform,
+    /// Blobs where smooth gradient (Perlin) noise is above a threshold, empty between.
+    Blobs,
+    /// Parallel bands in a random direction.
+    Stripes,
+    /// Concentric rings around the center.
+    Rings,
+    /// Density rising across the soup in a random direction.
+    Gradient,
+}
+
+impl SoupNoise {
+    pub fn next(self) -> Self {
+        match self {
+            SoupNoise::Uniform => SoupNoise::Blobs,
+            SoupNoise::Blobs => SoupNoise::Stripes,
+            SoupNoise::Stripes => SoupNoise::Rings,
+            SoupNoise::Rings => SoupNoise::Gradient,
+            SoupNoise::Gradient => SoupNoise::Uniform,
+        }
+    }
+}
+
+impl fmt::Display for SoupNoise {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SoupNoise::Uniform => "uniform",
+            SoupNoise::Blobs => "blobs",
+            SoupNoise::Stripes => "stripes",
+            SoupNoise::Rings => "rings",
+            SoupNoise::Gradient => "gradient",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Whether `distance` falls into every other band of [`BAND_WIDTH`].
+fn banded(distance: f64) -> bool {
+    (distance / BAND_WIDTH).floor().rem_euclid(2.0) == 0.0
+}
+
+/// 2D Perlin noise, roughly in -1..1: random unit gradients on the integer lattice
+/// (picked by hashing the lattice point shifted by `salt`), blended with a smoothstep.
+fn gradient_noise(p: DVec2, salt: u64) -> f64 {
+    let corner = p.floor();
+    let f = p - corner;
+    let gradient = |offset: DVec2| {
+        let lattice = (corner + offset).as_i64vec2() + I64Vec2::new(salt as i64 & 0xFFFF_FFFF, 0);
+        let angle = (cell_hash(lattice) >> 11) as f64 / (1u64 << 53) as f64;
+        DVec2::from_angle(angle * TAU).dot(f - offset)
+    };
+    let fade = |t: f64| t * t * t * (t * (t * 6.0 - 15.0) + 10.0);
+    let (u, v) = (fade(f.x), fade(f.y));
+    let bottom = gradient(DVec2::new(0.0, 0.0)) * (1.0 - u) + gradient(DVec2::new(1.0, 0.0)) * u;
+    let top = gradient(DVec2::new(0.0, 1.0)) * (1.0 - u) + gradient(DVec2::new(1.0, 1.0)) * u;
+    (bottom * (1.0 - v) + top * v) * std::f64::consts::SQRT_2
+}
+
+/// Reads `--seed N` or `--seed=N` from the command line.
+fn seed_from_args() -> Option<u64> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = if arg == "--seed" {
+            args.next()
+        } else if let Some(value) = arg.strip_prefix("--seed=") {
+            Some(value.to_string())
+        } else {
+            continue;
+        };
+
+        match value.as_deref().map(str::parse::<u64>) {
+            Some(Ok(seed)) => return Some(seed),
+            _ => warn!("Ignoring invalid --seed value {value:?}"),
+        }
+    }
+    None
+}
+
+fn show_seed(rng: Res<SimulationRng>, mut stats: ResMut<StatsBoard>) {
+    stats.insert("Seed", rng.seed());
+}
+
+fn seed_universe(mut rng: ResMut<SimulationRng>, mut universe: ResMut<Universe>) {
+    let engine_seed = rng.rng().random();
+    universe.set_seed(engine_seed);
+}
+
+/// The cell under the middle of the screen.
+pub fn view_center(view: &SimulationView) -> I64Vec2 {
+    I64Vec2::new(view.center.x.floor() as i64, view.center.y.floor() as i64)
+}
+
+// Shift+R: next soup noise
+fn cycle_noise(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut noise: ResMut<SoupNoise>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    if keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight])
+        && keys.just_pressed(KeyCode::KeyR)
+    {
+        *noise = noise.next();
+    }
+    if noise.is_changed() {
+        stats.insert("Soup Noise", *noise);
+    }
+}
+
+// R: drop a 64x64 soup at the center of the view
+fn spawn_soup(
+    keys: Res<ButtonInput<KeyCode>>,
+    view: Res<SimulationView>,
+    noise: Res<SoupNoise>,
+    mut rng: ResMut<SimulationRng>,
+    mut universe: ResMut<Universe>,
+) {
+    if !keys.just_pressed(KeyCode::KeyR)
+        || keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight])
+    {
+        return;
+    }
+
+    let cells = rng.noise_soup(view_center(&view), 64, 0.5, *noise);
+    universe.add_cells(cells);
+}
+
+// F: randomly fill everything currently on screen
+fn random_fill(
+    keys: Res<ButtonInput<KeyCode>>,
+    view: Res<SimulationView>,
+    noise: Res<SoupNoise>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut rng: ResMut<SimulationRng>,
+    mut universe: ResMut<Universe>,
+) {
+    if !keys.just_pressed(KeyCode::KeyF) {
+        return;
+    }
+    let Ok(window) = q_window.single() else {
+        return;
+    };
+
+    let visible = window.width().max(window.height()) as f64 / view.zoom;
+    let size = (visible.ceil() as i64).clamp(1, MAX_FILL_SIZE);
+
+    let cells = rng.noise_soup(view_center(&view), size, 0.25, *noise);
+    universe.add_cells(cells);
+}
@@ -0,0 +1,171 @@
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::simulation::SimulationInput;
+use crate::simulation::engine::CellRegion;
+use crate::simulation::graphics::{
+    LayerCanvas, LayerDesc, LayerSpawner, LayerViewport, PixelLayer,
+};
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::universe::Universe;
+use crate::simulation::view::{MouseWorldPosition, SimulationView};
+
+const OUTLINE: u8 = 1;
+const ISOLATED: u8 = 2;
+
+pub struct SelectionPlugin;
+
+impl Plugin for SelectionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Selection>()
+            .add_systems(Startup, setup_selection_layer)
+            .add_systems(
+                Update,
+                (
+                    drag_selection.in_set(SimulationInput),
+                    toggle_isolation.in_set(SimulationInput),
+                    render_selection,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Rectangle of cells picked with Shift + drag.
+#[derive(Resource, Default)]
+pub struct Selection {
+    pub region: Option<CellRegion>,
+    drag_start: Option<I64Vec2>,
+}
+
+/// Run condition for drawing input that must not fire while Shift picks a selection.
+pub fn no_selection_input(keys: Res<ButtonInput<KeyCode>>) -> bool {
+    !shift_held(&keys)
+}
+
+fn shift_held(keys: &ButtonInput<KeyCode>) -> bool {
+    keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight])
+}
+
+#[derive(Component)]
+struct SelectionLayer;
+
+fn setup_selection_layer(mut layers: LayerSpawner) {
+    layers
+        .spawn(
+            LayerDesc::new("selection", 0.15)
+                .palette([
+                    Vec4::ZERO,
+                    Vec4::new(0.9, 0.9, 0.9, 0.8),
+                    Vec4::new(1.0, 0.6, 0.1, 0.9),
+                ])
+                .hidden(),
+        )
+        .insert(SelectionLayer);
+}
+
+// Shift + drag: select a rectangle, Shift + click: drop the selection
+fn drag_selection(
+    keys: Res<ButtonInput<KeyCode>>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    mouse_res: Res<MouseWorldPosition>,
+    mut selection: ResMut<Selection>,
+) {
+    let Some(pos) = mouse_res.grid_pos else {
+        return;
+    };
+
+    if buttons.just_pressed(MouseButton::Left) && shift_held(&keys) {
+        selection.drag_start = Some(pos);
+        selection.region = None;
+        return;
+    }
+    let Some(start) = selection.drag_start else {
+        return;
+    };
+
+    if buttons.pressed(MouseButton::Left) {
+        let region = (pos != start).then(|| CellRegion::from_corners(start, pos));
+        if selection.region != region {
+            selection.region = region;
+        }
+    } else {
+        selection.drag_start = None;
+    }
+}
+
+// I: step only the selection, keeping the rest of the universe frozen
+fn toggle_isolation(
+    keys: Res<ButtonInput<KeyCode>>,
+    selection: Res<Selection>,
+    mut universe: ResMut<Universe>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    let isolated = universe.step_region().is_some();
+    if keys.just_pressed(KeyCode::KeyI) {
+        let region = if isolated { None } else { selection.region };
+        universe.set_step_region(region);
+    } else if isolated && selection.is_changed() && universe.step_region() != selection.region {
+        // Follow the selection while it is being edited
+        universe.set_step_region(selection.region);
+    } else if !selection.is_changed() {
+        return;
+    }
+
+    match selection.region {
+        Some(region) => stats.insert(
+            "Selection",
+            format!(
+                "{}x{}{}",
+                region.size().x,
+                region.size().y,
+                if universe.step_region().is_some() {
+                    " (only this runs)"
+                } else {
+                    ""
+                }
+            ),
+        ),
+        None => stats.remove("Selection"),
+    }
+}
+
+fn render_selection(
+    selection: Res<Selection>,
+    universe: Res<Universe>,
+    view: Res<SimulationView>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut q_layer: Query<(&mut PixelLayer, &mut LayerCanvas), With<SelectionLayer>>,
+) {
+    let Ok((mut layer, mut canvas)) = q_layer.single_mut() else {
+        return;
+    };
+    if layer.visible != selection.region.is_some() {
+        layer.visible = selection.region.is_some();
+    }
+    let Some(region) = selection.region else {
+        return;
+    };
+    let Ok(window) = q_window.single() else {
+        return;
+    };
+    let Some(viewport) = LayerViewport::new(window, &view, &layer) else {
+        return;
+    };
+    let buffer = canvas.buffer(&viewport);
+    buffer.fill(0);
+
+    let color = if universe.step_region() == Some(region) {
+        ISOLATED
+    } else {
+        OUTLINE
+    };
+
+    // One cell thick frame just outside the region
+    let (min, size) = (region.min, region.size());
+    viewport.draw_rect(buffer, min.x - 1, min.y - 1, size.x + 2, 1, color);
+    viewport.draw_rect(buffer, min.x - 1, region.max.y, size.x + 2, 1, color);
+    viewport.draw_rect(buffer, min.x - 1, min.y, 1, size.y, color);
+    viewport.draw_rect(buffer, region.max.x, min.y, 1, size.y, color);
+}
@@ -0,0 +1,226 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+
+use crate::simulation::SimulationInput;
+use crate::simulation::batch::arg_value;
+use crate::simulation::universe::{PatternLoaded, Universe};
+
+/// Tallies a session: generations simulated, the highest population, how long each
+/// engine ran and the patterns loaded. F7 shows the summary (with the `ui` feature) and
+/// appends it to the session log, as does quitting. The log is `--session-log PATH`,
+/// `life.rs-sessions.log` in the temp directory by default. Desktop only.
+pub struct SessionPlugin;
+
+impl Plugin for SessionPlugin {
+    fn build(&self, app: &mut App) {
+        let log = arg_value("--session-log").map_or_else(
+            || std::env::temp_dir().join("life.rs-sessions.log"),
+            PathBuf::from,
+        );
+        app.insert_resource(Session::new(log))
+            .add_systems(
+                Update,
+                (tally_session, show_summary.in_set(SimulationInput)).chain(),
+            )
+            .add_systems(Last, log_on_exit);
+        #[cfg(feature = "ui")]
+        app.add_systems(Startup, setup_summary_panel)
+            .add_systems(Update, update_summary_panel.after(show_summary));
+    }
+}
+
+#[derive(Resource)]
+pub struct Session {
+    pub log: PathBuf,
+    started: Instant,
+    generations: u64,
+    max_population: u64,
+    // Time spent running (not paused), by engine name
+    engine_time: BTreeMap<String, Duration>,
+    patterns: Vec<String>,
+    // Generation seen last frame; loads and history jumps move it without simulating
+    last_generation: u64,
+    shown: bool,
+}
+
+impl Session {
+    fn new(log: PathBuf) -> Self {
+        Self {
+            log,
+            started: Instant::now(),
+            generations: 0,
+            max_population: 0,
+            engine_time: BTreeMap::new(),
+            patterns: Vec::new(),
+            last_generation: 0,
+            shown: false,
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "Session of {}",
+            format_duration(self.started.elapsed())
+        );
+        let _ = writeln!(out, "Generations simulated: {}", self.generations);
+        let _ = writeln!(out, "Max population: {}", self.max_population);
+        if self.engine_time.is_empty() {
+            let _ = writeln!(out, "Running time: none");
+        } else {
+            let _ = writeln!(out, "Running time:");
+            for (engine, time) in &self.engine_time {
+                let _ = writeln!(out, "  {engine}: {}", format_duration(*time));
+            }
+        }
+        let _ = writeln!(out, "Patterns loaded: {}", self.patterns.len());
+        for name in &self.patterns {
+            let _ = writeln!(out, "  {name}");
+        }
+        out
+    }
+
+    fn append_to_log(&self, reason: &str) {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let written = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.log)
+            .and_then(|mut file| {
+                write!(
+                    file,
+                    "--- {reason} at {stamp} (Unix time)\n{}\n",
+                    self.summary()
+                )
+            });
+        match written {
+            Ok(()) => info!("Session summary written to {}", self.log.display()),
+            Err(err) => warn!("Couldn't write {}: {err}", self.log.display()),
+        }
+    }
+}
+
+/// `1h 02m 03s`, `2m 03s` or `3s`.
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+    match (hours, minutes) {
+        (0, 0) => format!("{seconds}s"),
+        (0, _) => format!("{minutes}m {seconds:02}s"),
+        _ => format!("{hours}h {minutes:02}m {seconds:02}s"),
+    }
+}
+
+fn tally_session(
+    time: Res<Time<Real>>,
+    universe: Res<Universe>,
+    mut loaded: MessageReader<PatternLoaded>,
+    mut session: ResMut<Session>,
+) {
+    let session = session.bypass_change_detection();
+    let generation = universe.generation();
+    if generation > session.last_generation {
+        session.generations += generation - session.last_generation;
+    }
+    session.last_generation = generation;
+    session.max_population = session.max_population.max(universe.population());
+    if !universe.paused {
+        *session
+            .engine_time
+            .entry(universe.engine_name())
+            .or_default() += time.delta();
+    }
+    session
+        .patterns
+        .extend(loaded.read().map(|pattern| pattern.name.clone()));
+}
+
+// F7: show or hide the summary; showing it also logs it
+fn show_summary(keys: Res<ButtonInput<KeyCode>>, mut session: ResMut<Session>) {
+    if !keys.just_pressed(KeyCode::F7) {
+        return;
+    }
+    session.shown = !session.shown;
+    if session.shown {
+        session.append_to_log("Summary");
+    }
+}
+
+fn log_on_exit(mut exits: MessageReader<AppExit>, session: Res<Session>) {
+    if exits.read().next().is_none() {
+        return;
+    }
+    info!("Session summary:\n{}", session.summary());
+    session.append_to_log("Exit");
+}
+
+#[cfg(feature = "ui")]
+#[derive(Component)]
+struct SummaryPanel;
+
+#[cfg(feature = "ui")]
+fn setup_summary_panel(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(20.0),
+                left: Val::Percent(35.0),
+                min_width: Val::Percent(30.0),
+                padding: UiRect::all(Val::Px(16.0)),
+                ..default()
+            },
+            BackgroundColor(Color::BLACK.with_alpha(0.85)),
+            GlobalZIndex(120),
+            Visibility::Hidden,
+            SummaryPanel,
+        ))
+        .with_child((
+            Text::default(),
+            TextFont {
+                font,
+                font_size: 20.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+        ));
+}
+
+// Refreshed about once a second while shown; the tallies change every frame
+#[cfg(feature = "ui")]
+fn update_summary_panel(
+    session: Res<Session>,
+    mut refreshed: Local<Option<Instant>>,
+    mut q_panel: Query<(&mut Visibility, &Children), With<SummaryPanel>>,
+    mut q_text: Query<&mut Text>,
+) {
+    let Ok((mut visibility, children)) = q_panel.single_mut() else {
+        return;
+    };
+    if !session.shown {
+        visibility.set_if_neq(Visibility::Hidden);
+        return;
+    }
+    let stale = refreshed.is_none_or(|at| at.elapsed() >= Duration::from_secs(1));
+    if session.is_changed() || stale {
+        *refreshed = Some(Instant::now());
+        for &child in children {
+            if let Ok(mut text) = q_text.get_mut(child) {
+                text.0 = format!("{}\nF7 closes", session.summary());
+            }
+        }
+    }
+    visibility.set_if_neq(Visibility::Inherited);
+}
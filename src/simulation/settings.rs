@@ -0,0 +1,118 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+
+use crate::simulation::error::LifeError;
+
+/// Preferences kept between runs, such as whether the tutorial was finished. On the
+/// desktop they are `key = value` lines in `--settings PATH`, `life.rs/settings` in the
+/// user's config directory by default; the web build keeps the same lines in the
+/// browser's IndexedDB.
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        #[cfg(not(target_arch = "wasm32"))]
+        let settings = Settings::load(
+            crate::simulation::batch::arg_value("--settings")
+                .map(PathBuf::from)
+                .or_else(default_path),
+        );
+        #[cfg(target_arch = "wasm32")]
+        let settings = Settings {
+            values: parse(
+                &crate::simulation::browser_storage::get(
+                    crate::simulation::browser_storage::SETTINGS_KEY,
+                )
+                .unwrap_or_default(),
+            ),
+        };
+        app.insert_resource(settings);
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Settings {
+    #[cfg(not(target_arch = "wasm32"))]
+    path: Option<PathBuf>,
+    values: BTreeMap<String, String>,
+}
+
+impl Settings {
+    /// Reads the settings file at `path`; a missing or unreadable one gives defaults.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn load(path: Option<PathBuf>) -> Self {
+        let text = path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            values: parse(&text),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    /// Whether `key` is set to `true`.
+    pub fn flag(&self, key: &str) -> bool {
+        self.get(key) == Some("true")
+    }
+
+    /// Sets `key` and writes the settings out.
+    pub fn set(&mut self, key: &str, value: impl Display) -> Result<(), LifeError> {
+        self.values.insert(key.to_string(), value.to_string());
+        self.save()
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save(&self) -> Result<(), LifeError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let text = self.to_text();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|err| LifeError::io(dir, err))?;
+        }
+        std::fs::write(path, text).map_err(|err| LifeError::io(path, err))
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn save(&self) -> Result<(), LifeError> {
+        crate::simulation::browser_storage::put(
+            crate::simulation::browser_storage::SETTINGS_KEY,
+            self.to_text(),
+        );
+        Ok(())
+    }
+
+    fn to_text(&self) -> String {
+        self.values
+            .iter()
+            .map(|(key, value)| format!("{key} = {value}\n"))
+            .collect()
+    }
+}
+
+/// Reads `key = value` lines, skipping any without `=`.
+fn parse(text: &str) -> BTreeMap<String, String> {
+    text.lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect()
+}
+
+/// `life.rs/settings` in `$XDG_CONFIG_HOME`, `%APPDATA%` or `~/.config`.
+#[cfg(not(target_arch = "wasm32"))]
+fn default_path() -> Option<PathBuf> {
+    let config = std::env::var_os("XDG_CONFIG_HOME")
+        .or_else(|| std::env::var_os("APPDATA"))
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config.join("life.rs").join("settings"))
+}
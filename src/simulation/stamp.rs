@@ -0,0 +1,237 @@
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::simulation::SimulationInput;
+use crate::simulation::clipboard::Clipboard;
+use crate::simulation::engine::create_rule_engine;
+use crate::simulation::graphics::{
+    LayerCanvas, LayerDesc, LayerSpawner, LayerViewport, PixelLayer,
+};
+use crate::simulation::period::PeriodDetector;
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::universe::Universe;
+use crate::simulation::view::{MouseWorldPosition, SimulationView};
+
+/// Longest period whose phases can be cycled through.
+const MAX_PHASES: u64 = 256;
+
+const GHOST: u8 = 1;
+const FOOTPRINT: u8 = 2;
+
+/// S toggles stamp mode: the clipboard follows the mouse as a ghost of what Ctrl+V would
+/// paste. Q turns it a quarter clockwise, U mirrors it and / cycles through its phases
+/// (the pattern stepped on its own), so oscillators and ships go down in the right phase.
+pub struct StampPlugin;
+
+impl Plugin for StampPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Stamp>()
+            .add_systems(Startup, setup_stamp_layer)
+            .add_systems(
+                Update,
+                (stamp_input.in_set(SimulationInput), render_stamp).chain(),
+            );
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Stamp {
+    pub active: bool,
+    /// Quarter turns clockwise, applied after mirroring.
+    pub rotation: u8,
+    pub mirrored: bool,
+    pub phase: usize,
+    // The clipboard as copied, and its phases once they were asked for
+    original: Vec<I64Vec2>,
+    copied_at: u64,
+    phases: Vec<Vec<I64Vec2>>,
+    // What was last written into the clipboard, to tell new copies apart
+    written: Vec<I64Vec2>,
+}
+
+#[derive(Component)]
+struct StampLayer;
+
+fn setup_stamp_layer(mut layers: LayerSpawner) {
+    layers
+        .spawn(
+            LayerDesc::new("stamp", 0.11)
+                .palette([
+                    Vec4::ZERO,
+                    Vec4::new(1.0, 1.0, 1.0, 0.7),
+                    Vec4::new(0.5, 0.8, 1.0, 0.15),
+                ])
+                .hidden(),
+        )
+        .insert(StampLayer);
+}
+
+// S: toggle stamp mode, Q: rotate, U: mirror, /: next phase
+fn stamp_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    universe: Res<Universe>,
+    mut stamp: ResMut<Stamp>,
+    mut clipboard: ResMut<Clipboard>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if keys.just_pressed(KeyCode::KeyS) && !ctrl {
+        stamp.active = !stamp.active;
+        if !stamp.active {
+            stats.remove("Stamp");
+        }
+    }
+    if clipboard.is_changed() && clipboard.cells != stamp.written {
+        // A fresh copy starts out as it was copied
+        *stamp = Stamp {
+            active: stamp.active,
+            original: clipboard.cells.clone(),
+            copied_at: clipboard.copied_at,
+            written: clipboard.cells.clone(),
+            ..default()
+        };
+    }
+    if !stamp.active || clipboard.cells.is_empty() {
+        return;
+    }
+
+    let mut changed = false;
+    if keys.just_pressed(KeyCode::KeyQ) {
+        stamp.rotation = (stamp.rotation + 1) % 4;
+        changed = true;
+    }
+    if keys.just_pressed(KeyCode::KeyU) {
+        stamp.mirrored = !stamp.mirrored;
+        changed = true;
+    }
+    if keys.just_pressed(KeyCode::Slash) {
+        if stamp.phases.is_empty() {
+            stamp.phases = phases(&stamp.original, &universe);
+        }
+        stamp.phase = (stamp.phase + 1) % stamp.phases.len();
+        changed = true;
+    }
+
+    if changed {
+        let base = stamp.phases.get(stamp.phase).unwrap_or(&stamp.original);
+        let (cells, size) = orient(base, stamp.rotation, stamp.mirrored);
+        clipboard.cells = cells.clone();
+        clipboard.size = size;
+        // Pasting phase n of a pattern is like having copied it n generations later
+        clipboard.copied_at = stamp.copied_at + stamp.phase as u64;
+        stamp.written = cells;
+    }
+    if changed || !stats.contains("Stamp") {
+        let phases = match stamp.phases.len() {
+            0 => "/ for phases".to_string(),
+            1 => "no other phases".to_string(),
+            n => format!("phase {}/{n}", stamp.phase + 1),
+        };
+        stats.insert(
+            "Stamp",
+            format!(
+                "{}°{}, {phases}",
+                stamp.rotation as u32 * 90,
+                if stamp.mirrored { " mirrored" } else { "" }
+            ),
+        );
+    }
+}
+
+/// The pattern in each generation until it repeats, each relative to its own corner.
+/// Just the pattern itself if it doesn't repeat within [`MAX_PHASES`] generations.
+fn phases(cells: &[I64Vec2], universe: &Universe) -> Vec<Vec<I64Vec2>> {
+    let mut engine = create_rule_engine(universe.rule());
+    engine.import(cells);
+    let mut detector = PeriodDetector::default();
+    let mut phases = Vec::new();
+    for _ in 0..=MAX_PHASES {
+        let cells = engine.export();
+        detector.observe(engine.generation(), &cells);
+        if let Some(periodicity) = detector.detected {
+            phases.truncate(periodicity.period as usize);
+            return phases;
+        }
+        phases.push(normalized(cells));
+        engine.step(1);
+    }
+    vec![cells.to_vec()]
+}
+
+/// `cells` mirrored and then turned `rotation` quarters clockwise, moved back to the
+/// origin, with their new size.
+fn orient(cells: &[I64Vec2], rotation: u8, mirrored: bool) -> (Vec<I64Vec2>, I64Vec2) {
+    let turned: Vec<I64Vec2> = cells
+        .iter()
+        .map(|&pos| {
+            let pos = if mirrored {
+                I64Vec2::new(-pos.x, pos.y)
+            } else {
+                pos
+            };
+            // y points up, so clockwise takes +x to -y
+            (0..rotation).fold(pos, |p, _| I64Vec2::new(p.y, -p.x))
+        })
+        .collect();
+    let size = turned
+        .iter()
+        .copied()
+        .reduce(I64Vec2::max)
+        .zip(turned.iter().copied().reduce(I64Vec2::min))
+        .map_or(I64Vec2::ZERO, |(max, min)| max - min + I64Vec2::ONE);
+    (normalized(turned), size)
+}
+
+fn normalized(mut cells: Vec<I64Vec2>) -> Vec<I64Vec2> {
+    if let Some(min) = cells.iter().copied().reduce(I64Vec2::min) {
+        for pos in &mut cells {
+            *pos -= min;
+        }
+    }
+    cells
+}
+
+fn render_stamp(
+    stamp: Res<Stamp>,
+    clipboard: Res<Clipboard>,
+    mouse_res: Res<MouseWorldPosition>,
+    view: Res<SimulationView>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut q_layer: Query<(&mut PixelLayer, &mut LayerCanvas), With<StampLayer>>,
+) {
+    let Ok((mut layer, mut canvas)) = q_layer.single_mut() else {
+        return;
+    };
+    let target = mouse_res
+        .grid_pos
+        .filter(|_| (stamp.active || clipboard.attached) && !clipboard.cells.is_empty());
+    if layer.visible != target.is_some() {
+        layer.visible = target.is_some();
+    }
+    let Some(pos) = target else {
+        return;
+    };
+    let Ok(window) = q_window.single() else {
+        return;
+    };
+    let Some(viewport) = LayerViewport::new(window, &view, &layer) else {
+        return;
+    };
+    let buffer = canvas.buffer(&viewport);
+    buffer.fill(0);
+
+    let (region, cells) = clipboard.placed_at(clipboard.snap(pos));
+    let size = region.size();
+    viewport.draw_rect(
+        buffer,
+        region.min.x,
+        region.min.y,
+        size.x,
+        size.y,
+        FOOTPRINT,
+    );
+    for cell in cells {
+        viewport.draw_cell(buffer, cell.x, cell.y, GHOST);
+    }
+}
@@ -2,6 +2,9 @@ use std::{collections::BTreeMap, fmt::Display};
 
 use bevy::prelude::*;
 
+#[cfg(feature = "ui")]
+use crate::simulation::locale::Localization;
+
 #[derive(Resource, Default)]
 pub struct StatsBoard {
     data: BTreeMap<String, String>,
@@ -21,6 +24,16 @@ impl StatsBoard {
         self.data.remove(key);
     }
 
+    /// Whether a stat is currently shown.
+    pub fn contains(&self, key: &str) -> bool {
+        self.data.contains_key(key)
+    }
+
+    /// All stats in display order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.data.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
     #[allow(unused)]
     /// Clear all stats
     pub fn clear(&mut self) {
@@ -32,16 +45,23 @@ pub struct StatsBoardPlugin;
 
 impl Plugin for StatsBoardPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<StatsBoard>()
-            .add_systems(Startup, setup_stats_ui)
+        app.init_resource::<StatsBoard>();
+        #[cfg(feature = "ui")]
+        app.add_systems(Startup, setup_stats_ui)
             .add_systems(Update, update_stats_display);
     }
 }
 
+#[cfg(feature = "ui")]
 #[derive(Component)]
 struct StatsText;
 
-fn setup_stats_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
+#[cfg(feature = "ui")]
+fn setup_stats_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    locale: Res<Localization>,
+) {
     let font = asset_server.load("fonts/FiraSans-Bold.ttf");
 
     commands
@@ -58,7 +78,7 @@ fn setup_stats_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
         ))
         .with_children(|parent| {
             parent.spawn((
-                Text::new("Initializing Stats..."),
+                Text::new(locale.get("stats-loading")),
                 TextFont {
                     font,
                     font_size: 20.0,
@@ -70,17 +90,22 @@ fn setup_stats_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
         });
 }
 
-fn update_stats_display(board: Res<StatsBoard>, mut query: Query<&mut Text, With<StatsText>>) {
+#[cfg(feature = "ui")]
+fn update_stats_display(
+    board: Res<StatsBoard>,
+    locale: Res<Localization>,
+    mut query: Query<&mut Text, With<StatsText>>,
+) {
     if board.is_changed() {
         for mut text in &mut query {
             if board.data.is_empty() {
-                **text = "No Stats".to_string();
+                **text = locale.get("stats-empty").to_string();
             } else {
                 // Build a single string: "Key: Value\nKey2: Value2"
                 let mut output = String::new();
                 for (key, value) in &board.data {
                     use std::fmt::Write; // Allow write! macro on String
-                    let _ = writeln!(output, "{}: {}", key, value);
+                    let _ = writeln!(output, "{}: {}", locale.label("stat", key), value);
                 }
                 // Update the Text component
                 **text = output;
@@ -0,0 +1,269 @@
+//! Stepping off the page's thread. Browsers give the web build a single thread, where
+//! rayon can't help and a long step stalls drawing and input. A module worker loads
+//! this same build and keeps its own copy of the engine: the page sends the cells when
+//! they changed other than by stepping, the worker steps and hands back the new cells,
+//! which are swapped in like a pipelined step's result. Cells travel as a
+//! [`BlockDiff`], so this needs no `SharedArrayBuffer` and works on any host.
+//!
+//! Only engines whose whole state is their live cells are stepped this way, and only
+//! without walls or a frozen region; everything else, and every step after the worker
+//! failed, runs on the page as before.
+
+use std::cell::{Cell, RefCell};
+
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{DedicatedWorkerGlobalScope, MessageEvent, Worker};
+
+use crate::simulation::codec::{BlockDiff, collect_tiles};
+use crate::simulation::engine::{EngineRegistry, LifeEngine, create_rule_engine};
+use crate::simulation::rules::LifeRule;
+use crate::simulation::universe::Universe;
+
+/// The script wasm-bindgen generates next to `index.html`; the worker imports it too.
+const GLUE_SCRIPT: &str = "index.js";
+
+/// Engines whose cells are all there is to them. The continuous ones keep levels the
+/// cells don't show, and LargerThanLife its own range rule.
+const OFFLOADED: [&str; 4] = ["arena-life", "sparse-life", "hash-life", "stochastic-life"];
+
+thread_local! {
+    // Spawned on the first step that can use it
+    static WORKER: RefCell<Option<Worker>> = const { RefCell::new(None) };
+    // Set once the worker couldn't start or broke; the page steps from then on
+    static FAILED: Cell<bool> = const { Cell::new(false) };
+    // Revision and generation of the universe the worker's engine holds
+    static SYNCED: Cell<Option<(u64, u64)>> = const { Cell::new(None) };
+    // Settles the reply of the step in flight
+    static REPLY: RefCell<Option<(js_sys::Function, js_sys::Function)>> =
+        const { RefCell::new(None) };
+}
+
+/// A step to run on the worker, prepared on the page.
+pub struct StepJob {
+    steps: u64,
+    // What the worker needs to start over, if its engine is out of date
+    state: Option<js_sys::Object>,
+    // The engine the result goes into
+    blank: Box<dyn LifeEngine>,
+}
+
+impl StepJob {
+    /// A job for the next `steps` generations, or `None` if they have to be stepped on
+    /// the page.
+    pub fn prepare(universe: &Universe, steps: u64) -> Option<Self> {
+        let id = universe.engine_id();
+        if FAILED.get()
+            || !OFFLOADED.contains(&id.as_str())
+            || universe.step_region().is_some()
+            || universe.walls().next().is_some()
+        {
+            return None;
+        }
+
+        let current = (universe.revision(), universe.generation());
+        let state = (SYNCED.get() != Some(current)).then(|| {
+            let engine = universe.read_engine();
+            let diff = BlockDiff {
+                generation: engine.generation(),
+                tiles: collect_tiles(engine.as_ref()).into_iter().collect(),
+            };
+            let state = js_sys::Object::new();
+            set(&state, "engine", &id);
+            set(&state, "rule", &universe.rule().to_string());
+            set(&state, "seed", &universe.seed().to_string());
+            if let Some(size) = universe.torus() {
+                set(&state, "torus", &format!("{} {}", size.x, size.y));
+            }
+            let cells = js_sys::Uint8Array::from(diff.encode().as_slice());
+            let _ = js_sys::Reflect::set(&state, &"cells".into(), &cells);
+            state
+        });
+        // Unless the state changes meanwhile, the worker holds the result next time
+        SYNCED.set(Some((current.0, current.1 + steps)));
+
+        Some(Self {
+            steps,
+            state,
+            blank: universe.blank_engine(),
+        })
+    }
+
+    /// Steps on the worker, returning the stepped engine and its population. `None`
+    /// if the worker isn't there or broke, in which case the caller steps itself.
+    pub async fn run(self) -> Option<(Box<dyn LifeEngine>, u64)> {
+        match self.exchange().await {
+            Ok(done) => Some(done),
+            Err(err) => {
+                warn!("Stepping on the page from now on, the step worker failed: {err:?}");
+                FAILED.set(true);
+                SYNCED.set(None);
+                None
+            }
+        }
+    }
+
+    async fn exchange(self) -> Result<(Box<dyn LifeEngine>, u64), JsValue> {
+        let worker = worker()?;
+        let request = self.state.unwrap_or_else(js_sys::Object::new);
+        set(&request, "steps", &self.steps.to_string());
+
+        let reply = js_sys::Promise::new(&mut |resolve, reject| {
+            REPLY.set(Some((resolve, reject)));
+        });
+        worker.post_message(&request)?;
+        let reply = JsFuture::from(reply).await?;
+        if let Some(err) = get(&reply, "error") {
+            return Err(err.into());
+        }
+
+        let bytes = js_sys::Reflect::get(&reply, &"cells".into())?
+            .dyn_into::<js_sys::Uint8Array>()?
+            .to_vec();
+        let diff = BlockDiff::decode(&bytes).map_err(|err| err.to_string())?;
+        let population = get(&reply, "population")
+            .and_then(|population| population.parse().ok())
+            .ok_or("reply without a population")?;
+
+        let mut engine = self.blank;
+        for (pos, tile) in &diff.tiles {
+            engine.add_tile(*pos, tile);
+        }
+        engine.set_generation(diff.generation);
+        Ok((engine, population))
+    }
+}
+
+/// The worker, started on first use.
+fn worker() -> Result<Worker, JsValue> {
+    if let Some(worker) = WORKER.with_borrow(Clone::clone) {
+        return Ok(worker);
+    }
+
+    // Blob scripts can't import relative to the page, so the glue's URL is spelled out
+    let window = web_sys::window().ok_or("no window")?;
+    let glue = web_sys::Url::new_with_base(GLUE_SCRIPT, &window.location().href()?)?.href();
+    let source = format!("import init from {glue:?};\ninit();\n");
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type("text/javascript");
+    let script = web_sys::Blob::new_with_str_sequence_and_options(
+        &js_sys::Array::of1(&source.into()),
+        &options,
+    )?;
+    let options = web_sys::WorkerOptions::new();
+    options.set_type(web_sys::WorkerType::Module);
+    let worker = Worker::new_with_options(
+        &web_sys::Url::create_object_url_with_blob(&script)?,
+        &options,
+    )?;
+
+    let on_message = Closure::<dyn FnMut(MessageEvent)>::new(|event: MessageEvent| {
+        if let Some((resolve, _)) = REPLY.take() {
+            let _ = resolve.call1(&JsValue::NULL, &event.data());
+        }
+    });
+    // A panicking engine takes the worker down without a reply
+    let on_error = Closure::<dyn FnMut(web_sys::Event)>::new(|event: web_sys::Event| {
+        if let Some((_, reject)) = REPLY.take() {
+            let _ = reject.call1(&JsValue::NULL, &event);
+        }
+    });
+    worker.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    worker.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+    // Both live as long as the page
+    on_message.forget();
+    on_error.forget();
+
+    WORKER.set(Some(worker.clone()));
+    Ok(worker)
+}
+
+/// Serves steps if this instance was started as the step worker, returning whether it
+/// was; `main` runs nothing else then.
+pub fn serve_if_worker() -> bool {
+    let Ok(scope) = js_sys::global().dyn_into::<DedicatedWorkerGlobalScope>() else {
+        return false;
+    };
+
+    let engine: RefCell<Option<Box<dyn LifeEngine>>> = RefCell::new(None);
+    let replies = scope.clone();
+    let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+        let reply = js_sys::Object::new();
+        match serve(&mut engine.borrow_mut(), &event.data()) {
+            Ok((cells, population)) => {
+                let cells = js_sys::Uint8Array::from(cells.as_slice());
+                let _ = js_sys::Reflect::set(&reply, &"cells".into(), &cells);
+                set(&reply, "population", &population.to_string());
+            }
+            Err(err) => set(&reply, "error", &err),
+        }
+        if let Err(err) = replies.post_message(&reply) {
+            warn!("Couldn't answer the page: {err:?}");
+        }
+    });
+    scope.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    on_message.forget();
+    true
+}
+
+// One request on the worker: take over the state if it came along, then step
+fn serve(
+    engine: &mut Option<Box<dyn LifeEngine>>,
+    request: &JsValue,
+) -> Result<(Vec<u8>, u64), String> {
+    if let Ok(cells) = js_sys::Reflect::get(request, &"cells".into())
+        && let Ok(cells) = cells.dyn_into::<js_sys::Uint8Array>()
+    {
+        let rule = LifeRule::parse(&get(request, "rule").unwrap_or_default())
+            .map_err(|err| err.to_string())?;
+        let torus = get(request, "torus").and_then(|size| {
+            let (x, y) = size.split_once(' ')?;
+            Some(I64Vec2::new(x.parse().ok()?, y.parse().ok()?))
+        });
+        // Picked the way the universe picks engines
+        let mut fresh = if rule == LifeRule::CONWAY && torus.is_none() {
+            let id = get(request, "engine").unwrap_or_default();
+            EngineRegistry::with_builtins()
+                .create(&id)
+                .ok_or_else(|| format!("no engine '{id}' in this build"))?
+        } else {
+            create_rule_engine(rule)
+        };
+        fresh.set_seed(
+            get(request, "seed")
+                .and_then(|seed| seed.parse().ok())
+                .unwrap_or_default(),
+        );
+        fresh.set_torus(torus);
+
+        let diff = BlockDiff::decode(&cells.to_vec()).map_err(|err| err.to_string())?;
+        for (pos, tile) in &diff.tiles {
+            fresh.add_tile(*pos, tile);
+        }
+        fresh.set_generation(diff.generation);
+        *engine = Some(fresh);
+    }
+
+    let engine = engine.as_mut().ok_or("no cells to step")?;
+    let steps = get(request, "steps")
+        .and_then(|steps| steps.parse().ok())
+        .ok_or("no step count")?;
+    engine.step(steps);
+    let diff = BlockDiff {
+        generation: engine.generation(),
+        tiles: collect_tiles(engine.as_ref()).into_iter().collect(),
+    };
+    Ok((diff.encode(), engine.population()))
+}
+
+fn set(object: &js_sys::Object, key: &str, value: &str) {
+    // Setting a field on a plain object can't fail
+    let _ = js_sys::Reflect::set(object, &key.into(), &value.into());
+}
+
+fn get(object: &JsValue, key: &str) -> Option<String> {
+    js_sys::Reflect::get(object, &key.into()).ok()?.as_string()
+}
@@ -0,0 +1,176 @@
+#[cfg(target_arch = "wasm32")]
+use std::cell::RefCell;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use bevy::prelude::*;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::JsCast;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+/// The text on the operating system's clipboard, or the browser's on the web. Reads are
+/// asked for and picked up later: on the desktop right away, in browsers once the page
+/// was handed the text.
+#[derive(Resource, Default, Clone)]
+pub struct SystemClipboard {
+    // The finished read, with `None` inside if there was no text to read
+    received: Arc<Mutex<Option<Option<String>>>>,
+}
+
+impl SystemClipboard {
+    /// Starts reading the clipboard; [`SystemClipboard::take_text`] has the text when done.
+    pub fn request_text(&self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            *self.received.lock().unwrap_or_else(PoisonError::into_inner) = Some(read_text());
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            let received = self.received.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let text = read_text().await;
+                *received.lock().unwrap_or_else(PoisonError::into_inner) = Some(text);
+            });
+        }
+    }
+
+    /// Puts `text` on the clipboard for other programs. Failures are only logged; the
+    /// copy inside the app is there either way.
+    pub fn write_text(&self, text: &str) {
+        write_text(text);
+    }
+
+    /// The result of the last read, once, if it has finished.
+    pub fn take_text(&self) -> Option<Option<String>> {
+        self.received
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_text() -> Option<String> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .inspect_err(|err| debug!("No text on the clipboard: {err}"))
+        .ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_text(text: &str) {
+    if let Err(err) = arboard::Clipboard::new().and_then(|mut clipboard| clipboard.set_text(text)) {
+        warn!("Couldn't write the clipboard: {err}");
+    }
+}
+
+/// Browsers ask for permission or want a recent key press before giving out the text.
+#[cfg(target_arch = "wasm32")]
+async fn read_text() -> Option<String> {
+    let clipboard = web_sys::window()?.navigator().clipboard();
+    wasm_bindgen_futures::JsFuture::from(clipboard.read_text())
+        .await
+        .inspect_err(|err| warn!("Couldn't read the clipboard: {err:?}"))
+        .ok()?
+        .as_string()
+}
+
+#[cfg(target_arch = "wasm32")]
+thread_local! {
+    // Settles the clipboard write Ctrl+C started, once the copy has its text
+    static PENDING_WRITE: RefCell<Option<(js_sys::Function, js_sys::Function)>> =
+        const { RefCell::new(None) };
+}
+
+/// Browsers only open the clipboard while a key press is being handled, and Bevy sees
+/// key presses a frame later. So the page listens for Ctrl+V and Ctrl+C itself: Ctrl+V
+/// starts the read at once, Ctrl+C starts a write whose text follows when the copy is
+/// made (see [`SystemClipboard::write_text`]).
+#[cfg(target_arch = "wasm32")]
+pub fn listen_for_shortcuts(system: Res<SystemClipboard>) {
+    let system = system.clone();
+    let on_key =
+        Closure::<dyn FnMut(web_sys::KeyboardEvent)>::new(move |event: web_sys::KeyboardEvent| {
+            if !event.ctrl_key() || event.repeat() {
+                return;
+            }
+            match event.code().as_str() {
+                "KeyV" => system.request_text(),
+                "KeyC" => start_write(),
+                _ => {}
+            }
+        });
+    // Capturing on the window runs before the canvas handles the key
+    if let Some(window) = web_sys::window()
+        && let Err(err) = window.add_event_listener_with_callback_and_bool(
+            "keydown",
+            on_key.as_ref().unchecked_ref(),
+            true,
+        )
+    {
+        warn!("Couldn't listen for clipboard shortcuts: {err:?}");
+    }
+    // Listens for as long as the page is open
+    on_key.forget();
+}
+
+// Asks the browser for a write with the text still to come
+#[cfg(target_arch = "wasm32")]
+fn start_write() {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    let text = js_sys::Promise::new(&mut |resolve, reject| {
+        // A Ctrl+C with nothing selected never got its text
+        if let Some((_, reject)) = PENDING_WRITE.replace(Some((resolve, reject))) {
+            let _ = reject.call0(&JsValue::NULL);
+        }
+    });
+    let items = js_sys::Object::new();
+    if let Err(err) = js_sys::Reflect::set(&items, &JsValue::from_str("text/plain"), &text)
+        .and_then(|_| web_sys::ClipboardItem::new_with_record_from_str_to_blob_promise(&items))
+        .map(|item| {
+            let written = window
+                .navigator()
+                .clipboard()
+                .write(&js_sys::Array::of1(&item));
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Err(err) = wasm_bindgen_futures::JsFuture::from(written).await {
+                    debug!("Nothing written to the clipboard: {err:?}");
+                }
+            });
+        })
+    {
+        warn!("Couldn't write the clipboard: {err:?}");
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write_text(text: &str) {
+    if let Some((resolve, _)) = PENDING_WRITE.take() {
+        let options = web_sys::BlobPropertyBag::new();
+        options.set_type("text/plain");
+        match web_sys::Blob::new_with_str_sequence_and_options(
+            &js_sys::Array::of1(&JsValue::from_str(text)),
+            &options,
+        ) {
+            Ok(blob) => {
+                let _ = resolve.call1(&JsValue::NULL, &blob);
+                return;
+            }
+            Err(err) => warn!("Couldn't write the clipboard: {err:?}"),
+        }
+    }
+
+    // Without a write from the key press, which works while the press is recent
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    let written = window.navigator().clipboard().write_text(text);
+    wasm_bindgen_futures::spawn_local(async move {
+        if let Err(err) = wasm_bindgen_futures::JsFuture::from(written).await {
+            warn!("Couldn't write the clipboard: {err:?}");
+        }
+    });
+}
@@ -0,0 +1,76 @@
+use bevy::ecs::system::NonSendMarker;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy::winit::WINIT_WINDOWS;
+
+use crate::simulation::metrics::PopulationHistory;
+
+/// Draws a live population sparkline into the window icon so unattended runs
+/// can be watched from the taskbar. Desktop only; a no-op where the platform
+/// ignores window icons (e.g. Wayland).
+pub struct TaskbarIconPlugin;
+
+impl Plugin for TaskbarIconPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, update_window_icon);
+    }
+}
+
+const ICON_SIZE: usize = 32;
+const ICON_REFRESH_SECS: f32 = 1.0;
+
+fn update_window_icon(
+    time: Res<Time>,
+    history: Res<PopulationHistory>,
+    q_window: Query<Entity, With<PrimaryWindow>>,
+    mut elapsed: Local<f32>,
+    _main_thread: NonSendMarker,
+) {
+    *elapsed += time.delta_secs();
+    if *elapsed < ICON_REFRESH_SECS || history.is_empty() {
+        return;
+    }
+    *elapsed = 0.0;
+
+    let Ok(entity) = q_window.single() else {
+        return;
+    };
+
+    let samples: Vec<u64> = history.latest(ICON_SIZE).collect();
+    let rgba = render_sparkline(&samples, ICON_SIZE);
+    let Ok(icon) = winit::window::Icon::from_rgba(rgba, ICON_SIZE as u32, ICON_SIZE as u32) else {
+        return;
+    };
+
+    WINIT_WINDOWS.with_borrow(|windows| {
+        if let Some(window) = windows.get_window(entity) {
+            window.set_window_icon(Some(icon));
+        }
+    });
+}
+
+/// Rasterizes `samples` as a filled sparkline into a `size`×`size` RGBA image.
+/// Samples are scaled to the buffer's maximum and right-aligned.
+fn render_sparkline(samples: &[u64], size: usize) -> Vec<u8> {
+    const BACKGROUND: [u8; 4] = [26, 26, 26, 255];
+    const FILL: [u8; 4] = [0, 140, 140, 255];
+    const LINE: [u8; 4] = [0, 255, 255, 255];
+
+    let mut rgba = BACKGROUND.repeat(size * size);
+    let max = samples.iter().copied().max().unwrap_or(0).max(1);
+    let offset = size.saturating_sub(samples.len());
+
+    for (i, &value) in samples.iter().enumerate().take(size) {
+        let x = offset + i;
+        // Leave a one pixel margin at the top so the peak stays visible
+        let height = ((value as f64 / max as f64) * (size - 1) as f64).round() as usize;
+        for h in 0..=height {
+            let y = size - 1 - h;
+            let color = if h == height { LINE } else { FILL };
+            let idx = (y * size + x) * 4;
+            rgba[idx..idx + 4].copy_from_slice(&color);
+        }
+    }
+
+    rgba
+}
@@ -0,0 +1,160 @@
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use rustc_hash::FxHashMap;
+
+use crate::simulation::SimulationInput;
+use crate::simulation::graphics::{
+    LayerCanvas, LayerDesc, LayerSpawner, LayerViewport, PixelLayer,
+};
+use crate::simulation::versus::{PLAYER_COLORS, VersusGame};
+use crate::simulation::view::SimulationView;
+
+/// Side length of one territory bin, in cells.
+const BIN_SIZE: i64 = 4;
+/// Box blur radius, in bins. Larger values merge nearby colonies into one region.
+const BLUR_RADIUS: i64 = 3;
+/// Average live cells per bin at which a region is shaded at full strength.
+const SATURATION: f32 = 1.5;
+/// Opacity of fully dominated regions.
+const TERRITORY_ALPHA: f32 = 0.35;
+
+const TERRITORY_LAYERS: [&str; 2] = ["territory_red", "territory_blue"];
+
+pub struct TerritoryPlugin;
+
+impl Plugin for TerritoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TerritoryMap>()
+            .add_systems(Startup, setup_territory_layers)
+            .add_systems(
+                Update,
+                (
+                    toggle_territories.in_set(SimulationInput),
+                    update_territories,
+                    render_territories,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Blurred per-player cell density over a coarse grid of `BIN_SIZE` bins.
+#[derive(Resource, Default)]
+pub struct TerritoryMap {
+    pub enabled: bool,
+    bins: FxHashMap<I64Vec2, [f32; 2]>,
+}
+
+impl TerritoryMap {
+    /// Rebuilds the map from colored cells (`(position, player)` pairs).
+    pub fn rebuild(&mut self, cells: impl Iterator<Item = (I64Vec2, usize)>) {
+        let mut counts: FxHashMap<I64Vec2, [f32; 2]> = FxHashMap::default();
+        for (pos, player) in cells {
+            let bin = I64Vec2::new(pos.x.div_euclid(BIN_SIZE), pos.y.div_euclid(BIN_SIZE));
+            counts.entry(bin).or_default()[player] += 1.0;
+        }
+
+        // Box blur: spread every occupied bin over its neighbourhood
+        let window = ((2 * BLUR_RADIUS + 1) * (2 * BLUR_RADIUS + 1)) as f32;
+        self.bins.clear();
+        for (bin, [red, blue]) in counts {
+            for dy in -BLUR_RADIUS..=BLUR_RADIUS {
+                for dx in -BLUR_RADIUS..=BLUR_RADIUS {
+                    let entry = self.bins.entry(bin + I64Vec2::new(dx, dy)).or_default();
+                    entry[0] += red / window;
+                    entry[1] += blue / window;
+                }
+            }
+        }
+    }
+
+    /// How strongly `player` dominates each bin, 0 (not at all) to 255 (uncontested and dense).
+    fn dominance(&self, player: usize) -> impl Iterator<Item = (I64Vec2, u8)> + '_ {
+        self.bins.iter().filter_map(move |(&bin, planes)| {
+            let own = planes[player];
+            let other = planes[1 - player];
+            if own <= other {
+                return None;
+            }
+            let majority = (own - other) / (own + other);
+            let density = ((own + other) / SATURATION).min(1.0);
+            Some((bin, (majority * density * 255.0) as u8))
+        })
+    }
+}
+
+#[derive(Component)]
+struct TerritoryLayer(usize);
+
+fn setup_territory_layers(mut layers: LayerSpawner) {
+    for player in 0..2 {
+        let color = PLAYER_COLORS[player].with_w(TERRITORY_ALPHA);
+        layers
+            .spawn(
+                LayerDesc::new(TERRITORY_LAYERS[player], 0.15 + player as f32 * 0.01)
+                    .colors(color, Vec4::ZERO)
+                    .hidden(),
+            )
+            .insert(TerritoryLayer(player));
+    }
+}
+
+// T: toggle the territory overlay for colored boards
+fn toggle_territories(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut map: ResMut<TerritoryMap>,
+    game: Res<VersusGame>,
+    mut q_layers: Query<&mut PixelLayer, With<TerritoryLayer>>,
+) {
+    if keys.just_pressed(KeyCode::KeyT) {
+        map.enabled = !map.enabled;
+    }
+
+    let visible = map.enabled && game.is_active();
+    for mut layer in &mut q_layers {
+        if layer.visible != visible {
+            layer.visible = visible;
+        }
+    }
+}
+
+fn update_territories(mut map: ResMut<TerritoryMap>, game: Res<VersusGame>) {
+    if map.enabled && game.is_active() && (game.is_changed() || map.is_changed()) {
+        map.rebuild(game.cells());
+    }
+}
+
+fn render_territories(
+    map: Res<TerritoryMap>,
+    game: Res<VersusGame>,
+    view: Res<SimulationView>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut q_layers: Query<(&PixelLayer, &mut LayerCanvas, &TerritoryLayer)>,
+) {
+    if !map.enabled || !game.is_active() {
+        return;
+    }
+    let Ok(window) = q_window.single() else {
+        return;
+    };
+
+    for (layer, mut canvas, &TerritoryLayer(player)) in &mut q_layers {
+        let Some(viewport) = LayerViewport::new(window, &view, layer) else {
+            continue;
+        };
+        let buffer = canvas.buffer(&viewport);
+        buffer.fill(0);
+
+        for (bin, value) in map.dominance(player) {
+            viewport.draw_rect(
+                buffer,
+                bin.x * BIN_SIZE,
+                bin.y * BIN_SIZE,
+                BIN_SIZE,
+                BIN_SIZE,
+                value,
+            );
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use bevy::prelude::*;
+
+use crate::simulation::SimulationInput;
+use crate::simulation::graphics::{LayerShader, PixelLayer};
+use crate::simulation::stats_boards::StatsBoard;
+
+pub struct ThemePlugin;
+
+impl Plugin for ThemePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Theme>().add_systems(
+            Update,
+            (cycle_universe_shader.in_set(SimulationInput), apply_theme).chain(),
+        );
+    }
+}
+
+/// Colors and shader choices for the built-in layers.
+/// Changing this resource re-skins the layers on the next frame.
+#[derive(Resource, Clone, Debug)]
+pub struct Theme {
+    pub universe_alive: Vec4,
+    pub universe_dead: Vec4,
+    pub universe_shader: LayerShader,
+    pub draw_color: Vec4,
+    pub wall_color: Vec4,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            universe_alive: Vec4::new(1.0, 1.0, 1.0, 1.0),
+            universe_dead: Vec4::new(0.1, 0.1, 0.1, 1.0),
+            universe_shader: LayerShader::Gradient,
+            draw_color: Vec4::new(0.0, 1.0, 1.0, 0.6),
+            wall_color: Vec4::new(0.45, 0.35, 0.6, 1.0),
+        }
+    }
+}
+
+impl Theme {
+    /// Pure black and white cells without blending, with overlays in saturated colors.
+    pub fn high_contrast() -> Self {
+        Self {
+            universe_alive: Vec4::new(1.0, 1.0, 1.0, 1.0),
+            universe_dead: Vec4::new(0.0, 0.0, 0.0, 1.0),
+            universe_shader: LayerShader::Binary,
+            draw_color: Vec4::new(1.0, 0.85, 0.0, 1.0),
+            wall_color: Vec4::new(0.2, 0.5, 1.0, 1.0),
+        }
+    }
+}
+
+fn cycle_universe_shader(keys: Res<ButtonInput<KeyCode>>, mut theme: ResMut<Theme>) {
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if keys.just_pressed(KeyCode::KeyV) && !ctrl {
+        theme.universe_shader = theme.universe_shader.next();
+    }
+}
+
+fn apply_theme(
+    theme: Res<Theme>,
+    mut q_layers: Query<&mut PixelLayer>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    if !theme.is_changed() {
+        return;
+    }
+
+    for mut layer in &mut q_layers {
+        match layer.name.as_str() {
+            "universe" => {
+                layer.color_alive = theme.universe_alive;
+                layer.color_dead = theme.universe_dead;
+                layer.shader = theme.universe_shader;
+            }
+            "draw" => {
+                layer.color_alive = theme.draw_color;
+            }
+            "walls" => {
+                layer.color_alive = theme.wall_color;
+            }
+            _ => {}
+        }
+    }
+
+    stats.insert("Shader", format!("{:?}", theme.universe_shader));
+}
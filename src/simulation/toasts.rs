@@ -0,0 +1,127 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::simulation::error::LifeError;
+use crate::simulation::locale::Localization;
+
+/// How long an error stays on screen.
+const TOAST_SECS: f32 = 8.0;
+/// Older errors are dropped from the panel beyond this many (they stay in the log).
+const MAX_TOASTS: usize = 5;
+
+/// Logs every [`LifeError`] and, with the `ui` feature, shows the recent ones in a panel
+/// in the top right corner.
+pub struct ToastPlugin;
+
+impl Plugin for ToastPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<LifeError>()
+            .init_resource::<Toasts>()
+            .add_systems(Update, collect_errors);
+        #[cfg(feature = "ui")]
+        app.add_systems(Startup, setup_toast_panel)
+            .add_systems(Update, update_toast_panel.after(collect_errors));
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Toasts {
+    // (text, seconds left), oldest first
+    entries: VecDeque<(String, f32)>,
+}
+
+#[cfg(feature = "ui")]
+#[derive(Component)]
+struct ToastPanel;
+
+#[cfg(feature = "ui")]
+fn setup_toast_panel(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(10.0),
+                right: Val::Px(10.0),
+                max_width: Val::Px(480.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.4, 0.05, 0.05, 0.85)),
+            GlobalZIndex(110),
+            Visibility::Hidden,
+            ToastPanel,
+        ))
+        .with_child((
+            Text::default(),
+            TextFont {
+                font,
+                font_size: 18.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+        ));
+}
+
+fn collect_errors(
+    time: Res<Time>,
+    locale: Res<Localization>,
+    mut errors: MessageReader<LifeError>,
+    mut toasts: ResMut<Toasts>,
+) {
+    for err in errors.read() {
+        error!("{err}");
+        if toasts.entries.len() == MAX_TOASTS {
+            toasts.entries.pop_front();
+        }
+        toasts.entries.push_back((locale.error(err), TOAST_SECS));
+    }
+
+    if toasts.entries.is_empty() {
+        return;
+    }
+    let dt = time.delta_secs();
+    for (_, left) in &mut toasts.entries {
+        *left -= dt;
+    }
+    toasts.entries.retain(|(_, left)| *left > 0.0);
+}
+
+#[cfg(feature = "ui")]
+fn update_toast_panel(
+    toasts: Res<Toasts>,
+    mut q_panel: Query<(&mut Visibility, &Children), With<ToastPanel>>,
+    mut q_text: Query<&mut Text>,
+) {
+    if !toasts.is_changed() {
+        return;
+    }
+    let Ok((mut visibility, children)) = q_panel.single_mut() else {
+        return;
+    };
+
+    let visible = if toasts.entries.is_empty() {
+        Visibility::Hidden
+    } else {
+        Visibility::Visible
+    };
+    if *visibility != visible {
+        *visibility = visible;
+    }
+
+    let output = toasts
+        .entries
+        .iter()
+        .map(|(text, _)| text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    for &child in children {
+        if let Ok(mut text) = q_text.get_mut(child)
+            && **text != output
+        {
+            **text = output.clone();
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::simulation::SimulationInput;
+use crate::simulation::graphics::{
+    LayerCanvas, LayerDesc, LayerSpawner, LayerViewport, PixelLayer,
+};
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::universe::Universe;
+use crate::simulation::view::SimulationView;
+
+const DEFAULT_SIZE: I64Vec2 = I64Vec2::new(64, 64);
+const MIN_SIZE: i64 = 8;
+const MAX_SIZE: i64 = 1024;
+/// Cells added or removed per key press.
+const RESIZE_STEP: i64 = 8;
+/// Upper bound on ghost cells drawn per frame; beyond it only the boundary is shown.
+const MAX_GHOST_CELLS: usize = 1 << 20;
+
+const BOUNDARY: u8 = 1;
+const GHOST: u8 = 2;
+
+pub struct TorusPlugin;
+
+impl Plugin for TorusPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TorusEditor>()
+            .add_systems(Startup, setup_torus_layer)
+            .add_systems(
+                Update,
+                (edit_torus.in_set(SimulationInput), render_torus).chain(),
+            );
+    }
+}
+
+/// Size the torus gets when (re-)enabled, and whether copies are drawn past its edges.
+#[derive(Resource)]
+pub struct TorusEditor {
+    pub size: I64Vec2,
+    pub ghosts: bool,
+}
+
+impl Default for TorusEditor {
+    fn default() -> Self {
+        Self {
+            size: DEFAULT_SIZE,
+            ghosts: true,
+        }
+    }
+}
+
+#[derive(Component)]
+struct TorusLayer;
+
+fn setup_torus_layer(mut layers: LayerSpawner) {
+    layers
+        .spawn(
+            LayerDesc::new("torus", 0.08)
+                .palette([
+                    Vec4::ZERO,
+                    Vec4::new(1.0, 0.8, 0.2, 0.9),
+                    Vec4::new(1.0, 1.0, 1.0, 0.25),
+                ])
+                .hidden(),
+        )
+        .insert(TorusLayer);
+}
+
+// O: wrap/unwrap, [ ]: width, - =: height, H: ghost copies
+fn edit_torus(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut editor: ResMut<TorusEditor>,
+    mut universe: ResMut<Universe>,
+    mut q_layer: Query<&mut PixelLayer, With<TorusLayer>>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    if keys.just_pressed(KeyCode::KeyH) {
+        editor.ghosts = !editor.ghosts;
+    }
+
+    let mut resize = I64Vec2::ZERO;
+    if keys.just_pressed(KeyCode::BracketLeft) {
+        resize.x -= RESIZE_STEP;
+    }
+    if keys.just_pressed(KeyCode::BracketRight) {
+        resize.x += RESIZE_STEP;
+    }
+    if keys.just_pressed(KeyCode::Minus) {
+        resize.y -= RESIZE_STEP;
+    }
+    if keys.just_pressed(KeyCode::Equal) {
+        resize.y += RESIZE_STEP;
+    }
+
+    let wrapped = universe.torus().is_some();
+    if keys.just_pressed(KeyCode::KeyO) {
+        let size = (!wrapped).then_some(editor.size);
+        universe.set_torus(size);
+    } else if wrapped && resize != I64Vec2::ZERO {
+        editor.size =
+            (editor.size + resize).clamp(I64Vec2::splat(MIN_SIZE), I64Vec2::splat(MAX_SIZE));
+        universe.set_torus(Some(editor.size));
+    }
+
+    // The universe may also have left the torus on its own (engine switch)
+    let torus = universe.torus();
+    for mut layer in &mut q_layer {
+        if layer.visible != torus.is_some() {
+            layer.visible = torus.is_some();
+        }
+    }
+    match torus {
+        Some(size) => stats.insert(
+            "Torus",
+            format!("{}x{} ([ ] - = resize, H ghosts)", size.x, size.y),
+        ),
+        None => stats.remove("Torus"),
+    }
+}
+
+fn render_torus(
+    universe: Res<Universe>,
+    editor: Res<TorusEditor>,
+    view: Res<SimulationView>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut q_layer: Query<(&PixelLayer, &mut LayerCanvas), With<TorusLayer>>,
+) {
+    let Some(size) = universe.torus() else {
+        return;
+    };
+    let Ok((layer, mut canvas)) = q_layer.single_mut() else {
+        return;
+    };
+    let Ok(window) = q_window.single() else {
+        return;
+    };
+    let Some(viewport) = LayerViewport::new(window, &view, layer) else {
+        return;
+    };
+    let buffer = canvas.buffer(&viewport);
+    buffer.fill(0);
+
+    // Copies of the fundamental domain that overlap the screen
+    let rect = viewport.get_world_rect();
+    let first = I64Vec2::new(
+        (rect.min.x as f64 / size.x as f64).floor() as i64,
+        (rect.min.y as f64 / size.y as f64).floor() as i64,
+    );
+    let last = I64Vec2::new(
+        (rect.max.x as f64 / size.x as f64).floor() as i64,
+        (rect.max.y as f64 / size.y as f64).floor() as i64,
+    );
+
+    if editor.ghosts {
+        let cells = universe.read_engine().export();
+        let copies = ((last.x - first.x + 1) * (last.y - first.y + 1)) as usize;
+        if cells.len() * copies <= MAX_GHOST_CELLS {
+            for ty in first.y..=last.y {
+                for tx in first.x..=last.x {
+                    if (tx, ty) == (0, 0) {
+                        continue;
+                    }
+                    let offset = I64Vec2::new(tx, ty) * size;
+                    for &pos in &cells {
+                        let ghost = pos + offset;
+                        viewport.draw_cell(buffer, ghost.x, ghost.y, GHOST);
+                    }
+                }
+            }
+        }
+    }
+
+    // One cell thick frame just outside the fundamental domain
+    viewport.draw_rect(buffer, -1, -1, size.x + 2, 1, BOUNDARY);
+    viewport.draw_rect(buffer, -1, size.y, size.x + 2, 1, BOUNDARY);
+    viewport.draw_rect(buffer, -1, 0, 1, size.y, BOUNDARY);
+    viewport.draw_rect(buffer, size.x, 0, 1, size.y, BOUNDARY);
+}
@@ -0,0 +1,226 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::simulation::SimulationInput;
+use crate::simulation::engine::TreeNodeInfo;
+use crate::simulation::render::format_metric;
+use crate::simulation::universe::Universe;
+use crate::simulation::view::SimulationView;
+
+const QUADRANTS: [&str; 4] = ["NW", "NE", "SW", "SE"];
+
+/// F6 opens a debug panel for walking the HashLife quadtree from the root. Clicking a
+/// child descends into it and zooms the view onto its region; Backspace goes back up.
+pub struct TreeExplorerPlugin;
+
+impl Plugin for TreeExplorerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TreeExplorer>()
+            .add_systems(Startup, setup_explorer_panel)
+            .add_systems(
+                Update,
+                (
+                    explorer_input.in_set(SimulationInput),
+                    click_child,
+                    update_explorer_panel,
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct TreeExplorer {
+    pub open: bool,
+    /// Child indices from the root to the inspected node.
+    pub path: Vec<usize>,
+}
+
+#[derive(Component)]
+struct ExplorerPanel;
+
+#[derive(Component)]
+struct ExplorerHeader;
+
+/// Descends into the child with this index when clicked.
+#[derive(Component)]
+struct ChildButton(usize);
+
+fn setup_explorer_panel(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+    let text_font = TextFont {
+        font,
+        font_size: 16.0,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(30.0),
+                left: Val::Px(10.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                row_gap: Val::Px(4.0),
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            BackgroundColor(Color::BLACK.with_alpha(0.7)),
+            GlobalZIndex(100),
+            Visibility::Hidden,
+            ExplorerPanel,
+        ))
+        .with_children(|panel| {
+            panel.spawn((
+                Text::default(),
+                text_font.clone(),
+                TextColor(Color::WHITE),
+                ExplorerHeader,
+            ));
+            for index in 0..QUADRANTS.len() {
+                panel
+                    .spawn((
+                        Button,
+                        Node {
+                            padding: UiRect::axes(Val::Px(6.0), Val::Px(2.0)),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgba(0.25, 0.25, 0.3, 0.9)),
+                        ChildButton(index),
+                    ))
+                    .with_child((Text::default(), text_font.clone(), TextColor(Color::WHITE)));
+            }
+        });
+}
+
+// F6: open/close the tree explorer, Backspace: back to the parent node
+fn explorer_input(keys: Res<ButtonInput<KeyCode>>, mut explorer: ResMut<TreeExplorer>) {
+    if keys.just_pressed(KeyCode::F6) {
+        explorer.open = !explorer.open;
+    }
+    if explorer.open && keys.just_pressed(KeyCode::Backspace) {
+        explorer.path.pop();
+    }
+}
+
+fn click_child(
+    q_buttons: Query<(&Interaction, &ChildButton), Changed<Interaction>>,
+    universe: Res<Universe>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut explorer: ResMut<TreeExplorer>,
+    mut view: ResMut<SimulationView>,
+) {
+    for (interaction, &ChildButton(index)) in &q_buttons {
+        if *interaction != Interaction::Pressed || !explorer.open {
+            continue;
+        }
+        explorer.path.push(index);
+        let Some(node) = universe.read_engine().inspect_tree(&explorer.path) else {
+            explorer.path.pop();
+            continue;
+        };
+
+        let size = node.region.size().as_dvec2();
+        view.center = node.region.min.as_dvec2() + size / 2.0;
+        if let Ok(window) = q_window.single() {
+            let fit = (window.width().min(window.height()) as f64 / size.max_element()) * 0.9;
+            view.zoom = fit.clamp(0.01, 500.0);
+        }
+    }
+}
+
+fn update_explorer_panel(
+    universe: Res<Universe>,
+    mut explorer: ResMut<TreeExplorer>,
+    mut q_panel: Query<&mut Visibility, With<ExplorerPanel>>,
+    mut q_header: Query<&mut Text, With<ExplorerHeader>>,
+    mut q_buttons: Query<(&ChildButton, &Children, &mut Visibility), Without<ExplorerPanel>>,
+    mut q_text: Query<&mut Text, Without<ExplorerHeader>>,
+) {
+    let Ok(mut panel_visibility) = q_panel.single_mut() else {
+        return;
+    };
+    let visible = if explorer.open {
+        Visibility::Visible
+    } else {
+        Visibility::Hidden
+    };
+    if *panel_visibility != visible {
+        *panel_visibility = visible;
+    }
+    if !explorer.open {
+        return;
+    }
+
+    let engine = universe.read_engine();
+    // Steps may have reshaped the tree under the path; fall back to the deepest valid node
+    let node = loop {
+        match engine.inspect_tree(&explorer.path) {
+            Some(node) => break Some(node),
+            None if !explorer.path.is_empty() => {
+                explorer.path.pop();
+            }
+            None => break None,
+        }
+    };
+
+    let header = match &node {
+        Some(node) => {
+            let path = explorer
+                .path
+                .iter()
+                .map(|&i| QUADRANTS[i])
+                .collect::<Vec<_>>()
+                .join(" > ");
+            format!(
+                "HashLife tree (Backspace: up)\nroot{}{path}\n{}",
+                if path.is_empty() { "" } else { " > " },
+                describe(node)
+            )
+        }
+        None => format!("{} has no tree to explore", engine.name()),
+    };
+    if let Ok(mut text) = q_header.single_mut()
+        && **text != header
+    {
+        **text = header;
+    }
+
+    let children = node.map(|n| n.children).unwrap_or_default();
+    for (ChildButton(index), button_children, mut visibility) in &mut q_buttons {
+        let child = children.get(*index);
+        let wanted = if child.is_some() {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        if *visibility != wanted {
+            *visibility = wanted;
+        }
+        let Some(child) = child else {
+            continue;
+        };
+        let label = format!("{} {}", QUADRANTS[*index], describe(child));
+        for &entity in button_children {
+            if let Ok(mut text) = q_text.get_mut(entity)
+                && **text != label
+            {
+                **text = label.clone();
+            }
+        }
+    }
+}
+
+fn describe(node: &TreeNodeInfo) -> String {
+    let cached = match (node.cached_jump, node.cached_step) {
+        (true, true) => "jump+step cached",
+        (true, false) => "jump cached",
+        (false, true) => "step cached",
+        (false, false) => "not cached",
+    };
+    format!(
+        "level {}, population {}, {cached}",
+        node.level,
+        format_metric(node.population)
+    )
+}
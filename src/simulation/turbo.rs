@@ -0,0 +1,113 @@
+use bevy::diagnostic::DiagnosticsStore;
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+
+use crate::simulation::SimulationInput;
+use crate::simulation::calibration::Calibration;
+use crate::simulation::render::format_metric;
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::universe::{STEP_TIME, Universe};
+
+/// Step time turbo aims for; steps grow or shrink to stay close to it.
+const TARGET_STEP_MS: f64 = 40.0;
+const MAX_STEPS: u64 = 1 << 20;
+
+pub struct TurboPlugin;
+
+impl Plugin for TurboPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Turbo>().add_systems(
+            Update,
+            (toggle_turbo.in_set(SimulationInput), adapt_turbo_steps).chain(),
+        );
+    }
+}
+
+/// While Tab is held the universe isn't drawn and steps as far as it can each frame.
+/// Steps start out as long as the engine's calibrated speed allows (see [`Calibration`]).
+#[derive(Resource, Default)]
+pub struct Turbo {
+    pub active: bool,
+    // Step settings to hand back on release
+    saved: Option<(u64, bool)>,
+    started_at: u64,
+}
+
+/// Run condition for systems that rasterize the universe.
+pub fn no_turbo(turbo: Res<Turbo>) -> bool {
+    !turbo.active
+}
+
+// Tab (hold): fast-forward without rendering
+fn toggle_turbo(
+    keys: Res<ButtonInput<KeyCode>>,
+    calibration: Res<Calibration>,
+    mut turbo: ResMut<Turbo>,
+    mut universe: ResMut<Universe>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    let held = keys.pressed(KeyCode::Tab);
+    if held == turbo.active {
+        return;
+    }
+    turbo.active = held;
+
+    if held {
+        turbo.saved = Some((universe.steps_per_tick, universe.pipelined));
+        turbo.started_at = universe.generation();
+        // Nobody reads the front engine meanwhile, so copying it would be wasted work
+        universe.pipelined = false;
+        universe.unthrottled = true;
+        // Rather than doubling up from the current speed over many steps
+        if let Some(steps) = calibration.generations_in(&universe.engine_id(), TARGET_STEP_MS) {
+            universe.steps_per_tick = steps.clamp(universe.steps_per_tick, MAX_STEPS);
+        }
+    } else {
+        if let Some((steps, pipelined)) = turbo.saved.take() {
+            universe.steps_per_tick = steps;
+            universe.pipelined = pipelined;
+        }
+        universe.unthrottled = false;
+        stats.remove("Turbo");
+    }
+}
+
+fn adapt_turbo_steps(
+    turbo: Res<Turbo>,
+    store: Res<DiagnosticsStore>,
+    mut universe: ResMut<Universe>,
+    mut stats: ResMut<StatsBoard>,
+    mut last_measured: Local<Option<Instant>>,
+) {
+    if !turbo.active {
+        return;
+    }
+
+    // Only react once per finished step
+    let step_ms = store
+        .get(&STEP_TIME)
+        .and_then(|d| d.measurement())
+        .filter(|m| *last_measured != Some(m.time))
+        .map(|m| {
+            *last_measured = Some(m.time);
+            m.value
+        });
+    let steps = universe.steps_per_tick.max(1);
+    let next = match step_ms {
+        Some(ms) if ms < TARGET_STEP_MS / 2.0 => (steps * 2).min(MAX_STEPS),
+        Some(ms) if ms > TARGET_STEP_MS * 2.0 => (steps / 2).max(1),
+        _ => steps,
+    };
+    if universe.steps_per_tick != next {
+        universe.steps_per_tick = next;
+    }
+
+    stats.insert(
+        "Turbo",
+        format!(
+            "+{} generations, {next} per step",
+            universe.generation().saturating_sub(turbo.started_at)
+        ),
+    );
+    stats.insert("Population", format_metric(universe.population()));
+}
@@ -0,0 +1,219 @@
+use bevy::prelude::*;
+
+use crate::simulation::SimulationInput;
+use crate::simulation::accessibility::Accessibility;
+use crate::simulation::error::LifeError;
+use crate::simulation::locale::Localization;
+use crate::simulation::settings::Settings;
+use crate::simulation::universe::{PatternLoaded, Universe};
+use crate::simulation::view::SimulationView;
+
+/// Settings key set once the tutorial was finished or dismissed.
+const DONE_KEY: &str = "tutorial";
+/// How long the panel lights up when a step is done.
+const FLASH_SECS: f32 = 0.6;
+
+/// Message keys of the steps, in order.
+const STEPS: [&str; 6] = [
+    "tutorial-draw",
+    "tutorial-pause",
+    "tutorial-resume",
+    "tutorial-zoom",
+    "tutorial-load",
+    "tutorial-engine",
+];
+
+/// Walks new users through drawing, running and pausing, zooming, loading a pattern and
+/// switching engines, one step at a time, each done by doing it. Shown on the first
+/// run and with `--tutorial`; Backspace ends it early. Finishing or ending it is
+/// remembered in the settings.
+pub struct TutorialPlugin;
+
+impl Plugin for TutorialPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Tutorial>()
+            .add_systems(Startup, (start_tutorial, setup_tutorial_panel))
+            .add_systems(
+                Update,
+                (
+                    advance_tutorial,
+                    end_tutorial.in_set(SimulationInput),
+                    update_tutorial_panel,
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Tutorial {
+    /// Index into [`STEPS`] while the tutorial runs.
+    pub step: Option<usize>,
+    // What the current step is waiting to see change
+    revision: u64,
+    zoom: f64,
+    engine: String,
+    // Seconds the panel stays lit after the last step was done
+    flash: f32,
+}
+
+impl Tutorial {
+    fn begin_step(&mut self, step: usize, universe: &Universe, view: &SimulationView) {
+        self.step = Some(step);
+        self.revision = universe.revision();
+        self.zoom = view.zoom;
+        self.engine = universe.engine_id();
+    }
+}
+
+fn start_tutorial(
+    settings: Res<Settings>,
+    universe: Res<Universe>,
+    view: Res<SimulationView>,
+    mut tutorial: ResMut<Tutorial>,
+) {
+    if !settings.flag(DONE_KEY) || std::env::args().any(|arg| arg == "--tutorial") {
+        tutorial.begin_step(0, &universe, &view);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn advance_tutorial(
+    time: Res<Time>,
+    accessibility: Res<Accessibility>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    universe: Res<Universe>,
+    view: Res<SimulationView>,
+    mut loaded: MessageReader<PatternLoaded>,
+    mut tutorial: ResMut<Tutorial>,
+    mut settings: ResMut<Settings>,
+    mut errors: MessageWriter<LifeError>,
+) {
+    if tutorial.flash > 0.0 {
+        tutorial.flash = (tutorial.flash - time.delta_secs()).max(0.0);
+    }
+    let pattern_loaded = loaded.read().count() > 0;
+    let Some(step) = tutorial.step else {
+        return;
+    };
+    let done = match step {
+        0 => buttons.pressed(MouseButton::Left) && universe.revision() != tutorial.revision,
+        1 => universe.paused,
+        2 => !universe.paused,
+        3 => view.zoom != tutorial.zoom,
+        4 => pattern_loaded,
+        _ => universe.engine_id() != tutorial.engine,
+    };
+    if !done {
+        return;
+    }
+
+    if !accessibility.reduced_motion {
+        tutorial.flash = FLASH_SECS;
+    }
+    if step + 1 < STEPS.len() {
+        tutorial.begin_step(step + 1, &universe, &view);
+    } else {
+        info!("Tutorial finished");
+        tutorial.step = None;
+        if let Err(err) = settings.set(DONE_KEY, true) {
+            errors.write(err);
+        }
+    }
+}
+
+// Backspace: end the tutorial
+fn end_tutorial(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut tutorial: ResMut<Tutorial>,
+    mut settings: ResMut<Settings>,
+    mut errors: MessageWriter<LifeError>,
+) {
+    if tutorial.step.is_none() || !keys.just_pressed(KeyCode::Backspace) {
+        return;
+    }
+    tutorial.step = None;
+    if let Err(err) = settings.set(DONE_KEY, true) {
+        errors.write(err);
+    }
+}
+
+#[derive(Component)]
+struct TutorialPanel;
+
+fn setup_tutorial_panel(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(10.0),
+                left: Val::Percent(30.0),
+                min_width: Val::Percent(40.0),
+                padding: UiRect::all(Val::Px(12.0)),
+                ..default()
+            },
+            BackgroundColor(Color::BLACK.with_alpha(0.8)),
+            GlobalZIndex(115),
+            Visibility::Hidden,
+            TutorialPanel,
+        ))
+        .with_child((
+            Text::default(),
+            TextFont {
+                font,
+                font_size: 20.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+        ));
+}
+
+fn update_tutorial_panel(
+    tutorial: Res<Tutorial>,
+    locale: Res<Localization>,
+    mut q_panel: Query<(&mut Visibility, &mut BackgroundColor, &Children), With<TutorialPanel>>,
+    mut q_text: Query<&mut Text>,
+) {
+    if !tutorial.is_changed() {
+        return;
+    }
+    let Ok((mut visibility, mut background, children)) = q_panel.single_mut() else {
+        return;
+    };
+    let Some(current) = tutorial.step else {
+        visibility.set_if_neq(Visibility::Hidden);
+        return;
+    };
+    visibility.set_if_neq(Visibility::Inherited);
+
+    // Fades from the highlight back to the usual background
+    let lit = tutorial.flash / FLASH_SECS;
+    background.0 = Color::BLACK
+        .mix(&Color::srgb(0.1, 0.45, 0.2), lit)
+        .with_alpha(0.8);
+
+    let mut output = locale.format(
+        "tutorial-title",
+        &[("step", &(current + 1)), ("steps", &STEPS.len())],
+    );
+    for (i, step) in STEPS.iter().enumerate() {
+        let marker = match i.cmp(&current) {
+            std::cmp::Ordering::Less => "[x]",
+            std::cmp::Ordering::Equal => "[>]",
+            std::cmp::Ordering::Greater => "[ ]",
+        };
+        output.push_str(&format!("\n{marker} {}", locale.get(step)));
+    }
+    output.push('\n');
+    output.push_str(locale.get("tutorial-end"));
+
+    for &child in children {
+        if let Ok(mut text) = q_text.get_mut(child)
+            && **text != output
+        {
+            **text = output.clone();
+        }
+    }
+}
@@ -1,93 +1,698 @@
+use bevy::diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic};
 use bevy::math::I64Vec2;
+use bevy::platform::time::Instant;
 use bevy::prelude::*;
 use bevy::tasks::{AsyncComputeTaskPool, Task};
-use std::sync::{Arc, RwLock};
+use bevy::window::PrimaryWindow;
+use rustc_hash::FxHashSet;
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::Duration;
 
-use crate::simulation::engine::{EngineMode, LifeEngine, create_engine};
+use crate::simulation::SimulationInput;
+use crate::simulation::engine::{
+    CellRegion, EngineEntry, EngineRegistry, Keyframe, LifeEngine, PasteMode, create_rule_engine,
+    default_engine, in_supported_range,
+};
+use crate::simulation::error::LifeError;
+use crate::simulation::rules::LifeRule;
 use crate::simulation::stats_boards::StatsBoard;
 
 pub struct UniversePlugin;
 
 impl Plugin for UniversePlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<Universe>()
-            // The step logic now initiates and polls tasks.
+        let mut universe = Universe::default();
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(value) = crate::simulation::batch::arg_value("--unfocused") {
+            match UnfocusedPolicy::parse(&value) {
+                Some(policy) => universe.unfocused = policy,
+                None => warn!("--unfocused takes run, pause or throttle:N, not {value}"),
+            }
+        }
+        app.insert_resource(universe)
+            .init_resource::<EngineRegistry>()
+            .add_message::<RuleChanged>()
+            .add_message::<PatternLoaded>()
+            .register_diagnostic(Diagnostic::new(STEP_TIME).with_suffix("ms"))
+            // Fixed ticks set the pace; the step logic initiates and polls tasks.
+            .add_systems(FixedUpdate, tick_universe)
             .add_systems(Update, step_universe)
             // Separate system to handle input and trigger state changes.
-            .add_systems(PreUpdate, handle_input);
+            .add_systems(PreUpdate, handle_input.in_set(SimulationInput))
+            .add_systems(
+                PostUpdate,
+                (announce_rule, report_out_of_range, list_engines),
+            );
     }
 }
 
+/// Sent when the universe starts running a different rule, so systems that learned
+/// something about the old dynamics (overlays, classifiers, detectors) can start over.
+#[derive(Message, Clone, Copy, Debug)]
+pub struct RuleChanged {
+    pub rule: LifeRule,
+}
+
+/// Sent when a pattern, preset or workspace replaces what the universe holds.
+#[derive(Message, Clone, Debug)]
+pub struct PatternLoaded {
+    /// File or preset name.
+    pub name: String,
+}
+
+/// What the universe does while its window doesn't have focus (`--unfocused`, F3).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UnfocusedPolicy {
+    /// Keeps stepping as if focused.
+    #[default]
+    Run,
+    /// At most this many generations per second, whatever the speed.
+    Throttle(u64),
+    /// No new steps until the window has focus again.
+    Pause,
+}
+
+/// Generations per second F3 throttles to.
+const DEFAULT_THROTTLE: u64 = 10;
+
+impl UnfocusedPolicy {
+    /// Reads `run`, `pause` or `throttle:N`, as written by `Display`.
+    pub fn parse(text: &str) -> Option<Self> {
+        match text.trim() {
+            "run" => Some(Self::Run),
+            "pause" => Some(Self::Pause),
+            text => text
+                .strip_prefix("throttle:")
+                .and_then(|rate| rate.parse().ok())
+                .filter(|&rate| rate > 0)
+                .map(Self::Throttle),
+        }
+    }
+}
+
+impl std::fmt::Display for UnfocusedPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Run => write!(f, "run"),
+            Self::Throttle(rate) => write!(f, "throttle:{rate}"),
+            Self::Pause => write!(f, "pause"),
+        }
+    }
+}
+
+/// Wall time of one background engine step (all `steps_per_tick` generations).
+pub const STEP_TIME: DiagnosticPath = DiagnosticPath::const_new("engine/step_time");
+
 // --- Simplified Universe Resource ---
 
 // Use a type alias for cleaner code
 type SharedEngine = Arc<RwLock<Box<dyn LifeEngine>>>;
 
+/// Fixed ticks that may pile up while a slow step runs, by default (see
+/// [`Universe::max_owed_ticks`]). Beyond this the simulation falls behind real time
+/// instead of trying to catch up with ever bigger steps.
+pub const MAX_OWED_TICKS: u64 = 8;
+
+const ENGINE_KEYS: [KeyCode; 9] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+/// Lowest and highest tick rate reachable with PageDown/PageUp.
+const MIN_TICK_HZ: f64 = 0.5;
+const MAX_TICK_HZ: f64 = 960.0;
+
+/// How often engines stepped in place are exported as a crash snapshot.
+const SNAPSHOT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Stored as the population while it needs counting again.
+const UNCOUNTED: u64 = u64::MAX;
+
+/// Undo history is capped at this many pastes.
+const MAX_UNDO: usize = 32;
+
+/// A user edit. Edits made while a pipelined step is running are replayed onto its
+/// result; with [`Universe::journaling`] on they are also kept for the autosave journal,
+/// and while recording, for a macro.
+#[derive(Clone, Debug)]
+pub enum Edit {
+    Cells(Vec<I64Vec2>, bool),
+    Walls(Vec<I64Vec2>, bool),
+    Paste(CellRegion, Vec<I64Vec2>, PasteMode),
+}
+
+impl Edit {
+    /// The same edit `offset` cells away.
+    pub fn translated(self, offset: I64Vec2) -> Self {
+        let shift = |cells: Vec<I64Vec2>| -> Vec<I64Vec2> {
+            cells.into_iter().map(|pos| pos + offset).collect()
+        };
+        match self {
+            Edit::Cells(cells, alive) => Edit::Cells(shift(cells), alive),
+            Edit::Walls(cells, wall) => Edit::Walls(shift(cells), wall),
+            Edit::Paste(region, cells, mode) => Edit::Paste(
+                CellRegion {
+                    min: region.min + offset,
+                    max: region.max + offset,
+                },
+                shift(cells),
+                mode,
+            ),
+        }
+    }
+}
+
+/// What a region looked like before a paste; restoring it is a `Copy` paste.
+struct UndoEntry {
+    region: CellRegion,
+    cells: Vec<I64Vec2>,
+}
+
+/// What a background step hands back to the main thread.
+enum StepOutput {
+    /// The shared engine was stepped in place (under the write lock).
+    InPlace { population: u64, epoch: u64 },
+    /// A private copy was stepped and must be swapped in.
+    /// `epoch` identifies which universe state it was computed from.
+    Pipelined {
+        engine: Box<dyn LifeEngine>,
+        population: u64,
+        epoch: u64,
+    },
+    /// The engine panicked while stepping. If it was stepped in place it may be corrupt.
+    Panicked { reason: String, in_place: bool },
+}
+
 #[derive(Resource)]
 pub struct Universe {
-    // The single source of truth for the engine, shared between threads.
+    // The front engine: what rendering and queries read. Shared between threads.
     engine: SharedEngine,
 
-    // Stores the Task spawned for the background step. The task now returns () instead of Duration.
-    step_task: Option<Task<()>>,
+    // Stores the Task spawned for the background step, and how long the step took.
+    step_task: Option<Task<(StepOutput, Duration)>>,
+
+    // Bumped whenever the state is replaced wholesale (clear, import, engine switch)
+    // so an in-flight pipelined step computed from the old state gets discarded.
+    epoch: u64,
+
+    // Edits made while a pipelined step was in flight; replayed onto its result.
+    pending_edits: Vec<Edit>,
+
+    // Live cells of the front engine, counted by the step task so frames don't have to;
+    // `UNCOUNTED` after edits until the next read or step.
+    population: Arc<AtomicU64>,
+
+    // Config: Keep every edit, with the generation it was made at, for `take_journal`.
+    pub journaling: bool,
+    journal: Vec<(u64, Edit)>,
+
+    // Edits kept for a macro while one is being recorded (see `MacroPlugin`).
+    recording: Option<Vec<Edit>>,
+
+    // Config: How many steps to take per fixed tick (see `Time<Fixed>`)
+    pub steps_per_tick: u64,
+
+    // Fixed ticks that passed since the last step started; the next step covers them all.
+    owed_ticks: u64,
+
+    // Config: Fixed ticks that may pile up before further ones are dropped.
+    pub max_owed_ticks: u64,
+
+    // Ticks dropped because steps could not keep up (see `max_owed_ticks`).
+    skipped_ticks: u64,
+
+    // Config: Ignore the tick rate and start a new step as soon as the last one finishes.
+    pub unthrottled: bool,
+
+    // One-off generations added to the next step (see `queue_steps`).
+    extra_steps: u64,
+
+    // Config: Step a copy of the engine in the background while the front copy renders.
+    pub pipelined: bool,
+
+    // Seed handed to engines with stochastic rules (see `SeededRngPlugin`).
+    seed: u64,
+
+    // The Life-like rule the engine runs (continuous engines keep their own parameters).
+    rule: LifeRule,
+
+    // Wrap dimensions while the universe is a torus (see `TorusPlugin`).
+    torus: Option<I64Vec2>,
+
+    // Only cells inside evolve while set (see `SelectionPlugin`).
+    step_region: Option<CellRegion>,
+
+    // Wall cells; kept here so they survive clears, imports and engine switches.
+    walls: FxHashSet<I64Vec2>,
+
+    // Previous contents of pasted regions, most recent last.
+    undo: Vec<UndoEntry>,
+
+    // An empty, configured copy of the current engine to rebuild it from after a crash.
+    blank: Box<dyn LifeEngine>,
+
+    // Cells of a recent good state (only kept while stepping in place; a pipelined
+    // crash leaves the front engine untouched) and when it was taken.
+    snapshot: Vec<I64Vec2>,
+    snapshot_at: Option<Instant>,
+
+    // Set after a crash; stepping resumes once the state is replaced.
+    halted: bool,
+
+    // Config: No new steps start while set; fixed ticks don't pile up meanwhile.
+    pub paused: bool,
+
+    // Config: How stepping slows down while the window is in the background.
+    pub unfocused: UnfocusedPolicy,
 
-    // Config: How many steps to take per frame
-    pub steps_per_frame: u64,
+    // Fractional generations a throttled background run has earned but not stepped.
+    background_credit: f64,
+
+    // Bumped by every change that isn't a step (edits, clears, engine switches).
+    revision: u64,
+
+    // Cells dropped for lying outside the supported range, not yet reported.
+    out_of_range: usize,
 }
 
 impl Default for Universe {
     fn default() -> Self {
-        let engine = create_engine(EngineMode::ArenaLife);
+        let engine = default_engine();
         Self {
             // Initialize the engine wrapped in Arc<RwLock<...>>
             engine: Arc::new(RwLock::new(engine)),
             step_task: None,
-            steps_per_frame: 1,
+            epoch: 0,
+            pending_edits: Vec::new(),
+            population: Arc::new(AtomicU64::new(0)),
+            journaling: false,
+            journal: Vec::new(),
+            recording: None,
+            steps_per_tick: 1,
+            owed_ticks: 0,
+            max_owed_ticks: MAX_OWED_TICKS,
+            skipped_ticks: 0,
+            unthrottled: false,
+            extra_steps: 0,
+            pipelined: true,
+            seed: 0,
+            rule: LifeRule::CONWAY,
+            torus: None,
+            step_region: None,
+            walls: FxHashSet::default(),
+            undo: Vec::new(),
+            blank: default_engine(),
+            snapshot: Vec::new(),
+            snapshot_at: None,
+            halted: false,
+            paused: false,
+            unfocused: UnfocusedPolicy::Run,
+            background_credit: 0.0,
+            revision: 0,
+            out_of_range: 0,
         }
     }
 }
 
 impl Universe {
-    #[allow(unused)]
+    /// Read access to the front engine. If a step panicked the last state is still
+    /// readable, so the universe keeps rendering while the error is reported.
     pub fn read_engine(&self) -> std::sync::RwLockReadGuard<'_, Box<dyn LifeEngine>> {
-        self.engine.read().unwrap()
+        self.engine.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// An independent copy of the front engine, for previews and background work. The
+    /// block engines and HashLife share their state with the original until either one
+    /// changes it, so this is cheap however big the universe is.
+    pub fn fork(&self) -> Box<dyn LifeEngine> {
+        let _span = info_span!("universe::fork").entered();
+        self.read_engine().box_clone()
+    }
+
+    /// An empty engine of the current kind, set up like the current one (rule, seed, torus).
+    pub fn blank_engine(&self) -> Box<dyn LifeEngine> {
+        self.blank.box_clone()
+    }
+
+    /// Whether a step panicked while holding the engine; stepping stops until replaced.
+    pub fn is_poisoned(&self) -> bool {
+        self.engine.is_poisoned()
     }
 
-    #[allow(unused)]
     pub fn set_cell(&mut self, pos: I64Vec2, alive: bool) {
-        if let Ok(mut engine) = self.engine.write() {
-            engine.set_cell(pos, alive);
-        }
+        self.edit_cells(vec![pos], alive);
     }
 
     pub fn add_cells(&mut self, cells: Vec<I64Vec2>) {
+        self.edit_cells(cells, true);
+    }
+
+    pub fn remove_cells(&mut self, cells: Vec<I64Vec2>) {
+        self.edit_cells(cells, false);
+    }
+
+    /// Applies an edit to the front engine and remembers it (see [`Universe::record`]).
+    fn edit_cells(&mut self, cells: Vec<I64Vec2>, alive: bool) {
+        let cells = self.in_range(cells);
+        self.revision += 1;
         if let Ok(mut engine) = self.engine.write() {
-            engine.set_cells(&cells, true);
+            engine.set_cells(&cells, alive);
+        }
+        self.record(Edit::Cells(cells, alive));
+    }
+
+    /// Keeps an edit already applied to the front engine for replaying onto a running
+    /// pipelined step's result, and for the journal.
+    fn record(&mut self, edit: Edit) {
+        self.population.store(UNCOUNTED, Ordering::Relaxed);
+        if self.journaling {
+            self.journal.push((self.generation(), edit.clone()));
+        }
+        if let Some(recording) = &mut self.recording {
+            recording.push(edit.clone());
+        }
+        if self.step_task.is_some() {
+            self.pending_edits.push(edit);
+        }
+    }
+
+    /// Edits recorded since the last call, with the generation each was made at.
+    /// Empty unless [`Universe::journaling`] is on.
+    pub fn take_journal(&mut self) -> Vec<(u64, Edit)> {
+        std::mem::take(&mut self.journal)
+    }
+
+    /// Makes journaled edits again, stepping the engine right here to the generation
+    /// each was made at (counted from the current state). Stops if a step panics.
+    pub fn replay(&mut self, journal: Vec<(u64, Edit)>) {
+        self.invalidate_step();
+        for (generation, edit) in journal {
+            let stepped = match self.engine.write() {
+                Ok(mut engine) => {
+                    let behind = generation.saturating_sub(engine.generation());
+                    behind == 0 || guarded_step(&mut engine, behind).is_ok()
+                }
+                Err(_) => false,
+            };
+            if !stepped {
+                warn!("Stopped replaying the journal before generation {generation}");
+                return;
+            }
+            self.apply(edit);
+        }
+    }
+
+    /// Makes a recorded edit again, as it was made.
+    pub fn apply(&mut self, edit: Edit) {
+        match edit {
+            Edit::Cells(cells, alive) => self.edit_cells(cells, alive),
+            Edit::Walls(cells, _) => self.add_walls(cells),
+            Edit::Paste(region, cells, mode) => self.paste(region, cells, mode),
         }
     }
 
+    /// Starts keeping every edit for [`Universe::stop_recording`], dropping any kept so far.
+    pub fn start_recording(&mut self) {
+        self.recording = Some(Vec::new());
+    }
+
+    /// The edits made since [`Universe::start_recording`], in order.
+    pub fn stop_recording(&mut self) -> Vec<Edit> {
+        self.recording.take().unwrap_or_default()
+    }
+
+    /// Drops (and counts, for reporting) cells no engine can hold.
+    fn in_range(&mut self, mut cells: Vec<I64Vec2>) -> Vec<I64Vec2> {
+        let before = cells.len();
+        cells.retain(|&pos| in_supported_range(pos));
+        self.out_of_range += before - cells.len();
+        cells
+    }
+
+    /// Invalidates any in-flight pipelined step; used before replacing the whole state.
+    fn invalidate_step(&mut self) {
+        self.epoch += 1;
+        self.population.store(UNCOUNTED, Ordering::Relaxed);
+        self.revision += 1;
+        self.pending_edits.clear();
+        self.snapshot_at = None;
+        self.halted = false;
+    }
+
+    /// Rebuilds the engine from the last snapshot after a step crashed and stops
+    /// stepping, so a pattern that reliably crashes the engine doesn't loop.
+    fn recover(&mut self) {
+        warn!(
+            "Rebuilding {} from a snapshot of {} cells",
+            self.engine_name(),
+            self.snapshot.len()
+        );
+        self.invalidate_step();
+        let mut engine = self.blank.box_clone();
+        engine.import(&self.snapshot);
+        engine.set_walls(&self.wall_cells(), true);
+
+        self.engine.clear_poison();
+        *self.engine.write().unwrap_or_else(PoisonError::into_inner) = engine;
+        self.halted = true;
+    }
+
+    /// Remembers the current cells for [`Universe::recover`] if the last snapshot is stale.
+    fn refresh_snapshot(&mut self) {
+        if self
+            .snapshot_at
+            .is_some_and(|at| at.elapsed() < SNAPSHOT_INTERVAL)
+        {
+            return;
+        }
+        let _span = info_span!("universe::snapshot").entered();
+        // Exported first, so the read lock is gone before the field is replaced
+        let snapshot = self.read_engine().export();
+        self.snapshot = snapshot;
+        self.snapshot_at = Some(Instant::now());
+    }
+
+    /// Removes every cell and wall.
     pub fn clear(&mut self) {
+        self.invalidate_step();
+        self.walls.clear();
+        self.undo.clear();
         if let Ok(mut engine) = self.engine.write() {
             engine.clear();
         }
     }
 
-    #[allow(unused)]
     pub fn import(&mut self, cells: Vec<I64Vec2>) {
+        let cells = self.in_range(cells);
+        self.invalidate_step();
+        self.undo.clear();
+        let walls = self.wall_cells();
         if let Ok(mut engine) = self.engine.write() {
             engine.import(&cells);
+            engine.set_walls(&walls, true);
+        }
+    }
+
+    /// Replaces the cells with a macrocell file centered on `center`, keeping the walls.
+    /// `None` if the engine has no tree to read it into (see [`LifeEngine::read_macrocell`]).
+    pub fn load_macrocell(&mut self, text: &str, center: I64Vec2) -> Option<Result<(), LifeError>> {
+        self.read_engine().inspect_tree(&[])?;
+        self.invalidate_step();
+        self.undo.clear();
+        let walls = self.wall_cells();
+        let mut engine = self.engine.write().ok()?;
+        let loaded = engine.read_macrocell(text, center)?;
+        engine.set_walls(&walls, true);
+        Some(loaded)
+    }
+
+    /// Combines `cells` with the contents of `region` (see [`PasteMode`]) as one undoable edit.
+    pub fn paste(&mut self, region: CellRegion, cells: Vec<I64Vec2>, mode: PasteMode) {
+        // Cut off, the pattern would no longer be what was copied
+        if !in_supported_range(region.min) || !in_supported_range(region.max - I64Vec2::ONE) {
+            self.out_of_range += cells.len().max(1);
+            return;
         }
+        let previous = self.cells_in(region);
+        if self.undo.len() == MAX_UNDO {
+            self.undo.remove(0);
+        }
+        self.undo.push(UndoEntry {
+            region,
+            cells: previous,
+        });
+        self.apply_paste(region, cells, mode);
+    }
+
+    /// The current state as a keyframe, if the engine takes them (see [`Keyframe`]).
+    pub fn keyframe(&self) -> Option<Keyframe> {
+        self.read_engine().keyframe()
+    }
+
+    /// Puts the engine back to `keyframe`. Returns false if the current engine can't.
+    pub fn restore_keyframe(&mut self, keyframe: &Keyframe) -> bool {
+        self.invalidate_step();
+        self.engine
+            .write()
+            .is_ok_and(|mut engine| engine.restore_keyframe(keyframe))
     }
 
-    pub fn switch_engine(&mut self, mode: EngineMode) {
-        println!("Switching Engine to {:?}", mode);
+    /// Reverts the most recent paste. Returns false if there was nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        let Some(entry) = self.undo.pop() else {
+            return false;
+        };
+        self.apply_paste(entry.region, entry.cells, PasteMode::Copy);
+        true
+    }
+
+    fn apply_paste(&mut self, region: CellRegion, cells: Vec<I64Vec2>, mode: PasteMode) {
+        self.revision += 1;
+        if let Ok(mut engine) = self.engine.write() {
+            engine.paste(region, &cells, mode);
+        }
+        self.record(Edit::Paste(region, cells, mode));
+    }
+
+    /// Live cells inside `region`.
+    pub fn cells_in(&self, region: CellRegion) -> Vec<I64Vec2> {
+        self.read_engine().export_region(region)
+    }
+
+    /// Turns `cells` into walls, moving to an engine that supports them if necessary.
+    pub fn add_walls(&mut self, cells: Vec<I64Vec2>) {
+        let cells = self.in_range(cells);
+        let supported = self.read_engine().supports_walls();
+        if !supported {
+            self.replace_engine(self.rule_engine());
+        }
+
+        let torus = self.torus;
+        let cells: Vec<I64Vec2> = cells.into_iter().map(|pos| wrap(torus, pos)).collect();
+        self.walls.extend(cells.iter().copied());
+        self.revision += 1;
+        if let Ok(mut engine) = self.engine.write() {
+            engine.set_walls(&cells, true);
+        }
+        self.record(Edit::Walls(cells, true));
+    }
+
+    pub fn walls(&self) -> impl Iterator<Item = I64Vec2> + '_ {
+        self.walls.iter().copied()
+    }
+
+    fn wall_cells(&self) -> Vec<I64Vec2> {
+        self.walls.iter().copied().collect()
+    }
+
+    /// Sets the seed for stochastic engines, now and after future engine switches.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+        if let Ok(mut engine) = self.engine.write() {
+            engine.set_seed(seed);
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn switch_engine(&mut self, entry: &EngineEntry) {
+        info!("Switching engine to {}", entry.name);
+        let _span = info_span!("universe::switch_engine", id = entry.id).entered();
+        // The built-in engines are all hardwired to Conway's rule
+        self.rule = LifeRule::CONWAY;
+        self.replace_engine(entry.create());
+    }
+
+    /// Runs `rule` from now on, keeping the current cells.
+    /// Conway's rule on the plane goes back to the default bitwise engine; anything else
+    /// runs on the slower general-purpose rule engine.
+    pub fn set_rule(&mut self, rule: LifeRule) {
+        let _span = info_span!("universe::set_rule", %rule).entered();
+        self.rule = rule;
+        self.replace_engine(self.rule_engine());
+    }
+
+    /// Wraps the universe into a `size` torus (or unwraps it with `None`),
+    /// re-mapping the existing cells modulo the new size.
+    pub fn set_torus(&mut self, size: Option<I64Vec2>) {
+        let _span = info_span!("universe::set_torus", ?size).entered();
+        self.torus = size;
+        self.walls = self.walls.drain().map(|pos| wrap(size, pos)).collect();
+        self.replace_engine(self.rule_engine());
+    }
+
+    pub fn torus(&self) -> Option<I64Vec2> {
+        self.torus
+    }
+
+    /// Freezes everything outside `region` (or unfreezes with `None`).
+    pub fn set_step_region(&mut self, region: Option<CellRegion>) {
+        self.step_region = region;
+        let supported = self.read_engine().supports_step_region();
+        if supported {
+            self.invalidate_step();
+            if let Ok(mut engine) = self.engine.write() {
+                engine.set_step_region(region);
+            }
+        } else if region.is_some() {
+            self.replace_engine(self.rule_engine());
+        }
+    }
+
+    pub fn step_region(&self) -> Option<CellRegion> {
+        self.step_region
+    }
+
+    fn rule_engine(&self) -> Box<dyn LifeEngine> {
+        if self.rule == LifeRule::CONWAY && self.torus.is_none() {
+            default_engine()
+        } else {
+            create_rule_engine(self.rule)
+        }
+    }
+
+    pub fn rule(&self) -> LifeRule {
+        self.rule
+    }
+
+    fn replace_engine(&mut self, mut new_engine: Box<dyn LifeEngine>) {
+        self.invalidate_step();
         if let Ok(mut old_engine) = self.engine.write() {
-            // 1. Export state
-            let cells = old_engine.export();
+            // 1. Set up the new engine, wrapped if it can run on the torus
+            if self.torus.is_some() && !new_engine.supports_torus() {
+                info!("{} can't wrap around, leaving the torus", new_engine.name());
+                self.torus = None;
+            }
+            if !self.walls.is_empty() && !new_engine.supports_walls() {
+                info!("{} has no walls, removing them", new_engine.name());
+                self.walls.clear();
+            }
+            if self.step_region.is_some() && !new_engine.supports_step_region() {
+                info!(
+                    "{} can't step a region alone, unfreezing",
+                    new_engine.name()
+                );
+                self.step_region = None;
+            }
+            new_engine.set_seed(self.seed);
+            new_engine.set_torus(self.torus);
+            new_engine.set_step_region(self.step_region);
+            self.blank = new_engine.box_clone();
 
-            // 2. Create and import into the new engine
-            let mut new_engine = create_engine(mode);
-            new_engine.import(&cells);
+            // 2. Hand the cells over tile by tile rather than as one big list
+            old_engine.visit_tiles(&mut |pos, tile| new_engine.add_tile(pos, tile));
+            new_engine.set_walls(&self.wall_cells(), true);
 
             // 3. Swap the engine inside the lock
             *old_engine = new_engine;
@@ -96,31 +701,172 @@ impl Universe {
 
     // Public API for view/stats remains clean, reading from the single source of truth
     pub fn draw_to_buffer(&self, rect: Rect, buffer: &mut [u8], width: usize, height: usize) {
-        if let Ok(engine) = self.engine.read() {
-            engine.draw_to_buffer(rect, buffer, width, height);
+        let population = self.population();
+        self.read_engine()
+            .draw_to_buffer(rect, buffer, width, height, population);
+    }
+
+    /// Adds `steps` generations to the next step only, e.g. to shift the phase.
+    pub fn queue_steps(&mut self, steps: u64) {
+        self.extra_steps += steps;
+    }
+
+    /// Whether the next step will run on a copy of the engine (see [`Universe::pipelined`]).
+    pub fn is_pipelining(&self) -> bool {
+        self.pipelined && self.read_engine().prefers_pipelining()
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.read_engine().generation()
+    }
+
+    /// Counts generations on from `generation`, as if the universe had been run that far.
+    pub fn set_generation(&mut self, generation: u64) {
+        self.invalidate_step();
+        if let Ok(mut engine) = self.engine.write() {
+            engine.set_generation(generation);
         }
     }
 
+    pub fn memory_bytes(&self) -> usize {
+        self.read_engine().memory_bytes()
+    }
+
+    /// Live cells, as counted after the last step; only edits since then need a recount.
     pub fn population(&self) -> u64 {
-        self.engine.read().map(|e| e.population()).unwrap_or(0)
+        match self.population.load(Ordering::Relaxed) {
+            UNCOUNTED => {
+                let population = self.read_engine().population();
+                self.population.store(population, Ordering::Relaxed);
+                population
+            }
+            population => population,
+        }
     }
 
     pub fn engine_name(&self) -> String {
-        self.engine
-            .read()
-            .map(|e| e.name().to_string())
-            .unwrap_or_default()
+        self.read_engine().name().to_string()
+    }
+
+    pub fn engine_id(&self) -> String {
+        self.read_engine().id().to_string()
+    }
+
+    /// Changes whenever the cells change other than by stepping.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+}
+
+/// Maps `pos` into the fundamental domain of the torus, if there is one.
+fn wrap(torus: Option<I64Vec2>, pos: I64Vec2) -> I64Vec2 {
+    match torus {
+        Some(size) => I64Vec2::new(pos.x.rem_euclid(size.x), pos.y.rem_euclid(size.y)),
+        None => pos,
     }
 }
 
 // --- Systems ---
 
-fn step_universe(mut universe: ResMut<Universe>, mut stats: ResMut<StatsBoard>) {
+// Owes the universe `steps_per_tick` generations per fixed tick
+fn tick_universe(
+    time: Res<Time>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut universe: ResMut<Universe>,
+) {
+    if universe.paused {
+        return;
+    }
+    let focused = !matches!(q_window.single(), Ok(window) if !window.focused);
+    match universe.unfocused {
+        _ if focused => universe.background_credit = 0.0,
+        UnfocusedPolicy::Run => {}
+        UnfocusedPolicy::Pause => return,
+        UnfocusedPolicy::Throttle(rate) => {
+            // Whole generations go out as one-off steps instead of ticks of the set speed
+            universe.background_credit += rate as f64 * time.delta_secs_f64();
+            let whole = universe.background_credit.floor();
+            universe.background_credit -= whole;
+            universe.extra_steps += whole as u64;
+            return;
+        }
+    }
+    if universe.owed_ticks < universe.max_owed_ticks {
+        universe.owed_ticks += 1;
+    } else {
+        universe.skipped_ticks += 1;
+    }
+}
+
+fn step_universe(
+    mut universe: ResMut<Universe>,
+    mut stats: ResMut<StatsBoard>,
+    mut diagnostics: Diagnostics,
+    mut errors: MessageWriter<LifeError>,
+) {
+    // A panic outside the guarded step (e.g. while cloning) still poisons the lock
+    if universe.is_poisoned() && universe.step_task.is_none() {
+        errors.write(LifeError::EngineCrashed {
+            engine: universe.engine_name(),
+            reason: "engine lock poisoned".to_string(),
+        });
+        universe.recover();
+    }
+
     // 1. Check if a step is running and poll it
     if let Some(mut task) = universe.step_task.take() {
-        if poll_task_once(&mut task).is_some() {
-            // Task is complete: Update Stats (excluding step time)
+        if let Some((output, elapsed)) = poll_task_once(&mut task) {
+            diagnostics.add_measurement(&STEP_TIME, || elapsed.as_secs_f64() * 1000.0);
+
+            // Task is complete: swap in a pipelined result, unless it was made stale meanwhile
+            let edits = std::mem::take(&mut universe.pending_edits);
+            match output {
+                StepOutput::Panicked { reason, in_place } => {
+                    errors.write(LifeError::EngineCrashed {
+                        engine: universe.engine_name(),
+                        reason,
+                    });
+                    if in_place {
+                        universe.recover();
+                    } else {
+                        // Only the copy was lost; the front engine is still good
+                        universe.halted = true;
+                    }
+                }
+                StepOutput::Pipelined {
+                    mut engine,
+                    population,
+                    epoch,
+                } if epoch == universe.epoch => {
+                    for edit in &edits {
+                        match edit {
+                            Edit::Cells(cells, alive) => engine.set_cells(cells, *alive),
+                            Edit::Walls(cells, wall) => engine.set_walls(cells, *wall),
+                            Edit::Paste(region, cells, mode) => engine.paste(*region, cells, *mode),
+                        }
+                    }
+                    let old = universe
+                        .engine
+                        .write()
+                        .map(|mut front| std::mem::replace(&mut *front, engine));
+                    // Drop the previous generation outside the lock
+                    drop(old);
+                    if edits.is_empty() {
+                        universe.population.store(population, Ordering::Relaxed);
+                    }
+                }
+                StepOutput::InPlace { population, epoch }
+                    if epoch == universe.epoch && edits.is_empty() =>
+                {
+                    universe.population.store(population, Ordering::Relaxed);
+                }
+                // Stale: the state was replaced while this step ran
+                _ => {}
+            }
+
+            // Update Stats (excluding step time)
             stats.insert("Engine", universe.engine_name()); // Read from the live engine
+            stats.insert("Pipelined", universe.is_pipelining());
 
         // Task has been consumed by `task.take()`
         } else {
@@ -131,48 +877,217 @@ fn step_universe(mut universe: ResMut<Universe>, mut stats: ResMut<StatsBoard>)
     }
 
     // 2. Start a new step if no task is currently running/being polled
-    if universe.step_task.is_none() {
+    let due = universe.owed_ticks > 0 || universe.extra_steps > 0 || universe.unthrottled;
+    if universe.step_task.is_none() && !universe.halted && !universe.paused && due {
+        let ticks = match std::mem::take(&mut universe.owed_ticks) {
+            0 if universe.unthrottled => 1,
+            ticks => ticks,
+        };
         let shared_engine_ref = Arc::clone(&universe.engine);
-        let steps = universe.steps_per_frame;
+        let steps = universe.steps_per_tick * ticks + std::mem::take(&mut universe.extra_steps);
+        if universe.skipped_ticks > 0 {
+            stats.insert("Skipped Ticks", universe.skipped_ticks);
+        }
+        let pipelined = universe.is_pipelining();
+        let epoch = universe.epoch;
+        if !pipelined {
+            universe.refresh_snapshot();
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        let offloaded = crate::simulation::step_worker::StepJob::prepare(&universe, steps);
 
         let thread_pool = AsyncComputeTaskPool::get();
 
         let task = thread_pool.spawn(async move {
-            if let Ok(mut engine) = shared_engine_ref.write() {
-                engine.step(steps);
+            let start = Instant::now();
+            // Tasks run on the page's only thread on the web; a worker steps where it can
+            #[cfg(target_arch = "wasm32")]
+            if let Some(job) = offloaded
+                && let Some((engine, population)) = job.run().await
+            {
+                let output = StepOutput::Pipelined {
+                    engine,
+                    population,
+                    epoch,
+                };
+                return (output, start.elapsed());
+            }
+            if pipelined {
+                // Step a private copy so readers keep drawing generation N meanwhile
+                let copy = {
+                    let _span = info_span!("universe::clone_engine").entered();
+                    shared_engine_ref.read().map(|e| e.box_clone())
+                };
+                if let Ok(mut engine) = copy {
+                    let output = match guarded_step(&mut engine, steps) {
+                        Ok(()) => StepOutput::Pipelined {
+                            population: engine.population(),
+                            engine,
+                            epoch,
+                        },
+                        Err(reason) => StepOutput::Panicked {
+                            reason,
+                            in_place: false,
+                        },
+                    };
+                    return (output, start.elapsed());
+                }
+            } else if let Ok(mut engine) = shared_engine_ref.write() {
+                let output = match guarded_step(&mut engine, steps) {
+                    // Counted here, off the main thread, rather than by every frame
+                    Ok(()) => StepOutput::InPlace {
+                        population: engine.population(),
+                        epoch,
+                    },
+                    Err(reason) => StepOutput::Panicked {
+                        reason,
+                        in_place: true,
+                    },
+                };
+                return (output, start.elapsed());
             }
+            let output = StepOutput::InPlace {
+                population: UNCOUNTED,
+                epoch,
+            };
+            (output, start.elapsed())
         });
 
         universe.step_task = Some(task);
     }
 }
 
+// Tells the user about edits that were cut off at the edge of the supported range
+fn report_out_of_range(mut universe: ResMut<Universe>, mut errors: MessageWriter<LifeError>) {
+    if universe.out_of_range > 0 {
+        let count = std::mem::take(&mut universe.out_of_range);
+        errors.write(LifeError::OutOfRange { count });
+    }
+}
+
+// Shows which number key selects which engine, whenever the registry changes
+fn list_engines(registry: Res<EngineRegistry>, mut stats: ResMut<StatsBoard>) {
+    if !registry.is_changed() {
+        return;
+    }
+    let keys = registry
+        .iter()
+        .zip(1..=ENGINE_KEYS.len())
+        .map(|(entry, key)| format!("{key} {}", entry.name))
+        .collect::<Vec<_>>()
+        .join(", ");
+    stats.insert("Engines", keys);
+}
+
+/// Steps `engine`, turning a panic into an error instead of unwinding through the lock.
+fn guarded_step(engine: &mut Box<dyn LifeEngine>, steps: u64) -> Result<(), String> {
+    std::panic::catch_unwind(AssertUnwindSafe(|| {
+        engine.step(steps);
+    }))
+    .map_err(|payload| {
+        payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string())
+    })
+}
+
+// Shows the current rule and tells other systems when it changes
+fn announce_rule(
+    universe: Res<Universe>,
+    mut last: Local<Option<LifeRule>>,
+    mut changed: MessageWriter<RuleChanged>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    let rule = universe.rule();
+    if *last == Some(rule) {
+        return;
+    }
+    if last.is_some() {
+        changed.write(RuleChanged { rule });
+    }
+    *last = Some(rule);
+    stats.insert("Rule", rule);
+}
+
 // Handles key input and triggers state changes directly on the locked engine.
-fn handle_input(mut universe: ResMut<Universe>, keys: Res<ButtonInput<KeyCode>>) {
-    if keys.just_pressed(KeyCode::KeyC) {
+fn handle_input(
+    mut universe: ResMut<Universe>,
+    registry: Res<EngineRegistry>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut fixed: ResMut<Time<Fixed>>,
+    mut stats: ResMut<StatsBoard>,
+) {
+    // PageUp/PageDown: double/halve the tick rate
+    // Rounded, since the timestep is stored as a Duration
+    let hz = (1000.0 / fixed.timestep().as_secs_f64()).round() / 1000.0;
+    let new_hz = if keys.just_pressed(KeyCode::PageUp) {
+        (hz * 2.0).min(MAX_TICK_HZ)
+    } else if keys.just_pressed(KeyCode::PageDown) {
+        (hz / 2.0).max(MIN_TICK_HZ)
+    } else {
+        hz
+    };
+    if new_hz != hz || !stats.contains("Tick Rate") {
+        fixed.set_timestep_hz(new_hz);
+        universe.skipped_ticks = 0;
+        stats.remove("Skipped Ticks");
+        stats.insert("Tick Rate", format!("{new_hz} Hz"));
+    }
+
+    // F3: keep running, throttle or pause while the window is in the background
+    if keys.just_pressed(KeyCode::F3) {
+        universe.unfocused = match universe.unfocused {
+            UnfocusedPolicy::Run => UnfocusedPolicy::Throttle(DEFAULT_THROTTLE),
+            UnfocusedPolicy::Throttle(_) => UnfocusedPolicy::Pause,
+            UnfocusedPolicy::Pause => UnfocusedPolicy::Run,
+        };
+    }
+    if keys.just_pressed(KeyCode::F3) || !stats.contains("Unfocused") {
+        let text = match universe.unfocused {
+            UnfocusedPolicy::Run => "keep running".to_string(),
+            UnfocusedPolicy::Throttle(rate) => format!("{rate} generations/s"),
+            UnfocusedPolicy::Pause => "pause".to_string(),
+        };
+        stats.insert("Unfocused", format!("{text} (F3)"));
+    }
+
+    // Backslash: pause or resume
+    if keys.just_pressed(KeyCode::Backslash) {
+        universe.paused = !universe.paused;
+    }
+    if universe.paused != stats.contains("Paused") {
+        if universe.paused {
+            stats.insert("Paused", "\\ resumes");
+        } else {
+            stats.remove("Paused");
+        }
+    }
+
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if keys.just_pressed(KeyCode::KeyC) && !ctrl {
         universe.clear();
         println!("Universe cleared!");
     }
 
-    let switch_mode = if keys.just_pressed(KeyCode::Digit1) {
-        Some(EngineMode::ArenaLife)
-    } else if keys.just_pressed(KeyCode::Digit2) {
-        Some(EngineMode::SparseLife)
-    } else if keys.just_pressed(KeyCode::Digit3) {
-        Some(EngineMode::HashLife)
-    } else {
-        None
-    };
+    // 1-9: the registered engines, in order
+    let switch_to = registry
+        .iter()
+        .zip(ENGINE_KEYS)
+        .find(|&(_, key)| keys.just_pressed(key))
+        .map(|(entry, _)| entry);
 
-    if let Some(mode) = switch_mode {
+    if let Some(entry) = switch_to {
         // The switch happens synchronously on the main thread,
         // taking a brief write lock on the engine.
-        universe.switch_engine(mode);
+        universe.switch_engine(entry);
     }
 }
 
 // Standard Bevy boilerplate for polling tasks without blocking.
-fn poll_task_once<T>(task: &mut Task<T>) -> Option<T> {
+pub fn poll_task_once<T>(task: &mut Task<T>) -> Option<T> {
     let waker = noop_waker();
     let mut cx = std::task::Context::from_waker(&waker);
     match std::pin::Pin::new(task).poll(&mut cx) {
@@ -0,0 +1,377 @@
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use rustc_hash::FxHashMap;
+
+use crate::simulation::SimulationInput;
+use crate::simulation::graphics::{
+    LayerCanvas, LayerDesc, LayerSpawner, LayerViewport, PixelLayer,
+};
+use crate::simulation::view::{MouseWorldPosition, SimulationView};
+
+/// Cells each player may place in a round.
+const BUDGET: u32 = 40;
+/// Placements before the turn passes to the other player.
+const CELLS_PER_TURN: u32 = 5;
+/// Generations simulated once both players are done.
+const GENERATIONS: u32 = 300;
+/// Players place inside the square `-ARENA_HALF..ARENA_HALF`, red left of x = 0, blue right.
+const ARENA_HALF: i64 = 32;
+
+const PLAYER_NAMES: [&str; 2] = ["Red", "Blue"];
+pub const PLAYER_COLORS: [Vec4; 2] = [Vec4::new(1.0, 0.3, 0.3, 1.0), Vec4::new(0.3, 0.5, 1.0, 1.0)];
+/// Layers hidden while a match is on screen.
+const HIDDEN_LAYERS: [&str; 3] = ["universe", "walls", "draw"];
+
+pub struct VersusPlugin;
+
+impl Plugin for VersusPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<VersusGame>()
+            .add_systems(Startup, (setup_versus_layer, setup_score_ui))
+            .add_systems(
+                Update,
+                (
+                    toggle_versus.in_set(SimulationInput),
+                    place_cells.in_set(SimulationInput),
+                    end_turn.in_set(SimulationInput),
+                    run_match,
+                    render_versus,
+                    update_score_ui,
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VersusPhase {
+    /// No match; the normal sandbox is shown.
+    Inactive,
+    /// `player` is placing cells.
+    Placing { player: usize },
+    /// Both players are done; the board evolves.
+    Running { remaining: u32 },
+    /// The match is over and the board is frozen for inspection.
+    Finished,
+}
+
+/// Two-color Life (Immigration rule): births take the majority color of their three parents.
+#[derive(Resource)]
+pub struct VersusGame {
+    pub phase: VersusPhase,
+    /// Live cells and the player owning them.
+    cells: FxHashMap<I64Vec2, u8>,
+    placed: [u32; 2],
+    placed_this_turn: u32,
+}
+
+impl Default for VersusGame {
+    fn default() -> Self {
+        Self {
+            phase: VersusPhase::Inactive,
+            cells: FxHashMap::default(),
+            placed: [0; 2],
+            placed_this_turn: 0,
+        }
+    }
+}
+
+/// Run condition for sandbox input that must not fire during a match.
+pub fn no_match_running(game: Res<VersusGame>) -> bool {
+    !game.is_active()
+}
+
+impl VersusGame {
+    pub fn is_active(&self) -> bool {
+        self.phase != VersusPhase::Inactive
+    }
+
+    fn start(&mut self) {
+        *self = Self {
+            phase: VersusPhase::Placing { player: 0 },
+            ..default()
+        };
+    }
+
+    /// Living cells and the index of the player owning each.
+    pub fn cells(&self) -> impl Iterator<Item = (I64Vec2, usize)> + '_ {
+        self.cells
+            .iter()
+            .map(|(&pos, &owner)| (pos, owner as usize))
+    }
+
+    /// Living cells per player.
+    pub fn scores(&self) -> [u32; 2] {
+        let mut scores = [0; 2];
+        for &owner in self.cells.values() {
+            scores[owner as usize] += 1;
+        }
+        scores
+    }
+
+    fn remaining(&self, player: usize) -> u32 {
+        BUDGET - self.placed[player]
+    }
+
+    /// Why `player` may not place a cell at `pos`, if anything.
+    fn validate(&self, player: usize, pos: I64Vec2) -> Result<(), &'static str> {
+        if self.remaining(player) == 0 {
+            return Err("no cells left");
+        }
+        if pos.x.abs() >= ARENA_HALF || pos.y.abs() >= ARENA_HALF {
+            return Err("outside the arena");
+        }
+        let own_half = if player == 0 { pos.x < 0 } else { pos.x >= 0 };
+        if !own_half {
+            return Err("not your half");
+        }
+        if self.cells.contains_key(&pos) {
+            return Err("already occupied");
+        }
+        Ok(())
+    }
+
+    fn place(&mut self, player: usize, pos: I64Vec2) -> Result<(), &'static str> {
+        self.validate(player, pos)?;
+        self.cells.insert(pos, player as u8);
+        self.placed[player] += 1;
+        self.placed_this_turn += 1;
+        if self.placed_this_turn >= CELLS_PER_TURN || self.remaining(player) == 0 {
+            self.pass_turn(player);
+        }
+        Ok(())
+    }
+
+    /// Hands the turn to whoever still has cells, or starts the match.
+    fn pass_turn(&mut self, player: usize) {
+        self.placed_this_turn = 0;
+        let other = 1 - player;
+        self.phase = if self.remaining(other) > 0 {
+            VersusPhase::Placing { player: other }
+        } else if self.remaining(player) > 0 {
+            VersusPhase::Placing { player }
+        } else {
+            VersusPhase::Running {
+                remaining: GENERATIONS,
+            }
+        };
+    }
+
+    fn step(&mut self) {
+        // Neighbour counts per color
+        let mut counts: FxHashMap<I64Vec2, [u8; 2]> = FxHashMap::default();
+        for (&pos, &owner) in &self.cells {
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    if dx != 0 || dy != 0 {
+                        counts.entry(pos + I64Vec2::new(dx, dy)).or_default()[owner as usize] += 1;
+                    }
+                }
+            }
+        }
+
+        let mut next = FxHashMap::default();
+        for (pos, [red, blue]) in counts {
+            let total = red + blue;
+            match self.cells.get(&pos) {
+                Some(&owner) if total == 2 || total == 3 => {
+                    next.insert(pos, owner);
+                }
+                None if total == 3 => {
+                    next.insert(pos, if red > blue { 0 } else { 1 });
+                }
+                _ => {}
+            }
+        }
+        self.cells = next;
+    }
+}
+
+#[derive(Component)]
+struct VersusLayer;
+
+#[derive(Component)]
+struct ScoreText;
+
+// One palette layer for the whole board: state 0 is empty, state 1 + i is player i
+fn setup_versus_layer(mut layers: LayerSpawner) {
+    layers
+        .spawn(
+            LayerDesc::new("versus", 0.2)
+                .palette([Vec4::ZERO, PLAYER_COLORS[0], PLAYER_COLORS[1]])
+                .hidden(),
+        )
+        .insert(VersusLayer);
+}
+
+fn setup_score_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(10.0),
+                right: Val::Px(10.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+            BackgroundColor(Color::BLACK.with_alpha(0.7)),
+            GlobalZIndex(100),
+            Visibility::Hidden,
+            ScoreText,
+        ))
+        .with_child((
+            Text::default(),
+            TextFont {
+                font,
+                font_size: 20.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+        ));
+}
+
+// G: start a match, or leave the current one
+fn toggle_versus(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut game: ResMut<VersusGame>,
+    mut q_layers: Query<(&mut PixelLayer, Option<&VersusLayer>)>,
+) {
+    if !keys.just_pressed(KeyCode::KeyG) {
+        return;
+    }
+
+    if game.is_active() {
+        game.phase = VersusPhase::Inactive;
+    } else {
+        game.start();
+    }
+
+    let active = game.is_active();
+    for (mut layer, versus) in &mut q_layers {
+        if versus.is_some() {
+            layer.visible = active;
+        } else if HIDDEN_LAYERS.contains(&layer.name.as_str()) {
+            layer.visible = !active;
+        }
+    }
+}
+
+fn place_cells(
+    mut game: ResMut<VersusGame>,
+    mouse_res: Res<MouseWorldPosition>,
+    buttons: Res<ButtonInput<MouseButton>>,
+) {
+    let VersusPhase::Placing { player } = game.phase else {
+        return;
+    };
+    if !buttons.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let Some(pos) = mouse_res.grid_pos else {
+        return;
+    };
+
+    if let Err(reason) = game.place(player, pos) {
+        info!("{} can't place at {pos}: {reason}", PLAYER_NAMES[player]);
+    }
+}
+
+// Enter: done placing, forfeiting any cells left in the budget
+fn end_turn(keys: Res<ButtonInput<KeyCode>>, mut game: ResMut<VersusGame>) {
+    if let VersusPhase::Placing { player } = game.phase
+        && keys.just_pressed(KeyCode::Enter)
+    {
+        game.placed[player] = BUDGET;
+        game.pass_turn(player);
+    }
+}
+
+fn run_match(mut game: ResMut<VersusGame>) {
+    let VersusPhase::Running { remaining } = game.phase else {
+        return;
+    };
+
+    game.step();
+    game.phase = if remaining > 1 {
+        VersusPhase::Running {
+            remaining: remaining - 1,
+        }
+    } else {
+        let [red, blue] = game.scores();
+        info!("Versus finished: Red {red}, Blue {blue}");
+        VersusPhase::Finished
+    };
+}
+
+fn render_versus(
+    game: Res<VersusGame>,
+    view: Res<SimulationView>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut q_layer: Query<(&PixelLayer, &mut LayerCanvas), With<VersusLayer>>,
+) {
+    if !game.is_active() {
+        return;
+    }
+    let Ok((layer, mut canvas)) = q_layer.single_mut() else {
+        return;
+    };
+    let Ok(window) = q_window.single() else {
+        return;
+    };
+
+    let Some(viewport) = LayerViewport::new(window, &view, layer) else {
+        return;
+    };
+    let buffer = canvas.buffer(&viewport);
+    buffer.fill(0);
+
+    for (&pos, &owner) in &game.cells {
+        viewport.draw_cell(buffer, pos.x, pos.y, owner + 1);
+    }
+}
+
+fn update_score_ui(
+    game: Res<VersusGame>,
+    mut q_panel: Query<(&mut Visibility, &Children), With<ScoreText>>,
+    mut q_text: Query<&mut Text>,
+) {
+    if !game.is_changed() {
+        return;
+    }
+    let Ok((mut visibility, children)) = q_panel.single_mut() else {
+        return;
+    };
+
+    *visibility = if game.is_active() {
+        Visibility::Visible
+    } else {
+        Visibility::Hidden
+    };
+
+    let [red, blue] = game.scores();
+    let status = match game.phase {
+        VersusPhase::Inactive => return,
+        VersusPhase::Placing { player } => format!(
+            "{} to place ({} left, Enter when done)",
+            PLAYER_NAMES[player],
+            game.remaining(player)
+        ),
+        VersusPhase::Running { remaining } => {
+            format!("Generation {}/{GENERATIONS}", GENERATIONS - remaining)
+        }
+        VersusPhase::Finished => match red.cmp(&blue) {
+            std::cmp::Ordering::Greater => "Red wins!".to_string(),
+            std::cmp::Ordering::Less => "Blue wins!".to_string(),
+            std::cmp::Ordering::Equal => "Draw!".to_string(),
+        },
+    };
+
+    for &child in children {
+        if let Ok(mut text) = q_text.get_mut(child) {
+            **text = format!("Versus (G to quit)\nRed: {red}\nBlue: {blue}\n{status}");
+        }
+    }
+}
@@ -3,17 +3,25 @@ use bevy::math::{DVec2, I64Vec2, Vec2};
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
 
+use crate::simulation::ViewInput;
+
 pub struct ViewPlugin;
 
 impl Plugin for ViewPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<SimulationView>()
             .init_resource::<MouseWorldPosition>()
-            .add_systems(Update, (update_view_transform, update_mouse_world_pos));
+            .add_systems(
+                Update,
+                (
+                    update_view_transform.in_set(ViewInput),
+                    update_mouse_world_pos,
+                ),
+            );
     }
 }
 
-#[derive(Resource)]
+#[derive(Resource, Clone, Copy, Debug)]
 pub struct SimulationView {
     pub center: DVec2,
     pub zoom: f64,
@@ -74,14 +82,13 @@ fn update_view_transform(
     }
 
     if let Some(current_pos) = cursor_moved.read().last().map(|e| e.position) {
-        if let Some(prev_pos) = *last_cursor_pos {
-            if buttons.pressed(MouseButton::Right) || keys.pressed(KeyCode::Space) {
-                let screen_delta = current_pos - prev_pos;
-                // Important: Y is inverted for World Space
-                let world_delta =
-                    DVec2::new(screen_delta.x as f64, -screen_delta.y as f64) / view.zoom;
-                view.center -= world_delta;
-            }
+        if let Some(prev_pos) = *last_cursor_pos
+            && (buttons.pressed(MouseButton::Right) || keys.pressed(KeyCode::Space))
+        {
+            let screen_delta = current_pos - prev_pos;
+            // Important: Y is inverted for World Space
+            let world_delta = DVec2::new(screen_delta.x as f64, -screen_delta.y as f64) / view.zoom;
+            view.center -= world_delta;
         }
         *last_cursor_pos = Some(current_pos);
     }
@@ -0,0 +1,619 @@
+use std::fmt::Write as _;
+use std::path::Path;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+
+use bevy::math::{DVec2, I64Vec2};
+use bevy::prelude::*;
+
+use crate::simulation::SimulationInput;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::simulation::batch::arg_value;
+#[cfg(target_arch = "wasm32")]
+use crate::simulation::browser_storage::{self, WORKSPACE_KEY};
+use crate::simulation::codec::{parse_edit, write_edit};
+use crate::simulation::engine::{CellRegion, EngineRegistry, create_rule_engine, default_engine};
+use crate::simulation::error::LifeError;
+use crate::simulation::graphics::LayerShader;
+use crate::simulation::import::ImportBudget;
+use crate::simulation::macros::{Macro, Macros};
+use crate::simulation::rle;
+use crate::simulation::rules::LifeRule;
+use crate::simulation::seed::SimulationRng;
+use crate::simulation::selection::Selection;
+use crate::simulation::stats_boards::StatsBoard;
+use crate::simulation::theme::Theme;
+use crate::simulation::universe::{PatternLoaded, UnfocusedPolicy, Universe};
+use crate::simulation::view::SimulationView;
+
+/// File extension of saved workspaces; dropped files with it are restored, not imported.
+pub const EXTENSION: &str = "workspace";
+
+/// Wall cells per `#W walls` line.
+const WALLS_PER_LINE: usize = 8;
+
+/// Ctrl+Shift+S saves the whole working context into `--save-dir DIR` (the working
+/// directory by default): cells and walls, engine, rule, generation, torus, view,
+/// selection, frozen region, speed, background policy, seed, colors and macros. Dropping
+/// the `.workspace` file on the window puts it all back, as does starting with
+/// `--workspace PATH`, so a session can move to another machine. The file is an RLE
+/// pattern with everything else in `#W` lines, so other programs can still open the
+/// cells. The web build keeps one workspace in the browser instead and restores it when
+/// the page opens.
+pub struct WorkspacePlugin;
+
+impl Plugin for WorkspacePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PostStartup, load_startup_workspace)
+            .add_systems(Update, save_workspace.in_set(SimulationInput));
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_systems(Update, load_dropped_workspaces);
+    }
+}
+
+/// Everything a workspace file holds.
+pub struct Workspace {
+    /// Id of the engine; only used for Conway's rule on the plane, since other rules and
+    /// tori always run on the rule engine.
+    pub engine: String,
+    pub rule: LifeRule,
+    /// Generation when saved; the restored universe counts on from it.
+    pub generation: u64,
+    pub view: SimulationView,
+    pub selection: Option<CellRegion>,
+    pub step_region: Option<CellRegion>,
+    pub torus: Option<I64Vec2>,
+    pub steps_per_tick: u64,
+    pub paused: bool,
+    pub unfocused: UnfocusedPolicy,
+    pub seed: u64,
+    /// Colors and universe shader; older files have none and keep the current ones.
+    pub theme: Option<Theme>,
+    pub cells: Vec<I64Vec2>,
+    pub walls: Vec<I64Vec2>,
+    pub macros: Vec<Macro>,
+}
+
+impl Workspace {
+    pub fn capture(
+        universe: &Universe,
+        view: &SimulationView,
+        selection: &Selection,
+        rng: &SimulationRng,
+        macros: &Macros,
+        theme: &Theme,
+    ) -> Self {
+        Self {
+            cells: universe.read_engine().export(),
+            ..Self::capture_settings(universe, view, selection, rng, macros, theme)
+        }
+    }
+
+    /// [`Workspace::capture`] without the live cells, for callers that save those their
+    /// own way.
+    pub fn capture_settings(
+        universe: &Universe,
+        view: &SimulationView,
+        selection: &Selection,
+        rng: &SimulationRng,
+        macros: &Macros,
+        theme: &Theme,
+    ) -> Self {
+        Self {
+            engine: universe.engine_id(),
+            rule: universe.rule(),
+            generation: universe.generation(),
+            view: SimulationView {
+                center: view.center,
+                zoom: view.zoom,
+            },
+            selection: selection.region,
+            step_region: universe.step_region(),
+            torus: universe.torus(),
+            steps_per_tick: universe.steps_per_tick,
+            paused: universe.paused,
+            unfocused: universe.unfocused,
+            seed: rng.seed(),
+            theme: Some(theme.clone()),
+            cells: Vec::new(),
+            walls: universe.walls().collect(),
+            macros: macros.list.clone(),
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut out = String::from("#C life.rs workspace\n");
+        // Writing to a String can't fail
+        let _ = writeln!(out, "#W engine {}", self.engine);
+        let _ = writeln!(out, "#W generation {}", self.generation);
+        let _ = writeln!(
+            out,
+            "#W view {} {} {}",
+            self.view.center.x, self.view.center.y, self.view.zoom
+        );
+        for (key, region) in [
+            ("selection", self.selection),
+            ("step-region", self.step_region),
+        ] {
+            if let Some(region) = region {
+                let _ = writeln!(
+                    out,
+                    "#W {key} {} {} {} {}",
+                    region.min.x, region.min.y, region.max.x, region.max.y
+                );
+            }
+        }
+        if let Some(size) = self.torus {
+            let _ = writeln!(out, "#W torus {} {}", size.x, size.y);
+        }
+        let _ = writeln!(out, "#W speed {}", self.steps_per_tick);
+        let _ = writeln!(out, "#W paused {}", self.paused);
+        let _ = writeln!(out, "#W unfocused {}", self.unfocused);
+        let _ = writeln!(out, "#W seed {}", self.seed);
+        if let Some(theme) = &self.theme {
+            for (key, color) in [
+                ("alive", theme.universe_alive),
+                ("dead", theme.universe_dead),
+                ("draw", theme.draw_color),
+                ("wall", theme.wall_color),
+            ] {
+                let _ = writeln!(
+                    out,
+                    "#W color {key} {} {} {} {}",
+                    color.x, color.y, color.z, color.w
+                );
+            }
+            let _ = writeln!(out, "#W shader {:?}", theme.universe_shader);
+        }
+        for chunk in self.walls.chunks(WALLS_PER_LINE) {
+            out.push_str("#W walls");
+            for pos in chunk {
+                let _ = write!(out, " {},{}", pos.x, pos.y);
+            }
+            out.push('\n');
+        }
+        // One line per edit, each a line of the autosave journal
+        for recorded in &self.macros {
+            for edit in &recorded.edits {
+                let _ = write!(out, "#W macro {} ", recorded.name);
+                write_edit(&mut out, edit);
+            }
+        }
+
+        // Rows run down in RLE and up on screen; the corner is the top-left cell
+        if let Some(min) = self.cells.iter().copied().reduce(I64Vec2::min) {
+            let top = self.cells.iter().map(|pos| pos.y).max().unwrap_or(min.y);
+            let _ = writeln!(out, "#W corner {} {top}", min.x);
+        }
+        let flipped: Vec<I64Vec2> = self
+            .cells
+            .iter()
+            .map(|pos| I64Vec2::new(pos.x, -pos.y))
+            .collect();
+        out.push_str(&rle::encode(&flipped, self.rule, ""));
+        out
+    }
+
+    /// Reads a workspace written by [`Workspace::to_text`]. Unknown `#W` keys are skipped.
+    /// Its cells are refused unless they fit `budget` on the engine they are restored to.
+    pub fn parse(
+        text: &str,
+        budget: ImportBudget,
+        registry: &EngineRegistry,
+    ) -> Result<Self, LifeError> {
+        let size = rle::measure(text)?;
+        let mut workspace = Workspace {
+            engine: String::new(),
+            rule: size.rule.unwrap_or(LifeRule::CONWAY),
+            generation: 0,
+            view: SimulationView::default(),
+            selection: None,
+            step_region: None,
+            torus: None,
+            steps_per_tick: 1,
+            paused: false,
+            unfocused: UnfocusedPolicy::Run,
+            seed: 0,
+            theme: None,
+            cells: Vec::new(),
+            walls: Vec::new(),
+            macros: Vec::new(),
+        };
+        let mut corner = I64Vec2::ZERO;
+        let mut found = false;
+
+        for line in text.lines() {
+            let Some(line) = line.trim().strip_prefix("#W") else {
+                continue;
+            };
+            found = true;
+            let mut fields = line.split_whitespace();
+            let Some(key) = fields.next() else {
+                continue;
+            };
+            let values: Vec<&str> = fields.collect();
+            match key {
+                "engine" => workspace.engine = values.join(" "),
+                "generation" => workspace.generation = parse_value(key, &values, 0)?,
+                "view" => {
+                    workspace.view = SimulationView {
+                        center: DVec2::new(
+                            parse_value(key, &values, 0)?,
+                            parse_value(key, &values, 1)?,
+                        ),
+                        zoom: parse_value(key, &values, 2)?,
+                    }
+                }
+                "selection" | "step-region" => {
+                    let region = CellRegion {
+                        min: parse_point(key, &values, 0)?,
+                        max: parse_point(key, &values, 2)?,
+                    };
+                    if key == "selection" {
+                        workspace.selection = Some(region);
+                    } else {
+                        workspace.step_region = Some(region);
+                    }
+                }
+                "torus" => workspace.torus = Some(parse_point(key, &values, 0)?),
+                "speed" => workspace.steps_per_tick = parse_value(key, &values, 0)?,
+                "paused" => workspace.paused = parse_value(key, &values, 0)?,
+                "unfocused" => {
+                    workspace.unfocused = values
+                        .first()
+                        .and_then(|value| UnfocusedPolicy::parse(value))
+                        .ok_or_else(|| workspace_error("bad unfocused value"))?
+                }
+                "seed" => workspace.seed = parse_value(key, &values, 0)?,
+                "color" => {
+                    let color = Vec4::new(
+                        parse_value(key, &values, 1)?,
+                        parse_value(key, &values, 2)?,
+                        parse_value(key, &values, 3)?,
+                        parse_value(key, &values, 4)?,
+                    );
+                    let theme = workspace.theme.get_or_insert_with(Theme::default);
+                    match values.first().copied() {
+                        Some("alive") => theme.universe_alive = color,
+                        Some("dead") => theme.universe_dead = color,
+                        Some("draw") => theme.draw_color = color,
+                        Some("wall") => theme.wall_color = color,
+                        _ => {}
+                    }
+                }
+                "shader" => {
+                    let name = values.first().copied().unwrap_or_default();
+                    let shader = LayerShader::ALL
+                        .into_iter()
+                        .find(|shader| format!("{shader:?}") == name)
+                        .ok_or_else(|| workspace_error("unknown shader"))?;
+                    workspace
+                        .theme
+                        .get_or_insert_with(Theme::default)
+                        .universe_shader = shader;
+                }
+                "corner" => corner = parse_point(key, &values, 0)?,
+                "walls" => {
+                    for pair in &values {
+                        let coords: Vec<&str> = pair.split(',').collect();
+                        workspace.walls.push(parse_point(key, &coords, 0)?);
+                    }
+                }
+                "macro" => {
+                    let (name, edit) = values
+                        .split_first()
+                        .and_then(|(name, fields)| Some((name, parse_edit(fields)?)))
+                        .ok_or_else(|| workspace_error("invalid macro line"))?;
+                    match workspace.macros.last_mut() {
+                        Some(last) if last.name == *name => last.edits.push(edit),
+                        _ => workspace.macros.push(Macro {
+                            name: name.to_string(),
+                            edits: vec![edit],
+                        }),
+                    }
+                }
+                _ => {}
+            }
+        }
+        if !found {
+            return Err(workspace_error("no #W lines"));
+        }
+
+        // The engine `restore` ends up on, or the one it keeps if that one is missing
+        let engine = if workspace.rule == LifeRule::CONWAY && workspace.torus.is_none() {
+            registry
+                .create(&workspace.engine)
+                .unwrap_or_else(default_engine)
+        } else {
+            create_rule_engine(workspace.rule)
+        };
+        budget.check(&size, "workspace", engine.as_ref(), Some(registry))?;
+        workspace.cells = rle::decode(text)?
+            .cells
+            .into_iter()
+            .map(|p| I64Vec2::new(corner.x + p.x, corner.y - p.y))
+            .collect();
+        Ok(workspace)
+    }
+
+    /// Replaces the universe, view, selection, seed, colors and macros with the saved ones.
+    #[allow(clippy::too_many_arguments)]
+    pub fn restore(
+        self,
+        registry: &EngineRegistry,
+        universe: &mut Universe,
+        view: &mut SimulationView,
+        selection: &mut Selection,
+        rng: &mut SimulationRng,
+        macros: &mut Macros,
+        theme: &mut Theme,
+    ) {
+        // Torus and rule first: both move to the rule engine, a saved engine comes after
+        if universe.torus() != self.torus {
+            universe.set_torus(self.torus);
+        }
+        if universe.rule() != self.rule {
+            universe.set_rule(self.rule);
+        }
+        if self.rule == LifeRule::CONWAY
+            && self.torus.is_none()
+            && universe.engine_id() != self.engine
+        {
+            match registry.get(&self.engine) {
+                Some(entry) => universe.switch_engine(entry),
+                None => warn!(
+                    "Engine '{}' isn't available, keeping {}",
+                    self.engine,
+                    universe.engine_name()
+                ),
+            }
+        }
+
+        universe.clear();
+        universe.import(self.cells);
+        universe.set_generation(self.generation);
+        if !self.walls.is_empty() {
+            universe.add_walls(self.walls);
+        }
+        universe.set_step_region(self.step_region);
+        universe.steps_per_tick = self.steps_per_tick;
+        universe.paused = self.paused;
+        universe.unfocused = self.unfocused;
+        universe.set_seed(self.seed);
+        *rng = SimulationRng::new(self.seed);
+        *view = self.view;
+        if let Some(saved) = self.theme {
+            *theme = saved;
+        }
+        selection.region = self.selection;
+        macros.list = self.macros;
+        macros.current = 0;
+    }
+}
+
+fn parse_value<T: std::str::FromStr>(
+    key: &str,
+    values: &[&str],
+    index: usize,
+) -> Result<T, LifeError> {
+    values
+        .get(index)
+        .and_then(|value| value.parse().ok())
+        .ok_or_else(|| workspace_error(&format!("invalid {key} line")))
+}
+
+fn parse_point(key: &str, values: &[&str], index: usize) -> Result<I64Vec2, LifeError> {
+    Ok(I64Vec2::new(
+        parse_value(key, values, index)?,
+        parse_value(key, values, index + 1)?,
+    ))
+}
+
+fn workspace_error(reason: &str) -> LifeError {
+    LifeError::InvalidPattern(format!("workspace: {reason}"))
+}
+
+// Ctrl+Shift+S: save the workspace
+#[allow(clippy::too_many_arguments)]
+fn save_workspace(
+    keys: Res<ButtonInput<KeyCode>>,
+    universe: Res<Universe>,
+    view: Res<SimulationView>,
+    selection: Res<Selection>,
+    rng: Res<SimulationRng>,
+    macros: Res<Macros>,
+    theme: Res<Theme>,
+    mut stats: ResMut<StatsBoard>,
+    mut errors: MessageWriter<LifeError>,
+) {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+        || !keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight])
+        || !keys.just_pressed(KeyCode::KeyS)
+    {
+        return;
+    }
+
+    let text = Workspace::capture(&universe, &view, &selection, &rng, &macros, &theme).to_text();
+    match store(universe.generation(), text) {
+        Ok(place) => {
+            info!("Saved the workspace to {place}");
+            stats.insert("Saved", place);
+        }
+        Err(err) => {
+            errors.write(err);
+        }
+    }
+}
+
+/// Writes a saved workspace into `--save-dir DIR` and names the file.
+#[cfg(not(target_arch = "wasm32"))]
+fn store(generation: u64, text: String) -> Result<String, LifeError> {
+    let dir = arg_value("--save-dir").map_or_else(|| PathBuf::from("."), PathBuf::from);
+    let path = dir.join(format!("workspace-gen{generation}.{EXTENSION}"));
+    write_file(&path, &text).map_err(|err| LifeError::io(&path, err))?;
+    Ok(path.display().to_string())
+}
+
+/// Keeps a saved workspace in the browser, replacing the previous one; the page
+/// restores it when it opens again.
+#[cfg(target_arch = "wasm32")]
+fn store(_generation: u64, text: String) -> Result<String, LifeError> {
+    browser_storage::put(WORKSPACE_KEY, text);
+    Ok("this browser".to_string())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_file(path: &Path, text: &str) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, text)
+}
+
+/// Whether a dropped file is a workspace rather than a pattern.
+pub fn is_workspace(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == EXTENSION)
+}
+
+// Dropping a .workspace file on the window restores it
+#[cfg(not(target_arch = "wasm32"))]
+#[allow(clippy::too_many_arguments)]
+fn load_dropped_workspaces(
+    mut drops: MessageReader<bevy::window::FileDragAndDrop>,
+    budget: Res<ImportBudget>,
+    registry: Res<EngineRegistry>,
+    mut universe: ResMut<Universe>,
+    mut view: ResMut<SimulationView>,
+    mut selection: ResMut<Selection>,
+    mut rng: ResMut<SimulationRng>,
+    mut macros: ResMut<Macros>,
+    mut theme: ResMut<Theme>,
+    mut stats: ResMut<StatsBoard>,
+    mut errors: MessageWriter<LifeError>,
+    mut patterns_loaded: MessageWriter<PatternLoaded>,
+) {
+    for drop in drops.read() {
+        let bevy::window::FileDragAndDrop::DroppedFile { path_buf, .. } = drop else {
+            continue;
+        };
+        if !is_workspace(path_buf) {
+            continue;
+        }
+        let loaded = read_file(path_buf).and_then(|(name, text)| {
+            let generation = load_text(
+                &text,
+                *budget,
+                &registry,
+                &mut universe,
+                &mut view,
+                &mut selection,
+                &mut rng,
+                &mut macros,
+                &mut theme,
+            )?;
+            Ok((name, generation))
+        });
+        match loaded {
+            Ok((name, generation)) => {
+                report_loaded(name, generation, &mut stats, &mut patterns_loaded);
+            }
+            Err(err) => {
+                errors.write(err);
+            }
+        }
+    }
+}
+
+// --workspace PATH, or on the web the workspace saved in the browser: start where a
+// saved session left off
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn load_startup_workspace(
+    budget: Res<ImportBudget>,
+    registry: Res<EngineRegistry>,
+    mut universe: ResMut<Universe>,
+    mut view: ResMut<SimulationView>,
+    mut selection: ResMut<Selection>,
+    mut rng: ResMut<SimulationRng>,
+    mut macros: ResMut<Macros>,
+    mut theme: ResMut<Theme>,
+    mut stats: ResMut<StatsBoard>,
+    mut errors: MessageWriter<LifeError>,
+    mut patterns_loaded: MessageWriter<PatternLoaded>,
+) {
+    #[cfg(not(target_arch = "wasm32"))]
+    let Some(saved) = arg_value("--workspace").map(|path| read_file(Path::new(&path))) else {
+        return;
+    };
+    #[cfg(target_arch = "wasm32")]
+    let Some(saved) = browser_storage::get(WORKSPACE_KEY)
+        .map(|text| Ok(("workspace saved in this browser".to_string(), text)))
+    else {
+        return;
+    };
+
+    let loaded = saved.and_then(|(name, text)| {
+        let generation = load_text(
+            &text,
+            *budget,
+            &registry,
+            &mut universe,
+            &mut view,
+            &mut selection,
+            &mut rng,
+            &mut macros,
+            &mut theme,
+        )?;
+        Ok((name, generation))
+    });
+    match loaded {
+        Ok((name, generation)) => report_loaded(name, generation, &mut stats, &mut patterns_loaded),
+        Err(err) => {
+            errors.write(err);
+        }
+    }
+}
+
+/// The file name and contents of the workspace at `path`.
+#[cfg(not(target_arch = "wasm32"))]
+fn read_file(path: &Path) -> Result<(String, String), LifeError> {
+    let text = std::fs::read_to_string(path).map_err(|err| LifeError::io(path, err))?;
+    let name = path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned();
+    Ok((name, text))
+}
+
+/// Restores a workspace from its text, returning its generation.
+#[allow(clippy::too_many_arguments)]
+fn load_text(
+    text: &str,
+    budget: ImportBudget,
+    registry: &EngineRegistry,
+    universe: &mut Universe,
+    view: &mut SimulationView,
+    selection: &mut Selection,
+    rng: &mut SimulationRng,
+    macros: &mut Macros,
+    theme: &mut Theme,
+) -> Result<u64, LifeError> {
+    let workspace = Workspace::parse(text, budget, registry)?;
+    let generation = workspace.generation;
+    workspace.restore(registry, universe, view, selection, rng, macros, theme);
+    Ok(generation)
+}
+
+fn report_loaded(
+    name: String,
+    generation: u64,
+    stats: &mut StatsBoard,
+    patterns_loaded: &mut MessageWriter<PatternLoaded>,
+) {
+    info!("Restored {name}");
+    stats.insert(
+        "Workspace",
+        format!("{name} (saved at generation {generation})"),
+    );
+    patterns_loaded.write(PatternLoaded { name });
+}